@@ -0,0 +1,204 @@
+//! Audits written by flux with `-Faudit` listing what is not verified in a crate. This mirrors the
+//! format defined in `flux-middle`.
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct CrateAudit {
+    pub crate_name: String,
+    pub unchecked: Vec<AuditEntry>,
+    pub assumptions: Vec<AuditEntry>,
+    pub extern_specs: Vec<ExternSpec>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub def_path: String,
+    pub span: Span,
+    /// The reason the item is not checked or the assumption it relies on
+    pub kind: String,
+    pub origin: Origin,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Origin {
+    /// An attribute on the item or on one of its parents
+    Attribute { item: String, span: Span },
+    /// A flag, which can also be set in the `metadata` table or in `flux.toml`
+    Flag { flag: String },
+    /// The macro that generated the item
+    Macro { name: String },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExternSpec {
+    pub def_path: String,
+    pub crate_name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Span {
+    pub file: String,
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+/// The file where flux saves the audit of `crate_name` when `-Faudit` is a directory.
+pub fn audit_file(dir: &Path, crate_name: &str) -> PathBuf {
+    dir.join(format!("{crate_name}-audit.json"))
+}
+
+/// The file where we move the audit of `crate_name` while cargo runs, see [`set_aside`].
+fn previous_audit_file(dir: &Path, crate_name: &str) -> PathBuf {
+    dir.join(format!("{crate_name}-audit.json.prev"))
+}
+
+/// Moves the audits of the given crates saved in `dir` out of the way before running cargo. Flux
+/// writes the audit of a crate again only if cargo checks it again, so after running cargo we keep
+/// the previous audit of the crates that were fresh and drop the others with [`restore_fresh`].
+/// Otherwise, the audit of a crate that failed to compile would be the stale one of a previous run.
+pub fn set_aside(dir: &Path, crate_names: &[String]) -> io::Result<()> {
+    for crate_name in crate_names {
+        let path = audit_file(dir, crate_name);
+        if path.is_file() {
+            fs::rename(path, previous_audit_file(dir, crate_name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Restores the audits set aside with [`set_aside`] for the crates in `fresh`, i.e., the crates
+/// cargo didn't check again, and removes the audits set aside for the other crates.
+pub fn restore_fresh(dir: &Path, crate_names: &[String], fresh: &[String]) -> io::Result<()> {
+    for crate_name in crate_names {
+        let previous = previous_audit_file(dir, crate_name);
+        if !previous.is_file() {
+            continue;
+        }
+        if fresh.contains(crate_name) {
+            fs::rename(previous, audit_file(dir, crate_name))?;
+        } else {
+            fs::remove_file(previous)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the audits of the given crates saved in `dir`. Crates without an audit (e.g., because they
+/// failed to compile) are skipped.
+pub fn read(dir: &Path, crate_names: &[String]) -> io::Result<Vec<CrateAudit>> {
+    let mut audits = vec![];
+    for crate_name in crate_names {
+        let path = audit_file(dir, crate_name);
+        if !path.is_file() {
+            continue;
+        }
+        let audit = serde_json::from_reader(io::BufReader::new(fs::File::open(path)?))?;
+        audits.push(audit);
+    }
+    Ok(audits)
+}
+
+/// Writes the audits in a human readable format.
+pub fn write_human(mut w: impl io::Write, audits: &[CrateAudit]) -> io::Result<()> {
+    for (i, audit) in audits.iter().enumerate() {
+        if i > 0 {
+            writeln!(w)?;
+        }
+        writeln!(w, "crate `{}`", audit.crate_name)?;
+
+        writeln!(w, "  unchecked items: {}", audit.unchecked.len())?;
+        for entry in &audit.unchecked {
+            writeln!(w, "    {entry}")?;
+        }
+
+        writeln!(w, "  assumptions: {}", audit.assumptions.len())?;
+        for entry in &audit.assumptions {
+            writeln!(w, "    {entry}")?;
+        }
+
+        writeln!(w, "  extern specs: {}", audit.extern_specs.len())?;
+        for spec in &audit.extern_specs {
+            writeln!(w, "    {} (from `{}`)", spec.def_path, spec.crate_name)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the audits as a JSON array with one element per crate.
+pub fn write_json(w: impl io::Write, audits: &[CrateAudit]) -> io::Result<()> {
+    serde_json::to_writer_pretty(w, audits)?;
+    Ok(())
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind.as_str() {
+            "ignored" => "ignored",
+            "not_included" => "not included",
+            "macro_generated" => "generated by a macro",
+            "trusted" => "trusted",
+            "trusted_impl" => "implementation not checked against the trait",
+            "proven_externally" => "proven externally",
+            "allow_uninterpreted_cast" => "allows uninterpreted casts",
+            "allow_raw_deref" => "allows unchecked raw pointer dereferences",
+            kind => kind,
+        };
+        write!(f, "{} ({}): {kind}, {}", self.def_path, self.span, self.origin)
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Attribute { item, span } => write!(f, "by an attribute on `{item}` ({span})"),
+            Origin::Flag { flag } => write!(f, "by the `{flag}` flag"),
+            Origin::Macro { name } => write!(f, "expanded from `{name}`"),
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.start_line, self.start_col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_audits_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let crate_names = ["fresh", "checked", "failed"].map(String::from);
+        for crate_name in &crate_names {
+            fs::write(audit_file(dir, crate_name), "previous").unwrap();
+        }
+
+        set_aside(dir, &crate_names).unwrap();
+        assert!(
+            crate_names
+                .iter()
+                .all(|name| !audit_file(dir, name).exists())
+        );
+
+        // Cargo doesn't check `fresh` again, `checked` is checked again, and `failed` fails to
+        // compile before flux writes its audit.
+        fs::write(audit_file(dir, "checked"), "current").unwrap();
+        restore_fresh(dir, &crate_names, &["fresh".to_string()]).unwrap();
+
+        assert_eq!(fs::read_to_string(audit_file(dir, "fresh")).unwrap(), "previous");
+        assert_eq!(fs::read_to_string(audit_file(dir, "checked")).unwrap(), "current");
+        assert!(!audit_file(dir, "failed").exists());
+        assert_eq!(fs::read_dir(dir).unwrap().count(), 2);
+    }
+}
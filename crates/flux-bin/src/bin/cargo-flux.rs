@@ -1,15 +1,18 @@
 use std::{
     self, env,
-    io::{BufWriter, Write},
-    process::{Command, exit},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    process::{Command, Stdio, exit},
 };
 
 use anyhow::anyhow;
-use cargo_metadata::{Metadata, camino::Utf8Path};
+use cargo_metadata::{Message, Metadata, PackageId, camino::Utf8Path};
 use clap::Parser as _;
 use flux_bin::{
-    FluxMetadata,
-    cargo_flux_opts::{CargoFluxCommand, Cli},
+    FluxMetadata, audit,
+    cargo_flux_opts::{AuditFormat, AuditOpts, CargoFluxCommand, Cli, Workspace},
+    diagnostics::CargoMessage,
+    sarif,
     utils::{
         EXIT_ERR, flux_sysroot_dir, get_binary_path, get_flux_driver_path, get_rust_toolchain,
         print_version_and_exit,
@@ -42,7 +45,13 @@ fn run(cargo_flux_cmd: CargoFluxCommand) -> anyhow::Result<i32> {
     let cargo_path = get_binary_path(&toolchain, "cargo")?;
 
     let metadata = cargo_flux_cmd.metadata().cargo_path(&cargo_path).exec()?;
-    let config_file = write_cargo_config(metadata)?;
+    let workspace_root = metadata.workspace_root.clone().into_std_path_buf();
+    let audit_dir = metadata.target_directory.join("flux-audit");
+    let extra_flags = match &cargo_flux_cmd {
+        CargoFluxCommand::Audit(_) => vec![format!("-Faudit={audit_dir}/")],
+        _ => vec![],
+    };
+    let (config_file, enabled) = write_cargo_config(&metadata, &extra_flags)?;
 
     let sysroot = flux_sysroot_dir();
     let flux_driver_path = get_flux_driver_path(&sysroot)?;
@@ -58,12 +67,127 @@ fn run(cargo_flux_cmd: CargoFluxCommand) -> anyhow::Result<i32> {
         .env("RUSTC_WRAPPER", "")
         .arg(format!("+{toolchain}"));
 
-    cargo_flux_cmd.forward_args(&mut cargo_command, config_file.path());
+    let enabled_names = metadata
+        .packages
+        .iter()
+        .filter(|pkg| enabled.contains(&pkg.id))
+        .map(|pkg| pkg.name.as_str())
+        .collect_vec();
+    if let CargoFluxCommand::Clean(clean_opts) = &cargo_flux_cmd
+        && clean_opts.cleans_nothing(&enabled_names)
+    {
+        return Ok(0);
+    }
+
+    cargo_flux_cmd.forward_args(&mut cargo_command, config_file.path(), &enabled_names);
+
+    if cargo_flux_cmd.is_sarif() {
+        return run_sarif(cargo_command, &workspace_root);
+    }
+
+    if let CargoFluxCommand::Audit(audit_opts) = &cargo_flux_cmd {
+        let crate_names = audited_crates(&metadata, audit_opts, &enabled);
+        return run_audit(cargo_command, audit_opts, audit_dir.as_std_path(), &crate_names);
+    }
 
     Ok(cargo_command.status()?.code().unwrap_or(EXIT_ERR))
 }
 
-fn write_cargo_config(metadata: Metadata) -> anyhow::Result<NamedTempFile> {
+/// Runs cargo collecting the diagnostics from its json output and prints them as a SARIF log.
+fn run_sarif(mut cargo_command: Command, workspace_root: &Path) -> anyhow::Result<i32> {
+    let mut child = cargo_command.stdout(Stdio::piped()).spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("cannot read cargo output"))?;
+    let mut diagnostics = vec![];
+    for line in BufReader::new(stdout).lines() {
+        if let Ok(msg) = serde_json::from_str::<CargoMessage>(&line?)
+            && msg.reason == "compiler-message"
+            && let Some(diag) = msg.message
+        {
+            diagnostics.push(diag);
+        }
+    }
+    let status = child.wait()?;
+    sarif::write(io::stdout().lock(), workspace_root, &diagnostics)?;
+    Ok(status.code().unwrap_or(EXIT_ERR))
+}
+
+/// Runs cargo and prints the audits written by flux for `crate_names`. The audits are written to
+/// `audit_dir` and kept between runs because cargo doesn't run flux again on crates that didn't
+/// change. We keep the audit of a crate only if cargo checked it in this run or if it was fresh.
+fn run_audit(
+    mut cargo_command: Command,
+    audit_opts: &AuditOpts,
+    audit_dir: &Path,
+    crate_names: &[String],
+) -> anyhow::Result<i32> {
+    audit::set_aside(audit_dir, crate_names)?;
+    let mut child = cargo_command.stdout(Stdio::piped()).spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("cannot read cargo output"))?;
+    let mut fresh = vec![];
+    for msg in Message::parse_stream(BufReader::new(stdout)) {
+        if let Message::CompilerArtifact(artifact) = msg?
+            && artifact.fresh
+        {
+            fresh.push(artifact.target.name.replace('-', "_"));
+        }
+    }
+    let status = child.wait()?;
+    audit::restore_fresh(audit_dir, crate_names, &fresh)?;
+    let audits = audit::read(audit_dir, crate_names)?;
+    match audit_opts.message_format {
+        AuditFormat::Human => audit::write_human(io::stdout().lock(), &audits)?,
+        AuditFormat::Json => audit::write_json(io::stdout().lock(), &audits)?,
+    }
+    Ok(status.code().unwrap_or(EXIT_ERR))
+}
+
+/// The names of the crates of the packages selected for the audit, following the same rules as
+/// cargo to select packages. Only the library and binaries of packages with flux enabled are
+/// audited.
+fn audited_crates(
+    metadata: &Metadata,
+    audit_opts: &AuditOpts,
+    enabled: &[PackageId],
+) -> Vec<String> {
+    let Workspace { package, workspace, exclude } = &audit_opts.workspace;
+    let packages = if !package.package.is_empty() {
+        metadata
+            .packages
+            .iter()
+            .filter(|pkg| package.package.contains(&pkg.name))
+            .collect()
+    } else if *workspace {
+        metadata
+            .workspace_packages()
+            .into_iter()
+            .filter(|pkg| !exclude.contains(&pkg.name))
+            .collect()
+    } else if let Some(root) = metadata.root_package() {
+        vec![root]
+    } else {
+        metadata.workspace_default_packages()
+    };
+    packages
+        .into_iter()
+        .filter(|pkg| enabled.contains(&pkg.id))
+        .flat_map(|pkg| &pkg.targets)
+        .filter(|target| target.is_lib() || target.is_bin() || target.is_proc_macro())
+        .map(|target| target.name.replace('-', "_"))
+        .collect()
+}
+
+/// Writes the cargo config used to run flux and returns it together with the packages for which
+/// flux is enabled. The `extra_flags` are passed to every invocation of flux.
+fn write_cargo_config(
+    metadata: &Metadata,
+    extra_flags: &[String],
+) -> anyhow::Result<(NamedTempFile, Vec<PackageId>)> {
     let flux_flags: Option<Vec<String>> = if let Ok(flags) = env::var("FLUXFLAGS") {
         Some(flags.split(" ").map(Into::into).collect())
     } else {
@@ -78,6 +202,7 @@ fn write_cargo_config(metadata: Metadata) -> anyhow::Result<NamedTempFile> {
         return Err(anyhow!("`enabled` cannot be set in `flux.toml`"));
     }
 
+    let mut enabled = vec![];
     let mut file = NamedTempFile::new()?;
     {
         let mut w = BufWriter::new(&mut file);
@@ -90,18 +215,14 @@ profile-rustflags = true
 [env]
 FLUX_BUILD_SYSROOT = "1"
 FLUX_CARGO = "1"
-
-[profile.flux]
-inherits = "dev"
-incremental = false
         "#
         )?;
 
-        for package in metadata.packages {
+        for package in &metadata.packages {
             let flux_metadata: FluxMetadata = config::Config::builder()
                 .add_source(FluxMetadataSource::new(
                     package.manifest_path.to_string(),
-                    package.metadata,
+                    package.metadata.clone(),
                 ))
                 .add_source(flux_toml.clone())
                 .build()?
@@ -119,7 +240,7 @@ incremental = false
                 write!(
                     w,
                     r#"
-[profile.flux.package."{}"]
+[profile.dev.package."{}"]
 rustflags = [{:?}]
                         "#,
                     package.id,
@@ -127,14 +248,16 @@ rustflags = [{:?}]
                         .into_flags(&metadata.target_directory, manifest_dir_relative_to_workspace)
                         .iter()
                         .chain(flux_flags.iter().flatten())
+                        .chain(extra_flags)
                         .map(|s| s.as_ref())
                         .chain(["-Fverify=on", "-Ffull-compilation=on"])
                         .format(", ")
                 )?;
+                enabled.push(package.id.clone());
             }
         }
     }
-    Ok(file)
+    Ok((file, enabled))
 }
 
 #[derive(Clone, Debug)]
@@ -34,10 +34,15 @@ pub enum CargoFluxCommand {
     Check(CheckOpts),
     /// Remove artifacts that cargo-flux has generated in the past
     Clean(CleanOpts),
+    /// Check a local package and list the items that are not verified, the assumptions the
+    /// verified items rely on, and the extern specs it uses
+    Audit(AuditOpts),
 }
 
 impl CargoFluxCommand {
-    pub fn forward_args(&self, cmd: &mut Command, config_file: &Path) {
+    /// Forwards the arguments to `cmd`. The `enabled` packages are the names of the packages for
+    /// which flux is enabled, which are the ones cleaned when no package is selected.
+    pub fn forward_args(&self, cmd: &mut Command, config_file: &Path, enabled: &[&str]) {
         match self {
             CargoFluxCommand::Check(check_opts) => {
                 cmd.arg("check");
@@ -45,13 +50,26 @@ impl CargoFluxCommand {
             }
             CargoFluxCommand::Clean(clean_opts) => {
                 cmd.arg("clean");
-                clean_opts.forward_args(cmd);
+                clean_opts.forward_args(cmd, enabled);
+            }
+            CargoFluxCommand::Audit(audit_opts) => {
+                cmd.arg("check");
+                audit_opts.forward_args(cmd);
             }
         }
-        cmd.args(["--profile", "flux"]);
         cmd.args(["--config".as_ref(), config_file.as_os_str()]);
     }
 
+    /// Whether the diagnostics should be reported in the SARIF format
+    pub fn is_sarif(&self) -> bool {
+        match self {
+            CargoFluxCommand::Check(check_opts) => {
+                check_opts.message_format.as_deref() == Some(SARIF_MESSAGE_FORMAT)
+            }
+            CargoFluxCommand::Clean(_) | CargoFluxCommand::Audit(_) => false,
+        }
+    }
+
     pub fn metadata(&self) -> MetadataCommand {
         let mut meta = cargo_metadata::MetadataCommand::new();
         match self {
@@ -61,14 +79,19 @@ impl CargoFluxCommand {
             CargoFluxCommand::Clean(clean_options) => {
                 clean_options.forward_to_metadata(&mut meta);
             }
+            CargoFluxCommand::Audit(audit_options) => {
+                audit_options.forward_to_metadata(&mut meta);
+            }
         }
         meta
     }
 }
 
+const SARIF_MESSAGE_FORMAT: &str = "sarif";
+
 #[derive(clap::Args)]
 pub struct CheckOpts {
-    /// Error format [possible values: human, short, json, json-diagnostic-short, json-diagnostic-rendered-ansi, json-render-diagnostics]
+    /// Error format [possible values: human, short, json, json-diagnostic-short, json-diagnostic-rendered-ansi, json-render-diagnostics, sarif]
     #[arg(long, value_name = "FMT")]
     message_format: Option<String>,
 
@@ -86,6 +109,9 @@ impl CheckOpts {
     fn forward_args(&self, cmd: &mut Command) {
         let CheckOpts { message_format, workspace, features, compilation, manifest } = self;
         if let Some(message_format) = &message_format {
+            // Flux diagnostics are converted to SARIF from cargo's json output
+            let message_format =
+                if message_format == SARIF_MESSAGE_FORMAT { "json" } else { message_format };
             cmd.args(["--message-format", message_format]);
         }
         workspace.forward_args(cmd);
@@ -112,13 +138,24 @@ pub struct CleanOpts {
 }
 
 impl CleanOpts {
-    fn forward_args(&self, cmd: &mut Command) {
+    fn forward_args(&self, cmd: &mut Command, enabled: &[&str]) {
         let CleanOpts { package, features, manifest } = self;
+        // Flux shares the artifacts of the `dev` profile with `cargo check`, so we only clean the
+        // packages checked by flux instead of the whole target directory.
+        if package.package.is_empty() {
+            cmd.args(enabled.iter().flat_map(|name| ["--package", name]));
+        }
         package.forward_args(cmd);
         features.forward_args(cmd);
         manifest.forward_args(cmd);
     }
 
+    /// Whether there's nothing to clean because no package was selected and flux is not enabled for
+    /// any package.
+    pub fn cleans_nothing(&self, enabled: &[&str]) -> bool {
+        self.package.package.is_empty() && enabled.is_empty()
+    }
+
     fn forward_to_metadata(&self, meta: &mut MetadataCommand) {
         let CleanOpts { package: _, features, manifest } = self;
         features.forward_to_metadata(meta);
@@ -126,6 +163,47 @@ impl CleanOpts {
     }
 }
 
+#[derive(clap::Args)]
+pub struct AuditOpts {
+    /// Output format
+    #[arg(long, value_name = "FMT", value_enum, default_value_t = AuditFormat::Human)]
+    pub message_format: AuditFormat,
+
+    #[command(flatten)]
+    pub workspace: Workspace,
+    #[command(flatten)]
+    features: Features,
+    #[command(flatten)]
+    compilation: CompilationOptions,
+    #[command(flatten)]
+    manifest: ManifestOptions,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum AuditFormat {
+    Human,
+    Json,
+}
+
+impl AuditOpts {
+    fn forward_args(&self, cmd: &mut Command) {
+        let AuditOpts { message_format: _, workspace, features, compilation, manifest } = self;
+        // Diagnostics are rendered as usual, and we read the json messages to know which crates
+        // were fresh, see `audit::set_aside`.
+        cmd.args(["--message-format", "json-render-diagnostics"]);
+        workspace.forward_args(cmd);
+        features.forward_args(cmd);
+        compilation.forward_args(cmd);
+        manifest.forward_args(cmd);
+    }
+
+    fn forward_to_metadata(&self, meta: &mut MetadataCommand) {
+        let AuditOpts { features, manifest, .. } = self;
+        features.forward_to_metadata(meta);
+        manifest.forward_to_metadata(meta);
+    }
+}
+
 #[derive(Debug, clap::Args)]
 #[command(about = None, long_about = None, next_help_heading = "Package Selection")]
 pub struct Workspace {
@@ -0,0 +1,154 @@
+//! Diagnostics emitted by flux in the JSON format used by rustc with `--error-format=json` and by
+//! cargo with `--message-format=json`.
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// The families of errors reported by flux with a stable error code. This mirrors the codes defined
+/// in `flux-errors`.
+pub const ERROR_CODES: &[ErrorCode] = &[
+    ErrorCode {
+        code: "E0984",
+        name: "resource-limit",
+        description: "The solver exceeded its time or memory limit before answering a query.",
+    },
+    ErrorCode {
+        code: "E0985",
+        name: "raw-pointer",
+        description: "A raw pointer may be dereferenced or offset out of the bounds of its allocation.",
+    },
+    ErrorCode {
+        code: "E0986",
+        name: "pattern",
+        description: "A value may not match the pattern of a pattern type.",
+    },
+    ErrorCode {
+        code: "E0987",
+        name: "union",
+        description: "A union field may not be the active one, or a refined union has an invalid tag.",
+    },
+    ErrorCode {
+        code: "E0988",
+        name: "termination",
+        description: "A recursive call or a loop may not terminate.",
+    },
+    ErrorCode {
+        code: "E0989",
+        name: "out-of-bounds",
+        description: "An index may be out of bounds.",
+    },
+    ErrorCode {
+        code: "E0990",
+        name: "precondition",
+        description: "A precondition of a function call cannot be proven.",
+    },
+    ErrorCode {
+        code: "E0991",
+        name: "postcondition",
+        description: "The postcondition of a function cannot be proven.",
+    },
+    ErrorCode {
+        code: "E0992",
+        name: "overflow",
+        description: "An arithmetic operation may overflow.",
+    },
+    ErrorCode {
+        code: "E0993",
+        name: "underflow",
+        description: "An arithmetic operation may underflow.",
+    },
+    ErrorCode {
+        code: "E0994",
+        name: "division-by-zero",
+        description: "A division or remainder operation may have a divisor of zero.",
+    },
+    ErrorCode {
+        code: "E0995",
+        name: "fold",
+        description: "The invariant of a type may not hold when a place is folded.",
+    },
+    ErrorCode {
+        code: "E0996",
+        name: "no-panic",
+        description: "A call in a function that must not panic may panic.",
+    },
+    ErrorCode {
+        code: "E0997",
+        name: "invariant",
+        description: "A type invariant or a loop invariant cannot be proven.",
+    },
+    ErrorCode {
+        code: "E0998",
+        name: "unsupported",
+        description: "Code or a signature uses features not supported by flux.",
+    },
+    ErrorCode {
+        code: "E0999",
+        name: "other",
+        description: "An error reported by flux that doesn't belong to any other family.",
+    },
+];
+
+pub struct ErrorCode {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// A diagnostic in the format emitted by rustc with `--error-format=json`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Diagnostic {
+    pub message: String,
+    pub code: Option<DiagnosticCode>,
+    pub level: String,
+    pub spans: Vec<DiagnosticSpan>,
+    pub children: Vec<Diagnostic>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiagnosticCode {
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DiagnosticSpan {
+    pub file_name: PathBuf,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    pub is_primary: bool,
+    pub label: Option<String>,
+}
+
+/// A message emitted by cargo with `--message-format=json`. We only care about messages with
+/// diagnostics.
+#[derive(Deserialize)]
+pub struct CargoMessage {
+    pub reason: String,
+    pub message: Option<Diagnostic>,
+}
+
+impl Diagnostic {
+    /// Returns the error code if the diagnostic was reported by flux.
+    pub fn flux_error_code(&self) -> Option<&'static ErrorCode> {
+        let code = self.code.as_ref()?;
+        ERROR_CODES.iter().find(|c| c.code == code.code)
+    }
+
+    pub fn primary_span(&self) -> Option<&DiagnosticSpan> {
+        self.spans.iter().find(|span| span.is_primary)
+    }
+
+    /// Resolves relative paths in the diagnostic against `root`.
+    pub fn resolve_paths(&mut self, root: &Path) {
+        for span in &mut self.spans {
+            if span.file_name.is_relative() {
+                span.file_name = root.join(&span.file_name);
+            }
+        }
+        for child in &mut self.children {
+            child.resolve_paths(root);
+        }
+    }
+}
@@ -2,8 +2,12 @@ use cargo_metadata::camino::Utf8Path;
 use flux_config::{LeanMode, OverflowMode, SmtSolver};
 use serde::Deserialize;
 
+pub mod audit;
 pub mod cargo_flux_opts;
 pub mod cargo_style;
+pub mod diagnostics;
+pub mod lsp;
+pub mod sarif;
 pub mod utils;
 
 #[derive(Deserialize, Debug, Default)]
@@ -14,12 +18,22 @@ pub struct FluxMetadata {
     pub cache: Option<bool>,
     /// Set the default solver
     pub solver: Option<SmtSolver>,
+    /// Maximum number of seconds a fixpoint query can run
+    pub solver_timeout: Option<u64>,
+    /// Maximum amount of memory in megabytes a fixpoint query can use
+    pub solver_memory: Option<u64>,
     /// Enable qualifier scrapping in fixpoint
     pub scrape_quals: Option<bool>,
     /// Enable overflow checking
     pub check_overflow: Option<OverflowMode>,
     /// Enable uninterpreted casts
     pub allow_uninterpreted_cast: Option<bool>,
+    /// Interpret bitwise operations on unsigned integers as bit-vector operations
+    pub uint_as_bitvec: Option<bool>,
+    /// Index floats by the real number they denote
+    pub real_floats: Option<bool>,
+    /// Check the cleanup code executed when unwinding
+    pub check_unwind: Option<bool>,
     /// Enable flux-defs to be defined as SMT functions
     pub smt_define_fun: Option<bool>,
     /// Set trusted to trusted
@@ -42,6 +56,15 @@ pub struct FluxMetadata {
     pub lean: Option<LeanMode>,
     /// If present, dumps the fixpoint constraint to a file
     pub dump_constraint: Option<bool>,
+    /// If present, report counterexample models for failed obligations
+    pub counterexamples: Option<bool>,
+    /// If present, require a `decreases` clause on every recursive function
+    pub termination: Option<bool>,
+    /// Number of worker threads used to run fixpoint queries
+    pub jobs: Option<usize>,
+    /// If present, write a JSON report with the verification status of every function. Reports
+    /// are saved in `target/flux-report/<crate>-report.json`
+    pub report: Option<bool>,
 }
 
 impl FluxMetadata {
@@ -57,6 +80,12 @@ impl FluxMetadata {
         if let Some(v) = self.solver {
             flags.push(format!("-Fsolver={v}"));
         }
+        if let Some(v) = self.solver_timeout {
+            flags.push(format!("-Fsolver-timeout={v}"));
+        }
+        if let Some(v) = self.solver_memory {
+            flags.push(format!("-Fsolver-memory={v}"));
+        }
         if let Some(v) = self.check_overflow {
             flags.push(format!("-Fcheck-overflow={v}"));
         }
@@ -81,9 +110,30 @@ impl FluxMetadata {
         if let Some(v) = self.default_ignore {
             flags.push(format!("-Fignore={v}"));
         }
+        if let Some(v) = self.counterexamples {
+            flags.push(format!("-Fcounterexamples={v}"));
+        }
+        if let Some(v) = self.termination {
+            flags.push(format!("-Ftermination={v}"));
+        }
+        if let Some(v) = self.jobs {
+            flags.push(format!("-Fjobs={v}"));
+        }
+        if let Some(true) = self.report {
+            flags.push(format!("-Freport={}/", target_dir.join("flux-report")));
+        }
         if let Some(v) = self.allow_uninterpreted_cast {
             flags.push(format!("-Fallow-uninterpreted-cast={v}"));
         }
+        if let Some(v) = self.uint_as_bitvec {
+            flags.push(format!("-Fuint-as-bitvec={v}"));
+        }
+        if let Some(v) = self.real_floats {
+            flags.push(format!("-Freal-floats={v}"));
+        }
+        if let Some(v) = self.check_unwind {
+            flags.push(format!("-Fcheck-unwind={v}"));
+        }
         if let Some(patterns) = self.include {
             for pat in patterns {
                 if let Some(prefix) = include_pattern_prefix {
@@ -0,0 +1,182 @@
+//! Running flux on a file and collecting the diagnostics it reports.
+//!
+//! If the file belongs to a cargo package we check the package with `cargo flux`, otherwise we
+//! check the file as a standalone library with `flux`. In both cases we enable the checker trace
+//! which we later use to answer hover and definition requests, and the cache (see `-Fcache`), so
+//! only items affected by an edit are checked again. Items skipped because of the cache are not
+//! traced, so we use a cache private to the session instead of the one configured for the package:
+//! the first check traces every item and subsequent checks trace the items that changed.
+//!
+//! When we know the edit to a file only changed the body of a function we check that function
+//! alone, restricting the items checked by the driver with `-Finclude=span:...`.
+use std::{
+    collections::HashMap,
+    env,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{Result, anyhow};
+
+use super::trace::Pos;
+use crate::diagnostics::{CargoMessage, Diagnostic};
+
+/// The diagnostics reported by a run of flux grouped by file.
+pub(crate) type Diagnostics = HashMap<PathBuf, Vec<Diagnostic>>;
+
+impl Diagnostic {
+    /// Whether the diagnostic should be reported to the client. We report all errors emitted by
+    /// flux and all errors that prevent it from running, but not rustc warnings.
+    fn is_relevant(&self) -> bool {
+        self.level.starts_with("error") || self.flux_error_code().is_some()
+    }
+}
+
+/// The files flux produces for a run.
+pub(crate) struct Output {
+    pub diagnostics: Diagnostics,
+    pub trace_file: PathBuf,
+}
+
+/// Checks the package containing `file` or the file alone if it doesn't belong to a package. If
+/// `only` is given, only the function whose body contains that position in `file` is checked.
+pub(crate) fn check(file: &Path, log_dir: &Path, only: Option<Pos>) -> Result<Output> {
+    let mut flags = vec![
+        "-Fdump-checker-trace=info".to_string(),
+        format!("-Flog-dir={}", log_dir.display()),
+        format!("-Fcache={}", log_dir.join("FLUXCACHE").display()),
+    ];
+    let diagnostics = if let Some(manifest) = find_manifest(file) {
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .manifest_path(&manifest)
+            .no_deps()
+            .exec()?;
+        let workspace_root = metadata.workspace_root.into_std_path_buf();
+        flags.extend(only.map(|pos| include_flag(file, &workspace_root, pos)));
+        // Flags in `FLUXFLAGS` come after the ones in the package metadata so they take precedence.
+        let existing = env::var("FLUXFLAGS").unwrap_or_default();
+        let fluxflags = existing
+            .split_whitespace()
+            .map(str::to_string)
+            .chain(flags)
+            .collect::<Vec<_>>();
+
+        let mut cmd = Command::new(sibling_exe("cargo-flux")?);
+        cmd.args(["flux", "--message-format=json", "--manifest-path"])
+            .arg(&manifest)
+            .env("FLUXFLAGS", fluxflags.join(" "));
+        run(cmd, Stdio::piped(), Stdio::null(), |line| {
+            let msg: CargoMessage = serde_json::from_str(line).ok()?;
+            if msg.reason == "compiler-message" { msg.message } else { None }
+        })?
+        .into_iter()
+        .map(|mut diag| {
+            diag.resolve_paths(&workspace_root);
+            diag
+        })
+        .collect::<Vec<_>>()
+    } else {
+        let cwd = file.parent().unwrap_or(Path::new("."));
+        flags.extend(only.map(|pos| include_flag(file, cwd, pos)));
+        let mut cmd = Command::new(sibling_exe("flux")?);
+        cmd.args(["--error-format=json", "--crate-type=lib", "--edition=2021"])
+            .args(flags)
+            .arg(file);
+        cmd.current_dir(cwd);
+        run(cmd, Stdio::null(), Stdio::piped(), |line| serde_json::from_str(line).ok())?
+            .into_iter()
+            .map(|mut diag| {
+                diag.resolve_paths(cwd);
+                diag
+            })
+            .collect()
+    };
+
+    let mut by_file = Diagnostics::new();
+    for diag in diagnostics {
+        if !diag.is_relevant() {
+            continue;
+        }
+        if let Some(span) = diag.primary_span() {
+            by_file
+                .entry(span.file_name.clone())
+                .or_default()
+                .push(diag);
+        }
+    }
+    Ok(Output { diagnostics: by_file, trace_file: log_dir.join("checker") })
+}
+
+/// The flag restricting checking to the function whose body contains `pos`. The driver matches the
+/// file against the path of items relative to the directory it runs in, i.e., `cwd`.
+fn include_flag(file: &Path, cwd: &Path, pos: Pos) -> String {
+    let file = file.strip_prefix(cwd).unwrap_or(file);
+    format!("-Finclude=span:{}:{}:{}", file.display(), pos.line, pos.col)
+}
+
+/// Runs `cmd` parsing each line printed to either stdout or stderr (whichever is piped) with
+/// `parse`.
+fn run(
+    mut cmd: Command,
+    stdout: Stdio,
+    stderr: Stdio,
+    parse: impl Fn(&str) -> Option<Diagnostic>,
+) -> Result<Vec<Diagnostic>> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(stdout)
+        .stderr(stderr)
+        .spawn()?;
+    let output: Box<dyn std::io::Read> = match (child.stdout.take(), child.stderr.take()) {
+        (Some(stdout), _) => Box::new(stdout),
+        (_, Some(stderr)) => Box::new(stderr),
+        _ => return Err(anyhow!("no output to read diagnostics from")),
+    };
+    let mut diagnostics = vec![];
+    for line in BufReader::new(output).lines() {
+        if let Some(diag) = parse(&line?) {
+            diagnostics.push(diag);
+        }
+    }
+    child.wait()?;
+    Ok(diagnostics)
+}
+
+/// Returns the manifest of the closest enclosing cargo package.
+fn find_manifest(file: &Path) -> Option<PathBuf> {
+    file.ancestors()
+        .skip(1)
+        .map(|dir| dir.join("Cargo.toml"))
+        .find(|manifest| manifest.is_file())
+}
+
+/// Returns the path of an executable installed next to the current one, e.g., `cargo-flux`
+fn sibling_exe(name: &str) -> Result<PathBuf> {
+    let mut path = env::current_exe()?.with_file_name(name);
+    if cfg!(target_os = "windows") {
+        path.set_extension("exe");
+    }
+    if !path.is_file() {
+        return Err(anyhow!("path to {name} {:?} does not exist or is not a file", path));
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn include_flags() {
+        let pos = Pos { line: 12, col: 5 };
+        assert_eq!(
+            include_flag(Path::new("/ws/pkg/src/lib.rs"), Path::new("/ws"), pos),
+            "-Finclude=span:pkg/src/lib.rs:12:5"
+        );
+        assert_eq!(
+            include_flag(Path::new("/tmp/test.rs"), Path::new("/tmp"), pos),
+            "-Finclude=span:test.rs:12:5"
+        );
+    }
+}
@@ -0,0 +1,579 @@
+//! A language server reporting flux diagnostics and refined types.
+//!
+//! The server checks a file when it's opened or saved and publishes the errors reported by flux.
+//! Checking is done in a worker thread so the server stays responsive while flux runs. Requests to
+//! check a file arriving while a check is in progress are coalesced and processed when it finishes.
+//! Opening a file checks the package it belongs to. Saving a file checks only the function whose
+//! body contains the edit if there's one, and the package otherwise.
+//!
+//! Besides diagnostics, the server supports:
+//! * Hover: shows the refined type of the local under the cursor at that point in the program, as
+//!   computed by the refinement checker.
+//! * Go to definition: jumps from a call to the spec of the callee (which may be an extern spec or
+//!   a detached spec) and from an item to its detached spec.
+mod check;
+mod text;
+mod trace;
+
+use std::{
+    collections::HashMap,
+    fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex, mpsc},
+    thread,
+};
+
+use anyhow::Result;
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::{
+    DiagnosticRelatedInformation, DiagnosticSeverity, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, Location, MarkupContent, MarkupKind, NumberOrString,
+    OneOf, Position, PublishDiagnosticsParams, Range, SaveOptions, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, Uri,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{GotoDefinition, HoverRequest, Request as _},
+};
+use text::{LineEdit, Sources};
+use trace::Trace;
+
+use crate::diagnostics;
+
+/// Runs the server communicating with the client through stdin and stdout.
+pub fn run() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+            open_close: Some(true),
+            change: Some(TextDocumentSyncKind::FULL),
+            save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                include_text: Some(false),
+            })),
+            ..Default::default()
+        })),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+    let worker = Server::new(&connection)?.main_loop()?;
+    // The worker holds a handle to the connection, so we must wait for it to finish before
+    // closing the connection.
+    let _ = worker.join();
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+struct Server<'a> {
+    connection: &'a Connection,
+    /// The text of open documents, used to find the word under the cursor on hover.
+    documents: HashMap<PathBuf, String>,
+    trace: Arc<Mutex<Trace>>,
+    worker: mpsc::Sender<PathBuf>,
+    worker_thread: thread::JoinHandle<()>,
+}
+
+impl<'a> Server<'a> {
+    fn new(connection: &'a Connection) -> Result<Self> {
+        let trace = Arc::new(Mutex::new(Trace::default()));
+        let (worker, receiver) = mpsc::channel();
+        let log_dir = tempfile::tempdir()?;
+        let sender = connection.sender.clone();
+        let worker_trace = Arc::clone(&trace);
+        let worker_thread = thread::spawn(move || {
+            Worker {
+                sender,
+                trace: worker_trace,
+                checked: HashMap::new(),
+                published: HashMap::new(),
+            }
+            .run(receiver, log_dir.path());
+        });
+        Ok(Self { connection, documents: HashMap::new(), trace, worker, worker_thread })
+    }
+
+    /// Handles messages until the client requests a shutdown. Returns the worker thread, which
+    /// finishes after processing pending checks.
+    fn main_loop(mut self) -> Result<thread::JoinHandle<()>> {
+        while let Ok(msg) = self.connection.receiver.recv() {
+            match msg {
+                Message::Request(req) => {
+                    if self.connection.handle_shutdown(&req)? {
+                        break;
+                    }
+                    self.handle_request(req)?;
+                }
+                Message::Notification(not) => self.handle_notification(not)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(self.worker_thread)
+    }
+
+    fn handle_request(&mut self, req: Request) -> Result<()> {
+        let id = req.id.clone();
+        let result = match req.method.as_str() {
+            HoverRequest::METHOD => {
+                let (_, params) = req.extract::<HoverParams>(HoverRequest::METHOD)?;
+                serde_json::to_value(self.hover(params))?
+            }
+            GotoDefinition::METHOD => {
+                let (_, params) = req.extract::<GotoDefinitionParams>(GotoDefinition::METHOD)?;
+                serde_json::to_value(self.definition(params))?
+            }
+            method => {
+                return self.respond(Response::new_err(
+                    id,
+                    lsp_server::ErrorCode::MethodNotFound as i32,
+                    format!("unsupported request `{method}`"),
+                ));
+            }
+        };
+        self.respond(Response::new_ok(id, result))
+    }
+
+    fn respond(&self, response: Response) -> Result<()> {
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    fn handle_notification(&mut self, not: Notification) -> Result<()> {
+        match not.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = serde_json::from_value(not.params)?;
+                if let Some(path) = uri_to_path(&params.text_document.uri) {
+                    self.documents
+                        .insert(path.clone(), params.text_document.text);
+                    self.worker.send(path)?;
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams =
+                    serde_json::from_value(not.params)?;
+                if let Some(path) = uri_to_path(&params.text_document.uri)
+                    && let Some(change) = params.content_changes.into_iter().last()
+                {
+                    self.documents.insert(path, change.text);
+                }
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: DidSaveTextDocumentParams = serde_json::from_value(not.params)?;
+                if let Some(path) = uri_to_path(&params.text_document.uri) {
+                    self.worker.send(path)?;
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams =
+                    serde_json::from_value(not.params)?;
+                if let Some(path) = uri_to_path(&params.text_document.uri) {
+                    self.documents.remove(&path);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let doc = params.text_document_position_params;
+        let path = uri_to_path(&doc.text_document.uri)?;
+        let text = self.documents.get(&path)?;
+        let (word, range) = word_at(text, doc.position)?;
+        let pos = text::from_lsp(text, doc.position);
+        let ty = self
+            .trace
+            .lock()
+            .unwrap()
+            .type_of_local(&path, pos, &word)?;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```rust\n{ty}\n```"),
+            }),
+            range: Some(range),
+        })
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let doc = params.text_document_position_params;
+        let path = uri_to_path(&doc.text_document.uri)?;
+        let pos = text::from_lsp(self.documents.get(&path)?, doc.position);
+        let trace = self.trace.lock().unwrap();
+        let dst = &trace.definition(&path, pos)?.dst;
+        let file = dst.file.as_deref()?;
+        // Spans refer to the file as saved, so we use the text on disk.
+        let range = Sources::default().range(
+            file,
+            (dst.start_line, dst.start_col),
+            (dst.end_line, dst.end_col),
+        );
+        Some(GotoDefinitionResponse::Scalar(Location { uri: path_to_uri(file)?, range }))
+    }
+}
+
+/// LSP diagnostics grouped by file.
+type Published = HashMap<PathBuf, Vec<lsp_types::Diagnostic>>;
+
+/// Checks files and publishes the diagnostics.
+struct Worker {
+    sender: crossbeam_channel::Sender<Message>,
+    trace: Arc<Mutex<Trace>>,
+    /// The text of files when we last checked them, used to find what changed when a file is saved.
+    checked: HashMap<PathBuf, String>,
+    /// The diagnostics we have published in previous checks. We need to clear the diagnostics of
+    /// files that no longer have errors, and to keep the diagnostics of functions that were not
+    /// checked again.
+    published: Published,
+}
+
+impl Worker {
+    fn run(mut self, receiver: mpsc::Receiver<PathBuf>, log_dir: &Path) {
+        while let Ok(file) = receiver.recv() {
+            // Coalesce pending requests, checking each file once.
+            let mut files = vec![file];
+            for file in receiver.try_iter() {
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+            for file in files {
+                if let Err(err) = self.check(&file, log_dir) {
+                    self.log(format!("failed to check `{}`: {err}", file.display()));
+                }
+            }
+        }
+    }
+
+    fn check(&mut self, file: &Path, log_dir: &Path) -> Result<()> {
+        let text = fs::read_to_string(file)?;
+        let edit = self
+            .checked
+            .get(file)
+            .and_then(|old| LineEdit::diff(old, &text));
+        // If the edit is inside the body of a function we only check that function. The body is
+        // given by its first position and its last line after the edit.
+        let body = {
+            let mut trace = self.trace.lock().unwrap();
+            let body = edit.and_then(|edit| {
+                let body = trace.enclosing_item(file, &edit)?;
+                Some((edit, body.start(), edit.map_line(body.end_line)?))
+            });
+            if let Some(edit) = &edit {
+                trace.remap(file, edit);
+            }
+            body
+        };
+        let output = check::check(file, log_dir, body.map(|(_, start, _)| start))?;
+        self.trace.lock().unwrap().update(&output.trace_file)?;
+
+        let mut sources = Sources::default();
+        sources.insert(file.to_path_buf(), text.clone());
+        self.checked.insert(file.to_path_buf(), text);
+        let mut diagnostics: Published = output
+            .diagnostics
+            .iter()
+            .map(|(file, diagnostics)| {
+                let diagnostics = diagnostics
+                    .iter()
+                    .filter_map(|diag| to_lsp_diagnostic(diag, &mut sources))
+                    .collect();
+                (file.clone(), diagnostics)
+            })
+            .collect();
+        if let Some((edit, start, end)) = body {
+            merge_diagnostics(&self.published, &mut diagnostics, file, &edit, start.line..=end);
+        }
+
+        // Always publish for the checked file so the client knows the check finished.
+        diagnostics.entry(file.to_path_buf()).or_default();
+        for (file, diagnostics) in &diagnostics {
+            let Some(uri) = path_to_uri(file) else { continue };
+            self.publish(uri, diagnostics.clone())?;
+        }
+        for file in self.published.keys() {
+            if !diagnostics.contains_key(file)
+                && let Some(uri) = path_to_uri(file)
+            {
+                self.publish(uri, vec![])?;
+            }
+        }
+        diagnostics.retain(|_, diagnostics| !diagnostics.is_empty());
+        self.published = diagnostics;
+        Ok(())
+    }
+
+    fn publish(&self, uri: Uri, diagnostics: Vec<lsp_types::Diagnostic>) -> Result<()> {
+        let params = PublishDiagnosticsParams { uri, diagnostics, version: None };
+        self.sender.send(Message::Notification(Notification::new(
+            PublishDiagnostics::METHOD.to_string(),
+            params,
+        )))?;
+        Ok(())
+    }
+
+    fn log(&self, message: String) {
+        let params = lsp_types::LogMessageParams { typ: lsp_types::MessageType::ERROR, message };
+        let _ = self.sender.send(Message::Notification(Notification::new(
+            lsp_types::notification::LogMessage::METHOD.to_string(),
+            params,
+        )));
+    }
+}
+
+/// Adds to the diagnostics of a check of the function whose body spans `body` in `file` the
+/// diagnostics published before for the rest of the package, moved to account for `edit`.
+/// Diagnostics not specific to a function (e.g., for an invalid spec) are reported again by the
+/// check, so we skip duplicates.
+fn merge_diagnostics(
+    published: &Published,
+    diagnostics: &mut Published,
+    file: &Path,
+    edit: &LineEdit,
+    body: RangeInclusive<usize>,
+) {
+    let uri = path_to_uri(file);
+    for (path, old) in published {
+        let new = diagnostics.entry(path.clone()).or_default();
+        let kept = old
+            .iter()
+            .filter_map(|diag| {
+                if path != file {
+                    return Some(diag.clone());
+                }
+                let range = remap_range(diag.range, edit)?;
+                if body.contains(&(range.start.line as usize + 1)) {
+                    return None;
+                }
+                let related_information = diag.related_information.as_ref().map(|related| {
+                    related
+                        .iter()
+                        .filter_map(|info| {
+                            if Some(&info.location.uri) != uri.as_ref() {
+                                return Some(info.clone());
+                            }
+                            let mut info = info.clone();
+                            info.location.range = remap_range(info.location.range, edit)?;
+                            Some(info)
+                        })
+                        .collect()
+                });
+                Some(lsp_types::Diagnostic { range, related_information, ..diag.clone() })
+            })
+            .filter(|diag| !new.contains(diag))
+            .collect::<Vec<_>>();
+        new.extend(kept);
+    }
+}
+
+/// Moves a range to account for `edit`, or returns [`None`] if it overlaps the edited lines.
+fn remap_range(range: Range, edit: &LineEdit) -> Option<Range> {
+    let start = edit.map_line(range.start.line as usize + 1)? - 1;
+    let end = edit.map_line(range.end.line as usize + 1)? - 1;
+    if end - start != (range.end.line - range.start.line) as usize {
+        return None;
+    }
+    Some(Range {
+        start: Position { line: start as u32, ..range.start },
+        end: Position { line: end as u32, ..range.end },
+    })
+}
+
+fn to_lsp_diagnostic(
+    diag: &diagnostics::Diagnostic,
+    sources: &mut Sources,
+) -> Option<lsp_types::Diagnostic> {
+    let span = diag.primary_span()?;
+    let mut message = diag.message.clone();
+    if let Some(label) = &span.label {
+        message = format!("{message}\n{label}");
+    }
+    // Secondary spans are related to the diagnostic through their label and the spans of
+    // subdiagnostics (e.g., the constraint that cannot be proven) through the subdiagnostic message.
+    let secondary = diag
+        .spans
+        .iter()
+        .filter(|span| !span.is_primary)
+        .filter_map(|span| Some((span, span.label.clone()?)));
+    let children = diag
+        .children
+        .iter()
+        .flat_map(|child| child.spans.iter().map(|span| (span, child.message.clone())));
+    let related_information = secondary
+        .chain(children)
+        .filter_map(|(span, message)| {
+            let uri = path_to_uri(&span.file_name)?;
+            Some(DiagnosticRelatedInformation {
+                location: Location { uri, range: diagnostic_span_to_range(span, sources) },
+                message,
+            })
+        })
+        .collect::<Vec<_>>();
+    Some(lsp_types::Diagnostic {
+        range: diagnostic_span_to_range(span, sources),
+        severity: Some(if diag.level.starts_with("error") {
+            DiagnosticSeverity::ERROR
+        } else {
+            DiagnosticSeverity::WARNING
+        }),
+        code: diag
+            .code
+            .as_ref()
+            .map(|code| NumberOrString::String(code.code.clone())),
+        source: Some("flux".to_string()),
+        message,
+        related_information: (!related_information.is_empty()).then_some(related_information),
+        ..Default::default()
+    })
+}
+
+fn diagnostic_span_to_range(span: &diagnostics::DiagnosticSpan, sources: &mut Sources) -> Range {
+    sources.range(
+        &span.file_name,
+        (span.line_start, span.column_start),
+        (span.line_end, span.column_end),
+    )
+}
+
+/// Returns the identifier at `pos` in `text` together with its range.
+fn word_at(text: &str, pos: Position) -> Option<(String, Range)> {
+    let line_text = text.lines().nth(pos.line as usize)?;
+    let line: Vec<char> = line_text.chars().collect();
+    let is_ident = |c: &char| c.is_alphanumeric() || *c == '_';
+    let col = text::utf16_to_char(line_text, pos.character);
+    if !line.get(col).is_some_and(is_ident) {
+        return None;
+    }
+    let start = line[..col]
+        .iter()
+        .rposition(|c| !is_ident(c))
+        .map_or(0, |i| i + 1);
+    let end = line[col..]
+        .iter()
+        .position(|c| !is_ident(c))
+        .map_or(line.len(), |i| col + i);
+    let word = line[start..end].iter().collect();
+    let range = Range {
+        start: Position { line: pos.line, character: text::char_to_utf16(line_text, start) },
+        end: Position { line: pos.line, character: text::char_to_utf16(line_text, end) },
+    };
+    Some((word, range))
+}
+
+fn uri_to_path(uri: &Uri) -> Option<PathBuf> {
+    let path = uri.as_str().strip_prefix("file://")?;
+    let mut bytes = vec![];
+    let mut iter = path.bytes();
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(bytes).ok()?))
+}
+
+fn path_to_uri(path: &Path) -> Option<Uri> {
+    let mut uri = String::from("file://");
+    for b in path.to_str()?.bytes() {
+        if b.is_ascii_alphanumeric() || b"/-._~".contains(&b) {
+            uri.push(b as char);
+        } else {
+            uri.push_str(&format!("%{b:02X}"));
+        }
+    }
+    Uri::from_str(&uri).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(message: &str, line: u32) -> lsp_types::Diagnostic {
+        let range =
+            Range { start: Position { line, character: 4 }, end: Position { line, character: 8 } };
+        lsp_types::Diagnostic { range, message: message.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn word_at_counts_utf16() {
+        let text = "fn f() {\n    let 𝔽 = \"é\"; let x_1 = 0;\n}\n";
+        // `x_1` starts after `𝔽`, which takes two UTF-16 code units but a single char.
+        let (word, range) = word_at(text, Position { line: 1, character: 23 }).unwrap();
+        assert_eq!(word, "x_1");
+        assert_eq!(range.start, Position { line: 1, character: 22 });
+        assert_eq!(range.end, Position { line: 1, character: 25 });
+        assert_eq!(text::from_lsp(text, range.start), trace::Pos { line: 2, col: 22 });
+
+        let (word, _) = word_at(text, Position { line: 1, character: 8 }).unwrap();
+        assert_eq!(word, "𝔽");
+        assert!(word_at(text, Position { line: 1, character: 10 }).is_none());
+        assert!(word_at(text, Position { line: 5, character: 0 }).is_none());
+    }
+
+    #[test]
+    fn uri_round_trip() {
+        let path = PathBuf::from("/home/user/my crate/src/lib.rs");
+        let uri = path_to_uri(&path).unwrap();
+        assert_eq!(uri.as_str(), "file:///home/user/my%20crate/src/lib.rs");
+        assert_eq!(uri_to_path(&uri), Some(path));
+        assert_eq!(uri_to_path(&Uri::from_str("untitled:Untitled-1").unwrap()), None);
+    }
+
+    #[test]
+    fn ranges_move_with_edits() {
+        // Two lines inserted before line 5
+        let edit = LineEdit { start: 5, old_end: 5, new_end: 7 };
+        let range = |start, end| {
+            Range {
+                start: Position { line: start, character: 1 },
+                end: Position { line: end, character: 2 },
+            }
+        };
+        assert_eq!(remap_range(range(1, 2), &edit), Some(range(1, 2)));
+        assert_eq!(remap_range(range(4, 5), &edit), Some(range(6, 7)));
+        // The range surrounds the edit
+        assert_eq!(remap_range(range(3, 4), &edit), None);
+    }
+
+    #[test]
+    fn merge_keeps_diagnostics_of_unchecked_functions() {
+        let file = PathBuf::from("/ws/src/lib.rs");
+        let other = PathBuf::from("/ws/src/other.rs");
+        let published = Published::from([
+            (
+                file.clone(),
+                vec![
+                    diagnostic("before", 1),
+                    diagnostic("checked", 11),
+                    diagnostic("after", 20),
+                    diagnostic("spec", 30),
+                ],
+            ),
+            (other.clone(), vec![diagnostic("other", 3)]),
+        ]);
+        // A line was added to the body of a function spanning lines 10 to 15, which now ends at
+        // line 16 and has a new error. The error for the spec is reported again.
+        let edit = LineEdit { start: 12, old_end: 12, new_end: 13 };
+        let mut diagnostics =
+            Published::from([(file.clone(), vec![diagnostic("new", 12), diagnostic("spec", 31)])]);
+        merge_diagnostics(&published, &mut diagnostics, &file, &edit, 10..=16);
+
+        let messages = |file| {
+            diagnostics[file]
+                .iter()
+                .map(|diag| (diag.message.as_str(), diag.range.start.line))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(messages(&file), [("new", 12), ("spec", 31), ("before", 1), ("after", 21)]);
+        assert_eq!(messages(&other), [("other", 3)]);
+    }
+}
@@ -0,0 +1,184 @@
+//! Positions in documents and how they move when a document is edited.
+//!
+//! Flux reports 1-based lines and columns counting chars, while LSP positions are 0-based and count
+//! columns in UTF-16 code units, so converting between them requires the text of the line.
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use lsp_types::{Position, Range};
+
+use super::trace::Pos;
+
+/// Converts an LSP position in `text` into a flux position.
+pub(crate) fn from_lsp(text: &str, position: Position) -> Pos {
+    let line = text.lines().nth(position.line as usize).unwrap_or("");
+    Pos { line: position.line as usize + 1, col: utf16_to_char(line, position.character) + 1 }
+}
+
+/// Returns the index of the char at `character` UTF-16 code units from the start of `line`.
+pub(crate) fn utf16_to_char(line: &str, character: u32) -> usize {
+    let mut units = 0;
+    for (i, c) in line.chars().enumerate() {
+        if units >= character as usize {
+            return i;
+        }
+        units += c.len_utf16();
+    }
+    line.chars().count()
+}
+
+/// Returns the number of UTF-16 code units in the first `col` chars of `line`.
+pub(crate) fn char_to_utf16(line: &str, col: usize) -> u32 {
+    line.chars().take(col).map(char::len_utf16).sum::<usize>() as u32
+}
+
+/// The text of the files we report positions in. Files are read from disk the first time they
+/// are needed, unless their text is provided with [`Sources::insert`].
+#[derive(Default)]
+pub(crate) struct Sources {
+    texts: HashMap<PathBuf, Option<String>>,
+}
+
+impl Sources {
+    pub(crate) fn insert(&mut self, file: PathBuf, text: String) {
+        self.texts.insert(file, Some(text));
+    }
+
+    /// Converts a 1-based line and column in `file` into an LSP position. If we cannot read the
+    /// file we assume the line is ASCII.
+    pub(crate) fn position(&mut self, file: &Path, line: usize, col: usize) -> Position {
+        let col = col.saturating_sub(1);
+        let line = line.saturating_sub(1);
+        let text = self
+            .texts
+            .entry(file.to_path_buf())
+            .or_insert_with(|| fs::read_to_string(file).ok());
+        let character = match text.as_deref().and_then(|text| text.lines().nth(line)) {
+            Some(text) => char_to_utf16(text, col),
+            None => col as u32,
+        };
+        Position { line: line as u32, character }
+    }
+
+    pub(crate) fn range(
+        &mut self,
+        file: &Path,
+        start: (usize, usize),
+        end: (usize, usize),
+    ) -> Range {
+        Range {
+            start: self.position(file, start.0, start.1),
+            end: self.position(file, end.0, end.1),
+        }
+    }
+}
+
+/// The lines that changed between two versions of a document: lines `start..old_end` of the old
+/// version were replaced by lines `start..new_end` of the new one. Lines are 1-based.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct LineEdit {
+    pub start: usize,
+    pub old_end: usize,
+    pub new_end: usize,
+}
+
+impl LineEdit {
+    /// Returns the smallest block of lines whose replacement turns `old` into `new`, or [`None`] if
+    /// they have the same lines.
+    pub(crate) fn diff(old: &str, new: &str) -> Option<LineEdit> {
+        let old: Vec<&str> = old.lines().collect();
+        let new: Vec<&str> = new.lines().collect();
+        if old == new {
+            return None;
+        }
+        let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        Some(LineEdit {
+            start: prefix + 1,
+            old_end: old.len() - suffix + 1,
+            new_end: new.len() - suffix + 1,
+        })
+    }
+
+    /// Returns the line in the new version corresponding to `line` in the old one, or [`None`] if
+    /// the line was edited.
+    pub(crate) fn map_line(&self, line: usize) -> Option<usize> {
+        if line < self.start {
+            Some(line)
+        } else if line >= self.old_end {
+            Some(line - self.old_end + self.new_end)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the edit happened strictly inside the lines `start..=end` of the old version, i.e.,
+    /// neither the first nor the last of these lines changed.
+    pub(crate) fn is_inside(&self, start: usize, end: usize) -> bool {
+        start < self.start && self.old_end <= end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn utf16_columns() {
+        let line = "let é = \"𝔽\"; x";
+        assert_eq!(utf16_to_char(line, 4), 4);
+        assert_eq!(utf16_to_char(line, 9), 9);
+        // `𝔽` takes two code units
+        assert_eq!(utf16_to_char(line, 14), 13);
+        assert_eq!(char_to_utf16(line, 13), 14);
+        assert_eq!(utf16_to_char(line, 100), line.chars().count());
+        for col in 0..line.chars().count() {
+            assert_eq!(utf16_to_char(line, char_to_utf16(line, col)), col);
+        }
+    }
+
+    #[test]
+    fn lsp_positions() {
+        let text = "fn 𝔽() {}\nfn f() {}\n";
+        assert_eq!(from_lsp(text, Position { line: 0, character: 5 }), Pos { line: 1, col: 5 });
+        assert_eq!(from_lsp(text, Position { line: 1, character: 3 }), Pos { line: 2, col: 4 });
+
+        let mut sources = Sources::default();
+        let file = PathBuf::from("/nonexistent/lib.rs");
+        sources.insert(file.clone(), text.to_string());
+        assert_eq!(sources.position(&file, 1, 5), Position { line: 0, character: 5 });
+        assert_eq!(sources.position(&file, 2, 4), Position { line: 1, character: 3 });
+        let other = PathBuf::from("/nonexistent/other.rs");
+        assert_eq!(sources.position(&other, 3, 7), Position { line: 2, character: 6 });
+    }
+
+    #[test]
+    fn line_edits() {
+        let old = "a\nb\nc\nd\n";
+        assert_eq!(LineEdit::diff(old, old), None);
+
+        let edit = LineEdit::diff(old, "a\nb\nx\ny\nc\nd\n").unwrap();
+        assert_eq!(edit, LineEdit { start: 3, old_end: 3, new_end: 5 });
+        assert_eq!(edit.map_line(2), Some(2));
+        assert_eq!(edit.map_line(3), Some(5));
+        assert!(edit.is_inside(2, 3));
+        assert!(!edit.is_inside(3, 4));
+
+        let edit = LineEdit::diff(old, "a\nx\nd\n").unwrap();
+        assert_eq!(edit, LineEdit { start: 2, old_end: 4, new_end: 3 });
+        assert_eq!(edit.map_line(1), Some(1));
+        assert_eq!(edit.map_line(2), None);
+        assert_eq!(edit.map_line(3), None);
+        assert_eq!(edit.map_line(4), Some(3));
+        assert!(edit.is_inside(1, 4));
+        assert!(!edit.is_inside(1, 3));
+    }
+}
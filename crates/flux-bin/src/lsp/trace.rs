@@ -0,0 +1,469 @@
+//! Information about checked items read from the checker trace produced by a run of flux with
+//! `-Fdump-checker-trace=info`.
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use super::text::LineEdit;
+
+/// A position in a file with 1-based line and column as reported by flux.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub(crate) struct Pos {
+    pub line: usize,
+    pub col: usize,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub(crate) struct SpanTrace {
+    pub file: Option<PathBuf>,
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+impl SpanTrace {
+    pub(crate) fn start(&self) -> Pos {
+        Pos { line: self.start_line, col: self.start_col }
+    }
+
+    pub(crate) fn end(&self) -> Pos {
+        Pos { line: self.end_line, col: self.end_col }
+    }
+
+    fn is_in(&self, file: &Path) -> bool {
+        self.file.as_deref() == Some(file)
+    }
+
+    fn contains(&self, file: &Path, pos: Pos) -> bool {
+        self.is_in(file) && self.start() <= pos && pos <= self.end()
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.end_line - self.start_line, self.end_col.abs_diff(self.start_col))
+    }
+
+    /// Returns the span after `edit` was applied to `file`, or [`None`] if the span overlaps the
+    /// edited lines.
+    fn remap(&self, file: &Path, edit: &LineEdit) -> Option<SpanTrace> {
+        if !self.is_in(file) {
+            return Some(self.clone());
+        }
+        let start_line = edit.map_line(self.start_line)?;
+        let end_line = edit.map_line(self.end_line)?;
+        // The span surrounds the edit
+        if end_line - start_line != self.end_line - self.start_line {
+            return None;
+        }
+        Some(SpanTrace { start_line, end_line, ..self.clone() })
+    }
+}
+
+/// The type environment after checking a statement in refinement mode.
+#[derive(Clone, Debug)]
+struct Statement {
+    span: SpanTrace,
+    env: Vec<Binding>,
+}
+
+#[derive(Clone, Debug)]
+struct Binding {
+    name: String,
+    ty: String,
+    span: Option<SpanTrace>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Hyperlink {
+    src: SpanTrace,
+    pub dst: SpanTrace,
+}
+
+#[derive(Default)]
+pub(crate) struct Trace {
+    /// The span of the body of every function checked, or skipped because of the cache.
+    items: HashMap<String, SpanTrace>,
+    /// Statements grouped by the item being checked when they were traced. Items whose checking is
+    /// skipped because they didn't change (see `-Fcache`) are not traced again, so we keep the
+    /// statements from previous runs around and [remap](Trace::remap) them when a file is edited.
+    statements: HashMap<String, Vec<Statement>>,
+    /// Hyperlinks traced while checking an item, grouped like statements.
+    item_hyperlinks: HashMap<String, Vec<Hyperlink>>,
+    /// Hyperlinks traced outside of checking an item, e.g., when collecting specs.
+    hyperlinks: Vec<Hyperlink>,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    fields: Fields,
+    span: Option<EventSpan>,
+}
+
+#[derive(Deserialize)]
+struct Fields {
+    event: Option<String>,
+    def_id: Option<String>,
+    item_span: Option<String>,
+    env_json: Option<String>,
+    stmt_span_json: Option<String>,
+    src_span: Option<String>,
+    dst_span: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EventSpan {
+    def_id: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TypeEnvBind {
+    name: Option<String>,
+    ty: String,
+    span: Option<SpanTrace>,
+}
+
+#[derive(Deserialize)]
+struct NestedString {
+    text: String,
+}
+
+impl Trace {
+    /// Updates the information with the trace file produced by a run of flux.
+    pub(crate) fn update(&mut self, trace_file: &Path) -> io::Result<()> {
+        let mut items = HashMap::new();
+        let mut statements: HashMap<String, Vec<Statement>> = HashMap::new();
+        let mut item_hyperlinks: HashMap<String, Vec<Hyperlink>> = HashMap::new();
+        let mut hyperlinks = vec![];
+
+        if let Ok(file) = fs::File::open(trace_file) {
+            for line in BufReader::new(file).lines() {
+                let Ok(event) = serde_json::from_str::<Event>(&line?) else { continue };
+                let def_id = event.span.as_ref().and_then(|span| span.def_id.clone());
+                match event.fields.event.as_deref() {
+                    Some("item_span") => {
+                        let (Some(def_id), Some(span)) = (
+                            event.fields.def_id.clone(),
+                            event
+                                .fields
+                                .item_span
+                                .as_deref()
+                                .and_then(|span| serde_json::from_str(span).ok()),
+                        ) else {
+                            continue;
+                        };
+                        items.insert(def_id, span);
+                    }
+                    Some("statement_end")
+                        if event.span.as_ref().and_then(|span| span.name.as_deref())
+                            == Some("refine") =>
+                    {
+                        let (Some(def_id), Some(statement)) = (def_id, parse_statement(&event))
+                        else {
+                            continue;
+                        };
+                        statements.entry(def_id).or_default().push(statement);
+                    }
+                    Some("hyperlink") => {
+                        let Some(hyperlink) = parse_hyperlink(&event) else { continue };
+                        match def_id {
+                            Some(def_id) => {
+                                item_hyperlinks.entry(def_id).or_default().push(hyperlink);
+                            }
+                            None => hyperlinks.push(hyperlink),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            self.hyperlinks = hyperlinks;
+        }
+        self.items.extend(items);
+        self.statements.extend(statements);
+        self.item_hyperlinks.extend(item_hyperlinks);
+        Ok(())
+    }
+
+    /// Moves the spans in `file` to account for `edit`. Spans overlapping the edited lines are
+    /// dropped because we cannot tell where they are now, they are traced again if their item
+    /// changed.
+    pub(crate) fn remap(&mut self, file: &Path, edit: &LineEdit) {
+        self.items
+            .retain(|_, span| span.remap(file, edit).map(|new| *span = new).is_some());
+        for stmts in self.statements.values_mut() {
+            stmts.retain_mut(|stmt| {
+                let Some(span) = stmt.span.remap(file, edit) else { return false };
+                stmt.span = span;
+                for bind in &mut stmt.env {
+                    bind.span = bind.span.as_ref().and_then(|span| span.remap(file, edit));
+                }
+                true
+            });
+        }
+        self.statements.retain(|_, stmts| !stmts.is_empty());
+        for links in self
+            .item_hyperlinks
+            .values_mut()
+            .chain(std::iter::once(&mut self.hyperlinks))
+        {
+            links.retain_mut(|link| {
+                let (Some(src), Some(dst)) =
+                    (link.src.remap(file, edit), link.dst.remap(file, edit))
+                else {
+                    return false;
+                };
+                *link = Hyperlink { src, dst };
+                true
+            });
+        }
+    }
+
+    /// Returns the span of the body of the innermost function containing `edit` to `file`, if the
+    /// edit is strictly inside the body, i.e., it didn't change the lines with the opening or
+    /// closing braces. Such an edit cannot change the signature of the function, so it's enough to
+    /// check the function again.
+    pub(crate) fn enclosing_item(&self, file: &Path, edit: &LineEdit) -> Option<&SpanTrace> {
+        self.items
+            .values()
+            .filter(|span| span.is_in(file) && edit.is_inside(span.start_line, span.end_line))
+            .min_by_key(|span| span.size())
+    }
+
+    /// Returns the refined type of the local named `name` at `pos`, i.e., its type after the last
+    /// statement checked before `pos` in the item containing `pos`.
+    pub(crate) fn type_of_local(&self, file: &Path, pos: Pos, name: &str) -> Option<String> {
+        // The item containing `pos` is the one with the smallest extent covering it, where the
+        // extent of an item spans all its statements and the declarations of its locals.
+        let statements = self
+            .statements
+            .values()
+            .filter_map(|stmts| Some((extent(stmts)?, stmts)))
+            .filter(|(extent, _)| extent.contains(file, pos))
+            .min_by_key(|(extent, _)| extent.size())?
+            .1;
+        // Take the last statement ending before `pos`, or the first one if there's none (e.g.,
+        // when hovering over a parameter).
+        let mut before = None;
+        let mut first: Option<&Statement> = None;
+        for stmt in statements {
+            if stmt.span.end() <= pos
+                && before.is_none_or(|b: &Statement| b.span.end() <= stmt.span.end())
+            {
+                before = Some(stmt);
+            }
+            if first.is_none_or(|f| stmt.span.start() < f.span.start()) {
+                first = Some(stmt);
+            }
+        }
+        let stmt = before.or(first)?;
+        stmt.env
+            .iter()
+            .rev()
+            .find(|bind| bind.name == name)
+            .or_else(|| {
+                stmt.env.iter().find(|bind| {
+                    bind.span
+                        .as_ref()
+                        .is_some_and(|span| span.contains(file, pos))
+                })
+            })
+            .map(|bind| format!("{}: {}", bind.name, bind.ty))
+    }
+
+    /// Returns the target of the innermost hyperlink containing `pos`.
+    pub(crate) fn definition(&self, file: &Path, pos: Pos) -> Option<&Hyperlink> {
+        self.hyperlinks
+            .iter()
+            .chain(self.item_hyperlinks.values().flatten())
+            .filter(|link| link.src.contains(file, pos) && link.dst.file.is_some())
+            .min_by_key(|link| link.src.size())
+    }
+}
+
+fn parse_statement(event: &Event) -> Option<Statement> {
+    let span = serde_json::from_str(event.fields.stmt_span_json.as_deref()?).ok()?;
+    let env: Vec<TypeEnvBind> = serde_json::from_str(event.fields.env_json.as_deref()?).ok()?;
+    let env = env
+        .into_iter()
+        .filter_map(|bind| {
+            let ty = serde_json::from_str::<NestedString>(&bind.ty).ok()?.text;
+            Some(Binding { name: bind.name?, ty, span: bind.span })
+        })
+        .collect();
+    Some(Statement { span, env })
+}
+
+fn parse_hyperlink(event: &Event) -> Option<Hyperlink> {
+    let src = serde_json::from_str(event.fields.src_span.as_deref()?).ok()?;
+    let dst = serde_json::from_str(event.fields.dst_span.as_deref()?).ok()?;
+    Some(Hyperlink { src, dst })
+}
+
+fn extent(statements: &[Statement]) -> Option<SpanTrace> {
+    let spans = statements.iter().flat_map(|stmt| {
+        std::iter::once(&stmt.span).chain(stmt.env.iter().filter_map(|bind| bind.span.as_ref()))
+    });
+    let mut extent: Option<SpanTrace> = None;
+    for span in spans {
+        match &mut extent {
+            None => extent = Some(span.clone()),
+            Some(extent) if extent.file == span.file => {
+                if span.start() < extent.start() {
+                    (extent.start_line, extent.start_col) = (span.start_line, span.start_col);
+                }
+                if span.end() > extent.end() {
+                    (extent.end_line, extent.end_col) = (span.end_line, span.end_col);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    extent
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use serde_json::json;
+
+    use super::*;
+
+    fn span(
+        file: &str,
+        start_line: usize,
+        start_col: usize,
+        end_line: usize,
+        end_col: usize,
+    ) -> String {
+        json!({
+            "file": file,
+            "start_line": start_line,
+            "start_col": start_col,
+            "end_line": end_line,
+            "end_col": end_col,
+        })
+        .to_string()
+    }
+
+    fn statement(def_id: &str, line: usize, local: &str, ty: &str) -> String {
+        let env = json!([{
+            "name": local,
+            "ty": json!({ "text": ty }).to_string(),
+            "span": serde_json::from_str::<serde_json::Value>(&span("/src/lib.rs", 2, 9, 2, 10)).unwrap(),
+        }]);
+        json!({
+            "fields": {
+                "event": "statement_end",
+                "env_json": env.to_string(),
+                "stmt_span_json": span("/src/lib.rs", line, 5, line, 20),
+            },
+            "span": { "name": "refine", "def_id": def_id },
+        })
+        .to_string()
+    }
+
+    fn trace_file(events: &[String]) -> tempfile::NamedTempFile {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        for event in events {
+            writeln!(file, "{event}").unwrap();
+        }
+        file
+    }
+
+    /// A function `f` with body in lines 1 to 5 of `/src/lib.rs` calling `g`, whose spec is in line 8.
+    fn trace() -> Trace {
+        let events = [
+            json!({
+                "fields": {
+                    "event": "item_span",
+                    "def_id": "f",
+                    "item_span": span("/src/lib.rs", 1, 12, 5, 2),
+                },
+            })
+            .to_string(),
+            statement("f", 2, "x", "i32[0]"),
+            statement("f", 4, "x", "i32[1]"),
+            json!({
+                "fields": {
+                    "event": "hyperlink",
+                    "src_span": span("/src/lib.rs", 3, 5, 3, 6),
+                    "dst_span": span("/src/lib.rs", 8, 4, 8, 5),
+                },
+                "span": { "name": "check_fn", "def_id": "f" },
+            })
+            .to_string(),
+            "not json".to_string(),
+        ];
+        let mut trace = Trace::default();
+        trace.update(trace_file(&events).path()).unwrap();
+        trace
+    }
+
+    #[test]
+    fn parse_trace() {
+        let trace = trace();
+        let file = Path::new("/src/lib.rs");
+        assert_eq!(
+            trace
+                .type_of_local(file, Pos { line: 3, col: 1 }, "x")
+                .as_deref(),
+            Some("x: i32[0]")
+        );
+        assert_eq!(
+            trace
+                .type_of_local(file, Pos { line: 4, col: 20 }, "x")
+                .as_deref(),
+            Some("x: i32[1]")
+        );
+        assert_eq!(
+            trace.type_of_local(Path::new("/src/other.rs"), Pos { line: 3, col: 1 }, "x"),
+            None
+        );
+        let link = trace.definition(file, Pos { line: 3, col: 5 }).unwrap();
+        assert_eq!(link.dst.start(), Pos { line: 8, col: 4 });
+    }
+
+    #[test]
+    fn remap_trace() {
+        let mut trace = trace();
+        let file = Path::new("/src/lib.rs");
+
+        // An edit inside the body of `f`
+        let edit = LineEdit { start: 3, old_end: 4, new_end: 6 };
+        let body = trace.enclosing_item(file, &edit).unwrap();
+        assert_eq!((body.start_line, body.end_line), (1, 5));
+        // An edit changing the line with the opening brace
+        assert!(
+            trace
+                .enclosing_item(file, &LineEdit { start: 1, old_end: 2, new_end: 2 })
+                .is_none()
+        );
+
+        trace.remap(file, &edit);
+        // The statement in line 4 moved to line 6 and the hyperlink in the edited line is gone.
+        assert_eq!(
+            trace
+                .type_of_local(file, Pos { line: 6, col: 20 }, "x")
+                .as_deref(),
+            Some("x: i32[1]")
+        );
+        assert!(trace.definition(file, Pos { line: 3, col: 5 }).is_none());
+        // The body of `f` is dropped until it's traced again.
+        assert!(trace.items.is_empty());
+
+        // Statements of items that are not traced again are kept.
+        trace.update(trace_file(&[]).path()).unwrap();
+        assert_eq!(
+            trace
+                .type_of_local(file, Pos { line: 6, col: 20 }, "x")
+                .as_deref(),
+            Some("x: i32[1]")
+        );
+    }
+}
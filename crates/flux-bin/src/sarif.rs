@@ -0,0 +1,259 @@
+//! Conversion of flux diagnostics into a [SARIF] log, the format used by code scanning tools.
+//!
+//! Each flux error code is a rule. Diagnostics without a flux error code (e.g., rustc warnings) are
+//! not included in the log.
+//!
+//! [SARIF]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+use std::{io, path::Path};
+
+use serde_json::{Value, json};
+
+use crate::{
+    diagnostics::{Diagnostic, DiagnosticSpan, ERROR_CODES},
+    utils::get_version,
+};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The base id of uris relative to the workspace root.
+const SRCROOT: &str = "%SRCROOT%";
+
+/// Writes a SARIF log with the flux diagnostics in `diagnostics`. Relative paths in the
+/// diagnostics are interpreted relative to `workspace_root`.
+pub fn write(
+    mut writer: impl io::Write,
+    workspace_root: &Path,
+    diagnostics: &[Diagnostic],
+) -> io::Result<()> {
+    let rules = ERROR_CODES
+        .iter()
+        .map(|code| {
+            json!({
+                "id": code.code,
+                "name": code.name,
+                "shortDescription": { "text": code.description },
+                "defaultConfiguration": { "level": "error" },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let results = diagnostics
+        .iter()
+        .filter_map(|diag| result(diag, workspace_root))
+        .collect::<Vec<_>>();
+
+    let log = json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "flux",
+                    "informationUri": "https://github.com/flux-rs/flux",
+                    "version": get_version(),
+                    "rules": rules,
+                }
+            },
+            "originalUriBaseIds": {
+                SRCROOT: { "uri": directory_uri(workspace_root) },
+            },
+            "results": results,
+        }]
+    });
+    serde_json::to_writer_pretty(&mut writer, &log)?;
+    writeln!(writer)
+}
+
+fn result(diag: &Diagnostic, workspace_root: &Path) -> Option<Value> {
+    let code = diag.flux_error_code()?;
+    let rule_index = ERROR_CODES.iter().position(|c| c.code == code.code)?;
+    let span = diag.primary_span()?;
+
+    // Secondary spans and the spans of subdiagnostics (e.g., the condition that cannot be proven)
+    // are reported as related locations.
+    let secondary = diag
+        .spans
+        .iter()
+        .filter(|span| !span.is_primary)
+        .filter_map(|span| Some((span, span.label.as_deref()?)));
+    let children = diag.children.iter().flat_map(|child| {
+        child
+            .spans
+            .iter()
+            .map(|span| (span, child.message.as_str()))
+    });
+    let related_locations = secondary
+        .chain(children)
+        .enumerate()
+        .map(|(id, (span, message))| {
+            let mut location = location(span, workspace_root);
+            location["id"] = json!(id);
+            location["message"] = json!({ "text": message });
+            location
+        })
+        .collect::<Vec<_>>();
+
+    let mut message = diag.message.clone();
+    if let Some(label) = &span.label {
+        message = format!("{message}: {label}");
+    }
+    Some(json!({
+        "ruleId": code.code,
+        "ruleIndex": rule_index,
+        "level": if diag.level.starts_with("error") { "error" } else { "warning" },
+        "message": { "text": message },
+        "locations": [location(span, workspace_root)],
+        "relatedLocations": related_locations,
+    }))
+}
+
+fn location(span: &DiagnosticSpan, workspace_root: &Path) -> Value {
+    let artifact_location = match span.file_name.strip_prefix(workspace_root) {
+        Ok(relative) => json!({ "uri": path_to_uri(relative), "uriBaseId": SRCROOT }),
+        Err(_) if span.file_name.is_relative() => {
+            json!({ "uri": path_to_uri(&span.file_name), "uriBaseId": SRCROOT })
+        }
+        Err(_) => json!({ "uri": format!("file://{}", path_to_uri(&span.file_name)) }),
+    };
+    json!({
+        "physicalLocation": {
+            "artifactLocation": artifact_location,
+            "region": {
+                "startLine": span.line_start,
+                "startColumn": span.column_start,
+                "endLine": span.line_end,
+                "endColumn": span.column_end,
+            }
+        }
+    })
+}
+
+fn directory_uri(dir: &Path) -> String {
+    let mut uri = format!("file://{}", path_to_uri(dir));
+    if !uri.ends_with('/') {
+        uri.push('/');
+    }
+    uri
+}
+
+/// Percent-encodes a path to be used in a uri.
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::new();
+    for component in path {
+        if !uri.is_empty() && !uri.ends_with('/') {
+            uri.push('/');
+        }
+        for b in component.to_string_lossy().bytes() {
+            if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+                uri.push(b as char);
+            } else if b == b'/' && uri.is_empty() {
+                // The root component of an absolute path
+                uri.push('/');
+            } else {
+                uri.push_str(&format!("%{b:02X}"));
+            }
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(json: Value) -> Diagnostic {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn span(file: &str, line: usize, is_primary: bool, label: Option<&str>) -> Value {
+        json!({
+            "file_name": file,
+            "line_start": line,
+            "line_end": line,
+            "column_start": 5,
+            "column_end": 10,
+            "is_primary": is_primary,
+            "label": label,
+        })
+    }
+
+    #[test]
+    fn write_log() {
+        let diagnostics = [
+            diagnostic(json!({
+                "message": "refinement type error",
+                "code": { "code": "E0990" },
+                "level": "error",
+                "spans": [
+                    span("/ws/src/lib.rs", 7, true, Some("a precondition cannot be proved")),
+                    span("/ws/src/lib.rs", 2, false, Some("this is the condition")),
+                ],
+                "children": [{
+                    "message": "inside this call",
+                    "code": null,
+                    "level": "note",
+                    "spans": [span("/ws/src/my mod.rs", 3, false, None)],
+                    "children": [],
+                }],
+            })),
+            // Diagnostics without a flux error code are skipped
+            diagnostic(json!({
+                "message": "unused variable",
+                "code": { "code": "unused_variables" },
+                "level": "warning",
+                "spans": [span("src/lib.rs", 1, true, None)],
+                "children": [],
+            })),
+            diagnostic(json!({
+                "message": "unsupported",
+                "code": { "code": "E0998" },
+                "level": "error",
+                "spans": [span("/other/lib.rs", 4, true, None)],
+                "children": [],
+            })),
+        ];
+        let mut out = vec![];
+        write(&mut out, Path::new("/ws"), &diagnostics).unwrap();
+        let log: Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["originalUriBaseIds"][SRCROOT]["uri"], "file:///ws/");
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), ERROR_CODES.len());
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+
+        let precondition = &results[0];
+        assert_eq!(precondition["ruleId"], "E0990");
+        assert_eq!(rules[precondition["ruleIndex"].as_u64().unwrap() as usize]["id"], "E0990");
+        assert_eq!(precondition["level"], "error");
+        assert_eq!(
+            precondition["message"]["text"],
+            "refinement type error: a precondition cannot be proved"
+        );
+        let location = &precondition["locations"][0]["physicalLocation"];
+        assert_eq!(
+            location["artifactLocation"],
+            json!({ "uri": "src/lib.rs", "uriBaseId": SRCROOT })
+        );
+        assert_eq!(location["region"]["startLine"], 7);
+        assert_eq!(location["region"]["startColumn"], 5);
+        let related = precondition["relatedLocations"].as_array().unwrap();
+        assert_eq!(related.len(), 2);
+        assert_eq!(related[0]["message"]["text"], "this is the condition");
+        assert_eq!(related[0]["physicalLocation"]["region"]["startLine"], 2);
+        assert_eq!(related[1]["id"], 1);
+        assert_eq!(related[1]["message"]["text"], "inside this call");
+        assert_eq!(related[1]["physicalLocation"]["artifactLocation"]["uri"], "src/my%20mod.rs");
+
+        // Files outside the workspace get an absolute uri
+        let unsupported = &results[1];
+        assert_eq!(unsupported["ruleId"], "E0998");
+        assert_eq!(
+            unsupported["locations"][0]["physicalLocation"]["artifactLocation"],
+            json!({ "uri": "file:///other/lib.rs" })
+        );
+    }
+}
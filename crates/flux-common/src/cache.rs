@@ -1,7 +1,31 @@
-use std::{fs::File, path::Path};
+//! Persistent cache of verification results enabled with `-Fcache=<path>`.
+//!
+//! The cache stores two kinds of entries:
+//! * The result of each fixpoint query, keyed by the query and validated against the hash of the
+//!   constraint sent to fixpoint.
+//! * The fingerprint of each item that was successfully verified. The fingerprint covers
+//!   everything the result of checking the item depends on (see `flux_driver::fingerprint`), so an
+//!   item whose fingerprint didn't change since the last run doesn't need to be checked again.
+//!
+//! The same cache file may be shared by several crates (e.g., `cargo flux` uses a single cache for
+//! all crates in the workspace) which can be checked concurrently. When saving, we only write the
+//! entries updated during this run, merging them with the entries currently on disk while holding
+//! a lock, and replace the file atomically.
+//!
+//! The cache records the build of flux that produced it, and entries produced by a different build
+//! are discarded because a change to flux may change the constraints generated for an item or how
+//! they are solved.
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::UNIX_EPOCH,
+};
 
 use flux_config as config;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct QueryVal<R> {
@@ -9,8 +33,44 @@ struct QueryVal<R> {
     result: R,
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(bound(serialize = "R: serde::Serialize", deserialize = "R: serde::de::DeserializeOwned"))]
+struct CacheData<R> {
+    /// The build of flux that produced the entries, see [`FLUX_BUILD`].
+    #[serde(default)]
+    build: String,
+    queries: FxHashMap<String, QueryVal<R>>,
+    items: FxHashMap<String, u64>,
+}
+
+impl<R> Default for CacheData<R> {
+    fn default() -> Self {
+        Self {
+            build: FLUX_BUILD.clone(),
+            queries: FxHashMap::default(),
+            items: FxHashMap::default(),
+        }
+    }
+}
+
+/// Identifies the build of flux running. We use the version together with the size and
+/// modification time of the driver executable, so rebuilding or reinstalling flux invalidates the
+/// cache even if the version didn't change.
+static FLUX_BUILD: LazyLock<String> = LazyLock::new(|| {
+    let exe = env::current_exe().and_then(fs::metadata).ok();
+    let len = exe.as_ref().map(|metadata| metadata.len());
+    let modified = exe
+        .and_then(|metadata| metadata.modified().ok())
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok());
+    format!("{} {len:?} {modified:?}", env!("CARGO_PKG_VERSION"))
+});
+
 pub struct QueryCache<R> {
-    entries: FxHashMap<String, QueryVal<R>>,
+    data: CacheData<R>,
+    /// Keys of the queries updated during this run
+    dirty_queries: FxHashSet<String>,
+    /// Keys of the items updated during this run
+    dirty_items: FxHashSet<String>,
 }
 
 impl<R> Default for QueryCache<R> {
@@ -21,31 +81,49 @@ impl<R> Default for QueryCache<R> {
 
 impl<R> QueryCache<R> {
     pub fn new() -> Self {
-        QueryCache { entries: FxHashMap::default() }
+        QueryCache {
+            data: CacheData::default(),
+            dirty_queries: FxHashSet::default(),
+            dirty_items: FxHashSet::default(),
+        }
     }
 
     pub fn insert(&mut self, key: String, constr_hash: u64, result: R) {
         let val = QueryVal { constr_hash, result };
-        self.entries.insert(key, val);
+        self.dirty_queries.insert(key.clone());
+        self.data.queries.insert(key, val);
     }
 
-    pub fn lookup(&self, key: &String, constr_hash: u64) -> Option<&R> {
-        let val = self.entries.get(key)?;
+    pub fn lookup(&self, key: &str, constr_hash: u64) -> Option<&R> {
+        let val = self.data.queries.get(key)?;
         if val.constr_hash == constr_hash { Some(&val.result) } else { None }
     }
 
     /// Look up a result by key only (no hash validation).
     pub fn lookup_by_key(&self, key: &str) -> Option<&R> {
-        self.entries.get(key).map(|e| &e.result)
+        self.data.queries.get(key).map(|e| &e.result)
     }
 
     /// Mutate a cached result by key only (no hash validation).
     pub fn update_result_by_key(&mut self, key: &str, f: impl FnOnce(&mut R)) {
-        if let Some(entry) = self.entries.get_mut(key) {
+        if let Some(entry) = self.data.queries.get_mut(key) {
             f(&mut entry.result);
+            self.dirty_queries.insert(key.to_string());
         }
     }
 
+    /// Records that the item identified by `key` was verified with the given `fingerprint`.
+    pub fn insert_item(&mut self, key: String, fingerprint: u64) {
+        self.dirty_items.insert(key.clone());
+        self.data.items.insert(key, fingerprint);
+    }
+
+    /// Returns `true` if the item identified by `key` was verified in a previous run with the same
+    /// `fingerprint`.
+    pub fn is_item_verified(&self, key: &str, fingerprint: u64) -> bool {
+        self.data.items.get(key) == Some(&fingerprint)
+    }
+
     fn path() -> Result<&'static Path, std::io::Error> {
         if let Some(path) = config::cache_path()
             && let Some(parent) = path.parent()
@@ -63,23 +141,69 @@ impl<R> QueryCache<R> {
 }
 
 impl<R: std::fmt::Debug + serde::Serialize + serde::de::DeserializeOwned> QueryCache<R> {
-    pub fn save(&self) -> Result<(), std::io::Error> {
+    pub fn save(&mut self) -> Result<(), std::io::Error> {
         let path = Self::path()?;
-        let mut file = File::create(path).unwrap();
-        serde_json::to_writer(&mut file, &self.entries).unwrap();
-        Ok(())
+        if self.dirty_queries.is_empty() && self.dirty_items.is_empty() {
+            return Ok(());
+        }
+
+        // The lock is held until `lock_file` is dropped at the end of the function. We lock a
+        // separate file because the cache itself is replaced when renaming.
+        let lock_file = File::create(path_with_extension(path, "lock"))?;
+        lock_file.lock()?;
+
+        let mut data = Self::read(path).unwrap_or_default();
+        for key in self.dirty_queries.drain() {
+            if let Some(val) = self.data.queries.remove(&key) {
+                data.queries.insert(key, val);
+            }
+        }
+        for key in self.dirty_items.drain() {
+            if let Some(fingerprint) = self.data.items.get(&key) {
+                data.items.insert(key, *fingerprint);
+            }
+        }
+
+        let tmp_path = path_with_extension(path, &format!("{}.tmp", std::process::id()));
+        let result = Self::write(&tmp_path, &data).and_then(|_| fs::rename(&tmp_path, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp_path);
+        }
+        self.data = data;
+        result
     }
 
     pub fn load() -> Self {
         let path = Self::path();
         if let Ok(path) = path
-            && let Ok(file) = File::open(path)
+            && let Ok(data) = Self::read(path)
         {
-            let entries = serde_json::from_reader(file);
-            if let Ok(entries) = entries {
-                return QueryCache { entries };
-            }
+            return QueryCache { data, ..Self::default() };
         }
         Self::default()
     }
+
+    /// Reads the cache in `path`, discarding its entries if they were produced by a different
+    /// build of flux.
+    fn read(path: &Path) -> io::Result<CacheData<R>> {
+        let file = File::open(path)?;
+        let data: CacheData<R> = serde_json::from_reader(BufReader::new(file))?;
+        if data.build != *FLUX_BUILD {
+            return Ok(CacheData::default());
+        }
+        Ok(data)
+    }
+
+    fn write(path: &Path, data: &CacheData<R>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, data)?;
+        writer.flush()
+    }
+}
+
+fn path_with_extension(path: &Path, ext: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(ext);
+    PathBuf::from(path)
 }
@@ -176,6 +176,19 @@ macro_rules! _shape_goto_exit {
 }
 pub use crate::_shape_goto_exit as shape_goto_exit;
 
+#[macro_export]
+macro_rules! _item_span {
+    ($tcx:expr, $def_id:expr, $span:expr) => {{
+        if config::dump_checker_trace_info() {
+          let tcx = $tcx;
+          let path = tcx.def_path_str(rustc_hir::def_id::DefId::from($def_id));
+          let span_json = SpanTrace::new(tcx, $span);
+          tracing::info!(event = "item_span", def_id = path.as_str(), item_span = ?span_json)
+        }
+    }};
+}
+pub use crate::_item_span as item_span;
+
 #[macro_export]
 macro_rules! _hyperlink {
     ($tcx:expr, $src_span:expr, $dst_span:expr) => {{
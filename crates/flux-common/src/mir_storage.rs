@@ -9,10 +9,18 @@
 //! require the client to provide a witness: an instance of type `TyCtxt<'tcx>`
 //! that is used to show that the lifetime that the client provided is indeed
 //! `'tcx`.
+//!
+//! Bodies are normally stored when rustc calls `mir_borrowck`, but in an incremental session the
+//! result of `mir_borrowck` may be loaded from the cache without running the query. In that case,
+//! bodies are computed on demand when they are first retrieved.
 
-use std::{cell::RefCell, collections::HashMap, thread_local};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    thread_local,
+};
 
-use rustc_borrowck::consumers::BodyWithBorrowckFacts;
+use rustc_borrowck::consumers::{self, BodyWithBorrowckFacts, ConsumerOptions};
 use rustc_hir::def_id::LocalDefId;
 use rustc_middle::ty::TyCtxt;
 
@@ -22,6 +30,31 @@ thread_local! {
     pub static SHARED_STATE:
         RefCell<HashMap<LocalDefId, BodyWithBorrowckFacts<'static>>> =
         RefCell::new(HashMap::new());
+
+    /// Typeck roots whose bodies have already been computed and stored
+    static STORED_ROOTS: RefCell<HashSet<LocalDefId>> = RefCell::new(HashSet::new());
+}
+
+/// Computes and stores the bodies (with borrowck facts) of the typeck root of `def_id` and all its
+/// nested bodies, unless they were already stored.
+pub fn store_mir_bodies(tcx: TyCtxt, def_id: LocalDefId) {
+    #[allow(clippy::disallowed_methods, reason = "the typeck root of a local item is local")]
+    let def_id = tcx.typeck_root_def_id(def_id.to_def_id()).expect_local();
+    if !STORED_ROOTS.with(|roots| roots.borrow_mut().insert(def_id)) {
+        return;
+    }
+    let bodies_with_facts = consumers::get_bodies_with_borrowck_facts(
+        tcx,
+        def_id,
+        ConsumerOptions::RegionInferenceContext,
+    );
+    for (def_id, body_with_facts) in bodies_with_facts {
+        // SAFETY: This is safe because we are feeding in the same `tcx` that is
+        // going to be used as a witness when pulling out the data.
+        unsafe {
+            store_mir_body(tcx, def_id, body_with_facts);
+        }
+    }
 }
 
 /// # Safety
@@ -45,9 +78,10 @@ pub unsafe fn store_mir_body<'tcx>(
 ///
 /// See the module level comment.
 pub unsafe fn retrieve_mir_body<'tcx>(
-    _tcx: TyCtxt<'tcx>,
+    tcx: TyCtxt<'tcx>,
     def_id: LocalDefId,
 ) -> BodyWithBorrowckFacts<'tcx> {
+    store_mir_bodies(tcx, def_id);
     let body_with_facts: BodyWithBorrowckFacts<'static> = SHARED_STATE.with(|state| {
         let mut map = state.borrow_mut();
         match map.remove(&def_id) {
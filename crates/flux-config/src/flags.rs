@@ -35,12 +35,38 @@ pub struct Flags {
     pub timings: bool,
     /// Print statistics about number of functions checked, trusted, etc.
     pub summary: bool,
+    /// Number of worker threads used to run fixpoint queries (default `1`). Constraints are always
+    /// generated on the main thread and results are reported in source order.
+    pub jobs: usize,
+    /// If present, writes a JSON report with the verification status of each function to the
+    /// provided path. If the path is a directory (or ends with a path separator), the report is
+    /// saved as `<crate>-report.json` inside it.
+    pub report: Option<PathBuf>,
+    /// If present, writes a JSON file listing the items that are not checked (and why), the
+    /// assumptions under which checked items are verified, and the extern specs used by the crate.
+    /// If the path is a directory (or ends with a path separator), the file is saved as
+    /// `<crate>-audit.json` inside it.
+    pub audit: Option<PathBuf>,
     /// Default solver. Either `z3` or `cvc5`.
     pub solver: SmtSolver,
+    /// If present, maximum number of seconds a fixpoint query can run before it's stopped and
+    /// reported as timed out.
+    pub solver_timeout: Option<u64>,
+    /// If present, maximum amount of memory in megabytes each process running a fixpoint query
+    /// (i.e., fixpoint and the SMT solver it spawns) can use.
+    pub solver_memory: Option<u64>,
     /// Enables qualifier scrapping in fixpoint
     pub scrape_quals: bool,
     /// Enables uninterpreted casts
     pub allow_uninterpreted_cast: bool,
+    /// Interprets bitwise operations and shifts on unsigned integers in the theory of bit-vectors
+    pub uint_as_bitvec: bool,
+    /// Indexes `f32` and `f64` by the real number they denote. This treats floating-point
+    /// arithmetic as arithmetic on reals, which is unsound in the presence of rounding, infinities
+    /// and `NaN`. Not supported together with `lean`.
+    pub real_floats: bool,
+    /// Follows the unwind edges of calls, drops and assertions and checks the cleanup code
+    pub check_unwind: bool,
     /// Translates _monomorphic_ `defs` functions into SMT `define-fun` instead of inlining them
     /// away inside `flux`.
     pub smt_define_fun: bool,
@@ -74,6 +100,12 @@ pub struct Flags {
     pub lean: LeanMode,
     /// If `true`, every function is implicitly labeled with a `no_panic` by default.
     pub no_panic: bool,
+    /// If `true`, ask the SMT solver for a model of each failed obligation and report it as a
+    /// counterexample next to the error.
+    pub counterexamples: bool,
+    /// If `true`, every recursive function must have a `decreases` clause proving that it
+    /// terminates. Clauses that are present are checked regardless of this flag.
+    pub termination: bool,
 }
 
 impl Default for Flags {
@@ -96,17 +128,27 @@ impl Default for Flags {
             allow_raw_deref: RawDerefMode::default(),
             scrape_quals: false,
             allow_uninterpreted_cast: false,
+            uint_as_bitvec: false,
+            real_floats: false,
+            check_unwind: false,
             solver: SmtSolver::default(),
+            solver_timeout: None,
+            solver_memory: None,
             smt_define_fun: false,
             annots: false,
             timings: false,
             summary: true,
+            report: None,
+            audit: None,
+            jobs: 1,
             verify: false,
             full_compilation: false,
             trusted_default: false,
             ignore_default: false,
             lean: LeanMode::default(),
             no_panic: false,
+            counterexamples: false,
+            termination: false,
         }
     }
 }
@@ -133,11 +175,19 @@ pub(crate) static FLAGS: LazyLock<Flags> = LazyLock::new(|| {
             "allow-raw-deref" => parse_raw_deref(&mut flags.allow_raw_deref, value),
             "scrape-quals" => parse_bool(&mut flags.scrape_quals, value),
             "allow-uninterpreted-cast" => parse_bool(&mut flags.allow_uninterpreted_cast, value),
+            "uint-as-bitvec" => parse_bool(&mut flags.uint_as_bitvec, value),
+            "real-floats" => parse_bool(&mut flags.real_floats, value),
+            "check-unwind" => parse_bool(&mut flags.check_unwind, value),
             "solver" => parse_solver(&mut flags.solver, value),
+            "solver-timeout" => parse_opt_u64(&mut flags.solver_timeout, value),
+            "solver-memory" => parse_opt_u64(&mut flags.solver_memory, value),
             "smt-define-fun" => parse_bool(&mut flags.smt_define_fun, value),
             "annots" => parse_bool(&mut flags.annots, value),
             "timings" => parse_bool(&mut flags.timings, value),
             "summary" => parse_bool(&mut flags.summary, value),
+            "report" => parse_opt_path_buf(&mut flags.report, value),
+            "audit" => parse_opt_path_buf(&mut flags.audit, value),
+            "jobs" => parse_jobs(&mut flags.jobs, value),
             "cache" => parse_opt_path_buf(&mut flags.cache, value),
             "include" => parse_opt_include(&mut includes, value),
             "include-trusted" => parse_opt_include(&mut trusteds, value),
@@ -148,6 +198,8 @@ pub(crate) static FLAGS: LazyLock<Flags> = LazyLock::new(|| {
             "ignore" => parse_bool(&mut flags.ignore_default, value),
             "lean" => parse_lean_mode(&mut flags.lean, value),
             "no-panic" => parse_bool(&mut flags.no_panic, value),
+            "counterexamples" => parse_bool(&mut flags.counterexamples, value),
+            "termination" => parse_bool(&mut flags.termination, value),
             _ => {
                 eprintln!("error: unknown flux option: `{key}`");
                 process::exit(EXIT_FAILURE);
@@ -158,6 +210,10 @@ pub(crate) static FLAGS: LazyLock<Flags> = LazyLock::new(|| {
             process::exit(1);
         }
     }
+    if flags.real_floats && !matches!(flags.lean, LeanMode::Off) {
+        eprintln!("error: flux option `real-floats` is not supported with `lean`");
+        process::exit(1);
+    }
     if !includes.is_empty() {
         let include = IncludePattern::new(includes).unwrap_or_else(|err| {
             eprintln!("error: invalid include pattern: {err}");
@@ -282,6 +338,26 @@ fn parse_solver(slot: &mut SmtSolver, v: Option<&str>) -> Result<(), &'static st
     }
 }
 
+fn parse_jobs(slot: &mut usize, v: Option<&str>) -> Result<(), &'static str> {
+    match v.map(usize::from_str) {
+        Some(Ok(n)) if n > 0 => {
+            *slot = n;
+            Ok(())
+        }
+        _ => Err("a positive number"),
+    }
+}
+
+fn parse_opt_u64(slot: &mut Option<u64>, v: Option<&str>) -> Result<(), &'static str> {
+    match v.map(u64::from_str) {
+        Some(Ok(n)) if n > 0 => {
+            *slot = Some(n);
+            Ok(())
+        }
+        _ => Err("a positive number"),
+    }
+}
+
 fn parse_opt_path_buf(slot: &mut Option<PathBuf>, v: Option<&str>) -> Result<(), &'static str> {
     match v {
         Some(s) => {
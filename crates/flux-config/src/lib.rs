@@ -98,6 +98,18 @@ pub fn allow_uninterpreted_cast() -> bool {
     FLAGS.allow_uninterpreted_cast
 }
 
+fn uint_as_bitvec() -> bool {
+    FLAGS.uint_as_bitvec
+}
+
+pub fn real_floats() -> bool {
+    FLAGS.real_floats
+}
+
+fn check_unwind() -> bool {
+    FLAGS.check_unwind
+}
+
 fn scrape_quals() -> bool {
     FLAGS.scrape_quals
 }
@@ -106,6 +118,34 @@ pub fn no_panic() -> bool {
     FLAGS.no_panic
 }
 
+pub fn counterexamples() -> bool {
+    FLAGS.counterexamples
+}
+
+pub fn termination() -> bool {
+    FLAGS.termination
+}
+
+/// The global flags that can change the result of checking an item, rendered as a string so it can
+/// be hashed, e.g., when fingerprinting items for the cache (see `-Fcache`). Flags that can be
+/// overridden per item are also part of [`InferOpts`].
+pub fn check_flags() -> String {
+    format!(
+        "termination={} check_unwind={} uint_as_bitvec={} real_floats={} no_panic={} \
+         smt_define_fun={} pointer_width={} trusted_default={} ignore_default={} lean={:?}",
+        FLAGS.termination,
+        FLAGS.check_unwind,
+        FLAGS.uint_as_bitvec,
+        FLAGS.real_floats,
+        FLAGS.no_panic,
+        FLAGS.smt_define_fun,
+        FLAGS.pointer_width.bits(),
+        FLAGS.trusted_default,
+        FLAGS.ignore_default,
+        FLAGS.lean,
+    )
+}
+
 pub fn smt_define_fun() -> bool {
     FLAGS.smt_define_fun
 }
@@ -114,6 +154,14 @@ fn solver() -> SmtSolver {
     FLAGS.solver
 }
 
+fn solver_timeout() -> Option<u64> {
+    FLAGS.solver_timeout
+}
+
+fn solver_memory() -> Option<u64> {
+    FLAGS.solver_memory
+}
+
 pub fn catch_bugs() -> bool {
     FLAGS.catch_bugs
 }
@@ -134,6 +182,18 @@ pub fn summary() -> bool {
     FLAGS.summary
 }
 
+pub fn jobs() -> usize {
+    FLAGS.jobs
+}
+
+pub fn report_path() -> Option<&'static Path> {
+    FLAGS.report.as_deref()
+}
+
+pub fn audit_path() -> Option<&'static Path> {
+    FLAGS.audit.as_deref()
+}
+
 pub fn full_compilation() -> bool {
     FLAGS.full_compilation
 }
@@ -340,10 +400,13 @@ pub enum RawDerefMode {
     None,
     /// Allow raw pointer dereferences
     Ok,
+    /// Allow raw pointer dereferences if the pointer is in bounds of its allocation, i.e.,
+    /// `ptr_size(p) > 0`, and it doesn't point into a local that was deallocated or moved.
+    Checked,
 }
 
 impl RawDerefMode {
-    const ERROR: &'static str = "expected one of `none` or `ok`";
+    const ERROR: &'static str = "expected one of `none`, `ok` or `checked`";
 }
 
 impl FromStr for RawDerefMode {
@@ -354,6 +417,7 @@ impl FromStr for RawDerefMode {
         match s.as_str() {
             "none" => Ok(RawDerefMode::None),
             "ok" => Ok(RawDerefMode::Ok),
+            "checked" => Ok(RawDerefMode::Checked),
             _ => Err(Self::ERROR),
         }
     }
@@ -372,6 +436,7 @@ impl fmt::Display for RawDerefMode {
         match self {
             RawDerefMode::None => write!(f, "none"),
             RawDerefMode::Ok => write!(f, "ok"),
+            RawDerefMode::Checked => write!(f, "checked"),
         }
     }
 }
@@ -427,10 +492,20 @@ pub struct InferOpts {
     /// Whether qualifiers should be scraped from the constraint.
     pub scrape_quals: bool,
     pub solver: SmtSolver,
+    /// Maximum number of seconds a fixpoint query can run.
+    pub solver_timeout: Option<u64>,
+    /// Maximum amount of memory in megabytes a fixpoint query can use.
+    pub solver_memory: Option<u64>,
     /// Whether to allow uninterpreted casts (e.g., from some random `S` to `int`).
     pub allow_uninterpreted_cast: bool,
     /// Whether to allow raw pointer dereferences.
     pub allow_raw_deref: RawDerefMode,
+    /// Whether bitwise operations and shifts on unsigned integers should be interpreted in the
+    /// theory of bit-vectors.
+    pub uint_as_bitvec: bool,
+    /// Whether to follow unwind edges and check the cleanup code executed when a panic unwinds
+    /// the stack.
+    pub check_unwind: bool,
 }
 
 impl From<PartialInferOpts> for InferOpts {
@@ -439,10 +514,14 @@ impl From<PartialInferOpts> for InferOpts {
             check_overflow: opts.check_overflow.unwrap_or_else(check_overflow),
             scrape_quals: opts.scrape_quals.unwrap_or_else(scrape_quals),
             solver: opts.solver.unwrap_or_else(solver),
+            solver_timeout: opts.solver_timeout.or_else(solver_timeout),
+            solver_memory: opts.solver_memory.or_else(solver_memory),
             allow_uninterpreted_cast: opts
                 .allow_uninterpreted_cast
                 .unwrap_or_else(allow_uninterpreted_cast),
             allow_raw_deref: opts.allow_raw_deref.unwrap_or_else(allow_raw_deref),
+            uint_as_bitvec: opts.uint_as_bitvec.unwrap_or_else(uint_as_bitvec),
+            check_unwind: opts.check_unwind.unwrap_or_else(check_unwind),
         }
     }
 }
@@ -452,8 +531,12 @@ pub struct PartialInferOpts {
     pub check_overflow: Option<OverflowMode>,
     pub scrape_quals: Option<bool>,
     pub solver: Option<SmtSolver>,
+    pub solver_timeout: Option<u64>,
+    pub solver_memory: Option<u64>,
     pub allow_uninterpreted_cast: Option<bool>,
     pub allow_raw_deref: Option<RawDerefMode>,
+    pub uint_as_bitvec: Option<bool>,
+    pub check_unwind: Option<bool>,
 }
 
 impl PartialInferOpts {
@@ -464,7 +547,11 @@ impl PartialInferOpts {
             .or(other.allow_uninterpreted_cast);
         self.scrape_quals = self.scrape_quals.or(other.scrape_quals);
         self.solver = self.solver.or(other.solver);
+        self.solver_timeout = self.solver_timeout.or(other.solver_timeout);
+        self.solver_memory = self.solver_memory.or(other.solver_memory);
         self.allow_raw_deref = self.allow_raw_deref.or(other.allow_raw_deref);
+        self.uint_as_bitvec = self.uint_as_bitvec.or(other.uint_as_bitvec);
+        self.check_unwind = self.check_unwind.or(other.check_unwind);
     }
 }
 
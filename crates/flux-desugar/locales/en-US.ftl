@@ -7,6 +7,9 @@ desugar_invalid_alias_reft =
 desugar_int_too_large =
     integer literal is too large
 
+desugar_inexact_real_lit =
+    float literal cannot be represented exactly
+
 desugar_unexpected_literal =
     unexpected literal
 
@@ -17,7 +17,7 @@ use flux_middle::{
     fhir::{self, FhirId, FluxOwnerId, ParamId, QPathExpr, Res},
     global_env::GlobalEnv,
     query_bug,
-    rty::QualifierKind,
+    rty::{self, QualifierKind},
     try_alloc_slice,
 };
 use flux_syntax::{
@@ -490,7 +490,7 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
         fn_sig: Option<&surface::FnSig>,
     ) -> Result<(fhir::Generics<'genv>, fhir::FnSig<'genv>)> {
         let mut header = self.lift_fn_header();
-        let (generics, decl, expr) = if let Some(fn_sig) = fn_sig {
+        let (generics, decl, expr, annots) = if let Some(fn_sig) = fn_sig {
             self.fn_sig_scope = Some(fn_sig.node_id);
 
             let mut requires = vec![];
@@ -525,14 +525,40 @@ impl<'a, 'genv, 'tcx: 'genv> RustItemCtxt<'a, 'genv, 'tcx> {
                 header.asyncness = hir::IsAsync::Async(span);
             }
             let expr = fn_sig.no_panic.as_ref().map(|e| self.desugar_expr(e));
-            (generics, decl, expr)
+            let decreases = self
+                .genv
+                .alloc_slice_fill_iter(fn_sig.decreases.iter().map(|e| self.desugar_expr(e)));
+            let loop_invariants = self.genv.alloc_slice_fill_iter(
+                fn_sig
+                    .loop_invariants
+                    .iter()
+                    .map(|invariant| self.desugar_loop_invariant(invariant)),
+            );
+            (generics, decl, expr, fhir::FnAnnots { decreases, loop_invariants })
         } else {
-            (self.lift_generics(), self.lift_fn_decl(), None)
+            (self.lift_generics(), self.lift_fn_decl(), None, fhir::FnAnnots::default())
         };
         if config::dump_fhir() {
             dbg::dump_item_info(self.genv.tcx(), self.owner.local_id(), "fhir", decl).unwrap();
         }
-        Ok((generics, fhir::FnSig { header, decl: self.genv.alloc(decl), no_panic_if: expr }))
+        Ok((
+            generics,
+            fhir::FnSig {
+                header,
+                decl: self.genv.alloc(decl),
+                no_panic_if: expr,
+                annots: self.genv.alloc(annots),
+            },
+        ))
+    }
+
+    fn desugar_loop_invariant(
+        &mut self,
+        invariant: &surface::LoopInvariant,
+    ) -> fhir::LoopInvariant<'genv> {
+        let params = self.desugar_refine_params(&invariant.params);
+        let pred = self.desugar_expr(&invariant.pred);
+        fhir::LoopInvariant { kind: invariant.kind, params, pred, span: invariant.span }
     }
 
     fn desugar_fn_sig_refine_params(
@@ -898,6 +924,9 @@ impl<'genv, 'tcx> FluxItemCtxt<'genv, 'tcx> {
     }
 
     fn desugar_spec_func(&mut self, spec_func: &surface::SpecFunc) -> fhir::SpecFunc<'genv> {
+        let decreases = self
+            .genv
+            .alloc_slice_fill_iter(spec_func.decreases.iter().map(|e| self.desugar_expr(e)));
         let body = spec_func.body.as_ref().map(|body| self.desugar_expr(body));
         let params = spec_func.sort_vars.len();
         let sort = self.desugar_sort(&spec_func.output, None);
@@ -908,6 +937,7 @@ impl<'genv, 'tcx> FluxItemCtxt<'genv, 'tcx> {
             params,
             args,
             sort,
+            decreases,
             body,
             hide: spec_func.hide,
             ident_span,
@@ -1153,7 +1183,7 @@ trait DesugarCtxt<'genv, 'tcx: 'genv>: ErrorEmitter + ErrorCollector<ErrorGuaran
         let mut fhir_args = vec![];
         let mut constraints = vec![];
         if let Res::Def(
-            DefKind::TyAlias | DefKind::Struct | DefKind::Enum | DefKind::OpaqueTy,
+            DefKind::TyAlias | DefKind::Struct | DefKind::Enum | DefKind::Union | DefKind::OpaqueTy,
             def_id,
         ) = res
         {
@@ -1523,15 +1553,26 @@ trait DesugarCtxt<'genv, 'tcx: 'genv>: ErrorEmitter + ErrorCollector<ErrorGuaran
                 self.desugar_constructor(path.as_ref(), args)
             }
             surface::ExprKind::BoundedQuant(kind, param, rng, body) => {
-                let kind = match kind {
-                    surface::QuantKind::Exists => fhir::QuantKind::Exists,
-                    surface::QuantKind::Forall => fhir::QuantKind::Forall,
-                };
+                let kind = desugar_quant_kind(kind);
                 let body = self.genv().alloc(self.desugar_expr(body));
                 let param = self.desugar_refine_param(param);
                 let rng = fhir::Range { start: rng.start, end: rng.end };
                 fhir::ExprKind::BoundedQuant(kind, param, rng, body)
             }
+            surface::ExprKind::Quant(kind, params, triggers, body) => {
+                let kind = desugar_quant_kind(kind);
+                let params = self.desugar_refine_params(params);
+                let triggers = self
+                    .genv()
+                    .alloc_slice_fill_iter(triggers.iter().map(|trigger| {
+                        fhir::Trigger {
+                            exprs: self.desugar_exprs(&trigger.exprs),
+                            span: trigger.span,
+                        }
+                    }));
+                let body = self.genv().alloc(self.desugar_expr(body));
+                fhir::ExprKind::Quant(kind, params, triggers, body)
+            }
             surface::ExprKind::Block(decls, body) => {
                 let decls = self.genv().alloc_slice_fill_iter(decls.iter().map(|decl| {
                     fhir::LetDecl {
@@ -1548,6 +1589,12 @@ trait DesugarCtxt<'genv, 'tcx: 'genv>: ErrorEmitter + ErrorCollector<ErrorGuaran
                     .alloc_slice_fill_iter(exprs.iter().map(|expr| self.desugar_expr(expr)));
                 fhir::ExprKind::SetLiteral(exprs)
             }
+            surface::ExprKind::SeqLiteral(exprs) => {
+                let exprs = self
+                    .genv()
+                    .alloc_slice_fill_iter(exprs.iter().map(|expr| self.desugar_expr(expr)));
+                fhir::ExprKind::SeqLiteral(exprs)
+            }
             surface::ExprKind::Tuple(exprs) => {
                 let exprs = self
                     .genv()
@@ -1603,7 +1650,7 @@ trait DesugarCtxt<'genv, 'tcx: 'genv>: ErrorEmitter + ErrorCollector<ErrorGuaran
         args: &[surface::ConstructorArg],
     ) -> fhir::ExprKind<'genv> {
         let path = if let Some(path) = path {
-            let Some(res @ Res::Def(DefKind::Struct | DefKind::Enum, _)) =
+            let Some(res @ Res::Def(DefKind::Struct | DefKind::Enum | DefKind::Union, _)) =
                 self.resolver_output().expr_path_res_map[&path.node_id].full_res()
             else {
                 return fhir::ExprKind::Err(
@@ -1698,6 +1745,38 @@ trait DesugarCtxt<'genv, 'tcx: 'genv>: ErrorEmitter + ErrorCollector<ErrorGuaran
                     }
                 }
             }
+            surface::LitKind::Float => {
+                if let Some(suffix) = lit.suffix
+                    && suffix != sym::real
+                {
+                    return fhir::ExprKind::Err(
+                        self.emit(errors::InvalidNumericSuffix::new(span, suffix)),
+                    );
+                }
+                // A float literal denotes the exact real number written in decimal, which we
+                // represent as a fraction of two (integral) real literals.
+                let Some((num, den)) = rty::Real::parse_decimal(lit.symbol.as_str()) else {
+                    return fhir::ExprKind::Err(self.emit(errors::InexactRealLit { span }));
+                };
+                let num = fhir::Lit::Int(num, Some(fhir::NumLitKind::Real));
+                if den == 1 {
+                    num
+                } else {
+                    let den = fhir::Lit::Int(den, Some(fhir::NumLitKind::Real));
+                    let mk_lit = |lit| {
+                        fhir::Expr {
+                            kind: fhir::ExprKind::Literal(lit),
+                            span,
+                            fhir_id: self.next_fhir_id(),
+                        }
+                    };
+                    return fhir::ExprKind::BinaryOp(
+                        fhir::BinOp::Div,
+                        self.genv().alloc(mk_lit(num)),
+                        self.genv().alloc(mk_lit(den)),
+                    );
+                }
+            }
             surface::LitKind::Bool => fhir::Lit::Bool(lit.symbol == kw::True),
             surface::LitKind::Str => fhir::Lit::Str(lit.symbol),
             surface::LitKind::Char => fhir::Lit::Char(lit.symbol.as_str().parse::<char>().unwrap()),
@@ -1755,6 +1834,13 @@ impl<'genv, 'tcx> DesugarCtxt<'genv, 'tcx> for FluxItemCtxt<'genv, 'tcx> {
     }
 }
 
+fn desugar_quant_kind(kind: &surface::QuantKind) -> fhir::QuantKind {
+    match kind {
+        surface::QuantKind::Exists => fhir::QuantKind::Exists,
+        surface::QuantKind::Forall => fhir::QuantKind::Forall,
+    }
+}
+
 /// Traverses the `hir` for an item and collects the `def_id` of any opaque type (i.e., `impl Trait` or `async`)
 /// Currently, we only support up to one opaque type and we report an error if there's more than one.
 fn collect_opaque_types(
@@ -11,6 +11,7 @@ use flux_middle::{
     fhir::{self, FhirId, FluxOwnerId},
     query_bug, try_alloc_slice,
 };
+use flux_rustc_bridge::{lowering::Lower as _, ty::Pattern};
 use rustc_hir::{
     self as hir, FnHeader,
     def_id::{DefId, LocalDefId},
@@ -331,6 +332,18 @@ impl<'genv> RustItemCtxt<'_, 'genv, '_> {
                 fhir::TyKind::BareFn(self.genv.alloc(bare_fn))
             }
             hir::TyKind::Never => fhir::TyKind::Never,
+            hir::TyKind::Pat(base, _) => {
+                match self.lift_pattern(ty) {
+                    Ok(pat) => {
+                        let base = self.lift_ty(base);
+                        let kind = fhir::BaseTyKind::Pat(self.genv.alloc(base), pat);
+                        let bty =
+                            fhir::BaseTy { kind, fhir_id: self.next_fhir_id(), span: ty.span };
+                        fhir::TyKind::BaseTy(bty)
+                    }
+                    Err(err) => fhir::TyKind::Err(err),
+                }
+            }
             hir::TyKind::Tup(tys) => {
                 let tys = self
                     .genv
@@ -392,6 +405,22 @@ impl<'genv> RustItemCtxt<'_, 'genv, '_> {
         fhir::Ty { kind, span: ty.span }
     }
 
+    /// Patterns cannot be refined, so we take the pattern from the type rustc lowers for `ty`
+    fn lift_pattern(&self, ty: &hir::Ty) -> Result<&'genv Pattern> {
+        let tcx = self.genv.tcx();
+        let hir_ty = tcx.hir_node(ty.hir_id).expect_ty();
+        if let rustc_middle::ty::Pat(_, pat) = rustc_hir_analysis::lower_ty(tcx, hir_ty).kind()
+            && let Ok(pat) = pat.lower(tcx)
+        {
+            Ok(self.genv.alloc(pat))
+        } else {
+            Err(self.emit_unsupported(&format!(
+                "unsupported type: `{}`",
+                rustc_hir_pretty::ty_to_string(&tcx, ty)
+            )))
+        }
+    }
+
     fn lift_bare_fn(&mut self, span: Span, fn_ptr: &hir::FnPtrTy) -> fhir::BareFnTy<'genv> {
         let generic_params = self.genv.alloc_slice_fill_iter(
             fn_ptr
@@ -543,7 +572,12 @@ impl<'genv> RustItemCtxt<'_, 'genv, '_> {
 
     fn lift_fn_sig(&mut self, fn_sig: hir::FnSig) -> fhir::FnSig<'genv> {
         let decl = self.lift_fn_decl_inner(fn_sig.span, fn_sig.decl);
-        fhir::FnSig { header: fn_sig.header, decl: self.genv.alloc(decl), no_panic_if: None }
+        fhir::FnSig {
+            header: fn_sig.header,
+            decl: self.genv.alloc(decl),
+            no_panic_if: None,
+            annots: self.genv.alloc(fhir::FnAnnots::default()),
+        }
     }
 
     pub(crate) fn lift_foreign_item(
@@ -611,12 +645,12 @@ impl<'genv> RustItemCtxt<'_, 'genv, '_> {
 }
 
 pub mod errors {
-    use flux_errors::E0999;
+    use flux_errors::E0998;
     use flux_macros::Diagnostic;
     use rustc_span::Span;
 
     #[derive(Diagnostic)]
-    #[diag(desugar_unsupported_hir, code = E0999)]
+    #[diag(desugar_unsupported_hir, code = E0998)]
     #[note]
     pub(super) struct UnsupportedHir<'a> {
         #[primary_span]
@@ -1,4 +1,4 @@
-use flux_errors::E0999;
+use flux_errors::{E0998, E0999};
 use flux_macros::Diagnostic;
 use flux_syntax::surface;
 use rustc_span::{Span, Symbol};
@@ -10,6 +10,13 @@ pub(super) struct IntTooLarge {
     pub(super) span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(desugar_inexact_real_lit, code = E0999)]
+pub(super) struct InexactRealLit {
+    #[primary_span]
+    pub(super) span: Span,
+}
+
 #[derive(Diagnostic)]
 #[diag(desugar_unexpected_literal, code = E0999)]
 pub(super) struct UnexpectedLiteral {
@@ -102,7 +109,7 @@ impl MultipleSpreadsInConstructor {
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_unsupported_position, code = E0999)]
+#[diag(desugar_unsupported_position, code = E0998)]
 pub(super) struct UnsupportedPosition {
     #[primary_span]
     span: Span,
@@ -128,7 +135,7 @@ impl FinalAssocReftWithoutBody {
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_unsupported_const_generic_arg, code = E0999)]
+#[diag(desugar_unsupported_const_generic_arg, code = E0998)]
 pub(super) struct UnsupportedConstGenericArg {
     #[primary_span]
     #[label]
@@ -143,7 +150,7 @@ impl UnsupportedConstGenericArg {
 }
 
 #[derive(Diagnostic)]
-#[diag(desugar_unsupported_signature, code = E0999)]
+#[diag(desugar_unsupported_signature, code = E0998)]
 #[note]
 pub(super) struct UnsupportedSignature<'a> {
     #[primary_span]
@@ -162,6 +162,8 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
             .insert(Symbol::intern("cast"), fhir::SpecFuncKind::Cast);
         self.func_decls
             .insert(sym::ptr_size, fhir::SpecFuncKind::PtrSize);
+        self.func_decls
+            .insert(sym::ptr_offset, fhir::SpecFuncKind::PtrOffset);
     }
 
     fn define_items(&mut self, item_ids: impl IntoIterator<Item = &'tcx ItemId>) {
@@ -205,6 +207,7 @@ impl<'genv, 'tcx> CrateResolver<'genv, 'tcx> {
                 ItemKind::TyAlias(..) => DefKind::TyAlias,
                 ItemKind::Enum(..) => DefKind::Enum,
                 ItemKind::Struct(..) => DefKind::Struct,
+                ItemKind::Union(..) => DefKind::Union,
                 ItemKind::Trait(..) => DefKind::Trait,
                 ItemKind::Mod(..) => DefKind::Mod,
                 ItemKind::Const(..) => DefKind::Const,
@@ -206,6 +206,12 @@ impl<V: ScopedVisitor> surface::visit::Visitor for ScopedVisitorWrapper<V> {
         });
     }
 
+    fn visit_loop_invariant(&mut self, invariant: &surface::LoopInvariant) {
+        self.with_scope(ScopeKind::Misc, |this| {
+            surface::visit::walk_loop_invariant(this, invariant);
+        });
+    }
+
     fn visit_fn_output(&mut self, output: &surface::FnOutput) {
         self.with_scope(ScopeKind::FnOutput, |this| {
             this.on_fn_output(output);
@@ -321,6 +327,17 @@ impl<V: ScopedVisitor> surface::visit::Visitor for ScopedVisitorWrapper<V> {
         }
     }
 
+    fn visit_expr(&mut self, expr: &surface::Expr) {
+        match &expr.kind {
+            surface::ExprKind::BoundedQuant(..) | surface::ExprKind::Quant(..) => {
+                self.with_scope(ScopeKind::Misc, |this| {
+                    surface::visit::walk_expr(this, expr);
+                });
+            }
+            _ => surface::visit::walk_expr(self, expr),
+        }
+    }
+
     fn visit_path_expr(&mut self, path: &surface::ExprPath) {
         self.on_path(path);
     }
@@ -593,7 +610,7 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
             .resolver
             .resolve_path_with_ribs(&path.segments, TypeNS)?;
         match (partial_res.base_res(), partial_res.unresolved_segments()) {
-            (fhir::Res::Def(DefKind::Struct | DefKind::Enum, def_id), 0) => {
+            (fhir::Res::Def(DefKind::Struct | DefKind::Enum | DefKind::Union, def_id), 0) => {
                 Some(fhir::SortRes::Adt(def_id))
             }
             (fhir::Res::Def(DefKind::TyParam, def_id), 0) => Some(fhir::SortRes::TyParam(def_id)),
@@ -633,6 +650,8 @@ impl<'a, 'genv, 'tcx> RefinementResolver<'a, 'genv, 'tcx> {
             Some(fhir::SortRes::PrimSort(fhir::PrimSort::Set))
         } else if segment.name == sym::Map {
             Some(fhir::SortRes::PrimSort(fhir::PrimSort::Map))
+        } else if segment.name == sym::Seq {
+            Some(fhir::SortRes::PrimSort(fhir::PrimSort::Seq))
         } else if segment.name == sym::str {
             Some(fhir::SortRes::PrimSort(fhir::PrimSort::Str))
         } else if segment.name == sym::ptr {
@@ -1,6 +1,18 @@
 driver_no_panic_if_without_sig =
     `no_panic_if` attribute requires a `sig` annotation on the same item
 
+driver_decreases_without_sig =
+    `decreases` attribute requires a `sig` annotation on the same item
+
+driver_loop_invariant_without_sig =
+    loop invariants and variants require a `sig` annotation on the enclosing function
+
+driver_loop_invariant_not_on_loop =
+    `{$name}` attribute must be attached to a loop
+
+driver_loop_invariant_in_closure =
+    loop invariants and variants are not supported inside closures
+
 driver_duplicated_attr =
     duplicated attribute `{$name}`
 
@@ -1,22 +1,30 @@
-use flux_common::{bug, cache::QueryCache, iter::IterExt, result::ResultExt};
+use std::time::Instant;
+
+use flux_common::{
+    bug,
+    cache::QueryCache,
+    dbg::{self, SpanTrace},
+    iter::IterExt,
+    result::ResultExt,
+};
 use flux_config::{self as config};
 use flux_errors::FluxSession;
 use flux_infer::{
-    fixpoint_encoding::{FixQueryCache, LeanStatus, lean_task_key},
+    fixpoint_encoding::{FixpointQueue, LeanStatus, item_index, lean_task_key},
     lean_encoding,
 };
 use flux_metadata::CStore;
 use flux_middle::{
-    Specs,
+    Specs, audit,
     def_id::MaybeExternId,
     fhir::{self},
     global_env::GlobalEnv,
     metrics::{self, Metric, TimingKind},
     queries::{Providers, QueryResult},
+    report,
     rty::StaticInfo,
 };
 use flux_refineck as refineck;
-use rustc_borrowck::consumers::ConsumerOptions;
 use rustc_driver::{Callbacks, Compilation};
 use rustc_errors::ErrorGuaranteed;
 use rustc_hir::{
@@ -27,7 +35,7 @@ use rustc_interface::interface::Compiler;
 use rustc_middle::{query, ty::TyCtxt};
 use rustc_session::config::OutputType;
 
-use crate::{DEFAULT_LOCALE_RESOURCES, collector::SpecCollector};
+use crate::{DEFAULT_LOCALE_RESOURCES, collector::SpecCollector, fingerprint::Fingerprinter};
 
 #[derive(Default)]
 pub struct FluxCallbacks;
@@ -38,6 +46,7 @@ impl Callbacks for FluxCallbacks {
 
         config.override_queries = Some(|_, local| {
             local.mir_borrowck = mir_borrowck;
+            local.mir_drops_elaborated_and_const_checked = mir_drops_elaborated_and_const_checked;
         });
         // this should always be empty otherwise something changed in rustc and all our assumptions
         // about symbol interning are wrong.
@@ -75,9 +84,11 @@ impl FluxCallbacks {
             if result.is_ok() {
                 encode_and_save_metadata(genv);
             }
+            let _ = audit::dump_audit(genv);
             lean_encoding::finalize(genv).unwrap_or(());
         });
         let _ = metrics::print_and_dump_timings(tcx);
+        let _ = report::dump_report(tcx);
         sess.finish_diagnostics();
     }
 }
@@ -91,11 +102,17 @@ fn check_crate(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
 
         let mut ck = CrateChecker::new(genv);
 
+        let result = refineck::defns::check_defns(genv, &mut ck.queue);
+
         // Iterate over all def ids including dummy items for extern specs
         let result = genv
             .tcx()
             .iter_local_def_id()
-            .try_for_each_exhaust(|def_id| ck.check_def_catching_bugs(def_id));
+            .try_for_each_exhaust(|def_id| ck.check_def_catching_bugs(def_id))
+            .and(result);
+
+        // Wait for the fixpoint queries that are still running in parallel
+        let result = result.and(ck.queue.finish());
 
         if config::lean().is_check() || config::lean().is_emit() {
             lean_encoding::finalize(genv)
@@ -109,6 +126,7 @@ fn check_crate(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
                     // Skip proof check if previously verified successfully.
                     if config::is_cache_enabled()
                         && ck
+                            .queue
                             .cache
                             .lookup_by_key(&key)
                             .map(|r| matches!(r.lean_status, LeanStatus::Valid))
@@ -116,9 +134,10 @@ fn check_crate(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
                     {
                         return Ok(());
                     }
-                    lean_encoding::check_proof(genv, def_id.to_def_id())?;
+                    lean_encoding::check_proof(genv, def_id)?;
                     // Mark as valid in cache so future runs skip re-verification.
-                    ck.cache
+                    ck.queue
+                        .cache
                         .update_result_by_key(&key, |r| r.lean_status = LeanStatus::Valid);
                     Ok(())
                 } else {
@@ -129,7 +148,7 @@ fn check_crate(genv: GlobalEnv) -> Result<(), ErrorGuaranteed> {
             Ok(())
         };
 
-        ck.cache.save().unwrap_or(());
+        ck.queue.cache.save().unwrap_or(());
 
         tracing::info!("Callbacks::check_crate");
 
@@ -163,18 +182,37 @@ fn encode_and_save_metadata(genv: GlobalEnv) {
 
 struct CrateChecker<'genv, 'tcx> {
     genv: GlobalEnv<'genv, 'tcx>,
-    cache: FixQueryCache,
+    queue: FixpointQueue<'genv, 'tcx>,
+    fingerprinter: Fingerprinter<'genv, 'tcx>,
 }
 
 impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
     fn new(genv: GlobalEnv<'genv, 'tcx>) -> Self {
-        Self { genv, cache: QueryCache::load() }
+        Self {
+            genv,
+            queue: FixpointQueue::new(genv, QueryCache::load()),
+            fingerprinter: Fingerprinter::new(genv),
+        }
     }
 
     fn check_def_catching_bugs(&mut self, def_id: LocalDefId) -> Result<(), ErrorGuaranteed> {
+        let genv = self.genv;
         let mut this = std::panic::AssertUnwindSafe(self);
-        let msg = format!("def_id: {:?}, span: {:?}", def_id, this.genv.tcx().def_span(def_id));
-        flux_common::bug::catch_bugs(&msg, move || this.check_def(def_id))?
+        let msg = format!("def_id: {:?}, span: {:?}", def_id, genv.tcx().def_span(def_id));
+        let start = Instant::now();
+        let result = genv.sess().with_item(item_index(def_id), || {
+            flux_common::bug::catch_bugs(&msg, move || this.check_def(def_id))
+        });
+        if is_reported(genv, def_id) {
+            let status = match &result {
+                Ok(Ok(())) => report::Status::Verified,
+                Ok(Err(_)) => report::Status::Failed,
+                Err(_) => report::Status::Crashed,
+            };
+            report::record_status(def_id, status);
+            report::record_time(def_id, start.elapsed());
+        }
+        result?
     }
 
     fn check_def(&mut self, def_id: LocalDefId) -> Result<(), ErrorGuaranteed> {
@@ -194,22 +232,22 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
         // 3. It has a mir body
         // In particular, this excludes closures (because they dont have the right `DefKind`) and
         // trait methods without a default body.
-        let is_fn_with_body = def_id
-            .as_local()
-            .map(|local_id| {
-                matches!(kind, DefKind::Fn | DefKind::AssocFn)
-                    && genv.tcx().is_mir_available(local_id)
-            })
-            .unwrap_or(false);
+        let is_fn_with_body = is_fn_with_body(genv, def_id);
 
         metrics::incr_metric_if(is_fn_with_body, Metric::FnTotal);
 
         if genv.ignored(def_id.local_id()) {
             metrics::incr_metric_if(is_fn_with_body, Metric::FnIgnored);
+            if is_reported(genv, def_id.local_id()) {
+                report::record_status(def_id.local_id(), report::Status::Ignored);
+            }
             return Ok(());
         }
         if !self.genv.included(def_id) {
             metrics::incr_metric_if(is_fn_with_body, Metric::FnTrusted);
+            if is_reported(genv, def_id.local_id()) {
+                report::record_status(def_id.local_id(), report::Status::Trusted);
+            }
             return Ok(());
         }
 
@@ -219,7 +257,7 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
             DefKind::Fn | DefKind::AssocFn => {
                 let Some(local_id) = def_id.as_local() else { return Ok(()) };
                 if is_fn_with_body {
-                    refineck::check_fn(genv, &mut self.cache, local_id)?;
+                    self.check_fn(local_id)?;
                 }
             }
             DefKind::Enum => {
@@ -230,13 +268,13 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
                     .expect_enum();
                 refineck::invariants::check_invariants(
                     genv,
-                    &mut self.cache,
+                    &mut self.queue,
                     def_id,
                     enum_def.invariants,
                     &adt_def,
                 )?;
             }
-            DefKind::Struct => {
+            DefKind::Struct | DefKind::Union => {
                 // We check invariants for `struct` in `check_constructor` (i.e. when the struct is built),
                 // so nothing to do here.
             }
@@ -252,13 +290,58 @@ impl<'genv, 'tcx> CrateChecker<'genv, 'tcx> {
                 if let StaticInfo::Known(ty) = genv.static_info(def_id).emit(&genv)?
                     && let Some(local_id) = def_id.as_local()
                 {
-                    refineck::check_static(genv, &mut self.cache, local_id, ty)?;
+                    refineck::check_static(genv, &mut self.queue, local_id, ty)?;
                 }
             }
             _ => (),
         }
         Ok(())
     }
+
+    /// Checks a function unless caching is enabled and the function was verified in a previous run
+    /// with the same [fingerprint](crate::fingerprint).
+    fn check_fn(&mut self, def_id: LocalDefId) -> Result<(), ErrorGuaranteed> {
+        let genv = self.genv;
+        let tcx = genv.tcx();
+        dbg::item_span!(tcx, def_id, tcx.hir_body_owned_by(def_id).value.span);
+        // Functions proven externally still need their lean proofs to be checked.
+        if !config::is_cache_enabled() || genv.proven_externally(def_id).is_some() {
+            return refineck::check_fn(genv, &mut self.queue, def_id);
+        }
+
+        let key = self.fingerprinter.key(def_id);
+        let fingerprint = self.fingerprinter.fingerprint(def_id).emit(&genv)?;
+        if self.queue.cache.is_item_verified(&key, fingerprint) {
+            metrics::incr_metric(Metric::FnCached, 1);
+            report::record_cached(def_id);
+            return Ok(());
+        }
+        refineck::check_fn(genv, &mut self.queue, def_id)?;
+        self.queue
+            .submit_verified(MaybeExternId::Local(def_id), key, fingerprint);
+        Ok(())
+    }
+}
+
+fn is_fn_with_body(genv: GlobalEnv, def_id: MaybeExternId) -> bool {
+    def_id
+        .as_local()
+        .map(|local_id| {
+            matches!(genv.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+                && genv.tcx().is_mir_available(local_id)
+        })
+        .unwrap_or(false)
+}
+
+/// Whether the item gets a record in the report enabled with `-Freport`, i.e., it's a function
+/// with a body or a static.
+fn is_reported(genv: GlobalEnv, def_id: LocalDefId) -> bool {
+    if genv.is_dummy(def_id) {
+        return false;
+    }
+    let def_id = genv.maybe_extern_id(def_id);
+    is_fn_with_body(genv, def_id)
+        || (def_id.is_local() && matches!(genv.def_kind(def_id), DefKind::Static { .. }))
 }
 
 /// Triggers queries for the given `def_id` to mark it as "reached" for metadata encoding.
@@ -292,7 +375,7 @@ fn trigger_queries(genv: GlobalEnv, def_id: MaybeExternId) -> QueryResult {
             // should be reported at the use site.
             let _ = genv.fn_sig(def_id);
         }
-        DefKind::Enum | DefKind::Struct => {
+        DefKind::Enum | DefKind::Struct | DefKind::Union => {
             genv.generics_of(def_id)?;
             genv.predicates_of(def_id)?;
             genv.refinement_generics_of(def_id)?;
@@ -322,20 +405,25 @@ fn mir_borrowck<'tcx>(
     tcx: TyCtxt<'tcx>,
     def_id: LocalDefId,
 ) -> query::queries::mir_borrowck::ProvidedValue<'tcx> {
-    let bodies_with_facts = rustc_borrowck::consumers::get_bodies_with_borrowck_facts(
-        tcx,
-        def_id,
-        ConsumerOptions::RegionInferenceContext,
-    );
-    for (def_id, body_with_facts) in bodies_with_facts {
-        // SAFETY: This is safe because we are feeding in the same `tcx` that is
-        // going to be used as a witness when pulling out the data.
-        unsafe {
-            flux_common::mir_storage::store_mir_body(tcx, def_id, body_with_facts);
-        }
-    }
+    flux_common::mir_storage::store_mir_bodies(tcx, def_id);
     let mut providers = query::Providers::default();
     rustc_borrowck::provide(&mut providers);
     let original_mir_borrowck = providers.mir_borrowck;
     original_mir_borrowck(tcx, def_id)
 }
+
+/// In an incremental session `mir_borrowck` can be green and loaded from the cache while this query
+/// still runs and steals the promoted MIR we need to compute the bodies with borrowck facts, so we
+/// store them before that happens.
+fn mir_drops_elaborated_and_const_checked<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+) -> query::queries::mir_drops_elaborated_and_const_checked::ProvidedValue<'tcx> {
+    if !tcx.is_synthetic_mir(def_id) {
+        flux_common::mir_storage::store_mir_bodies(tcx, def_id);
+    }
+    let mut providers = rustc_middle::util::Providers::default();
+    rustc_mir_transform::provide(&mut providers);
+    let original = providers.queries.mir_drops_elaborated_and_const_checked;
+    original(tcx, def_id)
+}
@@ -229,6 +229,9 @@ impl<'a, 'sess, 'tcx> DetachedSpecsCollector<'a, 'sess, 'tcx> {
         let span = item.span();
         let dst_span = self.inner.tcx.def_span(def_id);
         dbg::hyperlink!(self.inner.tcx, span, dst_span);
+        // Also link the item back to its spec
+        let ident_span = self.inner.tcx.def_ident_span(def_id).unwrap_or(dst_span);
+        dbg::hyperlink!(self.inner.tcx, ident_span, span);
         match item.kind {
             surface::DetachedItemKind::FnSig(fn_sig) => {
                 self.inner.insert_item(
@@ -0,0 +1,198 @@
+//! Collection of `#[flux::loop_invariant(..)]` and `#[flux::loop_variant(..)]` annotations.
+//!
+//! Loop invariants and variants are attached to loops inside the body of a function. Rust locals
+//! mentioned in an invariant are bound as refinement parameters of the invariant, which are later
+//! matched with the corresponding mir locals at the head of the loop. To do so, we keep track of
+//! the bindings in scope while traversing the body and record the span of the binding for every
+//! local mentioned in the invariant.
+use flux_common::result::ResultExt;
+use flux_errors::Errors;
+use flux_syntax::{
+    ParseSess,
+    surface::{self, visit::Visitor as _},
+};
+use rustc_hir::{
+    self as hir, Attribute, BodyId, ExprKind, PatKind,
+    def_id::LocalDefId,
+    intravisit::{self, Visitor},
+};
+use rustc_middle::{hir::nested_filter, ty::TyCtxt};
+use rustc_span::{DesugaringKind, Ident, Span, Symbol, SyntaxContext};
+
+use super::{Result, errors};
+use crate::collector::attr_item_inner_span;
+
+pub(super) struct LoopInvariantCollector<'a, 'sess, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    parse_sess: &'a mut ParseSess,
+    errors: &'a Errors<'sess>,
+    /// The bindings in scope at the expression being visited. Inner bindings come last.
+    bindings: Vec<(Symbol, Span)>,
+    /// How many closures we are inside of
+    closure_depth: usize,
+    invariants: Vec<surface::LoopInvariant>,
+}
+
+impl<'a, 'sess, 'tcx> LoopInvariantCollector<'a, 'sess, 'tcx> {
+    pub(super) fn collect(
+        tcx: TyCtxt<'tcx>,
+        parse_sess: &'a mut ParseSess,
+        errors: &'a Errors<'sess>,
+        def_id: LocalDefId,
+    ) -> Vec<surface::LoopInvariant> {
+        let Some(body) = tcx.hir_maybe_body_owned_by(def_id) else { return vec![] };
+        let mut collector = Self {
+            tcx,
+            parse_sess,
+            errors,
+            bindings: vec![],
+            closure_depth: 0,
+            invariants: vec![],
+        };
+        collector.visit_body(body);
+        collector.invariants
+    }
+
+    fn with_scope(&mut self, f: impl FnOnce(&mut Self)) {
+        let len = self.bindings.len();
+        f(self);
+        self.bindings.truncate(len);
+    }
+
+    fn check_loop_invariants(&mut self, expr: &hir::Expr) {
+        for attr in self.tcx.hir_attrs(expr.hir_id) {
+            if let Attribute::Unparsed(attr_item) = attr
+                && let [first, segment] = &attr_item.path.segments[..]
+                && matches!(first.as_str(), "flux" | "flux_tool")
+            {
+                let kind = match segment.as_str() {
+                    "loop_invariant" => surface::LoopInvariantKind::Invariant,
+                    "loop_variant" => surface::LoopInvariantKind::Variant,
+                    _ => continue,
+                };
+                let _ = self.collect_loop_invariant(expr, attr_item, kind);
+            }
+        }
+    }
+
+    fn collect_loop_invariant(
+        &mut self,
+        expr: &hir::Expr,
+        attr_item: &hir::AttrItem,
+        kind: surface::LoopInvariantKind,
+    ) -> Result {
+        let span = attr_item_inner_span(attr_item);
+        // `while` and `loop` are lowered to a `Loop`. The attributes of a `for` loop are attached
+        // to the `DropTemps` wrapping the desugaring.
+        let is_loop = match expr.kind {
+            ExprKind::Loop(..) => true,
+            ExprKind::DropTemps(_) => expr.span.desugaring_kind() == Some(DesugaringKind::ForLoop),
+            _ => false,
+        };
+        if !is_loop {
+            let name = match kind {
+                surface::LoopInvariantKind::Invariant => "loop_invariant",
+                surface::LoopInvariantKind::Variant => "loop_variant",
+            };
+            return Err(self
+                .errors
+                .emit(errors::LoopInvariantNotOnLoop { span, name }));
+        }
+        if self.closure_depth > 0 {
+            return Err(self.errors.emit(errors::LoopInvariantInClosure { span }));
+        }
+        let hir::AttrArgs::Delimited(dargs) = &attr_item.args else {
+            return Err(self.errors.emit(errors::InvalidAttr { span }));
+        };
+        let entire = dargs.dspan.entire().with_ctxt(SyntaxContext::root());
+        let pred = self
+            .parse_sess
+            .parse_expr(&dargs.tokens, entire)
+            .map_err(errors::SyntaxErr::from)
+            .emit(self.errors)?;
+
+        let mut names = FreeNames::default();
+        names.visit_expr(&pred);
+
+        let mut params = vec![];
+        for name in names.0 {
+            let Some(&(_, binding_span)) = self.bindings.iter().rev().find(|(n, _)| *n == name)
+            else {
+                continue;
+            };
+            params.push(surface::RefineParam {
+                ident: Ident::new(name, binding_span),
+                sort: surface::Sort::Infer,
+                mode: None,
+                span: binding_span,
+                node_id: self.parse_sess.next_node_id(),
+            });
+        }
+        self.invariants
+            .push(surface::LoopInvariant { kind, params, pred, span: expr.span });
+        Ok(())
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for LoopInvariantCollector<'_, '_, 'tcx> {
+    type NestedFilter = nested_filter::OnlyBodies;
+
+    fn maybe_tcx(&mut self) -> Self::MaybeTyCtxt {
+        self.tcx
+    }
+
+    fn visit_nested_body(&mut self, id: BodyId) {
+        self.closure_depth += 1;
+        self.with_scope(|this| intravisit::walk_body(this, this.tcx.hir_body(id)));
+        self.closure_depth -= 1;
+    }
+
+    fn visit_block(&mut self, block: &'tcx hir::Block<'tcx>) {
+        self.with_scope(|this| intravisit::walk_block(this, block));
+    }
+
+    fn visit_arm(&mut self, arm: &'tcx hir::Arm<'tcx>) {
+        self.with_scope(|this| intravisit::walk_arm(this, arm));
+    }
+
+    fn visit_pat(&mut self, pat: &'tcx hir::Pat<'tcx>) {
+        // Skip bindings introduced by desugarings, e.g., the `iter` in a `for` loop.
+        if let PatKind::Binding(_, _, ident, _) = pat.kind
+            && pat.span.desugaring_kind().is_none()
+        {
+            self.bindings.push((ident.name, pat.span));
+        }
+        intravisit::walk_pat(self, pat);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        self.check_loop_invariants(expr);
+        match expr.kind {
+            // Bindings in the condition of an `if let` are only in scope in the `then` branch.
+            ExprKind::If(cond, then, els) => {
+                self.with_scope(|this| {
+                    this.visit_expr(cond);
+                    this.visit_expr(then);
+                });
+                if let Some(els) = els {
+                    self.visit_expr(els);
+                }
+            }
+            _ => intravisit::walk_expr(self, expr),
+        }
+    }
+}
+
+/// Collects the names of single-segment paths in an expression, in order of first occurrence.
+#[derive(Default)]
+struct FreeNames(Vec<Symbol>);
+
+impl surface::visit::Visitor for FreeNames {
+    fn visit_path_expr(&mut self, path: &surface::ExprPath) {
+        if let [segment] = &path.segments[..]
+            && !self.0.contains(&segment.ident.name)
+        {
+            self.0.push(segment.ident.name);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 mod annot_stats;
 mod detached_specs;
 mod extern_specs;
+mod loop_invariants;
 
 use std::{collections::HashMap, iter};
 
@@ -9,7 +10,6 @@ use extern_specs::ExternSpecCollector;
 use flux_common::{
     iter::IterExt,
     result::{ErrorCollector, ResultExt},
-    tracked_span_assert_eq,
 };
 use flux_config::{self as config, OverflowMode, PartialInferOpts, RawDerefMode, SmtSolver};
 use flux_errors::{Errors, FluxSession};
@@ -18,6 +18,7 @@ use flux_syntax::{
     ParseResult, ParseSess,
     surface::{self, NodeId, Trusted},
 };
+use loop_invariants::LoopInvariantCollector;
 use rustc_ast::{MetaItemInner, MetaItemKind, tokenstream::TokenStream};
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_errors::ErrorGuaranteed;
@@ -39,6 +40,9 @@ pub(crate) struct SpecCollector<'sess, 'tcx> {
     specs: Specs,
     errors: Errors<'sess>,
     stats: Stats,
+    /// Loop invariants collected from the body of functions. They are attached to the signature
+    /// of the function once all specs have been collected.
+    loop_invariants: FxIndexMap<OwnerId, Vec<surface::LoopInvariant>>,
 }
 
 macro_rules! attr_name {
@@ -76,10 +80,12 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
             specs: Specs::default(),
             errors: Errors::new(sess),
             stats: Default::default(),
+            loop_invariants: Default::default(),
         };
 
         let _ = collector.collect_crate();
         tcx.hir_walk_toplevel_module(&mut collector);
+        collector.attach_loop_invariants();
 
         if config::annots() {
             collector.stats.save(tcx).unwrap();
@@ -110,6 +116,7 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
 
         match &item.kind {
             ItemKind::Fn { .. } => {
+                self.collect_loop_invariants(owner_id);
                 if attrs.has_attrs() {
                     let fn_sig = attrs.fn_sig();
                     self.check_fn_sig_name(owner_id, fn_sig.as_ref())?;
@@ -124,12 +131,7 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
                     )?;
                 }
             }
-            ItemKind::Struct(_, _, variant) => {
-                self.collect_struct_def(owner_id, attrs, variant)?;
-            }
-            ItemKind::Union(_, _, variant) => {
-                // currently no refinements on unions
-                tracked_span_assert_eq!(attrs.items().is_empty(), true);
+            ItemKind::Struct(_, _, variant) | ItemKind::Union(_, _, variant) => {
                 self.collect_struct_def(owner_id, attrs, variant)?;
             }
             ItemKind::Enum(_, _, enum_def) => {
@@ -169,6 +171,9 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         let owner_id = trait_item.owner_id;
 
         let mut attrs = self.parse_attrs_and_report_dups(owner_id.def_id)?;
+        if let rustc_hir::TraitItemKind::Fn(_, _) = trait_item.kind {
+            self.collect_loop_invariants(owner_id);
+        }
         if let rustc_hir::TraitItemKind::Fn(_, _) = trait_item.kind
             && attrs.has_attrs()
         {
@@ -189,6 +194,9 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
 
         let mut attrs = self.parse_attrs_and_report_dups(owner_id.def_id)?;
 
+        if let ImplItemKind::Fn(..) = &impl_item.kind {
+            self.collect_loop_invariants(owner_id);
+        }
         if let ImplItemKind::Fn(..) = &impl_item.kind
             && attrs.has_attrs()
         {
@@ -465,6 +473,33 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
         Ok(())
     }
 
+    fn collect_loop_invariants(&mut self, owner_id: OwnerId) {
+        let invariants = LoopInvariantCollector::collect(
+            self.tcx,
+            &mut self.parse_sess,
+            &self.errors,
+            owner_id.def_id,
+        );
+        if !invariants.is_empty() {
+            self.loop_invariants.insert(owner_id, invariants);
+        }
+    }
+
+    /// Attach the collected loop invariants to the signature of their function. This is done at
+    /// the end because the signature may come from a detached spec.
+    fn attach_loop_invariants(&mut self) {
+        for (owner_id, invariants) in std::mem::take(&mut self.loop_invariants) {
+            if let Some(fn_sig) = self.specs.fn_sig_mut(owner_id) {
+                fn_sig.loop_invariants = invariants;
+            } else {
+                for invariant in invariants {
+                    self.errors
+                        .emit(errors::LoopInvariantWithoutSig { span: invariant.span });
+                }
+            }
+        }
+    }
+
     fn parse_attrs_and_report_dups(&mut self, def_id: LocalDefId) -> Result<FluxAttrs> {
         let attrs = self.parse_flux_attrs(def_id)?;
         self.report_dups(&attrs)?;
@@ -514,6 +549,25 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
             return Err(self.errors.emit(errors::NoPanicIfWithoutSig { span }));
         }
 
+        // a `decreases` attribute also needs a `sig` attribute and cannot be combined with a
+        // `decreases` clause in that signature.
+        if let Some(span) = attrs.iter().find_map(|attr| {
+            if let FluxAttrKind::Decreases(_) = attr.kind { Some(attr.span) } else { None }
+        }) {
+            let fn_sig = attrs.iter().find_map(|attr| {
+                if let FluxAttrKind::FnSig(fn_sig) = &attr.kind { Some(fn_sig) } else { None }
+            });
+            match fn_sig {
+                None => return Err(self.errors.emit(errors::DecreasesWithoutSig { span })),
+                Some(fn_sig) if !fn_sig.decreases.is_empty() => {
+                    return Err(self
+                        .errors
+                        .emit(errors::DuplicatedAttr { span, name: "Decreases" }));
+                }
+                Some(_) => {}
+            }
+        }
+
         Ok(FluxAttrs::new(attrs))
     }
 
@@ -585,6 +639,9 @@ impl<'a, 'tcx> SpecCollector<'a, 'tcx> {
             ("no_panic_if", hir::AttrArgs::Delimited(dargs)) => {
                 self.parse(dargs, ParseSess::parse_expr, FluxAttrKind::NoPanicIf)?
             }
+            ("decreases", hir::AttrArgs::Delimited(dargs)) => {
+                self.parse(dargs, ParseSess::parse_decreases, FluxAttrKind::Decreases)?
+            }
             ("constant", hir::AttrArgs::Delimited(dargs)) => {
                 self.parse(dargs, ParseSess::parse_constant_info, FluxAttrKind::Constant)?
             }
@@ -745,6 +802,7 @@ enum FluxAttrKind {
     ExternSpec,
     NoPanic,
     NoPanicIf(surface::Expr),
+    Decreases(Vec<surface::Expr>),
     /// See `detachXX.rs`
     DetachedSpecs(surface::DetachedSpecs),
 }
@@ -820,6 +878,9 @@ impl FluxAttrs {
         // annotation or we should at least show an error.
         if let Some(fn_sig) = &mut fn_sig {
             fn_sig.no_panic = read_attr!(self, NoPanicIf);
+            if let Some(decreases) = read_attr!(self, Decreases) {
+                fn_sig.decreases = decreases;
+            }
         }
         fn_sig
     }
@@ -918,7 +979,8 @@ impl FluxAttrs {
                 | FluxAttrKind::Invariant(_)
                 | FluxAttrKind::ExternSpec
                 | FluxAttrKind::DetachedSpecs(_)
-                | FluxAttrKind::NoPanicIf(_) => continue,
+                | FluxAttrKind::NoPanicIf(_)
+                | FluxAttrKind::Decreases(_) => continue,
             };
             attrs.push(attr);
         }
@@ -955,6 +1017,7 @@ impl FluxAttrKind {
             FluxAttrKind::DetachedSpecs(_) => attr_name!(DetachedSpecs),
             FluxAttrKind::NoPanic => attr_name!(NoPanic),
             FluxAttrKind::NoPanicIf(_) => attr_name!(NoPanicIf),
+            FluxAttrKind::Decreases(_) => attr_name!(Decreases),
         }
     }
 }
@@ -1051,8 +1114,12 @@ impl AttrMap {
         try_read_setting!(self, allow_uninterpreted_cast, bool, infer_opts);
         try_read_setting!(self, check_overflow, OverflowMode, infer_opts);
         try_read_setting!(self, allow_raw_deref, RawDerefMode, infer_opts);
+        try_read_setting!(self, uint_as_bitvec, bool, infer_opts);
+        try_read_setting!(self, check_unwind, bool, infer_opts);
         try_read_setting!(self, scrape_quals, bool, infer_opts);
         try_read_setting!(self, solver, SmtSolver, infer_opts);
+        try_read_setting!(self, solver_timeout, u64, infer_opts);
+        try_read_setting!(self, solver_memory, u64, infer_opts);
 
         if let Some((name, setting)) = self.map.iter().next() {
             return Err(errors::AttrMapErr {
@@ -1096,6 +1163,35 @@ mod errors {
         pub span: Span,
     }
 
+    #[derive(Diagnostic)]
+    #[diag(driver_decreases_without_sig, code = E0999)]
+    pub(super) struct DecreasesWithoutSig {
+        #[primary_span]
+        pub span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_loop_invariant_without_sig, code = E0999)]
+    pub(super) struct LoopInvariantWithoutSig {
+        #[primary_span]
+        pub span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_loop_invariant_not_on_loop, code = E0999)]
+    pub(super) struct LoopInvariantNotOnLoop {
+        #[primary_span]
+        pub span: Span,
+        pub name: &'static str,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(driver_loop_invariant_in_closure, code = E0999)]
+    pub(super) struct LoopInvariantInClosure {
+        #[primary_span]
+        pub span: Span,
+    }
+
     #[derive(Diagnostic)]
     #[diag(driver_duplicated_attr, code = E0999)]
     pub(super) struct DuplicatedAttr {
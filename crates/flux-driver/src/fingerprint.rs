@@ -0,0 +1,230 @@
+//! Fingerprints used to skip checking functions that haven't changed since the last run when
+//! caching is enabled with `-Fcache`.
+//!
+//! The fingerprint of a function combines:
+//! * The MIR of the function and of the closures defined inside it, the flux attributes of the
+//!   function and its parents, and the inference options that apply to it.
+//! * The spec of every item mentioned in the MIR, i.e., the attributes of the item (or of its
+//!   extern spec) and its parents, and for ADTs also the attributes of variants and fields. A call
+//!   to a trait method additionally depends on the method in the impl it resolves to.
+//! * A fingerprint of the crate covering the crate attributes, the flux items defined with `defs`,
+//!   type aliases, the hashes of all upstream crates, and the global flags that change the result
+//!   of checking (e.g., `-Ftermination`).
+//!
+//! Results produced by a different build of flux are discarded when loading the cache (see
+//! [`flux_common::cache`]), so the fingerprint doesn't need to cover the version of flux.
+//!
+//! Spans are not hashed, so moving a function around doesn't invalidate its fingerprint. The checker
+//! trace of a function skipped this way refers to its old spans, so consumers of the trace must
+//! re-map them (the trace records the current span of every function, see [`dbg::item_span`]).
+//!
+//! [`dbg::item_span`]: flux_common::dbg::item_span
+use flux_config as config;
+use flux_middle::{def_id::ResolvedDefId, global_env::GlobalEnv, queries::QueryResult};
+use rustc_data_structures::{
+    fingerprint::Fingerprint,
+    fx::FxIndexSet,
+    stable_hasher::{HashStable, StableHasher},
+};
+use rustc_hir::{
+    def::DefKind,
+    def_id::{CRATE_DEF_ID, DefId, LocalDefId},
+};
+use rustc_middle::ty::{self, TyCtxt, TypeSuperVisitable, TypeVisitable, TypeVisitor};
+
+pub(crate) struct Fingerprinter<'genv, 'tcx> {
+    genv: GlobalEnv<'genv, 'tcx>,
+    crate_fingerprint: Option<Fingerprint>,
+}
+
+impl<'genv, 'tcx> Fingerprinter<'genv, 'tcx> {
+    pub(crate) fn new(genv: GlobalEnv<'genv, 'tcx>) -> Self {
+        Self { genv, crate_fingerprint: None }
+    }
+
+    /// The key identifying the function in the cache. Unlike the keys of fixpoint queries, this
+    /// includes the crate name and disambiguator because the cache may be shared by several crates.
+    pub(crate) fn key(&self, def_id: LocalDefId) -> String {
+        self.genv.tcx().def_path_debug_str(def_id.to_def_id())
+    }
+
+    pub(crate) fn fingerprint(&mut self, def_id: LocalDefId) -> QueryResult<u64> {
+        let genv = self.genv;
+        let tcx = genv.tcx();
+        let crate_fingerprint = self.crate_fingerprint();
+
+        // Collect the bodies of the function and its closures, and the items they mention.
+        let mut bodies = vec![];
+        let mut deps = DepsCollector::new(tcx, def_id);
+        let mut worklist = vec![def_id];
+        while let Some(body_id) = worklist.pop() {
+            let body = genv.mir(body_id)?;
+            let rustc_bodies = std::iter::once(&body.body.rustc_body)
+                .chain(body.promoted.iter().map(|promoted| &promoted.rustc_body));
+            for rustc_body in rustc_bodies {
+                // Region variables cannot be hashed.
+                let rustc_body = tcx.erase_and_anonymize_regions(rustc_body.clone());
+                rustc_body.visit_with(&mut deps);
+                bodies.push(rustc_body);
+            }
+            worklist.extend(deps.take_nested_bodies());
+        }
+
+        let fingerprint = tcx.with_stable_hashing_context(|mut hcx| {
+            let mut hasher = StableHasher::new();
+            hcx.while_hashing_spans(false, |hcx| {
+                crate_fingerprint.hash_stable(hcx, &mut hasher);
+                format!("{:?}", genv.infer_opts(def_id)).hash_stable(hcx, &mut hasher);
+                for body in &bodies {
+                    body.hash_stable(hcx, &mut hasher);
+                }
+                self.attrs_with_parents(def_id.to_def_id())
+                    .for_each(|attrs| attrs.hash_stable(hcx, &mut hasher));
+                for &dep in &deps.deps {
+                    dep.hash_stable(hcx, &mut hasher);
+                    self.dep_attrs(dep)
+                        .for_each(|attrs| attrs.hash_stable(hcx, &mut hasher));
+                }
+            });
+            hasher.finish::<Fingerprint>()
+        });
+        tracing::debug!(?def_id, ?fingerprint, "fingerprint");
+        Ok(fingerprint.to_smaller_hash().as_u64())
+    }
+
+    fn crate_fingerprint(&mut self) -> Fingerprint {
+        let genv = self.genv;
+        let tcx = genv.tcx();
+        *self.crate_fingerprint.get_or_insert_with(|| {
+            tcx.with_stable_hashing_context(|mut hcx| {
+                let mut hasher = StableHasher::new();
+                hcx.while_hashing_spans(false, |hcx| {
+                    config::check_flags().hash_stable(hcx, &mut hasher);
+                    tcx.hir_attrs(tcx.local_def_id_to_hir_id(CRATE_DEF_ID))
+                        .hash_stable(hcx, &mut hasher);
+                    for def_id in tcx.iter_local_def_id() {
+                        // The flux-rs macro puts defs as an outer attribute on a `const`
+                        if matches!(tcx.def_kind(def_id), DefKind::Const | DefKind::TyAlias) {
+                            def_id.hash_stable(hcx, &mut hasher);
+                            tcx.get_all_attrs(def_id).hash_stable(hcx, &mut hasher);
+                        }
+                    }
+                    for &krate in tcx.crates(()) {
+                        tcx.crate_hash(krate).hash_stable(hcx, &mut hasher);
+                    }
+                });
+                hasher.finish()
+            })
+        })
+    }
+
+    /// The attributes a dependency's spec can come from. Specs of external items come from the
+    /// upstream crate (covered by the crate fingerprint) unless there's a local extern spec.
+    fn dep_attrs(&self, def_id: DefId) -> impl Iterator<Item = &'tcx [rustc_hir::Attribute]> {
+        let tcx = self.genv.tcx();
+        let local_id = match self.genv.resolve_id(def_id) {
+            ResolvedDefId::Local(local_id) | ResolvedDefId::ExternSpec(local_id, _) => {
+                Some(local_id)
+            }
+            ResolvedDefId::Extern(_) => None,
+        };
+        let children = local_id
+            .filter(|local_id| {
+                matches!(tcx.def_kind(*local_id), DefKind::Struct | DefKind::Enum | DefKind::Union)
+            })
+            .into_iter()
+            .flat_map(move |local_id| {
+                let adt_def = tcx.adt_def(local_id);
+                adt_def
+                    .variants()
+                    .iter()
+                    .flat_map(|variant| {
+                        std::iter::once(variant.def_id)
+                            .chain(variant.fields.iter().map(|field| field.did))
+                    })
+                    .map(move |did| tcx.get_all_attrs(did))
+                    .collect::<Vec<_>>()
+            });
+        local_id
+            .into_iter()
+            .flat_map(|local_id| self.attrs_with_parents(local_id.to_def_id()))
+            .chain(children)
+    }
+
+    fn attrs_with_parents(
+        &self,
+        def_id: DefId,
+    ) -> impl Iterator<Item = &'tcx [rustc_hir::Attribute]> {
+        let tcx = self.genv.tcx();
+        std::iter::successors(Some(def_id), move |did| tcx.opt_parent(*did))
+            .map(move |did| tcx.get_all_attrs(did))
+    }
+}
+
+/// Collects the items mentioned in a MIR body.
+struct DepsCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    typing_env: ty::TypingEnv<'tcx>,
+    deps: FxIndexSet<DefId>,
+    nested_bodies: Vec<LocalDefId>,
+}
+
+impl<'tcx> DepsCollector<'tcx> {
+    fn new(tcx: TyCtxt<'tcx>, def_id: LocalDefId) -> Self {
+        Self {
+            tcx,
+            typing_env: ty::TypingEnv::post_analysis(tcx, def_id),
+            deps: FxIndexSet::default(),
+            nested_bodies: vec![],
+        }
+    }
+
+    fn take_nested_bodies(&mut self) -> Vec<LocalDefId> {
+        std::mem::take(&mut self.nested_bodies)
+    }
+
+    /// Closures are checked together with the function defining them, so we also include their
+    /// bodies if they are local.
+    #[expect(clippy::disallowed_methods, reason = "closures cannot have extern specs")]
+    fn insert(&mut self, def_id: DefId) {
+        if self.deps.insert(def_id)
+            && self.tcx.is_closure_like(def_id)
+            && let Some(local_id) = def_id.as_local()
+        {
+            self.nested_bodies.push(local_id);
+        }
+    }
+}
+
+impl<'tcx> TypeVisitor<TyCtxt<'tcx>> for DepsCollector<'tcx> {
+    fn visit_ty(&mut self, ty: ty::Ty<'tcx>) {
+        match *ty.kind() {
+            ty::Adt(adt_def, _) => self.insert(adt_def.did()),
+            ty::FnDef(def_id, args) => {
+                self.insert(def_id);
+                if self.tcx.trait_of_assoc(def_id).is_some()
+                    && let Ok(Some(instance)) =
+                        ty::Instance::try_resolve(self.tcx, self.typing_env, def_id, args)
+                {
+                    self.insert(instance.def_id());
+                }
+            }
+            ty::Closure(def_id, _) | ty::Coroutine(def_id, _) | ty::CoroutineClosure(def_id, _) => {
+                self.insert(def_id);
+            }
+            ty::Alias(_, alias_ty) => self.insert(alias_ty.def_id),
+            ty::Foreign(def_id) => self.insert(def_id),
+            ty::Dynamic(preds, ..) => {
+                for pred in preds {
+                    match pred.skip_binder() {
+                        ty::ExistentialPredicate::Trait(trait_ref) => self.insert(trait_ref.def_id),
+                        ty::ExistentialPredicate::Projection(proj) => self.insert(proj.def_id),
+                        ty::ExistentialPredicate::AutoTrait(def_id) => self.insert(def_id),
+                    }
+                }
+            }
+            _ => {}
+        }
+        ty.super_visit_with(self);
+    }
+}
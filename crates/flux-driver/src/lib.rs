@@ -9,11 +9,13 @@ extern crate rustc_hir;
 extern crate rustc_hir_pretty;
 extern crate rustc_interface;
 extern crate rustc_middle;
+extern crate rustc_mir_transform;
 extern crate rustc_session;
 extern crate rustc_span;
 
 pub mod callbacks;
 mod collector;
+mod fingerprint;
 
 use flux_macros::fluent_messages;
 
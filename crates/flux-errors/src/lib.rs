@@ -8,13 +8,14 @@ extern crate rustc_span;
 use std::{cell::Cell, io, sync::Arc};
 
 use flux_common::result::{ErrorCollector, ErrorEmitter};
-use rustc_data_structures::sync;
+use rustc_data_structures::sync::{self, Lock};
 pub use rustc_errors::ErrorGuaranteed;
 use rustc_errors::{
-    Diagnostic, ErrCode, FatalAbort, FatalError, LazyFallbackBundle, TerminalUrl,
+    Diag, DiagInner, Diagnostic, ErrCode, FatalAbort, FatalError, LazyFallbackBundle, TerminalUrl,
     annotate_snippet_emitter_writer::AnnotateSnippetEmitter,
     emitter::{Emitter, HumanEmitter, HumanReadableErrorType, OutputTheme, stderr_destination},
     json::JsonEmitter,
+    registry::Registry,
     translation::Translator,
 };
 use rustc_session::{config, parse::ParseSess};
@@ -22,9 +23,46 @@ use rustc_span::source_map::SourceMap;
 
 pub struct FluxSession {
     pub parse_sess: ParseSess,
+    held: Arc<Lock<HeldDiagnostics>>,
 }
 
+// Error codes for the families of errors reported by flux. These codes are stable, i.e., tools
+// consuming flux diagnostics can rely on them to categorize errors. The list is mirrored in the
+// rules of the SARIF output in `flux-bin`.
+
+/// The solver exceeded its time or memory limit before answering a query.
+pub const E0984: ErrCode = ErrCode::from_u32(984);
+/// A raw pointer may be dereferenced or offset out of the bounds of its allocation.
+pub const E0985: ErrCode = ErrCode::from_u32(985);
+/// A value may not match the pattern of a pattern type.
+pub const E0986: ErrCode = ErrCode::from_u32(986);
+/// A union field may not be the active one, or a refined union has an invalid tag.
+pub const E0987: ErrCode = ErrCode::from_u32(987);
+/// A recursive call or a loop may not terminate.
+pub const E0988: ErrCode = ErrCode::from_u32(988);
+/// An index may be out of bounds.
+pub const E0989: ErrCode = ErrCode::from_u32(989);
+
+/// A precondition of a function call cannot be proven.
+pub const E0990: ErrCode = ErrCode::from_u32(990);
+/// The postcondition of a function cannot be proven.
+pub const E0991: ErrCode = ErrCode::from_u32(991);
+/// An arithmetic operation may overflow.
+pub const E0992: ErrCode = ErrCode::from_u32(992);
+/// An arithmetic operation may underflow.
+pub const E0993: ErrCode = ErrCode::from_u32(993);
+/// A division or remainder operation may have a divisor of zero.
+pub const E0994: ErrCode = ErrCode::from_u32(994);
+/// The invariant of a type may not hold when a place is folded.
+pub const E0995: ErrCode = ErrCode::from_u32(995);
+/// A call in a function that must not panic may panic.
+pub const E0996: ErrCode = ErrCode::from_u32(996);
+/// A type invariant or a loop invariant cannot be proven.
+pub const E0997: ErrCode = ErrCode::from_u32(997);
+/// Code or a signature uses features not supported by flux.
+pub const E0998: ErrCode = ErrCode::from_u32(998);
 // FIXME(nilehmann) We probably need to move out of this error reporting
+/// Catch-all code for errors that don't belong to any of the families above.
 pub const E0999: ErrCode = ErrCode::from_u32(999);
 
 impl FluxSession {
@@ -33,9 +71,56 @@ impl FluxSession {
         source_map: Arc<SourceMap>,
         fallback_bundle: LazyFallbackBundle,
     ) -> Self {
-        let emitter = emitter(opts, source_map.clone(), fallback_bundle);
-        let dcx = rustc_errors::DiagCtxt::new(emitter);
-        Self { parse_sess: ParseSess::with_dcx(dcx, source_map) }
+        let translator =
+            Translator { fluent_bundle: None, fallback_fluent_bundle: fallback_bundle };
+        // The session is only used from the rustc thread, and `Lock` is what the `DiagCtxt` uses
+        // to be shared when rustc runs in parallel.
+        #[allow(clippy::arc_with_non_send_sync)]
+        let held = Arc::new(Lock::new(HeldDiagnostics {
+            emitter: emitter(opts, source_map.clone(), translator.clone()),
+            holding: false,
+            item: 0,
+            diags: vec![],
+        }));
+        let emitter =
+            OrderedEmitter { held: Arc::clone(&held), source_map: source_map.clone(), translator };
+        let dcx = rustc_errors::DiagCtxt::new(Box::new(emitter));
+        Self { parse_sess: ParseSess::with_dcx(dcx, source_map), held }
+    }
+
+    /// Holds back the diagnostics emitted from now on until they are released with
+    /// [`FluxSession::release_diagnostics`]. Diagnostics are attributed to the item set with
+    /// [`FluxSession::with_item`] when they are emitted, and they are released in the order of
+    /// their items. This lets us report errors in the order items are defined when the errors of
+    /// an item are found after we moved on to check the next ones, e.g., with `-Fjobs`.
+    pub fn hold_diagnostics(&self) {
+        self.held.lock().holding = true;
+    }
+
+    /// Emits the held diagnostics of items before `before` and of items before the current one,
+    /// i.e., the diagnostics of items we are done with. If `before` is [`None`], emits all of
+    /// them and stops holding diagnostics back.
+    pub fn release_diagnostics(&self, before: Option<usize>) {
+        let mut held = self.held.lock();
+        match before {
+            Some(before) => {
+                let before = usize::min(before, held.item);
+                held.release(before);
+            }
+            None => {
+                held.release(usize::MAX);
+                held.holding = false;
+            }
+        }
+    }
+
+    /// Runs `f` attributing the diagnostics it emits to `item`. Items are identified by their
+    /// position in the order we want their diagnostics to be reported.
+    pub fn with_item<R>(&self, item: usize, f: impl FnOnce() -> R) -> R {
+        let prev = std::mem::replace(&mut self.held.lock().item, item);
+        let r = f();
+        self.held.lock().item = prev;
+        r
     }
 
     pub fn err_count(&self) -> usize {
@@ -47,21 +132,29 @@ impl FluxSession {
         self.parse_sess.dcx().emit_err(err)
     }
 
+    #[track_caller]
+    pub fn create_err<'a>(&'a self, err: impl Diagnostic<'a>) -> Diag<'a> {
+        self.parse_sess.dcx().create_err(err)
+    }
+
     #[track_caller]
     pub fn emit_fatal<'a>(&'a self, fatal: impl Diagnostic<'a, FatalAbort>) -> ! {
+        self.release_diagnostics(None);
         self.parse_sess.dcx().emit_fatal(fatal)
     }
 
     pub fn abort(&self, _: ErrorGuaranteed) -> ! {
-        self.parse_sess.dcx().abort_if_errors();
+        self.abort_if_errors();
         FatalError.raise()
     }
 
     pub fn abort_if_errors(&self) {
+        self.release_diagnostics(None);
         self.parse_sess.dcx().abort_if_errors();
     }
 
     pub fn finish_diagnostics(&self) {
+        self.release_diagnostics(None);
         self.parse_sess.dcx().print_error_count();
         self.abort_if_errors();
     }
@@ -71,13 +164,75 @@ impl FluxSession {
     }
 }
 
+/// Diagnostics held back to be emitted in the order of the items they belong to. See
+/// [`FluxSession::hold_diagnostics`].
+struct HeldDiagnostics {
+    emitter: Box<dyn Emitter + sync::DynSend>,
+    holding: bool,
+    /// The item diagnostics are currently attributed to
+    item: usize,
+    diags: Vec<(usize, DiagInner)>,
+}
+
+impl HeldDiagnostics {
+    fn release(&mut self, before: usize) {
+        // The sort is stable so diagnostics of the same item are emitted in the order they were
+        // reported.
+        self.diags.sort_by_key(|(item, _)| *item);
+        let n = self.diags.partition_point(|(item, _)| *item < before);
+        for (_, diag) in self.diags.drain(..n) {
+            self.emitter.emit_diagnostic(diag, &Registry::new(&[]));
+        }
+    }
+}
+
+/// The emitter of the [`FluxSession`], which forwards diagnostics to the actual emitter unless they
+/// are being held back.
+struct OrderedEmitter {
+    held: Arc<Lock<HeldDiagnostics>>,
+    source_map: Arc<SourceMap>,
+    translator: Translator,
+}
+
+impl Emitter for OrderedEmitter {
+    fn emit_diagnostic(&mut self, diag: DiagInner, registry: &Registry) {
+        let mut held = self.held.lock();
+        if held.holding {
+            let item = held.item;
+            held.diags.push((item, diag));
+        } else {
+            held.emitter.emit_diagnostic(diag, registry);
+        }
+    }
+
+    fn should_show_explain(&self) -> bool {
+        self.held.lock().emitter.should_show_explain()
+    }
+
+    fn supports_color(&self) -> bool {
+        self.held.lock().emitter.supports_color()
+    }
+
+    fn source_map(&self) -> Option<&SourceMap> {
+        Some(&self.source_map)
+    }
+
+    fn translator(&self) -> &Translator {
+        &self.translator
+    }
+}
+
+impl Drop for OrderedEmitter {
+    fn drop(&mut self) {
+        self.held.lock().release(usize::MAX);
+    }
+}
+
 fn emitter(
     sopts: &config::Options,
     source_map: Arc<SourceMap>,
-    fallback_fluent_bundle: LazyFallbackBundle,
+    translator: Translator,
 ) -> Box<dyn Emitter + sync::DynSend> {
-    let translator = Translator { fluent_bundle: None, fallback_fluent_bundle };
-
     // All the code below is copied from rustc_session::session::default_emitter
     let macro_backtrace = sopts.unstable_opts.macro_backtrace;
     let track_diagnostics = sopts.unstable_opts.track_diagnostics;
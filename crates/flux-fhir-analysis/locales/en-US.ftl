@@ -86,6 +86,18 @@ fhir_analysis_param_not_determined =
     .label = undetermined parameter
     .help = try indexing a type with `{$name}` in a position that fully determines its value
 
+fhir_analysis_trigger_missing_param =
+    trigger does not mention quantified variable `{$name}`
+    .label = every trigger must mention all quantified variables
+
+fhir_analysis_invalid_trigger =
+    invalid trigger
+    .label = a trigger must be a function application
+
+fhir_analysis_interpreted_term_in_trigger =
+    invalid trigger
+    .label = triggers cannot contain {$descr}
+
 fhir_analysis_sort_annotation_needed =
     sort annotation needed
     .label = help: consider giving this parameter an explicit sort
@@ -157,6 +169,7 @@ fhir_analysis_field_count_mismatch =
 fhir_analysis_definition_cycle =
     cycle in definitions
     .label = {$msg}
+    .help = add a `decreases` clause to every function in the cycle to check that it terminates
 
 # Conv errors
 
@@ -245,6 +258,11 @@ fhir_analysis_fields_on_reflected_enum_variant =
     reflected enum variants cannot have any fields
     .label = found fields on reflected enum variant
 
+fhir_analysis_invalid_union_tag =
+    invalid tag for refined union
+    .label = the first refinement parameter of this union must have sort `int`
+    .note = the first refinement parameter of a union is a tag holding the index of the active field
+
 fhir_analysis_incorrect_generics_on_opaque_sort =
     opaque sort {$name} expects {$expected ->
         [0] no generics
@@ -309,8 +309,16 @@ pub(crate) fn conv_adt_sort_def(
                 .iter()
                 .map(|(name, sort)| -> QueryResult<_> { Ok((*name, cx.conv_sort(sort)?)) })
                 .try_collect_vec()?;
-            let variants = IndexVec::from([rty::AdtSortVariant::new(fields)]);
             let def_id = def_id.resolved_id();
+            // The first field of a refined union is the tag of the active field
+            if genv.tcx().adt_def(def_id).is_union()
+                && let Some((_, sort)) = fields.first()
+                && !matches!(sort, rty::Sort::Int)
+            {
+                let span = genv.tcx().def_span(def_id);
+                Err(genv.sess().emit_err(errors::InvalidUnionTag::new(span)))?;
+            }
+            let variants = IndexVec::from([rty::AdtSortVariant::new(fields)]);
             Ok(rty::AdtSortDef::new(def_id, params, variants, false, true))
         }
         fhir::RefinementKind::Reflected => {
@@ -496,6 +504,22 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
         }
     }
 
+    /// Converts the `decreases` metric of a spec function. The metric is bound by the parameters of
+    /// the function, same as its body.
+    pub(crate) fn conv_defn_decreases(
+        &mut self,
+        func: &fhir::SpecFunc,
+    ) -> QueryResult<rty::Binder<List<Expr>>> {
+        let mut env = Env::new(&[]);
+        env.push_layer(Layer::list(self.results(), 0, func.args));
+        let decreases = func
+            .decreases
+            .iter()
+            .map(|metric| self.conv_expr(&mut env, metric))
+            .try_collect()?;
+        Ok(rty::Binder::bind_with_vars(decreases, env.pop_layer().into_bound_vars(self.genv())?))
+    }
+
     pub(crate) fn conv_primop_prop(
         &mut self,
         primop_prop: &fhir::PrimOpProp,
@@ -683,8 +707,31 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
             if self.genv().no_panic(fn_id) { Expr::tt() } else { Expr::ff() }
         };
 
-        let fn_sig =
-            self.conv_fn_decl(&mut env, header.safety(), header.abi, decl, body_id, no_panic)?;
+        let decreases = fn_sig
+            .annots
+            .decreases
+            .iter()
+            .map(|metric| self.conv_expr(&mut env, metric))
+            .try_collect()?;
+
+        let loop_invariants = fn_sig
+            .annots
+            .loop_invariants
+            .iter()
+            .map(|invariant| self.conv_loop_invariant(&mut env, invariant))
+            .try_collect()?;
+
+        let fn_sig = self
+            .conv_fn_decl(
+                &mut env,
+                header.safety(),
+                header.abi,
+                decl,
+                body_id,
+                no_panic,
+                decreases,
+            )?
+            .with_loop_invariants(loop_invariants);
 
         let vars = late_bound_regions
             .iter()
@@ -927,6 +974,10 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 self.check_prim_sort_generics(path, fhir::PrimSort::Map)?;
                 rty::SortCtor::Map
             }
+            fhir::SortRes::PrimSort(fhir::PrimSort::Seq) => {
+                self.check_prim_sort_generics(path, fhir::PrimSort::Seq)?;
+                rty::SortCtor::Seq
+            }
             fhir::SortRes::User(def_id) => {
                 self.check_user_defined_sort_param_count(path, def_id)?;
                 rty::SortCtor::User(def_id)
@@ -989,6 +1040,7 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
 
 /// Conversion of types
 impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
+    #[expect(clippy::too_many_arguments)]
     fn conv_fn_decl(
         &mut self,
         env: &mut Env,
@@ -997,6 +1049,7 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
         decl: &fhir::FnDecl,
         body_id: Option<BodyId>,
         no_panic: Expr,
+        decreases: List<Expr>,
     ) -> QueryResult<rty::FnSig> {
         let mut requires = vec![];
         for req in decl.requires {
@@ -1026,6 +1079,7 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
             inputs.into(),
             output,
             no_panic,
+            decreases,
             decl.lifted,
         ))
     }
@@ -1045,6 +1099,30 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
         }
     }
 
+    fn conv_loop_invariant(
+        &mut self,
+        env: &mut Env,
+        invariant: &fhir::LoopInvariant,
+    ) -> QueryResult<rty::LoopInvariant> {
+        env.push_layer(Layer::list(self.results(), 0, invariant.params));
+        let pred = self.conv_expr(env, &invariant.pred)?;
+        let vars = env.pop_layer().into_bound_vars(self.genv())?;
+        let kind = match invariant.kind {
+            fhir::LoopInvariantKind::Invariant => rty::LoopInvariantKind::Invariant,
+            fhir::LoopInvariantKind::Variant => rty::LoopInvariantKind::Variant,
+        };
+        Ok(rty::LoopInvariant {
+            kind,
+            span: invariant.span,
+            locals: invariant
+                .params
+                .iter()
+                .map(|param| rty::LoopLocal { name: param.name, span: param.span })
+                .collect(),
+            pred: rty::Binder::bind_with_vars(pred, vars),
+        })
+    }
+
     fn conv_ensures(
         &mut self,
         env: &mut Env,
@@ -1271,6 +1349,7 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                     bare_fn.decl,
                     None,
                     Expr::ff(),
+                    List::empty(),
                 )?;
                 let vars = bare_fn
                     .generic_params
@@ -1484,6 +1563,13 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 let ty = rty::Ty::indexed(bty, rty::Expr::nu());
                 Ok(rty::TyOrCtor::Ctor(rty::Binder::bind_with_sort(ty, sort)))
             }
+            fhir::BaseTyKind::Pat(ty, pat) => {
+                let bty = rty::BaseTy::Pat(self.conv_ty(env, ty, name)?, (*pat).clone())
+                    .shift_in_escaping(1);
+                let sort = bty.sort();
+                let ty = rty::Ty::indexed(bty, rty::Expr::nu());
+                Ok(rty::TyOrCtor::Ctor(rty::Binder::bind_with_sort(ty, sort)))
+            }
             fhir::BaseTyKind::Err(err) => Err(QueryErr::Emitted(*err)),
         }
     }
@@ -2123,6 +2209,8 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                             } else {
                                 Err(self.emit(errors::InvalidBitVectorConstant::new(span, sort)))?
                             }
+                        } else if let rty::Sort::Real = sort {
+                            Ok(rty::Constant::Real(rty::Real(n)))
                         } else {
                             Ok(rty::Constant::from(n))
                         }
@@ -2219,6 +2307,24 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 let body = rty::Binder::bind_with_vars(pred, vars);
                 rty::Expr::bounded_quant(kind, rng, body)
             }
+            fhir::ExprKind::Quant(kind, params, triggers, body) => {
+                env.push_layer(Layer::list(self.results(), 0, params));
+                let pred = self.conv_expr(env, body)?;
+                let triggers = triggers
+                    .iter()
+                    .map(|trigger| {
+                        let exprs = trigger
+                            .exprs
+                            .iter()
+                            .map(|expr| self.conv_expr(env, expr))
+                            .try_collect()?;
+                        QueryResult::Ok(rty::Trigger { exprs })
+                    })
+                    .try_collect()?;
+                let vars = env.pop_layer().into_bound_vars(self.genv())?;
+                let body = rty::Binder::bind_with_vars(rty::QuantBody { pred, triggers }, vars);
+                rty::Expr::quant(kind, body)
+            }
             fhir::ExprKind::Record(flds) => {
                 let def_id = self.results().record_ctor(expr.fhir_id);
                 let flds = flds
@@ -2234,10 +2340,20 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                     .try_collect()?;
                 rty::Expr::set(elems)
             }
+            fhir::ExprKind::SeqLiteral(elems) => {
+                let elems = elems
+                    .iter()
+                    .map(|expr| self.conv_expr(env, expr))
+                    .try_collect()?;
+                rty::Expr::seq(self.results().node_sort_args(expr.fhir_id), elems)
+            }
             fhir::ExprKind::Constructor(path, exprs, spread) => {
                 let def_id = if let Some(path) = path {
                     match path.res {
-                        fhir::Res::Def(DefKind::Enum | DefKind::Struct, def_id) => def_id,
+                        fhir::Res::Def(
+                            DefKind::Enum | DefKind::Struct | DefKind::Union,
+                            def_id,
+                        ) => def_id,
                         _ => span_bug!(path.span, "unexpected path in constructor"),
                     }
                 } else {
@@ -2435,6 +2551,13 @@ impl<'genv, 'tcx: 'genv, P: ConvPhase<'genv, 'tcx>> ConvCtxt<P> {
                 );
                 (rty::Expr::internal_func(rty::InternalFuncKind::PtrSize), rty::Sort::Func(fsort))
             }
+            fhir::Res::GlobalFunc(fhir::SpecFuncKind::PtrOffset) => {
+                let fsort = rty::PolyFuncSort::new(
+                    List::empty(),
+                    rty::FuncSort::new(vec![rty::Sort::RawPtr], rty::Sort::Int),
+                );
+                (rty::Expr::internal_func(rty::InternalFuncKind::PtrOffset), rty::Sort::Func(fsort))
+            }
             fhir::Res::GlobalFunc(fhir::SpecFuncKind::Cast) => {
                 let fsort = rty::PolyFuncSort::new(
                     List::from_arr([rty::SortParamKind::Sort, rty::SortParamKind::Sort]),
@@ -2953,7 +3076,7 @@ fn transitive_bounds<'tcx>(
 }
 
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::{E0987, E0999};
     use flux_macros::Diagnostic;
     use flux_middle::{fhir, global_env::GlobalEnv, rty::Sort};
     use rustc_hir::def_id::DefId;
@@ -3169,6 +3292,21 @@ mod errors {
         }
     }
 
+    #[derive(Diagnostic)]
+    #[diag(fhir_analysis_invalid_union_tag, code = E0987)]
+    #[note]
+    pub(super) struct InvalidUnionTag {
+        #[primary_span]
+        #[label]
+        span: Span,
+    }
+
+    impl InvalidUnionTag {
+        pub(super) fn new(span: Span) -> Self {
+            Self { span }
+        }
+    }
+
     #[derive(Diagnostic)]
     #[diag(fhir_analysis_incorrect_generics_on_opaque_sort, code = E0999)]
     pub(super) struct IncorrectGenericsOnUserDefinedOpaqueSort {
@@ -368,6 +368,10 @@ impl<'genv, 'tcx> Zipper<'genv, 'tcx> {
             (rty::BaseTy::Foreign(def_id_a), rty::BaseTy::Foreign(def_id_b)) => {
                 assert_eq_or_incompatible(def_id_a, def_id_b)
             }
+            (rty::BaseTy::Pat(ty_a, pat_a), rty::BaseTy::Pat(ty_b, pat_b)) => {
+                assert_eq_or_incompatible(pat_a, pat_b)?;
+                self.zip_ty(ty_a, ty_b)
+            }
             (rty::BaseTy::Closure(..) | rty::BaseTy::Coroutine(..), _) => {
                 bug!("unexpected type `{a:?}`");
             }
@@ -120,8 +120,9 @@ fn try_normalized_defns(genv: GlobalEnv) -> Result<rty::NormalizedDefns, ErrorGu
             continue;
         };
         let mut cx = AfterSortck::new(genv, &wfckresults).into_conv_ctxt();
-        let Ok(defn) = cx.conv_defn(func).emit(&errors) else { continue };
-        defns.push((func.def_id, defn, func.hide));
+        let Ok(body) = cx.conv_defn(func).emit(&errors) else { continue };
+        let Ok(decreases) = cx.conv_defn_decreases(func).emit(&errors) else { continue };
+        defns.push(rty::Defn { def_id: func.def_id, body, decreases, hide: func.hide });
     }
     errors.to_result()?;
 
@@ -201,6 +202,10 @@ fn adt_def(genv: GlobalEnv, def_id: MaybeExternId) -> QueryResult<rty::AdtDef> {
     Ok(rty::AdtDef::new(adt_def, genv.adt_sort_def_of(def_id)?, invariants, is_opaque))
 }
 
+fn pattern_type_base(ty: rustc_middle::ty::Ty) -> rustc_middle::ty::Ty {
+    if let rustc_middle::ty::Pat(base, _) = ty.kind() { *base } else { ty }
+}
+
 fn constant_info(genv: GlobalEnv, def_id: MaybeExternId) -> QueryResult<rty::ConstantInfo> {
     let node = genv.fhir_node(def_id.local_id())?;
     let Some(sort) = genv.sort_of_def_id(def_id.resolved_id()).emit(&genv)? else {
@@ -220,8 +225,10 @@ fn constant_info(genv: GlobalEnv, def_id: MaybeExternId) -> QueryResult<rty::Con
         fhir::Node::Item(fhir::Item { kind: fhir::ItemKind::Const(None), .. })
         | fhir::Node::AnonConst
         | fhir::Node::ImplItem(fhir::ImplItem { kind: fhir::ImplItemKind::Const, .. }) => {
-            // For other constants, we try to evaluate them if they are integral
+            // For other constants, we try to evaluate them if they are integral (or a pattern
+            // type over an integral type)
             if let Some(ty) = tcx.type_of(def_id).no_bound_vars()
+                && let ty = pattern_type_base(ty)
                 && ty.is_integral()
                 && let Ok(val) = tcx.const_eval_poly(def_id.resolved_id())
                 && let Some(val) = val.try_to_scalar_int()
@@ -537,7 +544,11 @@ fn type_of(genv: GlobalEnv, def_id: MaybeExternId) -> QueryResult<rty::EarlyBind
                 }
             }
         }
-        DefKind::Impl { .. } | DefKind::Struct | DefKind::Enum | DefKind::AssocTy => {
+        DefKind::Impl { .. }
+        | DefKind::Struct
+        | DefKind::Enum
+        | DefKind::Union
+        | DefKind::AssocTy => {
             let ty = genv.lower_type_of(def_id)?.skip_binder();
             Refiner::default_for_item(genv, def_id.resolved_id())?
                 .refine_ty_or_base(&ty)?
@@ -643,7 +654,7 @@ fn fn_sig(genv: GlobalEnv, def_id: MaybeExternId) -> QueryResult<rty::EarlyBinde
                 _ => return Err(query_bug!("invalid `DefKind` for ctor node")),
             };
             genv.variant_sig(adt_id, variant_idx)?
-                .map(|sig| sig.to_poly_fn_sig(None))
+                .map(|sig| sig.to_poly_fn_sig())
                 .ok_or_query_err(adt_id)
         }
         node => Err(query_bug!("fn_sig called on unsupported node {node:?}")),
@@ -664,6 +675,7 @@ mod errors {
 
     #[derive(Diagnostic)]
     #[diag(fhir_analysis_definition_cycle, code = E0999)]
+    #[help]
     pub struct DefinitionCycle {
         #[primary_span]
         #[label]
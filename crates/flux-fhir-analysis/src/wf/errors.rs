@@ -1,4 +1,4 @@
-use flux_errors::E0999;
+use flux_errors::{E0998, E0999};
 use flux_macros::Diagnostic;
 use flux_middle::{fhir, rty};
 use rustc_span::{Span, Symbol, symbol::Ident};
@@ -64,7 +64,7 @@ impl MissingEnsures {
 }
 
 #[derive(Diagnostic)]
-#[diag(fhir_analysis_unsupported_primop, code = E0999)]
+#[diag(fhir_analysis_unsupported_primop, code = E0998)]
 pub(super) struct UnsupportedPrimOp {
     #[primary_span]
     span: Span,
@@ -233,6 +233,50 @@ impl ParamNotDetermined {
     }
 }
 
+#[derive(Diagnostic)]
+#[diag(fhir_analysis_trigger_missing_param, code = E0999)]
+pub(super) struct TriggerMissingParam {
+    #[primary_span]
+    #[label]
+    span: Span,
+    name: Symbol,
+}
+
+impl TriggerMissingParam {
+    pub(super) fn new(span: Span, name: Symbol) -> Self {
+        Self { span, name }
+    }
+}
+
+#[derive(Diagnostic)]
+#[diag(fhir_analysis_invalid_trigger, code = E0999)]
+pub(super) struct InvalidTrigger {
+    #[primary_span]
+    #[label]
+    span: Span,
+}
+
+impl InvalidTrigger {
+    pub(super) fn new(span: Span) -> Self {
+        Self { span }
+    }
+}
+
+#[derive(Diagnostic)]
+#[diag(fhir_analysis_interpreted_term_in_trigger, code = E0999)]
+pub(super) struct InterpretedTerm {
+    #[primary_span]
+    #[label]
+    span: Span,
+    descr: String,
+}
+
+impl InterpretedTerm {
+    pub(super) fn new(span: Span, descr: String) -> Self {
+        Self { span, descr }
+    }
+}
+
 #[derive(Diagnostic)]
 #[diag(fhir_analysis_sort_annotation_needed, code = E0999)]
 pub(super) struct SortAnnotationNeeded {
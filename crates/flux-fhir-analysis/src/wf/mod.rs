@@ -16,12 +16,14 @@ use flux_middle::{
     rty::{self, WfckResults},
 };
 use rustc_errors::ErrorGuaranteed;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 use rustc_hir::{
-    OwnerId,
+    HirId, OwnerId,
     def::DefKind,
-    def_id::{CrateNum, DefId, DefIndex},
+    def_id::{CrateNum, DefId, DefIndex, LocalDefId},
+    intravisit,
 };
+use rustc_span::Span;
 
 use self::sortck::{ImplicitParamInferer, InferCtxt};
 use crate::{
@@ -185,7 +187,42 @@ impl<'a, 'genv, 'tcx> Wf<'a, 'genv, 'tcx> {
 
     /// Recursively traverse `node` and declare all refinement parameters
     fn declare_params_for_node(&mut self, node: &fhir::OwnerNode<'genv>) -> Result {
-        visit_refine_params(|vis| vis.visit_node(node), |param| self.declare_param(param))
+        visit_refine_params(|vis| vis.visit_node(node), |param| self.declare_param(param))?;
+        if let Some(fn_sig) = node.fn_sig()
+            && !fn_sig.annots.loop_invariants.is_empty()
+        {
+            self.declare_loop_invariant_params(node.owner_id().local_id().def_id, fn_sig)?;
+        }
+        Ok(())
+    }
+
+    /// The parameters of a loop invariant stand for Rust locals, so instead of inferring their sort
+    /// from their usage we take it from the type of the local they are bound to.
+    fn declare_loop_invariant_params(
+        &mut self,
+        def_id: LocalDefId,
+        fn_sig: &fhir::FnSig<'genv>,
+    ) -> Result {
+        let tcx = self.genv().tcx();
+        let typeck_results = tcx.typeck(def_id);
+        let mut bindings = FxHashMap::default();
+        if let Some(body) = tcx.hir_maybe_body_owned_by(def_id) {
+            intravisit::walk_body(&mut BindingCollector(&mut bindings), body);
+        }
+        for invariant in fn_sig.annots.loop_invariants {
+            for param in invariant.params {
+                let Some(hir_id) = bindings.get(&param.span) else { continue };
+                let ty = typeck_results.node_type(*hir_id);
+                if let Some(sort) = self
+                    .genv()
+                    .sort_of_rust_ty(def_id.to_def_id(), ty)
+                    .emit(&self.genv())?
+                {
+                    self.infcx.declare_param(*param, sort);
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Recursively traverse `invariants` and declare all refinement parameters
@@ -329,6 +366,7 @@ impl<'a, 'genv, 'tcx> Wf<'a, 'genv, 'tcx> {
             }
             fhir::FluxItem::Func(spec_func) => {
                 cx.conv_defn(spec_func)?;
+                cx.conv_defn_decreases(spec_func)?;
             }
             fhir::FluxItem::PrimOpProp(prim_op_prop) => {
                 cx.conv_primop_prop(prim_op_prop)?;
@@ -385,6 +423,9 @@ impl<'genv> fhir::visit::Visitor<'genv> for Wf<'_, 'genv, '_> {
     }
 
     fn visit_func(&mut self, func: &fhir::SpecFunc<'genv>) {
+        for metric in func.decreases {
+            self.check_expr(metric, &rty::Sort::Int);
+        }
         if let Some(body) = &func.body {
             let Ok(output) = self.as_conv_ctxt().conv_sort(&func.sort).emit(&self.errors) else {
                 return;
@@ -431,6 +472,20 @@ impl<'genv> fhir::visit::Visitor<'genv> for Wf<'_, 'genv, '_> {
         self.check_expr(&ret.idx, &expected);
     }
 
+    fn visit_fn_sig(&mut self, sig: &fhir::FnSig<'genv>) {
+        self.visit_fn_decl(sig.decl);
+        for metric in sig.annots.decreases {
+            self.check_expr(metric, &rty::Sort::Int);
+        }
+        for invariant in sig.annots.loop_invariants {
+            let expected = match invariant.kind {
+                fhir::LoopInvariantKind::Invariant => rty::Sort::Bool,
+                fhir::LoopInvariantKind::Variant => rty::Sort::Int,
+            };
+            self.check_expr(&invariant.pred, &expected);
+        }
+    }
+
     fn visit_fn_decl(&mut self, decl: &fhir::FnDecl<'genv>) {
         fhir::visit::walk_fn_decl(self, decl);
         self.check_output_locs(decl);
@@ -503,6 +558,19 @@ where
     }
 }
 
+/// Collects the span of every binding in a body, which is what we use to identify the local a loop
+/// invariant parameter is bound to.
+struct BindingCollector<'a>(&'a mut FxHashMap<Span, HirId>);
+
+impl<'tcx> intravisit::Visitor<'tcx> for BindingCollector<'_> {
+    fn visit_pat(&mut self, pat: &'tcx rustc_hir::Pat<'tcx>) {
+        if let rustc_hir::PatKind::Binding(..) = pat.kind {
+            self.0.insert(pat.span, pat.hir_id);
+        }
+        intravisit::walk_pat(self, pat);
+    }
+}
+
 fn visit_refine_params<'a, F>(visit: impl FnOnce(&mut RefineParamVisitor<F>), f: F) -> Result
 where
     F: FnMut(&fhir::RefineParam<'a>) -> Result,
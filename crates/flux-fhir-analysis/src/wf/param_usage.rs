@@ -127,8 +127,17 @@ impl<'a, 'genv, 'tcx> ParamUsesChecker<'a, 'genv, 'tcx> {
             fhir::ExprKind::BoundedQuant(_, _, _, body) => {
                 self.check_func_params_uses(body, false);
             }
+            fhir::ExprKind::Quant(_, _, triggers, body) => {
+                for trigger in triggers {
+                    for expr in trigger.exprs {
+                        self.check_func_params_uses(expr, false);
+                    }
+                }
+                self.check_func_params_uses(body, false);
+            }
             fhir::ExprKind::Record(exprs)
             | fhir::ExprKind::SetLiteral(exprs)
+            | fhir::ExprKind::SeqLiteral(exprs)
             | fhir::ExprKind::Tuple(exprs) => {
                 for field in exprs {
                     self.check_func_params_uses(field, false);
@@ -154,6 +163,13 @@ impl<'a, 'genv, 'tcx> ParamUsesChecker<'a, 'genv, 'tcx> {
         }
     }
 
+    fn is_union(&self) -> bool {
+        self.infcx
+            .owner
+            .resolved_id()
+            .is_some_and(|def_id| self.infcx.genv.tcx().adt_def(def_id).is_union())
+    }
+
     /// Check that Hindly parameters in `params` appear in a value determined position
     fn check_params_are_value_determined(&mut self, params: &[fhir::RefineParam]) {
         for param in params {
@@ -187,7 +203,13 @@ impl<'genv> fhir::visit::Visitor<'genv> for ParamUsesChecker<'_, 'genv, '_> {
     fn visit_struct_def(&mut self, struct_def: &fhir::StructDef<'genv>) {
         if let fhir::StructKind::Transparent { fields } = struct_def.kind {
             walk_list!(self, visit_field_def, fields);
-            self.check_params_are_value_determined(struct_def.params);
+            // The tag of a union is determined by the field used to build it
+            let params = if self.is_union() {
+                struct_def.params.get(1..).unwrap_or_default()
+            } else {
+                struct_def.params
+            };
+            self.check_params_are_value_determined(params);
         }
     }
 
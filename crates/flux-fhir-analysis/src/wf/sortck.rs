@@ -17,7 +17,7 @@ use flux_middle::{
 use itertools::{Itertools, izip};
 use rustc_data_structures::unord::UnordMap;
 use rustc_errors::Diagnostic;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use rustc_hir::def::DefKind;
 use rustc_middle::ty::TypingMode;
 use rustc_span::{Span, def_id::DefId, symbol::Ident};
@@ -233,6 +233,7 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
             }
             fhir::ExprKind::UnaryOp(..)
             | fhir::ExprKind::SetLiteral(..)
+            | fhir::ExprKind::SeqLiteral(..)
             | fhir::ExprKind::BinaryOp(..)
             | fhir::ExprKind::Dot(..)
             | fhir::ExprKind::App(..)
@@ -240,6 +241,7 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
             | fhir::ExprKind::Var(..)
             | fhir::ExprKind::Literal(..)
             | fhir::ExprKind::BoundedQuant(..)
+            | fhir::ExprKind::Quant(..)
             | fhir::ExprKind::Block(..)
             | fhir::ExprKind::Constructor(..)
             | fhir::ExprKind::PrimApp(..) => {}
@@ -263,6 +265,82 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
         }
     }
 
+    /// Checks that a trigger is a valid SMT pattern and that it mentions all the parameters bound
+    /// by its quantifier. SMT solvers silently ignore triggers that don't.
+    fn check_trigger(&self, params: &[fhir::RefineParam], trigger: &fhir::Trigger) -> Result {
+        #[derive(Default)]
+        struct ParamCollector(FxHashSet<fhir::ParamId>);
+
+        impl<'v> fhir::visit::Visitor<'v> for ParamCollector {
+            fn visit_path_expr(&mut self, path: &fhir::PathExpr<'v>) {
+                if let fhir::Res::Param(_, id) = path.res {
+                    self.0.insert(id);
+                }
+            }
+        }
+
+        for expr in trigger.exprs {
+            if !matches!(expr.kind, fhir::ExprKind::App(..) | fhir::ExprKind::Alias(..)) {
+                return Err(self.emit_err(errors::InvalidTrigger::new(expr.span)));
+            }
+            self.check_trigger_term(expr)?;
+        }
+
+        let mut collector = ParamCollector::default();
+        for expr in trigger.exprs {
+            collector.visit_expr(expr);
+        }
+        for param in params {
+            if !collector.0.contains(&param.id) {
+                return Err(
+                    self.emit_err(errors::TriggerMissingParam::new(trigger.span, param.name))
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that a term in a trigger is built only from variables, literals and applications of
+    /// uninterpreted functions. Interpreted operators (e.g., arithmetic or equality) cannot be
+    /// matched by the solver.
+    fn check_trigger_term(&self, expr: &fhir::Expr) -> Result {
+        let descr = match expr.kind {
+            fhir::ExprKind::Var(..) | fhir::ExprKind::Literal(..) | fhir::ExprKind::Err(_) => {
+                return Ok(());
+            }
+            fhir::ExprKind::Dot(base, _) => return self.check_trigger_term(base),
+            fhir::ExprKind::App(_, args) | fhir::ExprKind::Alias(_, args) => {
+                return args.iter().try_for_each(|arg| self.check_trigger_term(arg));
+            }
+            fhir::ExprKind::Tuple(exprs) | fhir::ExprKind::Record(exprs) => {
+                return exprs.iter().try_for_each(|e| self.check_trigger_term(e));
+            }
+            fhir::ExprKind::Constructor(_, fields, None) => {
+                return fields
+                    .iter()
+                    .try_for_each(|field| self.check_trigger_term(&field.expr));
+            }
+            fhir::ExprKind::BinaryOp(fhir::BinOp::Eq | fhir::BinOp::Ne, ..) => {
+                "equality".to_string()
+            }
+            fhir::ExprKind::BinaryOp(op, ..) | fhir::ExprKind::PrimApp(op, ..) => {
+                format!("the interpreted operator `{op:?}`")
+            }
+            fhir::ExprKind::UnaryOp(op, _) => format!("the interpreted operator `{op:?}`"),
+            fhir::ExprKind::IfThenElse(..) => "an `if` expression".to_string(),
+            fhir::ExprKind::BoundedQuant(..) | fhir::ExprKind::Quant(..) => {
+                "a quantifier".to_string()
+            }
+            fhir::ExprKind::Abs(..) => "a lambda".to_string(),
+            fhir::ExprKind::Block(..) => "a `let` binding".to_string(),
+            fhir::ExprKind::SetLiteral(..) | fhir::ExprKind::SeqLiteral(..) => {
+                "a collection literal".to_string()
+            }
+            fhir::ExprKind::Constructor(..) => "a struct update".to_string(),
+        };
+        Err(self.emit_err(errors::InterpretedTerm::new(expr.span, descr)))
+    }
+
     fn synth_lit(&mut self, lit: fhir::Lit, expr: &fhir::Expr<'genv>) -> rty::Sort {
         match lit {
             fhir::Lit::Int(_, Some(fhir::NumLitKind::Int)) => rty::Sort::Int,
@@ -323,6 +401,16 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
                 self.check_expr(body, &rty::Sort::Bool)?;
                 Ok(rty::Sort::Bool)
             }
+            fhir::ExprKind::Quant(_, params, triggers, body) => {
+                for trigger in triggers {
+                    for expr in trigger.exprs {
+                        self.synth_expr(expr)?;
+                    }
+                    self.check_trigger(params, trigger)?;
+                }
+                self.check_expr(body, &rty::Sort::Bool)?;
+                Ok(rty::Sort::Bool)
+            }
             fhir::ExprKind::Alias(_alias_reft, args) => {
                 // To check the application we only need the sort of `_alias_reft` which we collected
                 // during early conv, but should we do any extra checks on `_alias_reft`?
@@ -379,7 +467,9 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
                 // first get the sort based on the path - for example S { ... } => S
                 // and we should expect sort to be a struct or enum app
                 let path_def_id = match path.res {
-                    fhir::Res::Def(DefKind::Enum | DefKind::Struct, def_id) => def_id,
+                    fhir::Res::Def(DefKind::Enum | DefKind::Struct | DefKind::Union, def_id) => {
+                        def_id
+                    }
                     _ => span_bug!(expr.span, "unexpected path in constructor"),
                 };
                 let sort_def = self
@@ -415,6 +505,17 @@ impl<'genv, 'tcx> InferCtxt<'genv, 'tcx> {
                 }
                 Ok(rty::Sort::App(rty::SortCtor::Set, List::singleton(elem_sort)))
             }
+            fhir::ExprKind::SeqLiteral(elems) => {
+                let elem_sort = self.next_sort_var();
+                for elem in elems {
+                    self.check_expr(elem, &elem_sort)?;
+                }
+                // A sequence literal is elaborated into applications of the (polymorphic) sequence
+                // functions, so we record the sort of the elements as if it were an application.
+                self.sort_args_of_app
+                    .insert(*expr, List::singleton(rty::SortArg::Sort(elem_sort.clone())));
+                Ok(rty::Sort::App(rty::SortCtor::Seq, List::singleton(elem_sort)))
+            }
             fhir::ExprKind::Tuple(exprs) => {
                 let sorts = exprs
                     .iter()
@@ -25,16 +25,17 @@ use flux_middle::{
     global_env::GlobalEnv,
     metrics::{self, Metric, TimingKind},
     pretty::{NestedString, PrettyCx, PrettyNested},
-    queries::QueryResult,
-    query_bug,
+    queries::{QueryErr, QueryResult},
+    query_bug, report,
     rty::{
         self, ESpan, EarlyReftParam, GenericArgsExt, InternalFuncKind, Lambda, List,
         NameProvenance, PrettyMap, PrettyVar, SpecFuncKind, VariantIdx, fold::TypeFoldable as _,
     },
 };
+use flux_rustc_bridge::lowering::UnsupportedErr;
 use itertools::Itertools;
 use liquid_fixpoint::{
-    FixpointStatus, KVarBind, SmtSolver, VerificationResult,
+    FixpointStatus, KVarBind, Limits, SmtSolver, VerificationResult,
     parser::{FromSexp, ParseError},
     sexp::Parser,
 };
@@ -55,7 +56,13 @@ use crate::{
     lean_encoding::LeanEncoder, projections::structurally_normalize_expr,
 };
 
+mod counterexample;
 pub mod decoding;
+mod queue;
+mod unfold;
+
+pub use counterexample::Counterexample;
+pub use queue::{FixpointQueue, item_index};
 
 pub mod fixpoint {
     use std::fmt;
@@ -256,13 +263,18 @@ pub struct ParsedResult {
     pub status: FixpointStatus<TagIdx>,
     pub solution: FxIndexMap<fixpoint::KVid, FixpointSolution>,
     pub non_cut_solution: FxIndexMap<fixpoint::KVid, FixpointSolution>,
+    pub counterexamples: FxIndexMap<TagIdx, Counterexample>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct Answer<Tag> {
     pub errors: Vec<Tag>,
     pub cut_solution: Solution,
     pub non_cut_solution: Solution,
+    /// Counterexamples for (some of) the errors. Only computed if `-Fcounterexamples` is set.
+    pub counterexamples: FxIndexMap<Tag, Counterexample>,
+    /// The resource that was exhausted if fixpoint was stopped before answering the query
+    pub exhausted: Option<Resource>,
 }
 
 impl<Tag> Answer<Tag> {
@@ -271,6 +283,8 @@ impl<Tag> Answer<Tag> {
             errors: Vec::new(),
             cut_solution: FxIndexMap::default(),
             non_cut_solution: FxIndexMap::default(),
+            counterexamples: FxIndexMap::default(),
+            exhausted: None,
         }
     }
 
@@ -307,6 +321,9 @@ pub struct SortEncodingCtxt {
     adt_sorts: FxIndexSet<DefId>,
     /// Set of all opaque types that need to be defined
     opaque_sorts: FxIndexSet<FluxDefId>,
+    /// Whether the constraint mentions sequences. The Haskell implementation of fixpoint doesn't
+    /// support the theory of sequences, so we report these constraints as unsupported.
+    uses_seq: bool,
 }
 
 impl SortEncodingCtxt {
@@ -335,6 +352,11 @@ impl SortEncodingCtxt {
                 let args = args.iter().map(|s| self.sort_to_fixpoint(s)).collect_vec();
                 fixpoint::Sort::App(fixpoint::SortCtor::Map, args)
             }
+            rty::Sort::App(rty::SortCtor::Seq, args) => {
+                self.uses_seq = true;
+                let args = args.iter().map(|s| self.sort_to_fixpoint(s)).collect_vec();
+                fixpoint::Sort::App(fixpoint::SortCtor::Seq, args)
+            }
             rty::Sort::App(rty::SortCtor::Adt(sort_def), args) => {
                 if let Some(variant) = sort_def.opt_struct_variant() {
                     let sorts = variant.field_sorts(args);
@@ -541,6 +563,7 @@ enum ConstKey<'tcx> {
     PrimOp(rty::BinOp),
     Cast(rty::Sort, rty::Sort),
     PtrSize,
+    PtrOffset,
 }
 
 #[derive(Clone)]
@@ -562,7 +585,7 @@ pub struct FixpointCtxt<'genv, 'tcx, T: Eq + Hash> {
 
 pub type FixQueryCache = QueryCache<VerificationResult<TagIdx>>;
 
-pub use liquid_fixpoint::LeanStatus;
+pub use liquid_fixpoint::{LeanStatus, Resource};
 
 /// Returns the cache key used for a function-body lean query.
 pub fn lean_task_key(tcx: rustc_middle::ty::TyCtxt, def_id: DefId) -> String {
@@ -597,6 +620,7 @@ where
         constraint: fixpoint::Constraint,
         scrape_quals: bool,
         solver: SmtSolver,
+        limits: Limits,
     ) -> QueryResult<fixpoint::Task> {
         let kvars = self.kcx.encode_kvars(&self.kvars, &mut self.scx);
 
@@ -641,6 +665,15 @@ where
         // We are done encoding expressions. Check if there are any errors.
         self.ecx.errors.to_result()?;
 
+        if self.scx.uses_seq
+            && matches!(self.ecx.backend, Backend::Fixpoint)
+            && !cfg!(feature = "rust-fixpoint")
+        {
+            let descr = "sequences require flux to be built with the `rust-fixpoint` feature";
+            let err = UnsupportedErr { descr: descr.to_string(), span: None };
+            return Err(QueryErr::unsupported(def_id.resolved_id(), err));
+        }
+
         let task = fixpoint::Task {
             comments: self.comments.clone(),
             constants,
@@ -650,6 +683,7 @@ where
             qualifiers,
             scrape_quals,
             solver,
+            limits,
             data_decls: self.scx.encode_data_decls(self.genv)?,
         };
 
@@ -667,27 +701,60 @@ where
         kind: FixpointQueryKind,
         task: &fixpoint::Task,
     ) -> QueryResult<ParsedResult> {
-        let result = Self::run_task_with_cache(self.genv, task, def_id.resolved_id(), kind, cache);
+        let result = Self::run_task_with_cache(self.genv, task, def_id, kind, cache);
+        Ok(self.parse_result(def_id, task, result))
+    }
+
+    fn parse_result(
+        &mut self,
+        def_id: MaybeExternId,
+        task: &fixpoint::Task,
+        result: VerificationResult<TagIdx>,
+    ) -> ParsedResult {
+        let counterexamples = if config::counterexamples() {
+            self.counterexamples(task, &result)
+        } else {
+            FxIndexMap::default()
+        };
 
         if config::dump_checker_trace_info()
             || self.genv.proven_externally(def_id.local_id()).is_some()
         {
-            Ok(ParsedResult {
+            ParsedResult {
                 status: result.status,
                 solution: self.parse_kvar_solutions(&result.solution),
                 non_cut_solution: self.parse_kvar_solutions(&result.non_cuts_solution),
-            })
+                counterexamples,
+            }
         } else {
-            Ok(ParsedResult {
+            ParsedResult {
                 status: result.status,
                 solution: FxIndexMap::default(),
                 non_cut_solution: FxIndexMap::default(),
-            })
+                counterexamples,
+            }
         }
     }
 
+    /// Parses the result of running `task` and converts it into an [`Answer`].
+    pub(crate) fn answer_for_result(
+        &mut self,
+        def_id: MaybeExternId,
+        task: &fixpoint::Task,
+        result: VerificationResult<TagIdx>,
+    ) -> Answer<Tag> {
+        let result = self.parse_result(def_id, task, result);
+        self.result_to_answer(result)
+    }
+
     pub(crate) fn result_to_answer(&mut self, result: ParsedResult) -> Answer<Tag> {
         let def_span = self.ecx.def_span();
+        if let Some(def_id) = self.ecx.def_id
+            && let FixpointStatus::Safe(stats) | FixpointStatus::Unsafe(stats, _) = &result.status
+        {
+            report::record_stats(def_id.local_id(), stats);
+        }
+        let mut exhausted = None;
         let errors = match result.status {
             FixpointStatus::Safe(_) => vec![],
             FixpointStatus::Unsafe(_, errors) => {
@@ -699,6 +766,10 @@ where
                     .collect_vec()
             }
             FixpointStatus::Crash(err) => span_bug!(def_span, "fixpoint crash: {err:?}"),
+            FixpointStatus::Exhausted(resource) => {
+                exhausted = Some(resource);
+                vec![]
+            }
         };
 
         let cut_solution = result
@@ -713,10 +784,18 @@ where
             .map(|(kvid, sol)| (kvid, self.fixpoint_to_solution(&sol)))
             .collect_vec();
 
+        let counterexamples = result
+            .counterexamples
+            .into_iter()
+            .map(|(tag, cex)| (self.tags[tag], cex))
+            .collect();
+
         Answer {
             errors,
             cut_solution: self.kcx.group_kvar_solution(cut_solution),
             non_cut_solution: self.kcx.group_kvar_solution(non_cut_solution),
+            counterexamples,
+            exhausted,
         }
     }
 
@@ -731,14 +810,16 @@ where
     }
 
     fn parse_kvar_solution(&mut self, expr: &str) -> FixpointSolution {
+        self.try_parse_kvar_solution(expr)
+            .unwrap_or_else(|err| tracked_span_bug!("{err}"))
+    }
+
+    fn try_parse_kvar_solution(&mut self, expr: &str) -> Result<FixpointSolution, String> {
         // 1. convert str -> sexp
         let mut sexp_parser = Parser::new(expr);
-        let sexp = match sexp_parser.parse() {
-            Ok(sexp) => sexp,
-            Err(err) => {
-                tracked_span_bug!("cannot parse sexp: {expr:?}: {err:?}");
-            }
-        };
+        let sexp = sexp_parser
+            .parse()
+            .map_err(|err| format!("cannot parse sexp: {expr:?}: {err:?}"))?;
         let mut fun_decl_map = HashMap::new();
         for (def_id, var) in &self.ecx.const_env.fun_decl_map {
             let fixpoint::Var::Global(idx, _) = var else {
@@ -756,9 +837,9 @@ where
         let mut sexp_ctx =
             SexpParseCtxt::new(&mut self.ecx.local_var_env, &fun_decl_map, &const_decl_map)
                 .into_wrapper();
-        sexp_ctx.parse_solution(&sexp).unwrap_or_else(|err| {
-            tracked_span_bug!("failed to parse solution sexp {sexp:?}: {err:?}");
-        })
+        sexp_ctx
+            .parse_solution(&sexp)
+            .map_err(|err| format!("failed to parse solution sexp {sexp:?}: {err:?}"))
     }
 
     fn is_assumed_constant(&self, const_decl: &fixpoint::ConstDecl) -> bool {
@@ -872,24 +953,22 @@ where
     fn run_task_with_cache(
         genv: GlobalEnv,
         task: &fixpoint::Task,
-        def_id: DefId,
+        def_id: MaybeExternId,
         kind: FixpointQueryKind,
         cache: &mut FixQueryCache,
     ) -> VerificationResult<TagIdx> {
-        let key = kind.task_key(genv.tcx(), def_id);
+        let key = kind.task_key(genv.tcx(), def_id.resolved_id());
 
         let hash = task.hash_with_default();
 
-        if config::is_cache_enabled()
-            && let Some(result) = cache.lookup(&key, hash)
-        {
-            metrics::incr_metric_if(kind.is_body(), Metric::FnCached);
-            return result.clone();
+        if let Some(result) = Self::lookup_cache(cache, def_id, kind, &key, hash, task.limits) {
+            return result;
         }
-        let result = metrics::time_it(TimingKind::FixpointQuery(def_id, kind), || {
-            task.run()
-                .unwrap_or_else(|err| tracked_span_bug!("failed to run fixpoint: {err}"))
-        });
+        let result =
+            metrics::time_it(TimingKind::FixpointQuery(def_id.resolved_id(), kind), || {
+                task.run()
+                    .unwrap_or_else(|err| tracked_span_bug!("failed to run fixpoint: {err}"))
+            });
 
         if config::is_cache_enabled() {
             cache.insert(key, hash, result.clone());
@@ -898,6 +977,32 @@ where
         result
     }
 
+    fn lookup_cache(
+        cache: &FixQueryCache,
+        def_id: MaybeExternId,
+        kind: FixpointQueryKind,
+        key: &str,
+        hash: u64,
+        limits: Limits,
+    ) -> Option<VerificationResult<TagIdx>> {
+        if !config::is_cache_enabled() {
+            return None;
+        }
+        let result = cache.lookup(key, hash)?;
+        // A query that exhausted a resource in a previous run is only run again if the limit on
+        // the resource was raised.
+        if let FixpointStatus::Exhausted(resource) = result.status
+            && !resource.is_exhausted_with(limits)
+        {
+            return None;
+        }
+        metrics::incr_metric_if(kind.is_body(), Metric::FnCached);
+        if kind.is_body() {
+            report::record_cached(def_id.local_id());
+        }
+        Some(result.clone())
+    }
+
     fn tag_idx(&mut self, tag: Tag) -> TagIdx
     where
         Tag: std::fmt::Debug,
@@ -1208,6 +1313,9 @@ struct LocalVarEnv {
     /// need to keep the output as an [`rty::Expr`] to reflect this.
     reverse_map: UnordMap<fixpoint::LocalVar, rty::Expr>,
     pretty_var_map: PrettyMap<fixpoint::LocalVar>,
+    /// Source names of the variables introduced by unfolding a named bound refinement, used to
+    /// report counterexamples.
+    source_names: UnordMap<fixpoint::LocalVar, Symbol>,
 }
 
 impl LocalVarEnv {
@@ -1218,6 +1326,7 @@ impl LocalVarEnv {
             layers: Vec::new(),
             reverse_map: Default::default(),
             pretty_var_map: PrettyMap::new(),
+            source_names: Default::default(),
         }
     }
 
@@ -1237,6 +1346,9 @@ impl LocalVarEnv {
         self.reverse_map.insert(fresh, rty::Expr::fvar(name));
         self.pretty_var_map
             .set(PrettyVar::Local(fresh), provenance.opt_symbol());
+        if let Some(name) = provenance.opt_symbol() {
+            self.source_names.insert(fresh, name);
+        }
         fresh
     }
 
@@ -1634,7 +1746,7 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
                         ])))
                     }
                     rty::CastKind::IntoUnit => self.expr_to_fixpoint(&rty::Expr::unit(), scx),
-                    rty::CastKind::Uninterpreted => {
+                    rty::CastKind::IntToReal | rty::CastKind::Uninterpreted => {
                         let func = fixpoint::Expr::Var(self.define_const_for_cast(from, to, scx));
                         let args = self.exprs_to_fixpoint(args, scx)?;
                         Ok(fixpoint::Expr::App(Box::new(func), None, args, None))
@@ -1642,7 +1754,14 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
                 }
             }
             InternalFuncKind::PtrSize => {
-                let func = fixpoint::Expr::Var(self.define_const_for_ptr_size(scx));
+                let func =
+                    fixpoint::Expr::Var(self.define_const_for_ptr_func(ConstKey::PtrSize, scx));
+                let args = self.exprs_to_fixpoint(args, scx)?;
+                Ok(fixpoint::Expr::App(Box::new(func), None, args, None))
+            }
+            InternalFuncKind::PtrOffset => {
+                let func =
+                    fixpoint::Expr::Var(self.define_const_for_ptr_func(ConstKey::PtrOffset, scx));
                 let args = self.exprs_to_fixpoint(args, scx)?;
                 Ok(fixpoint::Expr::App(Box::new(func), None, args, None))
             }
@@ -1729,7 +1848,19 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
 
                 fixpoint::Expr::Let(vars[0].into(), Box::new([init, body]))
             }
-            rty::ExprKind::GlobalFunc(SpecFuncKind::Thy(itf)) => fixpoint::Expr::ThyFunc(*itf),
+            rty::ExprKind::GlobalFunc(SpecFuncKind::Thy(itf)) => {
+                scx.uses_seq |= matches!(
+                    itf,
+                    fixpoint::ThyFunc::SeqEmpty
+                        | fixpoint::ThyFunc::SeqUnit
+                        | fixpoint::ThyFunc::SeqLen
+                        | fixpoint::ThyFunc::SeqNth
+                        | fixpoint::ThyFunc::SeqConcat
+                        | fixpoint::ThyFunc::SeqExtract
+                        | fixpoint::ThyFunc::SeqUpdate
+                );
+                fixpoint::Expr::ThyFunc(*itf)
+            }
             rty::ExprKind::GlobalFunc(SpecFuncKind::Def(def_id)) => {
                 fixpoint::Expr::Var(self.declare_fun(*def_id))
             }
@@ -1745,6 +1876,7 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
             | rty::ExprKind::InternalFunc(_) => {
                 span_bug!(self.def_span(), "unexpected expr: `{expr:?}`")
             }
+            rty::ExprKind::Quant(kind, body) => self.quant_to_fixpoint(*kind, body, scx)?,
             rty::ExprKind::BoundedQuant(kind, rng, body) => {
                 let exprs = (rng.start..rng.end).map(|i| {
                     let arg = rty::Expr::constant(rty::Constant::from(i));
@@ -2132,8 +2264,13 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
             .name
     }
 
-    fn define_const_for_ptr_size(&mut self, scx: &mut SortEncodingCtxt) -> fixpoint::Var {
-        let key = ConstKey::PtrSize;
+    /// Defines the UIF for [`ConstKey::PtrSize`] or [`ConstKey::PtrOffset`], both `RawPtr -> Int`
+    fn define_const_for_ptr_func(
+        &mut self,
+        key: ConstKey<'tcx>,
+        scx: &mut SortEncodingCtxt,
+    ) -> fixpoint::Var {
+        let name = if let ConstKey::PtrSize = key { "ptr_size" } else { "ptr_offset" };
         self.const_env
             .get_or_insert(key, |global_name| {
                 let fsort = rty::FuncSort::new(vec![rty::Sort::RawPtr], rty::Sort::Int);
@@ -2142,7 +2279,7 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
                 fixpoint::ConstDecl {
                     name: fixpoint::Var::Const(global_name, None),
                     sort,
-                    comment: Some("ptr_size uif: RawPtr -> Int".to_string()),
+                    comment: Some(format!("{name} uif: RawPtr -> Int")),
                 }
             })
             .name
@@ -2289,7 +2426,8 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
                 | ConstKey::Cast(..)
                 | ConstKey::Lambda(..)
                 | ConstKey::PrimOp(..)
-                | ConstKey::PtrSize => {}
+                | ConstKey::PtrSize
+                | ConstKey::PtrOffset => {}
             }
         }
         Ok(constraint)
@@ -2327,11 +2465,17 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
 
             let info = self.genv.normalized_info(did);
             let revealed = reveals.contains(&did);
-            let def = if info.uif || (info.hide && !revealed && proven_externally.is_none()) {
-                self.fun_decl_to_fixpoint(did, scx)
-            } else {
-                self.fun_def_to_fixpoint(did, scx)?
-            };
+            // Recursive functions cannot be encoded as a `define-fun`, they are declared as
+            // uninterpreted and unfolded where needed, except in lean where we emit their
+            // recursive definition.
+            let recursive = info.recursive && matches!(self.backend, Backend::Fixpoint);
+            let def =
+                if info.uif || recursive || (info.hide && !revealed && proven_externally.is_none())
+                {
+                    self.fun_decl_to_fixpoint(did, scx)
+                } else {
+                    self.fun_def_to_fixpoint(did, scx)?
+                };
             defs.push((info.rank, def));
         }
 
@@ -2363,12 +2507,29 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
         let name = *self.const_env.fun_decl_map.get(&def_id).unwrap();
         let body = self.genv.inlined_body(def_id);
         let output = scx.sort_to_fixpoint(self.genv.func_sort(def_id).expect_mono().output());
-        let (args, expr) = self.body_to_fixpoint(&body, scx)?;
-        let (args, inputs) = args.into_iter().unzip();
+        // The `decreases` metric is bound by the same parameters as the body, so both are encoded
+        // in the same layer.
+        self.local_var_env
+            .push_layer_with_fresh_names(body.vars().len());
+        let expr = self.expr_to_fixpoint(body.skip_binder_ref(), scx)?;
+        let decreases = if self.genv.normalized_info(def_id).recursive {
+            self.genv
+                .normalized_defns(def_id.krate())
+                .decreases(def_id)
+                .skip_binder_ref()
+                .iter()
+                .map(|metric| self.expr_to_fixpoint(metric, scx))
+                .try_collect()?
+        } else {
+            vec![]
+        };
+        let (args, inputs) = iter::zip(self.local_var_env.pop_layer(), body.vars())
+            .map(|(name, var)| (fixpoint::Var::from(name), scx.sort_to_fixpoint(var.expect_sort())))
+            .unzip();
         Ok(fixpoint::FunDef {
             name,
             sort: fixpoint::FunSort { params: 0, inputs, output },
-            body: Some(fixpoint::FunBody { args, expr }),
+            body: Some(fixpoint::FunBody { args, expr, decreases }),
             comment: Some(format!("flux def: {def_id:?}")),
         })
     }
@@ -2391,6 +2552,51 @@ impl<'genv, 'tcx> ExprEncodingCtxt<'genv, 'tcx> {
         Ok((args, expr))
     }
 
+    fn quant_to_fixpoint(
+        &mut self,
+        kind: flux_middle::fhir::QuantKind,
+        body: &rty::Binder<rty::QuantBody>,
+        scx: &mut SortEncodingCtxt,
+    ) -> QueryResult<fixpoint::Expr> {
+        self.local_var_env
+            .push_layer_with_fresh_names(body.vars().len());
+
+        let rty::QuantBody { pred, triggers } = body.skip_binder_ref();
+        let pred = self.expr_to_fixpoint(pred, scx)?;
+        let triggers: Vec<Vec<_>> = triggers
+            .iter()
+            .map(|trigger| {
+                trigger
+                    .exprs
+                    .iter()
+                    .map(|e| self.expr_to_fixpoint(e, scx))
+                    .try_collect()
+            })
+            .try_collect()?;
+
+        let binders: Vec<(fixpoint::Var, fixpoint::Sort)> =
+            iter::zip(self.local_var_env.pop_layer(), body.vars())
+                .map(|(name, var)| (name.into(), scx.sort_to_fixpoint(var.expect_sort())))
+                .collect();
+
+        let expr = match kind {
+            flux_middle::fhir::QuantKind::Forall => {
+                fixpoint::Expr::ForAll(binders, triggers, Box::new(pred))
+            }
+            flux_middle::fhir::QuantKind::Exists if triggers.is_empty() => {
+                fixpoint::Expr::Exists(binders, Box::new(pred))
+            }
+            flux_middle::fhir::QuantKind::Exists => {
+                // Triggers can only be attached to universal quantifiers, so we encode
+                // `∃x. p` as `¬∀x. ¬p`.
+                let pred = fixpoint::Expr::Not(Box::new(pred));
+                let forall = fixpoint::Expr::ForAll(binders, triggers, Box::new(pred));
+                fixpoint::Expr::Not(Box::new(forall))
+            }
+        };
+        Ok(expr)
+    }
+
     fn qualifier_to_fixpoint(
         &mut self,
         qualifier: &rty::Qualifier,
@@ -0,0 +1,256 @@
+//! Counterexample models for failed constraints.
+//!
+//! When fixpoint reports an error we take the path in the constraint leading to the failing head,
+//! replace kvars with their solution, and ask the SMT solver for a model of the hypotheses
+//! together with the negated head (see [`liquid_fixpoint::ModelQuery`]). The values in the model
+//! are then mapped back to the source names of the refinement variables in scope.
+use std::fmt;
+
+use itertools::Itertools;
+use liquid_fixpoint::{FixpointStatus, ModelQuery, VerificationResult};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_span::Symbol;
+
+use super::{FixpointCtxt, FixpointSolution, TagIdx, fixpoint, parse_kvid};
+
+/// Concrete values for (some of) the refinement variables in scope at a failed constraint.
+#[derive(Debug, Clone)]
+pub struct Counterexample {
+    values: Vec<(String, String)>,
+    /// Whether some hypotheses in scope could not be encoded in the query, in which case the
+    /// values may not satisfy them.
+    possibly_spurious: bool,
+}
+
+impl Counterexample {
+    pub fn is_possibly_spurious(&self) -> bool {
+        self.possibly_spurious
+    }
+}
+
+impl fmt::Display for Counterexample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = self
+            .values
+            .iter()
+            .format_with(", ", |(name, value), f| f(&format_args!("{name} = {value}")));
+        write!(f, "{values}")
+    }
+}
+
+type KVarSolutions = FxHashMap<fixpoint::KVid, FixpointSolution>;
+
+impl<Tag> FixpointCtxt<'_, '_, Tag>
+where
+    Tag: std::hash::Hash + Eq + Copy,
+{
+    pub(crate) fn counterexamples(
+        &mut self,
+        task: &fixpoint::Task,
+        result: &VerificationResult<TagIdx>,
+    ) -> FxIndexMap<TagIdx, Counterexample> {
+        let FixpointStatus::Unsafe(_, errors) = &result.status else {
+            return FxIndexMap::default();
+        };
+        let solutions: KVarSolutions = result
+            .solution
+            .iter()
+            .chain(&result.non_cuts_solution)
+            .filter_map(|bind| {
+                Some((parse_kvid(&bind.kvar), self.try_parse_kvar_solution(&bind.val).ok()?))
+            })
+            .collect();
+
+        let mut counterexamples = FxIndexMap::default();
+        for tag in errors.iter().map(|err| err.tag).unique() {
+            let mut paths = vec![];
+            collect_paths(&task.constraint, tag, &mut vec![], &mut paths);
+            if let Some(cex) = paths.iter().find_map(|(binds, head)| {
+                self.counterexample_for_path(task, &solutions, binds, head)
+            }) {
+                counterexamples.insert(tag, cex);
+            }
+        }
+        counterexamples
+    }
+
+    fn counterexample_for_path(
+        &self,
+        task: &fixpoint::Task,
+        solutions: &KVarSolutions,
+        binds: &[&fixpoint::Bind],
+        head: &fixpoint::Pred,
+    ) -> Option<Counterexample> {
+        let goal = pred_to_expr(head, solutions, None)?;
+        let mut weakened = false;
+        let hyps = binds
+            .iter()
+            .filter_map(|bind| pred_to_expr(&bind.pred, solutions, Some(&mut weakened)))
+            .collect();
+        let (labels, observations): (Vec<_>, Vec<_>) = self.observations(binds).into_iter().unzip();
+
+        let query = ModelQuery {
+            constants: &task.constants,
+            data_decls: &task.data_decls,
+            define_funs: &task.define_funs,
+            binds: binds
+                .iter()
+                .map(|bind| (bind.name, bind.sort.clone()))
+                .collect(),
+            hyps,
+            goal,
+            observations,
+            solver: task.solver,
+            limits: task.limits,
+        };
+        let model = match query.run() {
+            Ok(model) => model?,
+            Err(err) => {
+                tracing::warn!("failed to compute counterexample: {err}");
+                return None;
+            }
+        };
+        let values = std::iter::zip(labels, model.values)
+            .filter_map(|(label, value)| Some((label, value?)))
+            .collect_vec();
+        if values.is_empty() {
+            None
+        } else {
+            Some(Counterexample { values, possibly_spurious: weakened || model.possibly_spurious })
+        }
+    }
+
+    /// Returns the expressions whose value we want to report together with the name used to
+    /// display them. We only report variables with a source name; if a name is bound more than
+    /// once, the innermost binding wins. Variables of a struct sort are reported field by field.
+    fn observations(&self, binds: &[&fixpoint::Bind]) -> Vec<(String, fixpoint::Expr)> {
+        let mut named: FxIndexMap<Symbol, &fixpoint::Bind> = FxIndexMap::default();
+        for bind in binds {
+            let name = match &bind.name {
+                fixpoint::Var::Local(var) => self.ecx.local_var_env.source_names.get(var).copied(),
+                fixpoint::Var::Param(param) => Some(param.name),
+                _ => None,
+            };
+            if let Some(name) = name {
+                named.insert(name, bind);
+            }
+        }
+
+        let mut observations = vec![];
+        for (name, bind) in named {
+            let var = fixpoint::Expr::Var(bind.name);
+            let proj = |field| {
+                fixpoint::Expr::App(
+                    Box::new(fixpoint::Expr::Var(field)),
+                    None,
+                    vec![var.clone()],
+                    None,
+                )
+            };
+            match &bind.sort {
+                fixpoint::Sort::App(
+                    fixpoint::SortCtor::Data(fixpoint::DataSort::Adt(adt_id)),
+                    _,
+                ) => {
+                    let did = self.scx.adt_sorts[adt_id.as_usize()];
+                    let Ok(sort_def) = self.genv.adt_sort_def_of(did) else { continue };
+                    let Some(variant) = sort_def.opt_struct_variant() else { continue };
+                    for (i, field) in variant.field_names().iter().enumerate() {
+                        let field_var =
+                            fixpoint::Var::DataProj { adt_id: *adt_id, field: i as u32 };
+                        observations.push((format!("{name}.{field}"), proj(field_var)));
+                    }
+                }
+                fixpoint::Sort::App(
+                    fixpoint::SortCtor::Data(fixpoint::DataSort::Tuple(arity)),
+                    _,
+                ) => {
+                    for field in 0..*arity as u32 {
+                        let field_var = fixpoint::Var::TupleProj { arity: *arity, field };
+                        observations.push((format!("{name}.{field}"), proj(field_var)));
+                    }
+                }
+                _ => observations.push((format!("{name}"), var)),
+            }
+        }
+        observations
+    }
+}
+
+/// Collects all the paths in `cstr` leading to a head labeled with `tag`.
+fn collect_paths<'a>(
+    cstr: &'a fixpoint::Constraint,
+    tag: TagIdx,
+    binds: &mut Vec<&'a fixpoint::Bind>,
+    paths: &mut Vec<(Vec<&'a fixpoint::Bind>, &'a fixpoint::Pred)>,
+) {
+    match cstr {
+        fixpoint::Constraint::Pred(pred, Some(t)) if *t == tag => {
+            paths.push((binds.clone(), pred));
+        }
+        fixpoint::Constraint::Pred(..) => {}
+        fixpoint::Constraint::Conj(cstrs) => {
+            for cstr in cstrs {
+                collect_paths(cstr, tag, binds, paths);
+            }
+        }
+        fixpoint::Constraint::ForAll(bind, inner) => {
+            binds.push(bind);
+            collect_paths(inner, tag, binds, paths);
+            binds.pop();
+        }
+    }
+}
+
+/// Converts a predicate into an expression replacing kvars with their solution. When `weakened`
+/// is `Some`, conjuncts that cannot be converted are dropped (and the flag is set if that
+/// happens), otherwise the conversion fails.
+fn pred_to_expr(
+    pred: &fixpoint::Pred,
+    solutions: &KVarSolutions,
+    mut weakened: Option<&mut bool>,
+) -> Option<fixpoint::Expr> {
+    match pred {
+        fixpoint::Pred::Expr(expr) => Some(expr.clone()),
+        fixpoint::Pred::KVar(kvid, args) => {
+            let expr = kvar_to_expr(*kvid, args, solutions);
+            if expr.is_none()
+                && let Some(weakened) = weakened
+            {
+                *weakened = true;
+            }
+            expr
+        }
+        fixpoint::Pred::And(preds) => {
+            let exprs = if weakened.is_some() {
+                preds
+                    .iter()
+                    .filter_map(|pred| pred_to_expr(pred, solutions, weakened.as_deref_mut()))
+                    .collect()
+            } else {
+                preds
+                    .iter()
+                    .map(|pred| pred_to_expr(pred, solutions, None))
+                    .collect::<Option<Vec<_>>>()?
+            };
+            Some(fixpoint::Expr::And(exprs))
+        }
+    }
+}
+
+fn kvar_to_expr(
+    kvid: fixpoint::KVid,
+    args: &[fixpoint::Expr],
+    solutions: &KVarSolutions,
+) -> Option<fixpoint::Expr> {
+    let (params, body) = solutions.get(&kvid)?;
+    if params.len() != args.len() {
+        return None;
+    }
+    let expr = std::iter::zip(params, args)
+        .rev()
+        .fold(body.clone(), |body, ((param, _), arg)| {
+            fixpoint::Expr::Let(*param, Box::new([arg.clone(), body]))
+        });
+    Some(expr)
+}
@@ -1,6 +1,7 @@
 use flux_common::tracked_span_bug;
 use flux_middle::{
     big_int::BigInt,
+    fhir,
     rty::{self, Binder, EarlyReftParam, InternalFuncKind, List, SpecFuncKind},
 };
 use flux_rustc_bridge::lowering::Lower;
@@ -46,6 +47,7 @@ where
         match ctor {
             fixpoint::SortCtor::Set => Ok(rty::SortCtor::Set),
             fixpoint::SortCtor::Map => Ok(rty::SortCtor::Map),
+            fixpoint::SortCtor::Seq => Ok(rty::SortCtor::Seq),
             fixpoint::SortCtor::Data(fixpoint::DataSort::Tuple(_)) => {
                 panic!("oh no! tuple!") // Ok(rty::SortCtor::Tuple(*size))
             }
@@ -136,6 +138,9 @@ where
                                 ConstKey::PtrSize => {
                                     Ok(rty::Expr::internal_func(InternalFuncKind::PtrSize))
                                 }
+                                ConstKey::PtrOffset => {
+                                    Ok(rty::Expr::internal_func(InternalFuncKind::PtrOffset))
+                                }
                             }
                         } else {
                             Err(FixpointParseError::NoGlobalVar(*global_var))
@@ -291,7 +296,8 @@ where
                                 }
                                 ConstKey::RustConst(..)
                                 | ConstKey::Lambda(..)
-                                | ConstKey::PtrSize => {
+                                | ConstKey::PtrSize
+                                | ConstKey::PtrOffset => {
                                     // These should be treated as a normal app.
                                     self.fixpoint_app_to_expr(fhead, fargs)
                                 }
@@ -418,6 +424,32 @@ where
                 self.ecx.local_var_env.pop_layer();
                 Ok(rty::Expr::exists(Binder::bind_with_sorts(body, &sorts)))
             }
+            fixpoint::Expr::ForAll(binder, triggers, body) => {
+                let mut vars = vec![];
+                let mut sorts = vec![];
+                for (var, sort) in binder {
+                    let fixpoint::Var::Local(local_var) = var else {
+                        return Err(FixpointParseError::WrongVarInBinder(*var));
+                    };
+                    vars.push(*local_var);
+                    sorts.push(self.fixpoint_to_sort(sort)?);
+                }
+                self.ecx.local_var_env.push_layer(vars);
+                let pred = self.fixpoint_to_expr(body)?;
+                let triggers = triggers
+                    .iter()
+                    .map(|trigger| {
+                        let exprs = trigger
+                            .iter()
+                            .map(|e| self.fixpoint_to_expr(e))
+                            .try_collect()?;
+                        Ok(rty::Trigger { exprs })
+                    })
+                    .try_collect()?;
+                self.ecx.local_var_env.pop_layer();
+                let body = rty::QuantBody { pred, triggers };
+                Ok(rty::Expr::quant(fhir::QuantKind::Forall, Binder::bind_with_sorts(body, &sorts)))
+            }
         }
     }
 
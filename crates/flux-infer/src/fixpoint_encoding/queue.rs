@@ -0,0 +1,344 @@
+//! Dispatching of fixpoint queries to a pool of worker threads.
+//!
+//! Constraints are always generated and encoded on the rustc thread. With `-Fjobs=N` for `N > 1`,
+//! encoded tasks are sent to `N` worker threads running the fixpoint binary while the rustc thread
+//! moves on to the next item. Answers are processed back on the rustc thread in the order the
+//! queries were submitted, so errors are reported in the same order regardless of which query
+//! finishes first.
+//!
+//! Workers only get a [rendered] task because most of the data in a task can only be formatted on
+//! the rustc thread (e.g., symbols). For the same reason, the in-process solver enabled with the
+//! `rust-fixpoint` feature always runs on the rustc thread.
+//!
+//! Processing the answer of a query can report errors after the rustc thread reported errors for
+//! items checked later, so while queries are pending we hold diagnostics back and emit them in the
+//! order items are defined (see [`FluxSession::hold_diagnostics`]).
+//!
+//! [rendered]: liquid_fixpoint::RenderedTask
+//! [`FluxSession::hold_diagnostics`]: flux_errors::FluxSession::hold_diagnostics
+use std::{collections::VecDeque, panic::AssertUnwindSafe};
+#[cfg(not(feature = "rust-fixpoint"))]
+use std::{
+    io,
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread::JoinHandle,
+};
+
+use flux_common::bug::catch_bugs;
+#[cfg(not(feature = "rust-fixpoint"))]
+use flux_common::{bug, span_bug};
+use flux_config as config;
+#[cfg(not(feature = "rust-fixpoint"))]
+use flux_middle::metrics::{self, TimingKind};
+use flux_middle::{FixpointQueryKind, def_id::MaybeExternId, global_env::GlobalEnv, report};
+#[cfg(not(feature = "rust-fixpoint"))]
+use liquid_fixpoint::VerificationResult;
+use rustc_hash::FxHashSet;
+use rustc_span::{ErrorGuaranteed, def_id::LocalDefId};
+
+use super::{Answer, FixQueryCache, FixpointCtxt, TagIdx, fixpoint};
+
+/// How many queries per worker can be waiting to be processed before we block the rustc thread.
+/// This bounds the memory used by the encoding contexts of pending queries.
+#[cfg(not(feature = "rust-fixpoint"))]
+const PENDING_PER_WORKER: usize = 4;
+
+#[cfg(not(feature = "rust-fixpoint"))]
+type Job = Box<dyn FnOnce() + Send>;
+
+#[cfg(not(feature = "rust-fixpoint"))]
+type ProcessResult<'genv> =
+    Box<dyn FnOnce(VerificationResult<TagIdx>) -> Result<(), ErrorGuaranteed> + 'genv>;
+
+pub struct FixpointQueue<'genv, 'tcx> {
+    genv: GlobalEnv<'genv, 'tcx>,
+    pub cache: FixQueryCache,
+    #[cfg(not(feature = "rust-fixpoint"))]
+    workers: Option<Workers>,
+    pending: VecDeque<PendingQuery<'genv>>,
+    /// Items for which errors were reported while processing the answers of deferred queries
+    failed: FxHashSet<MaybeExternId>,
+    /// Errors reported while processing the answers of deferred queries
+    result: Result<(), ErrorGuaranteed>,
+}
+
+enum PendingQuery<'genv> {
+    /// A query whose answer is already known (e.g., because it was cached) but which has to wait
+    /// for previous queries to be processed.
+    Answered {
+        def_id: MaybeExternId,
+        process: Box<dyn FnOnce() -> Result<(), ErrorGuaranteed> + 'genv>,
+    },
+    /// A query running in one of the workers.
+    #[cfg(not(feature = "rust-fixpoint"))]
+    Running {
+        def_id: MaybeExternId,
+        key: String,
+        hash: u64,
+        receiver: Receiver<io::Result<VerificationResult<TagIdx>>>,
+        process: ProcessResult<'genv>,
+    },
+    /// The fingerprint of an item to be saved in the cache if all the queries submitted for the
+    /// item before it are answered without errors.
+    Verified { def_id: MaybeExternId, key: String, fingerprint: u64 },
+}
+
+impl PendingQuery<'_> {
+    fn def_id(&self) -> MaybeExternId {
+        match self {
+            PendingQuery::Answered { def_id, .. } | PendingQuery::Verified { def_id, .. } => {
+                *def_id
+            }
+            #[cfg(not(feature = "rust-fixpoint"))]
+            PendingQuery::Running { def_id, .. } => *def_id,
+        }
+    }
+}
+
+impl<'genv, 'tcx> FixpointQueue<'genv, 'tcx> {
+    pub fn new(genv: GlobalEnv<'genv, 'tcx>, cache: FixQueryCache) -> Self {
+        Self {
+            genv,
+            cache,
+            #[cfg(not(feature = "rust-fixpoint"))]
+            workers: (config::jobs() > 1).then(|| Workers::new(config::jobs())),
+            pending: VecDeque::new(),
+            failed: FxHashSet::default(),
+            result: Ok(()),
+        }
+    }
+
+    /// Runs `task` and calls `on_answer` with the answer. If queries are run in parallel, the task
+    /// is sent to a worker and `on_answer` is called later, in which case errors it returns are
+    /// reported by [`FixpointQueue::finish`].
+    pub(crate) fn submit<Tag>(
+        &mut self,
+        mut fcx: FixpointCtxt<'genv, 'tcx, Tag>,
+        def_id: MaybeExternId,
+        kind: FixpointQueryKind,
+        task: fixpoint::Task,
+        on_answer: impl FnOnce(Answer<Tag>) -> Result<(), ErrorGuaranteed> + 'genv,
+    ) -> Result<(), ErrorGuaranteed>
+    where
+        Tag: std::hash::Hash + Eq + Copy + 'genv,
+    {
+        #[cfg(not(feature = "rust-fixpoint"))]
+        if self.workers.is_some() {
+            return self.defer(fcx, def_id, kind, task, on_answer);
+        }
+        let result = FixpointCtxt::<Tag>::run_task_with_cache(
+            self.genv,
+            &task,
+            def_id,
+            kind,
+            &mut self.cache,
+        );
+        on_answer(fcx.answer_for_result(def_id, &task, result))
+    }
+
+    /// Calls `process` once the answers of all previously submitted queries have been processed.
+    pub(crate) fn submit_answered(
+        &mut self,
+        def_id: MaybeExternId,
+        process: impl FnOnce() -> Result<(), ErrorGuaranteed> + 'genv,
+    ) -> Result<(), ErrorGuaranteed> {
+        if self.pending.is_empty() {
+            return process();
+        }
+        self.pending
+            .push_back(PendingQuery::Answered { def_id, process: Box::new(process) });
+        Ok(())
+    }
+
+    /// Saves the `fingerprint` of an item in the cache once all the queries previously submitted
+    /// for the item have been answered, unless errors were reported for any of them. This should
+    /// only be called after checking the item returned without errors.
+    pub fn submit_verified(&mut self, def_id: MaybeExternId, key: String, fingerprint: u64) {
+        if self.pending.is_empty() {
+            self.cache.insert_item(key, fingerprint);
+        } else {
+            self.pending
+                .push_back(PendingQuery::Verified { def_id, key, fingerprint });
+        }
+    }
+
+    #[cfg(not(feature = "rust-fixpoint"))]
+    fn defer<Tag>(
+        &mut self,
+        mut fcx: FixpointCtxt<'genv, 'tcx, Tag>,
+        def_id: MaybeExternId,
+        kind: FixpointQueryKind,
+        task: fixpoint::Task,
+        on_answer: impl FnOnce(Answer<Tag>) -> Result<(), ErrorGuaranteed> + 'genv,
+    ) -> Result<(), ErrorGuaranteed>
+    where
+        Tag: std::hash::Hash + Eq + Copy + 'genv,
+    {
+        let Some(workers) = &self.workers else { bug!("queries are not run in parallel") };
+        let max_pending = workers.len() * PENDING_PER_WORKER;
+
+        let key = kind.task_key(self.genv.tcx(), def_id.resolved_id());
+        let hash = task.hash_with_default();
+        if let Some(result) =
+            FixpointCtxt::<Tag>::lookup_cache(&self.cache, def_id, kind, &key, hash, task.limits)
+        {
+            return self.submit_answered(def_id, move || {
+                on_answer(fcx.answer_for_result(def_id, &task, result))
+            });
+        }
+
+        let rendered = task.render();
+        let (sender, receiver) = mpsc::channel();
+        let resolved_id = def_id.resolved_id();
+        workers.execute(move || {
+            let result =
+                metrics::time_it(TimingKind::FixpointQuery(resolved_id, kind), || rendered.run());
+            // The receiver is gone if we stopped processing answers, e.g., after a fatal error.
+            let _ = sender.send(result);
+        });
+        self.genv.sess().hold_diagnostics();
+        self.pending.push_back(PendingQuery::Running {
+            def_id,
+            key,
+            hash,
+            receiver,
+            process: Box::new(move |result| {
+                on_answer(fcx.answer_for_result(def_id, &task, result))
+            }),
+        });
+
+        if self.pending.len() > max_pending {
+            self.process_next();
+        }
+        Ok(())
+    }
+
+    /// Waits for all pending queries and processes their answers in the order they were submitted.
+    pub fn finish(&mut self) -> Result<(), ErrorGuaranteed> {
+        while !self.pending.is_empty() {
+            self.process_next();
+        }
+        std::mem::replace(&mut self.result, Ok(()))
+    }
+
+    fn process_next(&mut self) {
+        let Some(query) = self.pending.pop_front() else { return };
+        let def_id = query.def_id();
+        let sess = self.genv.sess();
+        let def_span = self.genv.tcx().def_span(def_id.resolved_id());
+        let msg = format!("def_id: {:?}, span: {:?}", def_id.local_id(), def_span);
+        let result = sess.with_item(item_index(def_id.local_id()), || {
+            catch_bugs(
+                &msg,
+                AssertUnwindSafe(|| {
+                    match query {
+                        PendingQuery::Answered { process, .. } => process(),
+                        #[cfg(not(feature = "rust-fixpoint"))]
+                        PendingQuery::Running { key, hash, receiver, process, .. } => {
+                            let result = receiver
+                                .recv()
+                                .unwrap_or_else(|_| span_bug!(def_span, "fixpoint worker panicked"))
+                                .unwrap_or_else(|err| {
+                                    span_bug!(def_span, "failed to run fixpoint: {err}")
+                                });
+                            if config::is_cache_enabled() {
+                                self.cache.insert(key, hash, result.clone());
+                            }
+                            process(result)
+                        }
+                        PendingQuery::Verified { key, fingerprint, .. } => {
+                            if !self.failed.contains(&def_id) {
+                                self.cache.insert_item(key, fingerprint);
+                            }
+                            Ok(())
+                        }
+                    }
+                }),
+            )
+        });
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                self.failed.insert(def_id);
+                self.result = Err(err);
+            }
+            Err(err) => {
+                report::record_status(def_id.local_id(), report::Status::Crashed);
+                self.failed.insert(def_id);
+                self.result = Err(err);
+            }
+        }
+        // Items before the first pending one are done, so their diagnostics can be emitted.
+        sess.release_diagnostics(
+            self.pending
+                .iter()
+                .map(|query| item_index(query.def_id().local_id()))
+                .min(),
+        );
+    }
+}
+
+/// The position of an item in the order its diagnostics are reported, i.e., the order in which
+/// items are defined. See [`FluxSession::with_item`].
+///
+/// [`FluxSession::with_item`]: flux_errors::FluxSession::with_item
+pub fn item_index(def_id: LocalDefId) -> usize {
+    def_id.local_def_index.as_usize()
+}
+
+/// A fixed number of threads executing jobs in the order they are submitted.
+#[cfg(not(feature = "rust-fixpoint"))]
+struct Workers {
+    sender: Option<Sender<Job>>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+#[cfg(not(feature = "rust-fixpoint"))]
+impl Workers {
+    fn new(n: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let handles = (0..n)
+            .map(|i| {
+                let receiver = Arc::clone(&receiver);
+                std::thread::Builder::new()
+                    .name(format!("flux-fixpoint-{i}"))
+                    .spawn(move || {
+                        loop {
+                            // Release the lock before running the job so other workers can pick
+                            // up jobs in the meantime.
+                            let job = receiver.lock().unwrap().recv();
+                            match job {
+                                Ok(job) => job(),
+                                Err(_) => break,
+                            }
+                        }
+                    })
+                    .unwrap()
+            })
+            .collect();
+        Self { sender: Some(sender), handles }
+    }
+
+    fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        self.sender.as_ref().unwrap().send(Box::new(job)).unwrap();
+    }
+}
+
+#[cfg(not(feature = "rust-fixpoint"))]
+impl Drop for Workers {
+    fn drop(&mut self) {
+        // Dropping the sender makes workers exit their loop once all pending jobs are done.
+        drop(self.sender.take());
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Unfolding of recursive flux functions.
+//!
+//! Recursive functions cannot be encoded with `define-fun`. Instead, we declare them as
+//! uninterpreted functions and, for every application `f(e1, ..., en)` appearing in a constraint,
+//! we assume the equation `f(e1, ..., en) = body[e1/x1, ..., en/xn]`. The process is repeated for
+//! the applications appearing in the unfolded bodies up to a fixed amount of fuel.
+use std::ops::ControlFlow;
+
+use flux_middle::{
+    def_id::FluxDefId,
+    rty::{
+        self, SpecFuncKind,
+        fold::{TypeSuperVisitable, TypeVisitable, TypeVisitor},
+    },
+};
+use rustc_data_structures::fx::FxIndexSet;
+
+use super::{Backend, FixpointCtxt};
+
+/// How many times we unfold the applications produced by a previous unfolding.
+const UNFOLD_FUEL: usize = 2;
+
+impl<Tag> FixpointCtxt<'_, '_, Tag>
+where
+    Tag: std::hash::Hash + Eq + Copy,
+{
+    /// Returns the equations unfolding the applications of recursive functions in `expr`.
+    pub(crate) fn unfoldings(&self, expr: &rty::Expr) -> Vec<rty::Expr> {
+        // The lean backend emits recursive functions as real recursive definitions.
+        if matches!(self.ecx.backend, Backend::Lean) {
+            return vec![];
+        }
+        let mut seen = FxIndexSet::default();
+        let mut pending = self.recursive_apps(expr, &seen);
+        let mut eqs = vec![];
+        for _ in 0..UNFOLD_FUEL {
+            let mut next = vec![];
+            for (did, app, args) in pending {
+                if !seen.insert(app.erase_spans()) {
+                    continue;
+                }
+                let body = self.genv.inlined_body(did).replace_bound_refts(&args);
+                next.extend(self.recursive_apps(&body, &seen));
+                eqs.push(rty::Expr::eq(app, body));
+            }
+            pending = next;
+        }
+        eqs
+    }
+
+    /// Collects the applications of recursive (and not hidden) functions in `expr` that haven't
+    /// been unfolded yet.
+    fn recursive_apps(
+        &self,
+        expr: &rty::Expr,
+        seen: &FxIndexSet<rty::Expr>,
+    ) -> Vec<(FluxDefId, rty::Expr, rty::List<rty::Expr>)> {
+        struct AppCollector<'a, 'genv, 'tcx, Tag: std::hash::Hash + Eq> {
+            cx: &'a FixpointCtxt<'genv, 'tcx, Tag>,
+            seen: &'a FxIndexSet<rty::Expr>,
+            apps: Vec<(FluxDefId, rty::Expr, rty::List<rty::Expr>)>,
+        }
+        impl<Tag> TypeVisitor for AppCollector<'_, '_, '_, Tag>
+        where
+            Tag: std::hash::Hash + Eq + Copy,
+        {
+            fn visit_expr(&mut self, expr: &rty::Expr) -> ControlFlow<!> {
+                if let rty::ExprKind::App(func, _, args) = expr.kind()
+                    && let rty::ExprKind::GlobalFunc(SpecFuncKind::Def(did)) = func.kind()
+                    && self.cx.should_unfold(*did)
+                    && !expr.has_escaping_bvars()
+                    && !self.seen.contains(&expr.erase_spans())
+                {
+                    self.apps.push((*did, expr.clone(), args.clone()));
+                }
+                expr.super_visit_with(self)
+            }
+        }
+        let mut collector = AppCollector { cx: self, seen, apps: vec![] };
+        let _ = expr.visit_with(&mut collector);
+        collector.apps
+    }
+
+    fn should_unfold(&self, did: FluxDefId) -> bool {
+        let info = self.genv.normalized_info(did);
+        if !info.recursive {
+            return false;
+        }
+        !info.hide
+            || self
+                .ecx
+                .def_id
+                .is_some_and(|def_id| self.genv.reveals_for(def_id.local_id()).contains(&did))
+    }
+}
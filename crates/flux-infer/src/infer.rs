@@ -1,6 +1,9 @@
 use std::{cell::RefCell, fmt, iter};
 
-use flux_common::{bug, dbg, tracked_span_assert_eq, tracked_span_bug, tracked_span_dbg_assert_eq};
+use flux_common::{
+    bug, dbg, result::ResultExt as _, tracked_span_assert_eq, tracked_span_bug,
+    tracked_span_dbg_assert_eq,
+};
 use flux_config::{self as config, InferOpts, OverflowMode, RawDerefMode};
 use flux_macros::{TypeFoldable, TypeVisitable};
 use flux_middle::{
@@ -16,24 +19,25 @@ use flux_middle::{
         GenericArg, HoleKind, InferMode, Lambda, List, Loc, Mutability, Name, NameProvenance, Path,
         PolyVariant, PtrKind, RefineArgs, RefineArgsExt, Region, Sort, Ty, TyCtor, TyKind, Var,
         canonicalize::{Hoister, HoisterDelegate},
-        fold::TypeFoldable,
+        fold::{TypeFoldable, TypeVisitable},
     },
 };
 use itertools::{Itertools, izip};
+use rustc_abi::FieldIdx;
 use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_macros::extension;
 use rustc_middle::{
     mir::BasicBlock,
     ty::{TyCtxt, Variance},
 };
-use rustc_span::{Span, Symbol};
+use rustc_span::{ErrorGuaranteed, Span, Symbol};
 use rustc_type_ir::Variance::Invariant;
 
 use crate::{
     evars::{EVarState, EVarStore},
     fixpoint_encoding::{
-        Answer, Backend, FixQueryCache, FixpointCtxt, KVarEncoding, KVarGen, KVarSolutions,
-        lean_task_key,
+        Answer, Backend, FixQueryCache, FixpointCtxt, FixpointQueue, KVarEncoding, KVarGen,
+        KVarSolutions, fixpoint, lean_task_key,
     },
     lean_encoding::log_proof,
     projections::NormalizeExt as _,
@@ -67,6 +71,28 @@ pub enum SubtypeReason {
     Ensures,
 }
 
+/// The kind of check performed by an `assert` terminator
+#[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+pub enum AssertReason {
+    BoundsCheck,
+    DivisionByZero,
+    RemainderByZero,
+    DivisionOverflow,
+    RemainderOverflow,
+}
+
+impl AssertReason {
+    pub fn msg(self) -> &'static str {
+        match self {
+            AssertReason::BoundsCheck => "possible out-of-bounds access",
+            AssertReason::DivisionByZero => "possible division by zero",
+            AssertReason::RemainderByZero => "possible remainder with a divisor of zero",
+            AssertReason::DivisionOverflow => "possible division with overflow",
+            AssertReason::RemainderOverflow => "possible reminder with overflow",
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
 pub enum ConstrReason {
     Call,
@@ -75,7 +101,7 @@ pub enum ConstrReason {
     Fold,
     FoldLocal,
     Predicate,
-    Assert(&'static str),
+    Assert(AssertReason),
     Div,
     Rem,
     Goto(BasicBlock),
@@ -83,9 +109,49 @@ pub enum ConstrReason {
     Underflow,
     Subtype(SubtypeReason),
     NoPanic(DefId),
+    Termination,
+    LoopInvariant,
+    LoopVariant,
+    UnionField,
+    Pattern,
+    RawDeref,
+    PtrOffset,
     Other,
 }
 
+impl ConstrReason {
+    /// A short name for the reason used in machine-readable output.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConstrReason::Call => "call",
+            ConstrReason::Assign => "assign",
+            ConstrReason::Ret => "ret",
+            ConstrReason::Fold => "fold",
+            ConstrReason::FoldLocal => "fold_local",
+            ConstrReason::Predicate => "predicate",
+            ConstrReason::Assert(_) => "assert",
+            ConstrReason::Div => "div",
+            ConstrReason::Rem => "rem",
+            ConstrReason::Goto(_) => "goto",
+            ConstrReason::Overflow => "overflow",
+            ConstrReason::Underflow => "underflow",
+            ConstrReason::Subtype(SubtypeReason::Input) => "subtype_input",
+            ConstrReason::Subtype(SubtypeReason::Output) => "subtype_output",
+            ConstrReason::Subtype(SubtypeReason::Requires) => "subtype_requires",
+            ConstrReason::Subtype(SubtypeReason::Ensures) => "subtype_ensures",
+            ConstrReason::NoPanic(_) => "no_panic",
+            ConstrReason::Termination => "termination",
+            ConstrReason::LoopInvariant => "loop_invariant",
+            ConstrReason::LoopVariant => "loop_variant",
+            ConstrReason::UnionField => "union_field",
+            ConstrReason::Pattern => "pattern",
+            ConstrReason::RawDeref => "raw_deref",
+            ConstrReason::PtrOffset => "ptr_offset",
+            ConstrReason::Other => "other",
+        }
+    }
+}
+
 pub struct InferCtxtRoot<'genv, 'tcx> {
     pub genv: GlobalEnv<'genv, 'tcx>,
     inner: RefCell<InferCtxtInner>,
@@ -196,6 +262,8 @@ impl<'genv, 'tcx> InferCtxtRoot<'genv, 'tcx> {
             inner: &self.inner,
             check_overflow: self.opts.check_overflow,
             allow_raw_deref: self.opts.allow_raw_deref,
+            uint_as_bitvec: self.opts.uint_as_bitvec,
+            check_unwind: self.opts.check_unwind,
         }
     }
 
@@ -228,7 +296,13 @@ impl<'genv, 'tcx> InferCtxtRoot<'genv, 'tcx> {
         let mut fcx = FixpointCtxt::new(self.genv, def_id, kvars, Backend::Lean);
         let cstr = refine_tree.to_fixpoint(&mut fcx)?;
         let cstr_variable_sorts = cstr.variable_sorts();
-        let task = fcx.create_task(def_id, cstr, self.opts.scrape_quals, solver)?;
+        let task = fcx.create_task(
+            def_id,
+            cstr,
+            self.opts.scrape_quals,
+            solver,
+            solver_limits(&self.opts),
+        )?;
 
         log_proof(self.genv, def_id)?;
         // Skip re-generation if task is already cached (same hash → same lean files on disk).
@@ -253,12 +327,29 @@ impl<'genv, 'tcx> InferCtxtRoot<'genv, 'tcx> {
         )
     }
 
+    /// Encodes the refinement tree into a fixpoint constraint and submits it to `queue`. The answer
+    /// is passed to `on_answer`, which may be called after this function returns if queries are
+    /// run in parallel.
     pub fn execute_fixpoint_query(
         self,
-        cache: &mut FixQueryCache,
+        queue: &mut FixpointQueue<'genv, 'tcx>,
+        def_id: MaybeExternId,
+        kind: FixpointQueryKind,
+        on_answer: impl FnOnce(Answer<Tag>) -> Result<(), ErrorGuaranteed> + 'genv,
+    ) -> Result<(), ErrorGuaranteed> {
+        let genv = self.genv;
+        match self.encode_fixpoint_query(def_id, kind).emit(&genv)? {
+            Some((fcx, task)) => queue.submit(fcx, def_id, kind, task, on_answer),
+            None => queue.submit_answered(def_id, move || on_answer(Answer::trivial())),
+        }
+    }
+
+    /// Returns `None` if the constraint is trivial.
+    fn encode_fixpoint_query(
+        self,
         def_id: MaybeExternId,
         kind: FixpointQueryKind,
-    ) -> QueryResult<Answer<Tag>> {
+    ) -> QueryResult<Option<(FixpointCtxt<'genv, 'tcx, Tag>, fixpoint::Task)>> {
         let inner = self.inner.into_inner();
         let kvars = inner.kvars;
         let evars = inner.evars;
@@ -292,12 +383,17 @@ impl<'genv, 'tcx> InferCtxtRoot<'genv, 'tcx> {
         metrics::incr_metric(Metric::CsTotal, count as u32);
         if count == 0 {
             metrics::incr_metric_if(kind.is_body(), Metric::FnTrivial);
-            return Ok(Answer::trivial());
+            return Ok(None);
         }
 
-        let task = fcx.create_task(def_id, cstr, self.opts.scrape_quals, backend)?;
-        let result = fcx.run_task(cache, def_id, kind, &task)?;
-        Ok(fcx.result_to_answer(result))
+        let task = fcx.create_task(
+            def_id,
+            cstr,
+            self.opts.scrape_quals,
+            backend,
+            solver_limits(&self.opts),
+        )?;
+        Ok(Some((fcx, task)))
     }
 
     pub fn split(self) -> (RefineTree, KVarGen) {
@@ -305,12 +401,18 @@ impl<'genv, 'tcx> InferCtxtRoot<'genv, 'tcx> {
     }
 }
 
+pub(crate) fn solver_limits(opts: &InferOpts) -> liquid_fixpoint::Limits {
+    liquid_fixpoint::Limits { timeout: opts.solver_timeout, memory: opts.solver_memory }
+}
+
 pub struct InferCtxt<'infcx, 'genv, 'tcx> {
     pub genv: GlobalEnv<'genv, 'tcx>,
     pub region_infcx: &'infcx rustc_infer::infer::InferCtxt<'tcx>,
     pub def_id: DefId,
     pub check_overflow: OverflowMode,
     pub allow_raw_deref: flux_config::RawDerefMode,
+    pub uint_as_bitvec: bool,
+    pub check_unwind: bool,
     cursor: Cursor<'infcx>,
     inner: &'infcx RefCell<InferCtxtInner>,
 }
@@ -468,7 +570,11 @@ impl<'infcx, 'genv, 'tcx> InferCtxt<'infcx, 'genv, 'tcx> {
     }
 
     pub fn allow_raw_deref(&self) -> bool {
-        matches!(self.allow_raw_deref, RawDerefMode::Ok)
+        matches!(self.allow_raw_deref, RawDerefMode::Ok | RawDerefMode::Checked)
+    }
+
+    pub fn check_raw_deref(&self) -> bool {
+        matches!(self.allow_raw_deref, RawDerefMode::Checked)
     }
 }
 
@@ -694,6 +800,60 @@ impl<'genv, 'tcx> InferCtxtAt<'_, '_, 'genv, 'tcx> {
         Ok(self.fully_resolve_evars(&ret))
     }
 
+    /// Like [`InferCtxtAt::check_constructor`] but for a union initialized through `field`. The tag
+    /// of the resulting union is set to `field` and the refinements of the union that are not
+    /// mentioned in the type of `field` are left unconstrained.
+    pub fn check_union_constructor(
+        &mut self,
+        variant: EarlyBinder<PolyVariant>,
+        generic_args: &[GenericArg],
+        field: FieldIdx,
+        actual: &Ty,
+        reason: ConstrReason,
+    ) -> InferResult<Ty> {
+        let generic_args = self.instantiate_generic_args(generic_args);
+        let variant = variant.instantiate(self.tcx(), &generic_args, &[]);
+
+        // Name all the refinements to find out which ones are mentioned in the type of the field.
+        // We need evars for those, the others are left as fresh names.
+        let names = variant
+            .vars()
+            .iter()
+            .map(|var| {
+                let (sort, _, kind) = var.expect_refine();
+                self.define_bound_reft_var(sort, kind)
+            })
+            .collect_vec();
+        let exprs = names.iter().map(|name| Expr::fvar(*name)).collect_vec();
+        let mentioned = variant.replace_bound_refts(&exprs).fields[field.as_usize()].fvars();
+
+        let ret = self.ensure_resolved_evars(|this| {
+            let exprs = names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| {
+                    if i == 0 && variant.skip_binder_ref().adt_def.is_union() {
+                        Expr::constant(rty::Constant::from(field.as_usize()))
+                    } else if mentioned.contains(name) {
+                        this.fresh_evar()
+                    } else {
+                        Expr::fvar(*name)
+                    }
+                })
+                .collect_vec();
+            let variant = variant.replace_bound_refts(&exprs);
+
+            this.subtyping(actual, &variant.fields[field.as_usize()], reason)?;
+
+            for require in &variant.requires {
+                this.check_pred(require, ConstrReason::Fold);
+            }
+
+            Ok(variant.ret())
+        })?;
+        Ok(self.fully_resolve_evars(&ret))
+    }
+
     pub fn ensure_resolved_evars<R>(
         &mut self,
         f: impl FnOnce(&mut InferCtxtAt<'_, '_, 'genv, 'tcx>) -> InferResult<R>,
@@ -1062,6 +1222,12 @@ impl<'a, E: LocEnv> Sub<'a, E> {
                 Ok(())
             }
             (BaseTy::Foreign(did_a), BaseTy::Foreign(did_b)) if did_a == did_b => Ok(()),
+            (BaseTy::Pat(_, pat_a), BaseTy::Pat(_, pat_b)) => {
+                // The refinement of a pattern type is in its index, so the base types only need to
+                // agree on the pattern.
+                tracked_span_dbg_assert_eq!(pat_a, pat_b);
+                Ok(())
+            }
             _ => Err(query_bug!("incompatible base types: `{a:#?}` - `{b:#?}`"))?,
         }
     }
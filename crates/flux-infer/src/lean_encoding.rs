@@ -11,6 +11,7 @@ use flux_common::{
     result::ResultExt,
 };
 use flux_config as config;
+use flux_errors::E0984;
 use flux_middle::{
     def_id::{FluxDefId, MaybeExternId},
     global_env::GlobalEnv,
@@ -18,13 +19,15 @@ use flux_middle::{
     rty::{PrettyMap, local_deps},
 };
 use itertools::Itertools;
+use liquid_fixpoint::{Limits, Resource};
 use rustc_data_structures::fx::FxIndexSet;
 use rustc_hash::FxHashMap;
-use rustc_hir::def_id::DefId;
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_span::ErrorGuaranteed;
 
 use crate::{
     fixpoint_encoding::{ConstDeps, InterpretedConst, KVarSolutions, SortDeps, fixpoint},
+    infer::solver_limits,
     lean_format::{self, LeanCtxt, WithLeanCtxt, def_id_to_pascal_case, snake_case_to_pascal_case},
 };
 
@@ -103,6 +106,7 @@ fn constant_deps(expr: &fixpoint::Expr, acc: &mut FxIndexSet<fixpoint::Var>) {
             inner.iter().for_each(|expr| constant_deps(expr, acc));
         }
         fixpoint::Expr::Exists(_, inner)
+        | fixpoint::Expr::ForAll(_, _, inner)
         | fixpoint::Expr::Neg(inner)
         | fixpoint::Expr::Not(inner)
         | fixpoint::Expr::IsCtor(_, inner) => {
@@ -127,20 +131,28 @@ fn project_path(genv: GlobalEnv, kind: FileKind) -> PathBuf {
     }
 }
 
-fn run_lean(genv: GlobalEnv, def_id: DefId) -> io::Result<()> {
+/// Checks the external proof of `def_id` with Lean. Lean runs within the same [`Limits`] as the
+/// solver, returning the exhausted resource if it doesn't finish within them.
+fn run_lean(genv: GlobalEnv, def_id: DefId, limits: Limits) -> io::Result<Result<(), Resource>> {
     let proof_path = LeanFile::Proof(def_id).path(genv);
-    let out = Command::new("lake")
-        .arg("--quiet")
+    let mut cmd = Command::new("lake");
+    cmd.arg("--quiet")
         .arg("--log-level=error")
         .arg("lean")
         .arg(proof_path)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .current_dir(project_path(genv, FileKind::User))
-        .spawn()?
-        .wait_with_output()?;
+        .current_dir(project_path(genv, FileKind::User));
+    let child = match limits.spawn(&mut cmd)? {
+        Ok(child) => child,
+        Err(resource) => return Ok(Err(resource)),
+    };
+    let out = match limits.wait_with_output(child)? {
+        Ok(out) => out,
+        Err(resource) => return Ok(Err(resource)),
+    };
     if out.stderr.is_empty() && out.stdout.is_empty() {
-        Ok(())
+        Ok(Ok(()))
     } else {
         let stderr =
             std::str::from_utf8(&out.stderr).unwrap_or("Lean exited with a non-zero return code");
@@ -148,16 +160,38 @@ fn run_lean(genv: GlobalEnv, def_id: DefId) -> io::Result<()> {
     }
 }
 
-pub fn check_proof(genv: GlobalEnv, def_id: DefId) -> Result<(), ErrorGuaranteed> {
-    run_lean(genv, def_id)
-        .map_err(|_| {
-            let name = genv.tcx().def_path(def_id).to_string_no_crate_verbose();
-            let msg = format!("failed to check external proof for `crate{name}`");
-            let span = genv.tcx().def_span(def_id);
-            QueryErr::Emitted(genv.sess().dcx().handle().struct_span_err(span, msg).emit())
-        })
-        .emit(&genv)?;
-    Ok(())
+pub fn check_proof(genv: GlobalEnv, def_id: LocalDefId) -> Result<(), ErrorGuaranteed> {
+    let limits = solver_limits(&genv.infer_opts(def_id));
+    let def_id = def_id.to_def_id();
+    let name = genv.tcx().def_path(def_id).to_string_no_crate_verbose();
+    let msg = format!("failed to check external proof for `crate{name}`");
+    let span = genv.tcx().def_span(def_id);
+    match run_lean(genv, def_id, limits) {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(resource)) => {
+            let note = match resource {
+                Resource::Time(secs) => {
+                    format!(
+                        "Lean exceeded the limit of {secs}s, the limit can be raised with `solver_timeout`"
+                    )
+                }
+                Resource::Memory(mb) => {
+                    format!(
+                        "Lean exceeded the limit of {mb}MB, the limit can be raised with `solver_memory`"
+                    )
+                }
+            };
+            Err(genv
+                .sess()
+                .dcx()
+                .handle()
+                .struct_span_err(span, msg)
+                .with_code(E0984)
+                .with_note(note)
+                .emit())
+        }
+        Err(_) => Err(genv.sess().dcx().handle().struct_span_err(span, msg).emit()),
+    }
 }
 
 /// Create a file at the given path, creating any missing parent directories.
@@ -297,8 +331,37 @@ impl<'genv, 'tcx> LeanEncoder<'genv, 'tcx> {
     }
 
     fn lean_file_for_fun(&self, fun: &fixpoint::FunDef) -> LeanFile {
-        let name = self.var_name(&fun.name);
-        if fun.body.is_some() { LeanFile::Fun(name) } else { LeanFile::OpaqueFun(name) }
+        if fun.body.is_some() {
+            LeanFile::Fun(self.fun_group_name(fun))
+        } else {
+            LeanFile::OpaqueFun(self.var_name(&fun.name))
+        }
+    }
+
+    /// Returns the functions that must be defined together with `fun` in a `mutual` block, i.e.,
+    /// all the functions in its group of mutually recursive functions (including `fun` itself).
+    fn fun_group<'a>(&'a self, fun: &'a fixpoint::FunDef) -> Vec<&'a fixpoint::FunDef> {
+        let fixpoint::Var::Global(_, did) = fun.name else { bug!("expected global var with id") };
+        let info = self.genv.normalized_info(did);
+        if !info.recursive || fun.body.is_none() {
+            return vec![fun];
+        }
+        self.fun_deps
+            .iter()
+            .filter(|other| {
+                let fixpoint::Var::Global(_, other_did) = other.name else { return false };
+                if other.body.is_none() || other_did.krate() != did.krate() {
+                    return false;
+                }
+                let other_info = self.genv.normalized_info(other_did);
+                other_info.recursive && other_info.rank == info.rank
+            })
+            .collect()
+    }
+
+    /// Functions in the same group are defined in a single file named after the first one.
+    fn fun_group_name(&self, fun: &fixpoint::FunDef) -> String {
+        self.var_name(&self.fun_group(fun)[0].name)
     }
 
     fn lean_file_for_interpreted_const(&self, const_: &InterpretedConst) -> LeanFile {
@@ -360,8 +423,7 @@ impl<'genv, 'tcx> LeanEncoder<'genv, 'tcx> {
             let fixpoint::Var::Global(_, did) = fun_def.name else {
                 bug!("expected global var with id")
             };
-            let name = self.var_name(&fun_def.name);
-            let file = LeanFile::Fun(name);
+            let file = LeanFile::Fun(self.fun_group_name(fun_def));
             res.insert(did, file);
         }
         res
@@ -517,24 +579,40 @@ impl<'genv, 'tcx> LeanEncoder<'genv, 'tcx> {
         res
     }
 
-    fn generate_fun_def_file_if_not_present(
-        &self,
-        did: FluxDefId,
-        fun_def: &fixpoint::FunDef,
-    ) -> io::Result<()> {
-        let path = self.lean_file_for_fun(fun_def).path(self.genv);
+    fn generate_fun_def_file_if_not_present(&self, fun_def: &fixpoint::FunDef) -> io::Result<()> {
+        let lean_file = self.lean_file_for_fun(fun_def);
+        let path = lean_file.path(self.genv);
         if let Some(mut file) = create_file_with_dirs(path)? {
+            let group = self.fun_group(fun_def);
             // import prelude
             writeln!(file, "{}", &LeanFile::Fluxlib.import(self.genv))?;
             // import sort dependencies
-            for dep in self.fun_def_dependencies(did, fun_def) {
+            let deps = group
+                .iter()
+                .flat_map(|fun_def| {
+                    let fixpoint::Var::Global(_, did) = fun_def.name else {
+                        bug!("expected global var with id")
+                    };
+                    self.fun_def_dependencies(did, fun_def)
+                })
+                .filter(|dep| **dep != lean_file)
+                .unique();
+            for dep in deps {
                 writeln!(file, "{}", dep.import(self.genv))?;
             }
             writeln!(file, "{}", self.open_classical())?;
 
-            // write fun def
+            // write fun def, mutually recursive functions go in a `mutual` block
             namespaced(&mut file, |f| {
-                writeln!(f, "{}", WithLeanCtxt { item: fun_def, cx: &self.lean_cx() })
+                if let [fun_def] = &group[..] {
+                    writeln!(f, "{}", WithLeanCtxt { item: *fun_def, cx: &self.lean_cx() })
+                } else {
+                    writeln!(f, "mutual")?;
+                    for fun_def in &group {
+                        writeln!(f, "{}", WithLeanCtxt { item: *fun_def, cx: &self.lean_cx() })?;
+                    }
+                    writeln!(f, "end")
+                }
             })?;
             file.sync_all()?;
         }
@@ -621,6 +699,49 @@ impl<'genv, 'tcx> LeanEncoder<'genv, 'tcx> {
                 file,
                 "def SmtMap_select {{ t0 t1 : Type }} [Inhabited t0] [BEq t0] [Inhabited t1] (m : SmtMap t0 t1) (k : t0) := m k"
             )?;
+            writeln!(file, "def SmtSet (t : Type) : Type := t -> Prop")?;
+            writeln!(
+                file,
+                "def SmtSet_empty {{ t : Type }} (_ : Int) : SmtSet t := fun _ => False"
+            )?;
+            writeln!(file, "def SmtSet_sng {{ t : Type }} (x : t) : SmtSet t := fun y => y = x")?;
+            writeln!(
+                file,
+                "def SmtSet_cup {{ t : Type }} (s1 s2 : SmtSet t) : SmtSet t := fun x => s1 x ∨ s2 x"
+            )?;
+            writeln!(
+                file,
+                "def SmtSet_cap {{ t : Type }} (s1 s2 : SmtSet t) : SmtSet t := fun x => s1 x ∧ s2 x"
+            )?;
+            writeln!(
+                file,
+                "def SmtSet_dif {{ t : Type }} (s1 s2 : SmtSet t) : SmtSet t := fun x => s1 x ∧ ¬ s2 x"
+            )?;
+            writeln!(
+                file,
+                "def SmtSet_sub {{ t : Type }} (s1 s2 : SmtSet t) : Prop := ∀ x, s1 x -> s2 x"
+            )?;
+            writeln!(file, "def SmtSet_mem {{ t : Type }} (x : t) (s : SmtSet t) : Prop := s x")?;
+            writeln!(file, "abbrev SmtSeq (t : Type) : Type := List t")?;
+            writeln!(file, "def SmtSeq_empty {{ t : Type }} : SmtSeq t := []")?;
+            writeln!(file, "def SmtSeq_unit {{ t : Type }} (x : t) : SmtSeq t := [x]")?;
+            writeln!(file, "def SmtSeq_len {{ t : Type }} (s : SmtSeq t) : Int := s.length")?;
+            writeln!(
+                file,
+                "def SmtSeq_nth {{ t : Type }} [Inhabited t] (s : SmtSeq t) (i : Int) : t := s.getD i.toNat default"
+            )?;
+            writeln!(
+                file,
+                "def SmtSeq_concat {{ t : Type }} (s1 s2 : SmtSeq t) : SmtSeq t := s1 ++ s2"
+            )?;
+            writeln!(
+                file,
+                "def SmtSeq_extract {{ t : Type }} (s : SmtSeq t) (i n : Int) : SmtSeq t :=\n  if i < 0 then [] else (s.drop i.toNat).take n.toNat"
+            )?;
+            writeln!(
+                file,
+                "def SmtSeq_update {{ t : Type }} (s : SmtSeq t) (i : Int) (x : t) : SmtSeq t :=\n  if i < 0 then s else s.set i.toNat x"
+            )?;
         }
         Ok(())
     }
@@ -639,10 +760,7 @@ impl<'genv, 'tcx> LeanEncoder<'genv, 'tcx> {
         }
         // 3. Generate Func Def Files
         for fun_def in &self.fun_deps {
-            let fixpoint::Var::Global(_, did) = fun_def.name else {
-                bug!("expected global var with id")
-            };
-            self.generate_fun_def_file_if_not_present(did, fun_def)?;
+            self.generate_fun_def_file_if_not_present(fun_def)?;
         }
         // 4. Generate Const Decl Files
         for const_decl in &self.constants.interpreted {
@@ -664,8 +782,13 @@ impl<'genv, 'tcx> LeanEncoder<'genv, 'tcx> {
             writeln!(file, "{}", LeanFile::Struct(name).import(self.genv))?;
         }
 
-        for fun_def in &self.fun_deps {
-            writeln!(file, "{}", self.lean_file_for_fun(fun_def).import(self.genv))?;
+        for lean_file in self
+            .fun_deps
+            .iter()
+            .map(|fun_def| self.lean_file_for_fun(fun_def))
+            .unique()
+        {
+            writeln!(file, "{}", lean_file.import(self.genv))?;
         }
 
         for const_decl in &self.constants.interpreted {
@@ -18,8 +18,8 @@ use rustc_hir::def_id::DefId;
 use crate::fixpoint_encoding::{
     ClosedSolution, InterpretedConst, KVarSolutions,
     fixpoint::{
-        self, AdtId, BinOp, BinRel, Constant, Constraint, DataDecl, DataField, DataSort, Expr,
-        FunDef, FunSort, KVarDecl, KVid, LocalVar, Pred, Sort, SortCtor, SortDecl, Var,
+        AdtId, BinOp, BinRel, Constant, Constraint, DataDecl, DataSort, Expr, FunDef, FunSort,
+        KVarDecl, KVid, LocalVar, Pred, Sort, SortCtor, SortDecl, Var,
     },
 };
 
@@ -79,18 +79,6 @@ impl LeanFmt for InterpretedConst {
     }
 }
 
-// TODO(lean-localize-imports) this seems wrong, but related to lack of storing `VariantIdx` in the `DataProj`
-impl LeanFmt for DataField {
-    fn lean_fmt(&self, f: &mut fmt::Formatter, cx: &LeanCtxt) -> fmt::Result {
-        write!(
-            f,
-            "({} : {})",
-            self.name.display().to_string().replace("$", "_"),
-            WithLeanCtxt { item: &self.sort, cx }
-        )
-    }
-}
-
 impl LeanFmt for DataSort {
     fn lean_fmt(&self, f: &mut fmt::Formatter, cx: &LeanCtxt) -> std::fmt::Result {
         match self {
@@ -109,76 +97,142 @@ impl LeanFmt for DataSort {
 
 impl LeanFmt for DataDecl {
     fn lean_fmt(&self, f: &mut fmt::Formatter, cx: &LeanCtxt) -> fmt::Result {
-        if self.ctors.len() == 1 {
+        let params = (0..self.vars)
+            .map(|i| format!(" (t{i} : Type) [Inhabited t{i}]"))
+            .format("");
+        if let [ctor] = &self.ctors[..] {
             writeln!(f, "@[ext]")?;
             write!(f, "structure ")?;
             self.name.lean_fmt(f, cx)?;
-            writeln!(
-                f,
-                " {} where",
-                (0..self.vars)
-                    .map(|i| format!("(t{i} : Type) [Inhabited t{i}]"))
-                    .format(" ")
-            )?;
-            let ctor = &self.ctors[0];
-            if let fixpoint::Var::DataCtor(adt_id, _) = &ctor.name {
+            writeln!(f, "{params} where")?;
+            writeln!(f, "  {} ::", WithLeanCtxt { item: LeanCtorName(&ctor.name), cx })?;
+            for field in &ctor.fields {
                 writeln!(
                     f,
-                    "  mk{}{} ::",
-                    WithLeanCtxt { item: &DataSort::Adt(*adt_id), cx },
-                    as_subscript(0)
+                    "    {} : {}",
+                    WithLeanCtxt { item: LeanFieldName(&field.name), cx },
+                    WithLeanCtxt { item: &field.sort, cx }
                 )?;
-                for (idx, field) in ctor.fields.iter().enumerate() {
-                    writeln!(
+            }
+        } else {
+            write!(f, "inductive ")?;
+            self.name.lean_fmt(f, cx)?;
+            writeln!(f, "{params} where")?;
+            for ctor in &self.ctors {
+                write!(f, "  | {}", WithLeanCtxt { item: LeanCtorName(&ctor.name), cx })?;
+                for field in &ctor.fields {
+                    write!(
                         f,
-                        "    {} : {} ",
-                        WithLeanCtxt { item: LeanField(*adt_id, idx.try_into().unwrap()), cx },
+                        " ({} : {})",
+                        WithLeanCtxt { item: LeanFieldName(&field.name), cx },
                         WithLeanCtxt { item: &field.sort, cx }
                     )?;
                 }
-            } else {
-                bug!("unexpected ctor {ctor:?} in datadecl");
-            };
+                writeln!(f)?;
+            }
+        }
+        writeln!(f, "deriving Inhabited")?;
+
+        // Testers, i.e., the lean counterpart of `is$ctor` in fixpoint.
+        let implicit_params = (0..self.vars)
+            .map(|i| format!(" {{t{i} : Type}} [Inhabited t{i}]"))
+            .join("");
+        let data_ty = if self.vars == 0 {
+            format!("{}", WithLeanCtxt { item: &self.name, cx })
         } else {
-            write!(f, "inductive ")?;
-            self.name.lean_fmt(f, cx)?;
-            writeln!(
+            format!(
+                "({} {})",
+                WithLeanCtxt { item: &self.name, cx },
+                (0..self.vars).map(|i| format!("t{i}")).format(" ")
+            )
+        };
+        for ctor in &self.ctors {
+            writeln!(f)?;
+            write!(
                 f,
-                " {} where",
-                (0..self.vars)
-                    .map(|i| format!("(t{i} : Type) [Inhabited t{i}]"))
-                    .format(" ")
+                "def {}{implicit_params} : {data_ty} -> Prop",
+                WithLeanCtxt { item: LeanTester(&ctor.name), cx }
             )?;
-            for data_ctor in &self.ctors {
-                let fixpoint::Var::DataCtor(adt_id, variant_id) = &data_ctor.name else {
-                    bug!("unexpected ctor {data_ctor:?} in datadecl")
-                };
-                write!(f, "| ")?;
-                write!(
+            if self.ctors.len() == 1 {
+                writeln!(f, " := fun _ => True")?;
+            } else {
+                writeln!(f)?;
+                writeln!(
                     f,
-                    " mk{}{} ",
-                    WithLeanCtxt { item: &DataSort::Adt(*adt_id), cx },
-                    as_subscript(variant_id.as_usize()),
+                    "  | .{} .. => True",
+                    WithLeanCtxt { item: LeanCtorName(&ctor.name), cx }
                 )?;
-                // data_ctor.name.lean_fmt(f, cx)?;
-                for field in &data_ctor.fields {
-                    write!(f, " ")?;
-                    field.lean_fmt(f, cx)?;
-                }
-                writeln!(f)?;
+                writeln!(f, "  | _ => False")?;
             }
         }
         Ok(())
     }
 }
 
+/// The datasort a constructor belongs to
+fn ctor_data_sort(ctor: &Var) -> DataSort {
+    match ctor {
+        Var::DataCtor(adt_id, _) => DataSort::Adt(*adt_id),
+        Var::TupleCtor { arity } => DataSort::Tuple(*arity),
+        _ => bug!("unexpected ctor {ctor:?} in datadecl"),
+    }
+}
+
+/// The name of a constructor without the namespace of its datatype, e.g., `mkFoo₀`
+struct LeanCtorName<'a>(&'a Var);
+
+impl LeanFmt for LeanCtorName<'_> {
+    fn lean_fmt(&self, f: &mut fmt::Formatter, cx: &LeanCtxt) -> fmt::Result {
+        let sort = ctor_data_sort(self.0);
+        write!(f, "mk{}", WithLeanCtxt { item: &sort, cx })?;
+        if let Var::DataCtor(_, variant_idx) = self.0 {
+            write!(f, "{}", as_subscript(variant_idx.as_usize()))?;
+        }
+        Ok(())
+    }
+}
+
+/// The name of the tester for a constructor, e.g., `Foo.isMkFoo₀`
+struct LeanTester<'a>(&'a Var);
+
+impl LeanFmt for LeanTester<'_> {
+    fn lean_fmt(&self, f: &mut fmt::Formatter, cx: &LeanCtxt) -> fmt::Result {
+        let ctor_name = format!("{}", WithLeanCtxt { item: LeanCtorName(self.0), cx });
+        write!(
+            f,
+            "{}.is{}",
+            WithLeanCtxt { item: &ctor_data_sort(self.0), cx },
+            snake_case_to_pascal_case(&ctor_name)
+        )
+    }
+}
+
+/// The name of a field without the namespace of its datatype
+struct LeanFieldName<'a>(&'a Var);
+
+impl LeanFmt for LeanFieldName<'_> {
+    fn lean_fmt(&self, f: &mut fmt::Formatter, cx: &LeanCtxt) -> fmt::Result {
+        match self.0 {
+            Var::DataProj { adt_id, field } => LeanField(*adt_id, *field).lean_fmt(f, cx),
+            Var::TupleProj { field, .. } => write!(f, "fld{}", as_subscript(*field as usize)),
+            _ => bug!("unexpected field {:?} in datadecl", self.0),
+        }
+    }
+}
+
 impl<'a> fmt::Display for LeanThyFunc<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.0 {
             ThyFunc::IntToBv8 => write!(f, "BitVec.ofInt 8"),
+            ThyFunc::IntToBv16 => write!(f, "BitVec.ofInt 16"),
             ThyFunc::IntToBv32 => write!(f, "BitVec.ofInt 32"),
             ThyFunc::IntToBv64 => write!(f, "BitVec.ofInt 64"),
-            ThyFunc::Bv8ToInt | ThyFunc::Bv32ToInt | ThyFunc::Bv64ToInt => {
+            ThyFunc::IntToBv128 => write!(f, "BitVec.ofInt 128"),
+            ThyFunc::Bv8ToInt
+            | ThyFunc::Bv16ToInt
+            | ThyFunc::Bv32ToInt
+            | ThyFunc::Bv64ToInt
+            | ThyFunc::Bv128ToInt => {
                 write!(f, "BitVec.toNat")
             }
             ThyFunc::BvAdd => write!(f, "BitVec.add"),
@@ -209,6 +263,20 @@ impl<'a> fmt::Display for LeanThyFunc<'a> {
             ThyFunc::MapDefault => write!(f, "SmtMap_default"),
             ThyFunc::MapSelect => write!(f, "SmtMap_select"),
             ThyFunc::MapStore => write!(f, "SmtMap_store"),
+            ThyFunc::SetEmpty => write!(f, "SmtSet_empty"),
+            ThyFunc::SetSng => write!(f, "SmtSet_sng"),
+            ThyFunc::SetCup => write!(f, "SmtSet_cup"),
+            ThyFunc::SetCap => write!(f, "SmtSet_cap"),
+            ThyFunc::SetDif => write!(f, "SmtSet_dif"),
+            ThyFunc::SetSub => write!(f, "SmtSet_sub"),
+            ThyFunc::SetMem => write!(f, "SmtSet_mem"),
+            ThyFunc::SeqEmpty => write!(f, "SmtSeq_empty"),
+            ThyFunc::SeqUnit => write!(f, "SmtSeq_unit"),
+            ThyFunc::SeqLen => write!(f, "SmtSeq_len"),
+            ThyFunc::SeqNth => write!(f, "SmtSeq_nth"),
+            ThyFunc::SeqConcat => write!(f, "SmtSeq_concat"),
+            ThyFunc::SeqExtract => write!(f, "SmtSeq_extract"),
+            ThyFunc::SeqUpdate => write!(f, "SmtSeq_update"),
             func => panic!("Unsupported theory function {}", func),
         }
     }
@@ -229,8 +297,9 @@ impl LeanFmt for LeanField {
         let adt_id = self.0;
         if let Some(def_id) = cx.adt_map.get_index(adt_id.as_usize())
             && let Ok(adt_sort_def) = cx.genv.adt_sort_def_of(def_id)
+            && let Some(variant) = adt_sort_def.opt_struct_variant()
         {
-            write!(f, "{}", adt_sort_def.struct_variant().field_names()[self.1 as usize])
+            write!(f, "{}", variant.field_names()[self.1 as usize])
         } else {
             write!(f, "fld{}", as_subscript(self.1 as usize))
         }
@@ -280,16 +349,23 @@ impl LeanFmt for Var {
                     .replace("-", "_");
                 write!(f, "{path}")
             }
-            Var::DataCtor(adt_id, idx) => {
+            Var::DataCtor(..) | Var::TupleCtor { .. } => {
                 write!(
                     f,
-                    "{}.mk{}{}",
-                    WithLeanCtxt { item: LeanAdt(*adt_id), cx },
-                    WithLeanCtxt { item: &DataSort::Adt(*adt_id), cx },
-                    as_subscript(idx.as_usize())
+                    "{}.{}",
+                    WithLeanCtxt { item: &ctor_data_sort(self), cx },
+                    WithLeanCtxt { item: LeanCtorName(self), cx }
                 )
             }
             Var::DataProj { adt_id, field } => LeanDataProj(*adt_id, *field).lean_fmt(f, cx),
+            Var::TupleProj { arity, .. } => {
+                write!(
+                    f,
+                    "{}.{}",
+                    WithLeanCtxt { item: &DataSort::Tuple(*arity), cx },
+                    WithLeanCtxt { item: LeanFieldName(self), cx }
+                )
+            }
             Var::Local(local_var) => {
                 write!(f, "{}", cx.pretty_var_map.get(&PrettyVar::Local(*local_var)))
             }
@@ -342,7 +418,8 @@ impl LeanFmt for Sort {
                             WithLeanCtxt { item: &args[1], cx }
                         )
                     }
-                    _ => todo!(),
+                    SortCtor::Seq => write!(f, "(SmtSeq {})", WithLeanCtxt { item: &args[0], cx }),
+                    SortCtor::Set => write!(f, "(SmtSet {})", WithLeanCtxt { item: &args[0], cx }),
                 }
             }
             Sort::BitVec(bv_size) => {
@@ -364,7 +441,7 @@ impl LeanFmt for Sort {
                 )
             }
             Sort::Var(v) => write!(f, "t{v}"),
-            s => todo!("{:?}", s),
+            Sort::BvSize(size) => write!(f, "{size}"),
         }
     }
 }
@@ -504,8 +581,10 @@ impl LeanFmt for Expr {
             Expr::ThyFunc(thy_func) => {
                 write!(f, "{}", LeanThyFunc(thy_func))
             }
-            Expr::IsCtor(..) => {
-                todo!("not yet implemented: datatypes in lean")
+            Expr::IsCtor(ctor, e) => {
+                write!(f, "({} ", WithLeanCtxt { item: LeanTester(ctor), cx })?;
+                e.lean_fmt(f, cx)?;
+                write!(f, ")")
             }
             Expr::Exists(bind, expr) => {
                 write!(f, "(∃ ")?;
@@ -519,6 +598,19 @@ impl LeanFmt for Expr {
                 write!(f, ")")?;
                 Ok(())
             }
+            Expr::ForAll(bind, _triggers, expr) => {
+                // Triggers only guide SMT instantiation, they have no meaning in lean
+                write!(f, "(∀ ")?;
+                for (var, sort) in bind {
+                    write!(f, "(")?;
+                    var.lean_fmt(f, cx)?;
+                    write!(f, " : {})", WithLeanCtxt { item: sort, cx })?;
+                }
+                write!(f, ", ")?;
+                expr.lean_fmt(f, cx)?;
+                write!(f, ")")?;
+                Ok(())
+            }
         }
     }
 }
@@ -537,6 +629,29 @@ impl LeanFmt for FunDef {
             writeln!(f, " : {} :=", WithLeanCtxt { item: &sort.output, cx })?;
             write!(f, "  ")?;
             body.expr.lean_fmt(f, cx)?;
+            // Recursive functions terminate by their `decreases` metric, which must be non-negative
+            // and decrease lexicographically at every recursive call.
+            if !body.decreases.is_empty() {
+                write!(f, "\ntermination_by ")?;
+                if body.decreases.len() > 1 {
+                    write!(f, "(")?;
+                }
+                for (i, metric) in body.decreases.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "Int.toNat (")?;
+                    metric.lean_fmt(f, cx)?;
+                    write!(f, ")")?;
+                }
+                if body.decreases.len() > 1 {
+                    write!(f, ")")?;
+                }
+                write!(
+                    f,
+                    "\ndecreasing_by all_goals (try simp_wf); all_goals (try simp only [Prod.lex_def] at *); all_goals omega"
+                )?;
+            }
         } else {
             write!(f, " : {} := sorry", WithLeanCtxt { item: sort, cx })?;
         }
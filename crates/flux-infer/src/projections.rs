@@ -819,10 +819,18 @@ fn normalize_alias_reft<'tcx>(
                     .lower(tcx)
                     .map_err(|reason| query_bug!("{reason:?}"))?,
             )?;
-            let e = genv
-                .assoc_refinement_body_for_impl(alias_reft.assoc_id, impl_def_id)?
-                .instantiate(tcx, &args, &[])
-                .apply(refine_args);
+            let body = match genv.assoc_refinement_body_for_impl(alias_reft.assoc_id, impl_def_id) {
+                Ok(body) => body,
+                // An extern spec can add an associated refinement without a default body to a
+                // trait with implementations that don't have a spec. We leave the alias
+                // uninterpreted for those. A missing associated refinement in a local
+                // implementation is reported when comparing the implementation with the trait.
+                Err(QueryErr::MissingAssocReft { .. }) => {
+                    return Ok((false, Expr::alias(alias_reft.clone(), refine_args.clone())));
+                }
+                Err(err) => return Err(err),
+            };
+            let e = body.instantiate(tcx, &args, &[]).apply(refine_args);
             Ok((true, e))
         }
         Some(ImplSource::Builtin(BuiltinImplSource::Misc | BuiltinImplSource::Trivial, _)) => {
@@ -1,5 +1,6 @@
 use std::{
     cell::RefCell,
+    iter,
     ops::ControlFlow,
     rc::{Rc, Weak},
 };
@@ -502,7 +503,8 @@ impl Node {
                 })?
             }
             NodeKind::Assumption(pred) => {
-                let (mut bindings, pred) = cx.assumption_to_fixpoint(pred)?;
+                let pred = Expr::and_from_iter(iter::once(pred.clone()).chain(cx.unfoldings(pred)));
+                let (mut bindings, pred) = cx.assumption_to_fixpoint(&pred)?;
                 let Some(cstr) = children_to_fixpoint(cx, &self.children)? else {
                     return Ok(None);
                 };
@@ -514,7 +516,13 @@ impl Node {
                 Some(fixpoint::Constraint::foralls(bindings, cstr))
             }
             NodeKind::Head(pred, tag) => {
-                Some(cx.head_to_fixpoint(pred, |span| tag.with_dst(span))?)
+                let unfoldings = cx.unfoldings(pred);
+                let pred = if unfoldings.is_empty() {
+                    pred.clone()
+                } else {
+                    Expr::implies(Expr::and_from_iter(unfoldings), pred)
+                };
+                Some(cx.head_to_fixpoint(&pred, |span| tag.with_dst(span))?)
             }
             NodeKind::True => None,
         };
@@ -143,7 +143,11 @@ impl Renderer {
     }
 
     fn output_type(&self) -> syn::Result<TokenStream> {
-        let out = match &self.rule.output {
+        self.render_output(&self.rule.output)
+    }
+
+    fn render_output(&self, output: &Output) -> syn::Result<TokenStream> {
+        let out = match output {
             Output::Base(bty) => {
                 let bty = self.bty_arg_or_prim(bty)?;
                 quote!(#bty.to_ty())
@@ -160,6 +164,13 @@ impl Renderer {
                 let bty = self.bty_arg_or_prim(bty)?;
                 quote!(rty::Ty::constr(#pred, rty::Ty::indexed( #bty, #idx)))
             }
+            Output::Tuple(outputs) => {
+                let tys: Vec<_> = outputs
+                    .iter()
+                    .map(|output| self.render_output(output))
+                    .try_collect()?;
+                quote!(rty::Ty::tuple(vec![#(#tys),*]))
+            }
         };
         Ok(out)
     }
@@ -305,6 +316,7 @@ enum Output {
     Indexed(syn::Ident, TokenStream),
     Exists(syn::Ident, TokenStream),
     Constr(syn::Ident, TokenStream, TokenStream),
+    Tuple(Vec<Output>),
 }
 
 impl Parse for Output {
@@ -316,6 +328,11 @@ impl Parse for Output {
             let idx = parse_index(&content)?;
             let _: Token![|] = content.parse()?;
             Ok(Output::Constr(bty, idx, content.parse()?))
+        } else if input.peek(token::Paren) {
+            let content;
+            parenthesized!(content in input);
+            let outputs = content.parse_terminated(Output::parse, Token![,])?;
+            Ok(Output::Tuple(outputs.into_iter().collect()))
         } else {
             let bty: syn::Ident = input.parse()?;
             if input.peek(token::Bracket) {
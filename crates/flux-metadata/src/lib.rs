@@ -60,6 +60,8 @@ const METADATA_HEADER: &[u8] = &[b'f', b'l', b'u', b'x', 0, 0, 0, METADATA_VERSI
 pub struct CStore {
     local_tables: UnordMap<CrateNum, Tables<DefIndex>>,
     extern_tables: Tables<DefId>,
+    /// The crate defining each of the items in [`CStore::extern_tables`] with an extern spec
+    extern_spec_crates: UnordMap<DefId, CrateNum>,
 }
 
 /// From CREUSOT: used to store the info about source files
@@ -187,11 +189,25 @@ impl CStore {
             let Some(path) = flux_metadata_extern_location(tcx, *crate_num) else { continue };
             let Some(meta) = decode_crate_metadata(tcx, sess, path.as_path()) else { continue };
             cstore.local_tables.insert(*crate_num, meta.local_tables);
+            cstore.record_extern_spec_crate(*crate_num, &meta.extern_tables);
             cstore.merge_extern_tables(tcx, sess, meta.extern_tables);
         }
         cstore
     }
 
+    fn record_extern_spec_crate(&mut self, crate_num: CrateNum, extern_tables: &Tables<DefId>) {
+        let Tables { fn_sig, adt_def, static_info, assoc_refinements_of, .. } = extern_tables;
+        let crates = &mut self.extern_spec_crates;
+        crates.extend_unord(fn_sig.keys().map(|def_id| (*def_id, crate_num)));
+        crates.extend_unord(adt_def.keys().map(|def_id| (*def_id, crate_num)));
+        crates.extend_unord(static_info.keys().map(|def_id| (*def_id, crate_num)));
+        crates.extend_unord(
+            assoc_refinements_of
+                .keys()
+                .map(|def_id| (*def_id, crate_num)),
+        );
+    }
+
     fn merge_extern_tables(
         &mut self,
         tcx: TyCtxt,
@@ -260,6 +276,10 @@ impl CrateStore for CStore {
         get!(self, no_panic, def_id)
     }
 
+    fn extern_spec_crate(&self, def_id: DefId) -> Option<CrateNum> {
+        self.extern_spec_crates.get(&def_id).copied()
+    }
+
     fn variants_of(
         &self,
         def_id: DefId,
@@ -484,7 +504,7 @@ fn encode_def_ids<K: Eq + Hash + Copy>(
                     .fn_sig
                     .insert(key, genv.run_query_if_reached(def_id, GlobalEnv::fn_sig));
             }
-            DefKind::Enum | DefKind::Struct => {
+            DefKind::Enum | DefKind::Struct | DefKind::Union => {
                 tables
                     .generics_of
                     .insert(key, genv.run_query_if_reached(def_id, GlobalEnv::generics_of));
@@ -0,0 +1,290 @@
+//! Audit of what is verified in a crate enabled with `-Faudit=<path>`.
+//!
+//! The audit lists the items that are not checked and why, the assumptions under which the checked
+//! items are verified, and the extern specs the crate relies on. Unlike the report enabled with
+//! `-Freport`, it's computed from the attributes and flags after checking the crate.
+use std::io;
+
+use flux_config::{self as config, RawDerefMode};
+use rustc_data_structures::unord::{ExtendUnord, UnordMap};
+use rustc_hir::{
+    def::DefKind,
+    def_id::{LOCAL_CRATE, LocalDefId},
+};
+use serde::Serialize;
+
+use crate::{
+    def_id::MaybeExternId,
+    fhir,
+    global_env::GlobalEnv,
+    report::{self, SpanReport},
+};
+
+#[derive(Serialize)]
+struct AuditDump {
+    crate_name: String,
+    unchecked: Vec<AuditEntry<Unchecked>>,
+    assumptions: Vec<AuditEntry<Assumption>>,
+    extern_specs: Vec<ExternSpec>,
+}
+
+#[derive(Serialize)]
+struct AuditEntry<K> {
+    def_path: String,
+    span: SpanReport,
+    kind: K,
+    origin: Origin,
+}
+
+/// The reason an item is not checked
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Unchecked {
+    /// The item is ignored
+    Ignored,
+    /// The item doesn't match any of the `include` patterns
+    NotIncluded,
+    /// The item was generated by a macro
+    MacroGenerated,
+    /// The item is trusted
+    Trusted,
+}
+
+/// An assumption under which a checked item is verified
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Assumption {
+    /// The signature of a trait method implementation is not checked against the trait
+    TrustedImpl,
+    /// The body is checked with a proof written outside of Flux
+    ProvenExternally,
+    /// Casts between sorts that cannot be interpreted are treated as uninterpreted functions
+    AllowUninterpretedCast,
+    /// Raw pointers can be dereferenced without any check
+    AllowRawDeref,
+}
+
+/// Where the reason for an entry in the audit comes from
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Origin {
+    /// An attribute on the item or on one of its parents
+    Attribute { item: String, span: SpanReport },
+    /// A flag, which can also be set in the `metadata` table or in `flux.toml`
+    Flag { flag: &'static str },
+    /// The macro that generated the item
+    Macro { name: String },
+}
+
+#[derive(Serialize)]
+struct ExternSpec {
+    def_path: String,
+    crate_name: String,
+}
+
+/// Writes the audit to the path passed with `-Faudit` if present.
+pub fn dump_audit(genv: GlobalEnv) -> io::Result<()> {
+    let Some(path) = config::audit_path() else { return Ok(()) };
+    let tcx = genv.tcx();
+
+    let mut unchecked = vec![];
+    let mut assumptions = vec![];
+    for def_id in genv.iter_local_def_id() {
+        let Some(is_fn) = audited_kind(genv, def_id) else { continue };
+        if let Some((kind, origin)) = unchecked_reason(genv, def_id, is_fn) {
+            unchecked.push(AuditEntry::new(genv, def_id, kind, origin));
+            continue;
+        }
+        for (kind, origin) in assumptions_of(genv, def_id) {
+            assumptions.push(AuditEntry::new(genv, def_id, kind, origin));
+        }
+    }
+
+    let crate_name = tcx.crate_name(LOCAL_CRATE).to_string();
+    let mut extern_specs = genv
+        .iter_extern_def_id()
+        .map(|def_id| (def_id, LOCAL_CRATE))
+        .collect::<UnordMap<_, _>>();
+    extern_specs.extend_unord(genv.used_extern_specs().into_items());
+    let mut extern_specs = extern_specs
+        .into_items()
+        .filter(|(def_id, _)| {
+            matches!(
+                genv.def_kind(*def_id),
+                DefKind::Fn
+                    | DefKind::AssocFn
+                    | DefKind::Struct
+                    | DefKind::Enum
+                    | DefKind::Union
+                    | DefKind::Trait
+                    | DefKind::Impl { .. }
+                    | DefKind::Static { .. }
+            )
+        })
+        .map(|(def_id, krate)| {
+            ExternSpec {
+                def_path: tcx.def_path_str(def_id),
+                crate_name: tcx.crate_name(krate).to_string(),
+            }
+        })
+        .into_sorted_stable_ord_by_key(|spec| &spec.def_path);
+    extern_specs.sort_by(|a, b| a.crate_name.cmp(&b.crate_name));
+
+    let mut file = std::fs::File::create(report::output_file(path, &crate_name, "audit")?)?;
+    serde_json::to_writer_pretty(
+        &mut file,
+        &AuditDump { crate_name, unchecked, assumptions, extern_specs },
+    )?;
+    Ok(())
+}
+
+/// Whether the item is included in the audit, i.e., it's a function with a body or a static, and
+/// in that case whether it's a function.
+fn audited_kind(genv: GlobalEnv, def_id: LocalDefId) -> Option<bool> {
+    match genv.def_kind(def_id) {
+        DefKind::Fn | DefKind::AssocFn if genv.tcx().is_mir_available(def_id) => Some(true),
+        DefKind::Static { .. } => Some(false),
+        _ => None,
+    }
+}
+
+/// The reason the item is not checked, following the same order as the checks done when checking
+/// the crate.
+fn unchecked_reason(
+    genv: GlobalEnv,
+    def_id: LocalDefId,
+    is_fn: bool,
+) -> Option<(Unchecked, Origin)> {
+    let tcx = genv.tcx();
+    if genv.ignored(def_id) {
+        let origin = attr_origin(genv, def_id, |attrs| attrs.ignored().map(|i| i.to_bool()))
+            .unwrap_or(Origin::Flag { flag: "ignore" });
+        return Some((Unchecked::Ignored, origin));
+    }
+    if !genv.included(MaybeExternId::Local(def_id)) {
+        return Some((Unchecked::NotIncluded, Origin::Flag { flag: "include" }));
+    }
+    if !is_fn {
+        return None;
+    }
+    let span = tcx.def_span(def_id);
+    if !span.ctxt().is_root() {
+        let name = span.ctxt().outer_expn_data().kind.descr();
+        return Some((Unchecked::MacroGenerated, Origin::Macro { name }));
+    }
+    if genv.trusted(def_id) {
+        let origin = attr_origin(genv, def_id, |attrs| attrs.trusted().map(|t| t.to_bool()))
+            .unwrap_or_else(|| {
+                let annotated = genv
+                    .traverse_parents(def_id, |did| genv.fhir_attr_map(did).trusted())
+                    .is_some();
+                if !annotated && config::trusted_default() {
+                    Origin::Flag { flag: "trusted" }
+                } else {
+                    Origin::Flag { flag: "include-trusted" }
+                }
+            });
+        return Some((Unchecked::Trusted, origin));
+    }
+    None
+}
+
+fn assumptions_of(genv: GlobalEnv, def_id: LocalDefId) -> Vec<(Assumption, Origin)> {
+    let tcx = genv.tcx();
+    let mut assumptions = vec![];
+
+    if let Some(origin) = trusted_impl_origin(genv, def_id) {
+        assumptions.push((Assumption::TrustedImpl, origin));
+    }
+
+    if config::lean().is_check()
+        && let Some(span) = genv.proven_externally(def_id)
+    {
+        let origin =
+            Origin::Attribute { item: tcx.def_path_str(def_id), span: SpanReport::new(tcx, span) };
+        assumptions.push((Assumption::ProvenExternally, origin));
+    }
+
+    let opts = genv.infer_opts(def_id);
+    if opts.allow_uninterpreted_cast {
+        let origin = opts_origin(genv, def_id, |opts| opts.allow_uninterpreted_cast.is_some())
+            .unwrap_or(Origin::Flag { flag: "allow-uninterpreted-cast" });
+        assumptions.push((Assumption::AllowUninterpretedCast, origin));
+    }
+    if let RawDerefMode::Ok = opts.allow_raw_deref {
+        let origin = opts_origin(genv, def_id, |opts| opts.allow_raw_deref.is_some())
+            .unwrap_or(Origin::Flag { flag: "allow-raw-deref" });
+        assumptions.push((Assumption::AllowRawDeref, origin));
+    }
+
+    assumptions
+}
+
+/// If the item is a method in a trait implementation whose signature is not checked against the
+/// trait, returns the origin of the `trusted_impl` annotation either on the method or on the trait
+/// method.
+fn trusted_impl_origin(genv: GlobalEnv, def_id: LocalDefId) -> Option<Origin> {
+    let tcx = genv.tcx();
+    tcx.trait_impl_of_assoc(def_id.to_def_id())?;
+    let trait_item_id = tcx.associated_item(def_id).trait_item_def_id()?;
+    let trait_item_id = genv
+        .resolve_id(trait_item_id)
+        .as_maybe_extern()
+        .map(|id| id.local_id());
+    [Some(def_id), trait_item_id]
+        .into_iter()
+        .flatten()
+        .find(|did| genv.trusted_impl(*did))
+        .map(|did| {
+            attr_origin(genv, did, |attrs| attrs.trusted_impl().map(|t| t.to_bool()))
+                .unwrap_or(Origin::Flag { flag: "include-trusted-impl" })
+        })
+}
+
+/// Finds the closest item in the parent chain of `def_id` with an attribute for which `f` returns
+/// [`Some`] and returns it as the origin if `f` returned `true`.
+fn attr_origin(
+    genv: GlobalEnv,
+    def_id: LocalDefId,
+    f: impl Fn(fhir::AttrMap) -> Option<bool>,
+) -> Option<Origin> {
+    let (did, value) =
+        genv.traverse_parents(def_id, |did| Some((did, f(genv.fhir_attr_map(did))?)))?;
+    value.then(|| Origin::attribute(genv, did))
+}
+
+/// Finds the closest item in the parent chain of `def_id` whose `#[flux::opts]` attribute sets the
+/// option checked by `f`.
+fn opts_origin(
+    genv: GlobalEnv,
+    def_id: LocalDefId,
+    f: impl Fn(&config::PartialInferOpts) -> bool,
+) -> Option<Origin> {
+    let did = genv.traverse_parents(def_id, |did| {
+        let opts = genv.fhir_attr_map(did).infer_opts()?;
+        f(&opts).then_some(did)
+    })?;
+    Some(Origin::attribute(genv, did))
+}
+
+impl Origin {
+    fn attribute(genv: GlobalEnv, def_id: LocalDefId) -> Self {
+        let tcx = genv.tcx();
+        Origin::Attribute {
+            item: tcx.def_path_str(def_id),
+            span: SpanReport::new(tcx, tcx.def_span(def_id)),
+        }
+    }
+}
+
+impl<K> AuditEntry<K> {
+    fn new(genv: GlobalEnv, def_id: LocalDefId, kind: K, origin: Origin) -> Self {
+        let tcx = genv.tcx();
+        AuditEntry {
+            def_path: tcx.def_path_str(def_id),
+            span: SpanReport::new(tcx, tcx.def_span(def_id)),
+            kind,
+            origin,
+        }
+    }
+}
@@ -37,6 +37,8 @@ pub trait CrateStore {
     fn func_span(&self, def_id: FluxDefId) -> Option<rustc_span::Span>;
     fn sort_decl_param_count(&self, def_id: FluxDefId) -> Option<usize>;
     fn no_panic(&self, def_id: DefId) -> Option<bool>;
+    /// The crate providing an extern spec for `def_id`, if any
+    fn extern_spec_crate(&self, def_id: DefId) -> Option<CrateNum>;
 }
 
 pub type CrateStoreDyn = dyn CrateStore;
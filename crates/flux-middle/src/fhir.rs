@@ -19,8 +19,8 @@ use std::{borrow::Cow, fmt, iter};
 
 use flux_common::{bug, span_bug};
 use flux_config::PartialInferOpts;
-use flux_rustc_bridge::def_id_to_string;
-pub use flux_syntax::surface::{BinOp, UnOp};
+use flux_rustc_bridge::{def_id_to_string, ty::Pattern};
+pub use flux_syntax::surface::{BinOp, LoopInvariantKind, UnOp};
 use flux_syntax::surface::{Ignored, ParamMode, Trusted};
 use itertools::Itertools;
 use rustc_abi;
@@ -518,6 +518,29 @@ pub struct FnSig<'fhir> {
     pub header: FnHeader,
     pub decl: &'fhir FnDecl<'fhir>,
     pub no_panic_if: Option<Expr<'fhir>>,
+    pub annots: &'fhir FnAnnots<'fhir>,
+}
+
+/// Annotations of a function used to check its termination and its body. They are kept behind a
+/// reference because [`FnSig`] is stored inline in many types.
+#[derive(Clone, Copy, Default)]
+pub struct FnAnnots<'fhir> {
+    /// The (lexicographic) termination metric of the function, or an empty slice if the
+    /// function doesn't have a `decreases` clause.
+    pub decreases: &'fhir [Expr<'fhir>],
+    pub loop_invariants: &'fhir [LoopInvariant<'fhir>],
+}
+
+/// An invariant or a variant attached to a loop in the body of a function.
+#[derive(Clone, Copy)]
+pub struct LoopInvariant<'fhir> {
+    pub kind: LoopInvariantKind,
+    /// The Rust locals mentioned in the invariant. The span of each parameter is the span of the
+    /// binding declaring the local.
+    pub params: &'fhir [RefineParam<'fhir>],
+    pub pred: Expr<'fhir>,
+    /// The span of the loop
+    pub span: Span,
 }
 
 #[derive(Clone, Copy)]
@@ -659,6 +682,8 @@ pub enum BaseTyKind<'fhir> {
     Path(QPath<'fhir>),
     Slice(&'fhir Ty<'fhir>),
     RawPtr(&'fhir Ty<'fhir>, Mutability),
+    /// A pattern type. The pattern is lowered from rust when lifting since it cannot be refined.
+    Pat(&'fhir Ty<'fhir>, &'fhir Pattern),
     Err(ErrorGuaranteed),
 }
 
@@ -882,6 +907,7 @@ pub enum PrimSort {
     Real,
     Set,
     Map,
+    Seq,
     Str,
     RawPtr,
 }
@@ -896,6 +922,7 @@ impl PrimSort {
             PrimSort::Real => "real",
             PrimSort::Set => "Set",
             PrimSort::Map => "Map",
+            PrimSort::Seq => "Seq",
             PrimSort::RawPtr => "ptr",
         }
     }
@@ -909,7 +936,7 @@ impl PrimSort {
             | PrimSort::Char
             | PrimSort::Str
             | PrimSort::RawPtr => 0,
-            PrimSort::Set => 1,
+            PrimSort::Set | PrimSort::Seq => 1,
             PrimSort::Map => 2,
         }
     }
@@ -1053,8 +1080,11 @@ pub enum ExprKind<'fhir> {
     IfThenElse(&'fhir Expr<'fhir>, &'fhir Expr<'fhir>, &'fhir Expr<'fhir>),
     Abs(&'fhir [RefineParam<'fhir>], &'fhir Expr<'fhir>),
     BoundedQuant(QuantKind, RefineParam<'fhir>, Range, &'fhir Expr<'fhir>),
+    /// Unbounded quantifier with a (possibly empty) list of triggers
+    Quant(QuantKind, &'fhir [RefineParam<'fhir>], &'fhir [Trigger<'fhir>], &'fhir Expr<'fhir>),
     Record(&'fhir [Expr<'fhir>]),
     SetLiteral(&'fhir [Expr<'fhir>]),
+    SeqLiteral(&'fhir [Expr<'fhir>]),
     Constructor(Option<PathExpr<'fhir>>, &'fhir [FieldExpr<'fhir>], Option<&'fhir Spread<'fhir>>),
     Block(&'fhir [LetDecl<'fhir>], &'fhir Expr<'fhir>),
     Tuple(&'fhir [Expr<'fhir>]),
@@ -1067,6 +1097,13 @@ pub enum QPathExpr<'fhir> {
     TypeRelative(&'fhir Ty<'fhir>, Ident),
 }
 
+/// A trigger pattern for a quantifier. A trigger with more than one expression is a multi-pattern.
+#[derive(Clone, Copy)]
+pub struct Trigger<'fhir> {
+    pub exprs: &'fhir [Expr<'fhir>],
+    pub span: Span,
+}
+
 #[derive(Clone, Copy)]
 pub struct LetDecl<'fhir> {
     pub param: RefineParam<'fhir>,
@@ -1246,6 +1283,9 @@ pub struct SpecFunc<'fhir> {
     pub params: usize,
     pub args: &'fhir [RefineParam<'fhir>],
     pub sort: Sort<'fhir>,
+    /// The metric used to check termination of a recursive definition. Empty if the function
+    /// doesn't have a `decreases` clause.
+    pub decreases: &'fhir [Expr<'fhir>],
     pub body: Option<Expr<'fhir>>,
     pub hide: bool,
     pub ident_span: Span,
@@ -1269,6 +1309,9 @@ pub enum SpecFuncKind {
     Cast,
     /// Built-in function to get the size of a raw pointer's pointee type.
     PtrSize,
+    /// Built-in function to get the number of elements between the start of a raw pointer's
+    /// allocation and the pointer.
+    PtrOffset,
 }
 
 impl SpecFuncKind {
@@ -1388,6 +1431,23 @@ impl fmt::Debug for Requires<'_> {
     }
 }
 
+impl fmt::Debug for LoopInvariant<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.kind {
+            LoopInvariantKind::Invariant => "loop_invariant",
+            LoopInvariantKind::Variant => "loop_variant",
+        };
+        write!(
+            f,
+            "{name}({}) {:?}",
+            self.params.iter().format_with(",", |param, f| {
+                f(&format_args!("{}:{:?}", param.name, param.sort))
+            }),
+            self.pred
+        )
+    }
+}
+
 impl fmt::Debug for Ensures<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -1488,6 +1548,7 @@ impl fmt::Debug for BaseTy<'_> {
             BaseTyKind::Slice(ty) => write!(f, "[{ty:?}]"),
             BaseTyKind::RawPtr(ty, Mutability::Not) => write!(f, "*const {ty:?}"),
             BaseTyKind::RawPtr(ty, Mutability::Mut) => write!(f, "*mut {ty:?}"),
+            BaseTyKind::Pat(ty, pat) => write!(f, "pattern_type!({ty:?} is {pat:?})"),
             BaseTyKind::Err(_) => write!(f, "err"),
         }
     }
@@ -1605,6 +1666,9 @@ impl fmt::Debug for Expr<'_> {
             ExprKind::SetLiteral(elems) => {
                 write!(f, "#{{ {:?} }}", elems.iter().format(", "))
             }
+            ExprKind::SeqLiteral(elems) => {
+                write!(f, "#[{:?}]", elems.iter().format(", "))
+            }
             ExprKind::Constructor(path, exprs, spread) => {
                 if let Some(path) = path
                     && let Some(s) = spread
@@ -1621,6 +1685,13 @@ impl fmt::Debug for Expr<'_> {
             ExprKind::BoundedQuant(kind, refine_param, rng, expr) => {
                 write!(f, "{kind:?} {refine_param:?} in {}.. {} {{ {expr:?} }}", rng.start, rng.end)
             }
+            ExprKind::Quant(kind, params, triggers, expr) => {
+                write!(f, "{kind:?} {:?}", params.iter().format(", "))?;
+                for trigger in triggers {
+                    write!(f, " #[trigger({:?})]", trigger.exprs.iter().format(", "))?;
+                }
+                write!(f, ". {expr:?}")
+            }
             ExprKind::Err(_) => write!(f, "err"),
             ExprKind::Block(decls, body) => {
                 for decl in decls {
@@ -1688,6 +1759,7 @@ impl fmt::Debug for SortRes {
             SortRes::PrimSort(PrimSort::Str) => write!(f, "str"),
             SortRes::PrimSort(PrimSort::Set) => write!(f, "Set"),
             SortRes::PrimSort(PrimSort::Map) => write!(f, "Map"),
+            SortRes::PrimSort(PrimSort::Seq) => write!(f, "Seq"),
             SortRes::PrimSort(PrimSort::RawPtr) => write!(f, "ptr"),
             SortRes::SortParam(n) => write!(f, "@{n}"),
             SortRes::TyParam(def_id) => write!(f, "{}::sort", def_id_to_string(*def_id)),
@@ -2,10 +2,10 @@ use super::{
     AliasReft, AssocItemConstraint, AssocItemConstraintKind, BaseTy, BaseTyKind, Ensures, EnumDef,
     Expr, ExprKind, FieldDef, FieldExpr, FluxItem, FnDecl, FnOutput, FnSig, ForeignItem,
     ForeignItemKind, FuncSort, GenericArg, GenericBound, Generics, Impl, ImplAssocReft, ImplItem,
-    ImplItemKind, Item, ItemKind, Lifetime, Lit, OpaqueTy, OwnerNode, Path, PathExpr, PathSegment,
-    PolyFuncSort, PolyTraitRef, QPath, Qualifier, RefineParam, Requires, Sort, SortPath, SpecFunc,
-    StructDef, TraitAssocReft, TraitItem, TraitItemKind, Ty, TyAlias, TyKind, VariantDef,
-    VariantRet, WhereBoundPredicate,
+    ImplItemKind, Item, ItemKind, Lifetime, Lit, LoopInvariant, OpaqueTy, OwnerNode, Path,
+    PathExpr, PathSegment, PolyFuncSort, PolyTraitRef, QPath, Qualifier, RefineParam, Requires,
+    Sort, SortPath, SpecFunc, StructDef, TraitAssocReft, TraitItem, TraitItemKind, Ty, TyAlias,
+    TyKind, VariantDef, VariantRet, WhereBoundPredicate,
 };
 use crate::fhir::{PrimOpProp, QPathExpr, SortDecl, StructKind};
 
@@ -126,6 +126,10 @@ pub trait Visitor<'v>: Sized {
         walk_fn_decl(self, decl);
     }
 
+    fn visit_loop_invariant(&mut self, invariant: &LoopInvariant<'v>) {
+        walk_loop_invariant(self, invariant);
+    }
+
     fn visit_refine_param(&mut self, param: &RefineParam<'v>) {
         walk_refine_param(self, param);
     }
@@ -210,6 +214,7 @@ fn walk_sort_decl<'v, V: Visitor<'v>>(_vis: &mut V, _sort_decl: &SortDecl) {}
 fn walk_func<'v, V: Visitor<'v>>(vis: &mut V, func: &SpecFunc<'v>) {
     walk_list!(vis, visit_refine_param, func.args);
     vis.visit_sort(&func.sort);
+    walk_list!(vis, visit_expr, func.decreases);
     if let Some(body) = &func.body {
         vis.visit_expr(body);
     }
@@ -391,6 +396,13 @@ pub fn walk_where_predicate<'v, V: Visitor<'v>>(vis: &mut V, predicate: &WhereBo
 
 pub fn walk_fn_sig<'v, V: Visitor<'v>>(vis: &mut V, sig: &FnSig<'v>) {
     vis.visit_fn_decl(sig.decl);
+    walk_list!(vis, visit_expr, sig.annots.decreases);
+    walk_list!(vis, visit_loop_invariant, sig.annots.loop_invariants);
+}
+
+pub fn walk_loop_invariant<'v, V: Visitor<'v>>(vis: &mut V, invariant: &LoopInvariant<'v>) {
+    walk_list!(vis, visit_refine_param, invariant.params);
+    vis.visit_expr(&invariant.pred);
 }
 
 pub fn walk_fn_decl<'v, V: Visitor<'v>>(vis: &mut V, decl: &FnDecl<'v>) {
@@ -483,6 +495,7 @@ pub fn walk_bty<'v, V: Visitor<'v>>(vis: &mut V, bty: &BaseTy<'v>) {
         BaseTyKind::RawPtr(ty, _mtblt) => {
             vis.visit_ty(ty);
         }
+        BaseTyKind::Pat(ty, _) => vis.visit_ty(ty),
         BaseTyKind::Err(_) => {}
     }
 }
@@ -596,7 +609,10 @@ pub fn walk_expr<'v, V: Visitor<'v>>(vis: &mut V, expr: &Expr<'v>) {
             walk_list!(vis, visit_refine_param, refine_params);
             vis.visit_expr(body);
         }
-        ExprKind::Record(exprs) | ExprKind::SetLiteral(exprs) | ExprKind::Tuple(exprs) => {
+        ExprKind::Record(exprs)
+        | ExprKind::SetLiteral(exprs)
+        | ExprKind::SeqLiteral(exprs)
+        | ExprKind::Tuple(exprs) => {
             walk_list!(vis, visit_expr, exprs);
         }
         ExprKind::Constructor(path, exprs, spread) => {
@@ -612,6 +628,13 @@ pub fn walk_expr<'v, V: Visitor<'v>>(vis: &mut V, expr: &Expr<'v>) {
             vis.visit_refine_param(&param);
             vis.visit_expr(expr);
         }
+        ExprKind::Quant(_, params, triggers, expr) => {
+            walk_list!(vis, visit_refine_param, params);
+            for trigger in triggers {
+                walk_list!(vis, visit_expr, trigger.exprs);
+            }
+            vis.visit_expr(expr);
+        }
         ExprKind::Block(decls, body) => {
             for decl in decls {
                 vis.visit_expr(&decl.init);
@@ -12,7 +12,7 @@ use flux_config::{self as config, IncludePattern};
 use flux_errors::FluxSession;
 use flux_rustc_bridge::{self, lowering::Lower, mir, ty};
 use flux_syntax::symbols::sym;
-use rustc_data_structures::unord::UnordSet;
+use rustc_data_structures::unord::{UnordMap, UnordSet};
 use rustc_hir::{
     LangItem,
     def::DefKind,
@@ -75,6 +75,17 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
         self.inner.queries.queried(def_id)
     }
 
+    /// The extern specs defined in other crates that were used while checking the local crate,
+    /// together with the crate providing each of them.
+    pub fn used_extern_specs(self) -> UnordMap<DefId, CrateNum> {
+        self.inner
+            .queries
+            .queried_def_ids()
+            .into_items()
+            .filter_map(|def_id| Some((def_id, self.cstore().extern_spec_crate(def_id)?)))
+            .collect()
+    }
+
     /// Runs a query only if the given key's `DefId` was previously queried during checking.
     ///
     /// During checking, we track all items transitively reached from explicitly included items.
@@ -724,7 +735,7 @@ impl<'genv, 'tcx> GlobalEnv<'genv, 'tcx> {
     }
 
     /// Traverse the parent chain of `def_id` until the first node for which `f` returns [`Some`].
-    fn traverse_parents<T>(
+    pub(crate) fn traverse_parents<T>(
         self,
         mut def_id: LocalDefId,
         mut f: impl FnMut(LocalDefId) -> Option<T>,
@@ -26,6 +26,7 @@ extern crate rustc_type_ir;
 
 extern crate self as flux_middle;
 
+pub mod audit;
 pub mod big_int;
 mod builtin_assoc_refts;
 pub mod cstore;
@@ -35,6 +36,7 @@ pub mod global_env;
 pub mod metrics;
 pub mod pretty;
 pub mod queries;
+pub mod report;
 pub mod rty;
 mod sort_of;
 
@@ -126,10 +128,14 @@ pub fn name_of_thy_func(func: liquid_fixpoint::ThyFunc) -> Option<&'static str>
         ThyFunc::StrContains => "str_contains",
         ThyFunc::IntToBv8 => "bv_int_to_bv8",
         ThyFunc::Bv8ToInt => "bv_bv8_to_int",
+        ThyFunc::IntToBv16 => "bv_int_to_bv16",
+        ThyFunc::Bv16ToInt => "bv_bv16_to_int",
         ThyFunc::IntToBv32 => "bv_int_to_bv32",
         ThyFunc::Bv32ToInt => "bv_bv32_to_int",
         ThyFunc::IntToBv64 => "bv_int_to_bv64",
         ThyFunc::Bv64ToInt => "bv_bv64_to_int",
+        ThyFunc::IntToBv128 => "bv_int_to_bv128",
+        ThyFunc::Bv128ToInt => "bv_bv128_to_int",
         ThyFunc::BvUge => "bv_uge",
         ThyFunc::BvSge => "bv_sge",
         ThyFunc::BvUdiv => "bv_udiv",
@@ -163,6 +169,13 @@ pub fn name_of_thy_func(func: liquid_fixpoint::ThyFunc) -> Option<&'static str>
         ThyFunc::MapDefault => "map_default",
         ThyFunc::MapSelect => "map_select",
         ThyFunc::MapStore => "map_store",
+        ThyFunc::SeqEmpty => "seq_empty",
+        ThyFunc::SeqUnit => "seq_unit",
+        ThyFunc::SeqLen => "seq_len",
+        ThyFunc::SeqNth => "seq_nth",
+        ThyFunc::SeqConcat => "seq_concat",
+        ThyFunc::SeqExtract => "seq_sub",
+        ThyFunc::SeqUpdate => "seq_update",
     };
     Some(name)
 }
@@ -212,6 +225,20 @@ fn sort_of_thy_func(func: liquid_fixpoint::ThyFunc) -> Option<rty::PolyFuncSort>
                 rty::FuncSort::new(vec![BitVec(BvSize::Fixed(8))], Int),
             )
         }
+        ThyFunc::IntToBv16 => {
+            // int -> BitVec<16>
+            rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Int], BitVec(BvSize::Fixed(16))),
+            )
+        }
+        ThyFunc::Bv16ToInt => {
+            // BitVec<16> -> int
+            rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![BitVec(BvSize::Fixed(16))], Int),
+            )
+        }
         ThyFunc::IntToBv32 => {
             // int -> BitVec<32>
             rty::PolyFuncSort::new(
@@ -240,6 +267,20 @@ fn sort_of_thy_func(func: liquid_fixpoint::ThyFunc) -> Option<rty::PolyFuncSort>
                 rty::FuncSort::new(vec![BitVec(BvSize::Fixed(64))], Int),
             )
         }
+        ThyFunc::IntToBv128 => {
+            // int -> BitVec<128>
+            rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![rty::Sort::Int], BitVec(BvSize::Fixed(128))),
+            )
+        }
+        ThyFunc::Bv128ToInt => {
+            // BitVec<128> -> int
+            rty::PolyFuncSort::new(
+                List::empty(),
+                rty::FuncSort::new(vec![BitVec(BvSize::Fixed(128))], Int),
+            )
+        }
         ThyFunc::BvUdiv
         | ThyFunc::BvSdiv
         | ThyFunc::BvSrem
@@ -365,6 +406,70 @@ fn sort_of_thy_func(func: liquid_fixpoint::ThyFunc) -> Option<rty::PolyFuncSort>
                 ),
             )
         }
+        ThyFunc::SeqEmpty => {
+            // ∀s. () -> Seq<s>
+            rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(vec![], Sort::app(Seq, List::singleton(Var(param0)))),
+            )
+        }
+        ThyFunc::SeqUnit => {
+            // ∀s. s -> Seq<s>
+            rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(vec![Var(param0)], Sort::app(Seq, List::singleton(Var(param0)))),
+            )
+        }
+        ThyFunc::SeqLen => {
+            // ∀s. Seq<s> -> int
+            rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(vec![Sort::app(Seq, List::singleton(Var(param0)))], Int),
+            )
+        }
+        ThyFunc::SeqNth => {
+            // ∀s. (Seq<s>, int) -> s
+            rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(
+                    vec![Sort::app(Seq, List::singleton(Var(param0))), Int],
+                    Var(param0),
+                ),
+            )
+        }
+        ThyFunc::SeqConcat => {
+            // ∀s. (Seq<s>, Seq<s>) -> Seq<s>
+            rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(
+                    vec![
+                        Sort::app(Seq, List::singleton(Var(param0))),
+                        Sort::app(Seq, List::singleton(Var(param0))),
+                    ],
+                    Sort::app(Seq, List::singleton(Var(param0))),
+                ),
+            )
+        }
+        ThyFunc::SeqExtract => {
+            // ∀s. (Seq<s>, int, int) -> Seq<s>
+            rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(
+                    vec![Sort::app(Seq, List::singleton(Var(param0))), Int, Int],
+                    Sort::app(Seq, List::singleton(Var(param0))),
+                ),
+            )
+        }
+        ThyFunc::SeqUpdate => {
+            // ∀s. (Seq<s>, int, s) -> Seq<s>
+            rty::PolyFuncSort::new(
+                List::singleton(SortParamKind::Sort),
+                rty::FuncSort::new(
+                    vec![Sort::app(Seq, List::singleton(Var(param0))), Int, Var(param0)],
+                    Sort::app(Seq, List::singleton(Var(param0))),
+                ),
+            )
+        }
     };
     Some(sort)
 }
@@ -423,6 +528,20 @@ impl Specs {
         None
     }
 
+    /// Returns the signature annotated on the function `owner_id`, if any.
+    pub fn fn_sig_mut(&mut self, owner_id: OwnerId) -> Option<&mut surface::FnSig> {
+        if let Some(item) = self.items.get_mut(&owner_id) {
+            let surface::ItemKind::Fn(fn_sig) = &mut item.kind else { return None };
+            fn_sig.as_mut()
+        } else if let Some(trait_item) = self.trait_items.get_mut(&owner_id) {
+            trait_item.sig.as_mut()
+        } else if let Some(impl_item) = self.impl_items.get_mut(&owner_id) {
+            impl_item.sig.as_mut()
+        } else {
+            None
+        }
+    }
+
     pub fn get_trait_item(&self, owner_id: OwnerId) -> Option<&surface::TraitItemFn> {
         self.trait_items.get(&owner_id)
     }
@@ -582,6 +701,8 @@ pub enum FixpointQueryKind {
     Body,
     /// Query issued to check an (enum) invariant is implied by the type definition
     Invariant,
+    /// Query issued to check the recursive spec functions defined in an item terminate
+    Defns,
 }
 
 impl FixpointQueryKind {
@@ -590,6 +711,7 @@ impl FixpointQueryKind {
             FixpointQueryKind::Impl => "sub.fluxc",
             FixpointQueryKind::Body => "fluxc",
             FixpointQueryKind::Invariant => "fluxc",
+            FixpointQueryKind::Defns => "defns.fluxc",
         }
     }
 
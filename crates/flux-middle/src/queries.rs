@@ -6,7 +6,7 @@ use std::{
 use flux_arc_interner::List;
 use flux_common::{bug, tracked_span_bug};
 use flux_config as config;
-use flux_errors::{E0999, ErrorGuaranteed};
+use flux_errors::{E0998, E0999, ErrorGuaranteed};
 use flux_rustc_bridge::{
     self, def_id_to_string,
     lowering::{self, Lower, UnsupportedErr},
@@ -335,6 +335,10 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
         self.queried_def_ids.borrow().contains(&def_id)
     }
 
+    pub(crate) fn queried_def_ids(&self) -> UnordSet<DefId> {
+        self.queried_def_ids.borrow().clone()
+    }
+
     pub(crate) fn mir(
         &self,
         genv: GlobalEnv<'genv, 'tcx>,
@@ -595,6 +599,9 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
                 |def_id| {
                     // TODO(RJ): fix duplication with [`conv_constant`]` in `flux-fhir-analysis`
                     let ty = genv.tcx().type_of(def_id).no_bound_vars().unwrap();
+                    // Constants of pattern type are interpreted like their base type
+                    let ty =
+                        if let rustc_middle::ty::Pat(base, _) = ty.kind() { *base } else { ty };
                     if ty.is_integral() {
                         let val = genv.tcx().const_eval_poly(def_id).ok().and_then(|val| {
                             let val = val.try_to_scalar_int()?;
@@ -975,9 +982,8 @@ impl<'genv, 'tcx> Queries<'genv, 'tcx> {
 
                         let alias_reft = AliasReft { assoc_id: fn_once_no_panic.def_id, args };
 
-                        poly_sig = poly_sig.map(|mut fn_sig| {
-                            fn_sig.no_panic = Expr::alias(alias_reft, List::empty());
-                            fn_sig
+                        poly_sig = poly_sig.map(|fn_sig| {
+                            fn_sig.with_no_panic(Expr::alias(alias_reft, List::empty()))
                         });
                     }
 
@@ -1103,7 +1109,7 @@ impl<'a> Diagnostic<'a> for QueryErr {
                     QueryErr::Unsupported { def_id, err } => {
                         let span = err.span.unwrap_or_else(|| tcx.def_span(def_id));
                         let mut diag = dcx.struct_span_err(span, fluent::middle_query_unsupported);
-                        diag.code(E0999);
+                        diag.code(E0998);
                         diag.note(err.descr);
                         diag
                     }
@@ -1177,6 +1183,7 @@ impl<'a> Diagnostic<'a> for QueryErrAt {
             |tcx| {
                 let tcx = tcx.expect("no TyCtxt stored in tls");
                 let cx_span = self.cx.span();
+                let code = if let QueryErr::Unsupported { .. } = self.err { E0998 } else { E0999 };
                 let mut diag = match self.err {
                     QueryErr::Unsupported { def_id, err, .. } => {
                         let mut diag =
@@ -1235,7 +1242,7 @@ impl<'a> Diagnostic<'a> for QueryErrAt {
                         diag
                     }
                 };
-                diag.code(E0999);
+                diag.code(code);
                 diag
             },
         )
@@ -0,0 +1,219 @@
+//! Machine-readable report of verification results enabled with `-Freport=<path>`.
+//!
+//! Events are recorded while checking the crate and aggregated into one record per function when
+//! the report is dumped.
+use std::{
+    fs, io,
+    path::{MAIN_SEPARATOR, Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use flux_config as config;
+use liquid_fixpoint::Stats;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_hir::def_id::{LOCAL_CRATE, LocalDefId};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::{FileName, Span};
+use serde::Serialize;
+
+static EVENTS: Mutex<Vec<(LocalDefId, Event)>> = Mutex::new(Vec::new());
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    /// The function was checked and no errors were found
+    Verified,
+    /// The function was checked and some errors were found
+    Failed,
+    /// The function was not checked because it's trusted
+    Trusted,
+    /// The function was not checked because it's ignored
+    Ignored,
+    /// The function was verified reusing a cached result from a previous run
+    Cached,
+    /// Flux crashed while checking the function (only reported with `-Fcatch-bugs`)
+    Crashed,
+}
+
+impl Status {
+    fn priority(self) -> u8 {
+        match self {
+            Status::Verified => 0,
+            Status::Cached => 1,
+            Status::Failed => 2,
+            Status::Trusted | Status::Ignored => 3,
+            Status::Crashed => 4,
+        }
+    }
+}
+
+enum Event {
+    Status(Status),
+    Error { reason: &'static str, span: Span },
+    Stats(Stats),
+    Cached,
+    Time(Duration),
+}
+
+fn record(def_id: LocalDefId, event: Event) {
+    if config::report_path().is_none() {
+        return;
+    }
+    EVENTS.lock().unwrap().push((def_id, event));
+}
+
+/// Records the status of a function. If multiple statuses are recorded for the same function, the
+/// most severe one takes precedence, e.g., a function marked as trusted while checking is not later
+/// considered verified, and a function whose errors are reported after it was considered verified
+/// (which happens when fixpoint queries run in parallel) is marked as failed.
+pub fn record_status(def_id: LocalDefId, status: Status) {
+    record(def_id, Event::Status(status));
+}
+
+/// Records an error reported for a function. `reason` is the kind of obligation that failed.
+pub fn record_error(def_id: LocalDefId, reason: &'static str, span: Span) {
+    record(def_id, Event::Error { reason, span });
+}
+
+/// Records the statistics returned by fixpoint for one of the queries of a function.
+pub fn record_stats(def_id: LocalDefId, stats: &Stats) {
+    record(def_id, Event::Stats(stats.clone()));
+}
+
+/// Records that the result of the fixpoint query for the body of a function was found in the cache.
+pub fn record_cached(def_id: LocalDefId) {
+    record(def_id, Event::Cached);
+}
+
+/// Records the time taken to check a function.
+pub fn record_time(def_id: LocalDefId, time: Duration) {
+    record(def_id, Event::Time(time));
+}
+
+#[derive(Serialize)]
+struct ReportDump {
+    crate_name: String,
+    items: Vec<ItemReport>,
+}
+
+#[derive(Serialize)]
+struct ItemReport {
+    def_path: String,
+    span: SpanReport,
+    status: Status,
+    errors: Vec<ErrorReport>,
+    time_ms: u128,
+    stats: Stats,
+}
+
+#[derive(Serialize)]
+struct ErrorReport {
+    reason: &'static str,
+    span: SpanReport,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SpanReport {
+    file: String,
+    start_line: usize,
+    start_col: usize,
+    end_line: usize,
+    end_col: usize,
+}
+
+impl SpanReport {
+    pub(crate) fn new(tcx: TyCtxt, span: Span) -> Self {
+        let sm = tcx.sess.source_map();
+        let lo = sm.lookup_char_pos(span.lo());
+        let hi = sm.lookup_char_pos(span.hi());
+        let file = match &lo.file.name {
+            FileName::Real(name) => name.local_path_if_available().display().to_string(),
+            name => name.prefer_local().to_string(),
+        };
+        Self {
+            file,
+            start_line: lo.line,
+            start_col: lo.col_display + 1,
+            end_line: hi.line,
+            end_col: hi.col_display + 1,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Aggregate {
+    status: Option<Status>,
+    cached: bool,
+    errors: Vec<(&'static str, Span)>,
+    time: Duration,
+    stats: Stats,
+}
+
+impl Aggregate {
+    fn status(&self) -> Status {
+        match self.status {
+            Some(Status::Verified) | None if !self.errors.is_empty() => Status::Failed,
+            Some(Status::Verified) | None if self.cached => Status::Cached,
+            Some(status) => status,
+            None => Status::Verified,
+        }
+    }
+}
+
+/// Writes the report to the path passed with `-Freport` if present.
+pub fn dump_report(tcx: TyCtxt) -> io::Result<()> {
+    let Some(path) = config::report_path() else { return Ok(()) };
+
+    let events = std::mem::take(&mut *EVENTS.lock().unwrap());
+    let mut items: FxIndexMap<LocalDefId, Aggregate> = FxIndexMap::default();
+    for (def_id, event) in events {
+        let item = items.entry(def_id).or_default();
+        match event {
+            Event::Status(status) => {
+                if item.status.is_none_or(|s| s.priority() < status.priority()) {
+                    item.status = Some(status);
+                }
+            }
+            Event::Error { reason, span } => item.errors.push((reason, span)),
+            Event::Stats(stats) => item.stats = item.stats.merge(&stats),
+            Event::Cached => item.cached = true,
+            Event::Time(time) => item.time += time,
+        }
+    }
+
+    let crate_name = tcx.crate_name(LOCAL_CRATE).to_string();
+    let items = items
+        .into_iter()
+        .map(|(def_id, item)| {
+            ItemReport {
+                def_path: tcx.def_path_str(def_id),
+                span: SpanReport::new(tcx, tcx.def_span(def_id)),
+                status: item.status(),
+                errors: item
+                    .errors
+                    .iter()
+                    .map(|&(reason, span)| ErrorReport { reason, span: SpanReport::new(tcx, span) })
+                    .collect(),
+                time_ms: item.time.as_millis(),
+                stats: item.stats,
+            }
+        })
+        .collect();
+
+    let mut file = fs::File::create(output_file(path, &crate_name, "report")?)?;
+    serde_json::to_writer_pretty(&mut file, &ReportDump { crate_name, items })?;
+    Ok(())
+}
+
+/// The file where to dump a JSON file passed with a flag like `-Freport=<path>`. If `path` is a
+/// directory the file is saved as `<crate>-<suffix>.json` inside it.
+pub(crate) fn output_file(path: &Path, crate_name: &str, suffix: &str) -> io::Result<PathBuf> {
+    // A trailing separator also denotes a directory so that it can be created if missing.
+    if path.is_dir() || path.as_os_str().to_string_lossy().ends_with(MAIN_SEPARATOR) {
+        fs::create_dir_all(path)?;
+        Ok(path.join(format!("{crate_name}-{suffix}.json")))
+    } else {
+        Ok(PathBuf::from(path))
+    }
+}
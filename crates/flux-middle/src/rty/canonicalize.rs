@@ -289,15 +289,7 @@ impl PolyFnSig {
                 }
             });
 
-            FnSig::new(
-                fn_sig.safety,
-                fn_sig.abi,
-                preds.into(),
-                inputs.into(),
-                fn_sig.output().clone(),
-                fn_sig.no_panic.clone(),
-                fn_sig.lifted,
-            )
+            FnSig { requires: preds.into(), inputs: inputs.into(), ..fn_sig.clone() }
         })
     }
 }
@@ -236,6 +236,26 @@ impl Expr {
         ExprKind::Constant(c).intern()
     }
 
+    /// The real number `num / den`
+    pub fn real(num: u128, den: u128) -> Expr {
+        let num = Expr::constant(Constant::Real(Real(num)));
+        if den == 1 {
+            num
+        } else {
+            Expr::binary_op(BinOp::Div(Sort::Real), num, Expr::constant(Constant::Real(Real(den))))
+        }
+    }
+
+    /// The real number denoted by a (possibly negative) decimal literal. See [`Real::parse_decimal`].
+    pub fn real_from_decimal(s: &str) -> Option<Expr> {
+        if let Some(s) = s.strip_prefix('-') {
+            Some(Expr::real_from_decimal(s)?.neg())
+        } else {
+            let (num, den) = Real::parse_decimal(s)?;
+            Some(Expr::real(num, den))
+        }
+    }
+
     pub fn const_def_id(c: DefId) -> Expr {
         ExprKind::ConstDefId(c).intern()
     }
@@ -308,6 +328,23 @@ impl Expr {
         expr
     }
 
+    /// Builds the sequence with the given elements. The `sort_args` instantiate the sort of the
+    /// elements in the (polymorphic) sequence functions.
+    pub fn seq(sort_args: List<SortArg>, elems: List<Expr>) -> Expr {
+        let unit = Self::global_func(SpecFuncKind::Thy(ThyFunc::SeqUnit));
+        let concat = Self::global_func(SpecFuncKind::Thy(ThyFunc::SeqConcat));
+        elems
+            .iter()
+            .map(|elem| Expr::app(unit.clone(), sort_args.clone(), List::from_arr([elem.clone()])))
+            .reduce(|acc, elem| {
+                Expr::app(concat.clone(), sort_args.clone(), List::from_arr([acc, elem]))
+            })
+            .unwrap_or_else(|| {
+                let empty = Self::global_func(SpecFuncKind::Thy(ThyFunc::SeqEmpty));
+                Expr::app(empty, sort_args, List::empty())
+            })
+    }
+
     pub fn abs(lam: Lambda) -> Expr {
         ExprKind::Abs(lam).intern()
     }
@@ -320,6 +357,10 @@ impl Expr {
         ExprKind::BoundedQuant(kind, rng, body).intern()
     }
 
+    pub fn quant(kind: fhir::QuantKind, body: Binder<QuantBody>) -> Expr {
+        ExprKind::Quant(kind, body).intern()
+    }
+
     pub fn hole(kind: HoleKind) -> Expr {
         ExprKind::Hole(kind).intern()
     }
@@ -376,6 +417,24 @@ impl Expr {
         ExprKind::InternalFunc(kind).intern()
     }
 
+    /// The number of elements that can be accessed through the raw pointer `e`
+    pub fn ptr_size(e: impl Into<Expr>) -> Expr {
+        Expr::app(
+            Expr::internal_func(InternalFuncKind::PtrSize),
+            List::empty(),
+            List::singleton(e.into()),
+        )
+    }
+
+    /// The number of elements between the start of the allocation of the raw pointer `e` and `e`
+    pub fn ptr_offset(e: impl Into<Expr>) -> Expr {
+        Expr::app(
+            Expr::internal_func(InternalFuncKind::PtrOffset),
+            List::empty(),
+            List::singleton(e.into()),
+        )
+    }
+
     pub fn eq(e1: impl Into<Expr>, e2: impl Into<Expr>) -> Expr {
         ExprKind::BinaryOp(BinOp::Eq, e1.into(), e2.into()).intern()
     }
@@ -435,7 +494,10 @@ impl Expr {
     /// An expression is an *atom* if it is "self-delimiting", i.e., it has a clear boundary
     /// when printed. This is used to avoid unnecessary parenthesis when pretty printing.
     pub fn is_atom(&self) -> bool {
-        !matches!(self.kind(), ExprKind::Abs(..) | ExprKind::BinaryOp(..) | ExprKind::ForAll(..))
+        !matches!(
+            self.kind(),
+            ExprKind::Abs(..) | ExprKind::BinaryOp(..) | ExprKind::ForAll(..) | ExprKind::Quant(..)
+        )
     }
 
     /// Simple syntactic check to see if the expression is a trivially true predicate. This is used
@@ -750,6 +812,8 @@ pub enum InternalFuncKind {
     Cast,
     /// Built-in UIF for pointer size: `RawPtr -> Int`
     PtrSize,
+    /// Built-in UIF for the offset of a pointer from the start of its allocation: `RawPtr -> Int`
+    PtrOffset,
 }
 
 #[derive(Debug, Clone, TyEncodable, TyDecodable, PartialEq, Eq, Hash)]
@@ -799,6 +863,9 @@ pub enum ExprKind {
 
     /// Bounded quantifiers `exists i in 0..4 { pred(i) }` and `forall i in 0..4 { pred(i) }`.
     BoundedQuant(fhir::QuantKind, fhir::Range, Binder<Expr>),
+    /// Unbounded quantifiers `forall x: int. pred(x)` and `exists x: int. pred(x)`. They are
+    /// encoded directly as SMT quantifiers.
+    Quant(fhir::QuantKind, Binder<QuantBody>),
     /// A hole is an expression that must be inferred either *semantically* by generating a kvar or
     /// *syntactically* by generating an evar. Whether a hole can be inferred semantically or
     /// syntactically depends on the position it appears: only holes appearing in predicate position
@@ -826,6 +893,21 @@ impl ExprKind {
     }
 }
 
+/// The body of an unbounded quantifier.
+#[derive(Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable, TypeVisitable, TypeFoldable)]
+pub struct QuantBody {
+    pub pred: Expr,
+    /// Trigger patterns used to instantiate the quantifier
+    pub triggers: List<Trigger>,
+}
+
+/// A trigger pattern for a quantifier. A trigger with more than one expression is a multi-pattern,
+/// i.e., all its expressions must be matched for the trigger to fire.
+#[derive(Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable, TypeVisitable, TypeFoldable)]
+pub struct Trigger {
+    pub exprs: List<Expr>,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, TyEncodable, TyDecodable, Debug)]
 pub enum AggregateKind {
     Tuple(usize),
@@ -1154,6 +1236,33 @@ impl From<Local> for Loc {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encodable, Decodable)]
 pub struct Real(pub u128);
 
+impl Real {
+    /// Parses a decimal literal like `1.5`, `2.` or `1e-3` (possibly with `_` separators) into an
+    /// irreducible fraction `(num, den)`. Returns `None` if the literal is malformed or if the
+    /// fraction doesn't fit in a `u128`.
+    pub fn parse_decimal(s: &str) -> Option<(u128, u128)> {
+        let s = s.replace('_', "");
+        let (mantissa, exp) = match s.split_once(['e', 'E']) {
+            Some((mantissa, exp)) => (mantissa, exp.parse::<i32>().ok()?),
+            None => (&s[..], 0),
+        };
+        let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let mut num = format!("{int}{frac}").parse::<u128>().ok()?;
+        let mut den = 1;
+        let exp = exp.checked_sub(i32::try_from(frac.len()).ok()?)?;
+        if exp >= 0 {
+            num = num.checked_mul(10u128.checked_pow(exp.unsigned_abs())?)?;
+        } else {
+            den = 10u128.checked_pow(exp.unsigned_abs())?;
+        }
+        let (mut a, mut b) = (num, den);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        Some((num / a, den / a))
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encodable, Decodable)]
 pub enum Constant {
     Int(BigInt),
@@ -1319,7 +1428,7 @@ impl From<char> for Constant {
 }
 
 impl_internable!(ExprKind);
-impl_slice_internable!(Expr, KVar);
+impl_slice_internable!(Expr, KVar, Trigger);
 
 #[derive(Debug)]
 pub struct FieldBind<T> {
@@ -1420,6 +1529,7 @@ pub(crate) mod pretty {
                 InternalFuncKind::Rel(op) => w!(cx, f, "[{:?}]?", op),
                 InternalFuncKind::Cast => w!(cx, f, "cast"),
                 InternalFuncKind::PtrSize => w!(cx, f, "ptr_size"),
+                InternalFuncKind::PtrOffset => w!(cx, f, "ptr_offset"),
             }
         }
     }
@@ -1589,6 +1699,17 @@ pub(crate) mod pretty {
                         )
                     })
                 }
+                ExprKind::Quant(kind, body) => {
+                    let vars = body.vars();
+                    cx.with_bound_vars(vars, || {
+                        let left = match kind {
+                            fhir::QuantKind::Forall => "∀",
+                            fhir::QuantKind::Exists => "∃",
+                        };
+                        cx.fmt_bound_vars(false, left, vars, "", f)?;
+                        w!(cx, f, "{:?}", body.skip_binder_ref())
+                    })
+                }
                 ExprKind::Let(init, body) => {
                     let vars = body.vars();
                     cx.with_bound_vars(vars, || {
@@ -1646,6 +1767,15 @@ pub(crate) mod pretty {
         }
     }
 
+    impl Pretty for QuantBody {
+        fn fmt(&self, cx: &PrettyCx, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            for trigger in &self.triggers {
+                w!(cx, f, " #[trigger({:?})]", join!(", ", &trigger.exprs))?;
+            }
+            w!(cx, f, ". {:?}", &self.pred)
+        }
+    }
+
     impl Pretty for Var {
         fn fmt(&self, cx: &PrettyCx, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
@@ -1952,6 +2082,17 @@ pub(crate) mod pretty {
                         Ok(NestedString { text, children: expr_d.children, key: None })
                     })
                 }
+                ExprKind::Quant(kind, body) => {
+                    let left = match kind {
+                        fhir::QuantKind::Forall => "∀",
+                        fhir::QuantKind::Exists => "∃",
+                    };
+                    cx.nested_with_bound_vars(left, body.vars(), None, |all_str| {
+                        let expr_d = body.skip_binder_ref().pred.fmt_nested(cx)?;
+                        let text = format!("{}{}", all_str, expr_d.text);
+                        Ok(NestedString { text, children: expr_d.children, key: None })
+                    })
+                }
                 ExprKind::ForAll(expr) => {
                     cx.nested_with_bound_vars("∀", expr.vars(), None, |all_str| {
                         let expr_d = expr.as_ref().skip_binder().fmt_nested(cx)?;
@@ -3,7 +3,7 @@
 
 use std::ops::ControlFlow;
 
-use flux_arc_interner::{Internable, List};
+use flux_arc_interner::{Internable, Interned, List};
 use flux_common::bug;
 use itertools::Itertools;
 use rustc_data_structures::fx::FxHashMap;
@@ -12,8 +12,8 @@ use rustc_type_ir::{BoundVar, DebruijnIndex, INNERMOST};
 
 use super::{
     BaseTy, Binder, BoundVariableKinds, Const, EVid, EarlyReftParam, Ensures, Expr, ExprKind,
-    GenericArg, Name, OutlivesPredicate, PolyFuncSort, PtrKind, ReBound, ReErased, Region, Sort,
-    SubsetTy, Ty, TyKind, TyOrBase, normalize::Normalizer,
+    FnAnnots, GenericArg, Name, OutlivesPredicate, PolyFuncSort, PtrKind, ReBound, ReErased,
+    Region, Sort, SubsetTy, Ty, TyKind, TyOrBase, normalize::Normalizer,
 };
 use crate::{
     global_env::GlobalEnv,
@@ -897,8 +897,8 @@ impl TypeSuperVisitable for BaseTy {
                 upvars.visit_with(visitor)
             }
             BaseTy::Dynamic(exi_preds, _) => exi_preds.visit_with(visitor),
+            BaseTy::Pat(ty, _) => ty.visit_with(visitor),
             BaseTy::Int(_)
-            | BaseTy::Pat
             | BaseTy::Uint(_)
             | BaseTy::Bool
             | BaseTy::Float(_)
@@ -951,7 +951,7 @@ impl TypeSuperFoldable for BaseTy {
             BaseTy::Dynamic(preds, region) => {
                 BaseTy::Dynamic(preds.try_fold_with(folder)?, region.try_fold_with(folder)?)
             }
-            BaseTy::Pat => BaseTy::Pat,
+            BaseTy::Pat(ty, pat) => BaseTy::Pat(ty.try_fold_with(folder)?, pat.clone()),
             BaseTy::Int(_)
             | BaseTy::Param(_)
             | BaseTy::Uint(_)
@@ -1058,6 +1058,7 @@ impl TypeSuperVisitable for Expr {
             }
             ExprKind::Abs(body) => body.visit_with(visitor),
             ExprKind::BoundedQuant(_, _, body) => body.visit_with(visitor),
+            ExprKind::Quant(_, body) => body.visit_with(visitor),
             ExprKind::ForAll(expr) => expr.visit_with(visitor),
             ExprKind::Exists(expr) => expr.visit_with(visitor),
             ExprKind::Let(init, body) => {
@@ -1128,6 +1129,7 @@ impl TypeSuperFoldable for Expr {
             ExprKind::Alias(alias, args) => {
                 Expr::alias(alias.try_fold_with(folder)?, args.try_fold_with(folder)?)
             }
+            ExprKind::Quant(kind, body) => Expr::quant(*kind, body.try_fold_with(folder)?),
             ExprKind::ForAll(expr) => Expr::forall(expr.try_fold_with(folder)?),
             ExprKind::Exists(expr) => Expr::exists(expr.try_fold_with(folder)?),
             ExprKind::Let(init, body) => {
@@ -1158,6 +1160,18 @@ where
     }
 }
 
+impl TypeVisitable for Interned<FnAnnots> {
+    fn visit_with<V: TypeVisitor>(&self, visitor: &mut V) -> ControlFlow<V::BreakTy> {
+        FnAnnots::visit_with(self, visitor)
+    }
+}
+
+impl TypeFoldable for Interned<FnAnnots> {
+    fn try_fold_with<F: FallibleTypeFolder>(&self, folder: &mut F) -> Result<Self, F::Error> {
+        Ok(Interned::new(FnAnnots::try_fold_with(self, folder)?))
+    }
+}
+
 /// Used for types that are `Copy` and which **do not care arena allocated data** (i.e., don't need
 /// to be folded).
 macro_rules! TrivialTypeTraversalImpls {
@@ -1206,6 +1220,7 @@ TrivialTypeTraversalImpls! {
     crate::def_id::FluxDefId,
     crate::def_id::FluxLocalDefId,
     rustc_span::Symbol,
+    rustc_span::Span,
     rustc_hir::def_id::DefId,
     rustc_hir::Safety,
     rustc_abi::ExternAbi,
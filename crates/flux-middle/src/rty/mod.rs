@@ -20,7 +20,7 @@ use bitflags::bitflags;
 pub use expr::{
     AggregateKind, AliasReft, BinOp, BoundReft, Constant, Ctor, ESpan, EVid, EarlyReftParam, Expr,
     ExprKind, FieldProj, HoleKind, InternalFuncKind, KVar, KVid, Lambda, Loc, Name, NameProvenance,
-    Path, PrettyMap, PrettyVar, Real, SpecFuncKind, UnOp, Var,
+    Path, PrettyMap, PrettyVar, QuantBody, Real, SpecFuncKind, Trigger, UnOp, Var,
 };
 pub use flux_arc_interner::List;
 use flux_arc_interner::{Interned, impl_internable, impl_slice_internable};
@@ -29,7 +29,7 @@ use flux_config::OverflowMode;
 use flux_macros::{TypeFoldable, TypeVisitable};
 pub use flux_rustc_bridge::ty::{
     AliasKind, BoundRegion, BoundRegionKind, BoundVar, Const, ConstKind, ConstVid, DebruijnIndex,
-    EarlyParamRegion, LateParamRegion, LateParamRegionKind,
+    EarlyParamRegion, LateParamRegion, LateParamRegionKind, Pattern, PatternKind,
     Region::{self, *},
     RegionVid,
 };
@@ -39,7 +39,7 @@ use flux_rustc_bridge::{
     ty::{self, GenericArgsExt as _, VariantDef},
 };
 use itertools::Itertools;
-pub use normalize::{FuncInfo, NormalizedDefns, local_deps};
+pub use normalize::{Defn, FuncInfo, NormalizedDefns, local_deps};
 use refining::Refiner;
 use rustc_abi;
 pub use rustc_abi::{FIRST_VARIANT, VariantIdx};
@@ -702,6 +702,7 @@ impl FnTraitPredicate {
             inputs,
             output,
             Expr::ff(),
+            List::empty(),
             false,
         )
     }
@@ -756,6 +757,7 @@ pub fn to_closure_sig(
         inputs.into(),
         output,
         if no_panic { crate::rty::Expr::tt() } else { crate::rty::Expr::ff() },
+        List::empty(),
         false,
     );
 
@@ -822,6 +824,7 @@ impl AssocRefinements {
 pub enum SortCtor {
     Set,
     Map,
+    Seq,
     Adt(AdtSortDef),
     User(FluxDefId),
 }
@@ -1053,8 +1056,11 @@ pub enum CastKind {
     Identity,
     /// From bool to int
     BoolToInt,
-    /// Casts to unit index, (e.g. int -> float)
+    /// Casts to unit index, (e.g. int -> raw pointer)
     IntoUnit,
+    /// From int to real, e.g., when casting an integer to a float. We don't have a conversion
+    /// function in fixpoint so it is encoded as an uninterpreted function.
+    IntToReal,
     /// Uninterpreted casts, only allowed with explicit flag
     Uninterpreted,
 }
@@ -1116,6 +1122,8 @@ impl Sort {
             CastKind::Identity
         } else if matches!(self, Sort::Bool) && matches!(to, Sort::Int) {
             CastKind::BoolToInt
+        } else if matches!(self, Sort::Int) && matches!(to, Sort::Real) {
+            CastKind::IntToReal
         } else if to.is_unit() {
             CastKind::IntoUnit
         } else {
@@ -1400,11 +1408,56 @@ pub struct FnSig {
     pub requires: List<Expr>,
     pub inputs: List<Ty>,
     pub output: Binder<FnOutput>,
-    pub no_panic: Expr,
+    pub annots: Interned<FnAnnots>,
     /// was this auto-lifted (or from a spec)
     pub lifted: bool,
 }
 
+/// Annotations of a function that are not needed to construct its type. They are kept behind an
+/// [`Interned`] pointer because [`FnSig`] is stored inline in many types.
+#[derive(Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable, TypeVisitable, TypeFoldable)]
+pub struct FnAnnots {
+    pub no_panic: Expr,
+    /// Termination metric of the function, compared lexicographically. Empty if the function
+    /// doesn't have a `decreases` clause.
+    pub decreases: List<Expr>,
+    /// Invariants annotated on loops in the body of the function.
+    pub loop_invariants: List<LoopInvariant>,
+}
+
+/// An invariant or a variant attached to a loop in the body of a function. It binds a variable for
+/// each Rust local it mentions, standing for the value of the local at the head of the loop.
+#[derive(Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable, TypeVisitable, TypeFoldable)]
+pub struct LoopInvariant {
+    pub kind: LoopInvariantKind,
+    /// The span of the loop
+    pub span: Span,
+    /// The locals bound by [`LoopInvariant::pred`]
+    pub locals: List<LoopLocal>,
+    pub pred: Binder<Expr>,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, TyEncodable, TyDecodable, TypeVisitable, TypeFoldable,
+)]
+pub enum LoopInvariantKind {
+    /// [`LoopInvariant::pred`] is a boolean predicate that must hold at the head of the loop
+    Invariant,
+    /// [`LoopInvariant::pred`] is an integer metric that must stay non-negative and strictly
+    /// decrease at every iteration
+    Variant,
+}
+
+/// A Rust local mentioned in a [`LoopInvariant`]
+#[derive(
+    Clone, Copy, PartialEq, Eq, Hash, TyEncodable, TyDecodable, TypeVisitable, TypeFoldable,
+)]
+pub struct LoopLocal {
+    pub name: Symbol,
+    /// The span of the binding declaring the local
+    pub span: Span,
+}
+
 #[derive(
     Debug, Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable, TypeVisitable, TypeFoldable,
 )]
@@ -1841,7 +1894,9 @@ pub enum BaseTy {
     Param(ParamTy),
     Infer(TyVid),
     Foreign(DefId),
-    Pat,
+    /// A pattern type, e.g., `pattern_type!(u32 is 1..=10)`. It's indexed like the base type and
+    /// the pattern is an invariant of the type, i.e., `u32{v: 1 <= v && v <= 10}`.
+    Pat(Ty, Pattern),
 }
 
 impl BaseTy {
@@ -1973,7 +2028,9 @@ impl BaseTy {
         tcx: TyCtxt,
         overflow_mode: OverflowMode,
     ) -> impl Iterator<Item = Invariant> {
-        let (invariants, args) = match self {
+        // The invariants of a pattern type are the ones of its base type plus the pattern
+        let bty = self.pat_base().unwrap_or(self);
+        let (invariants, args) = match bty {
             BaseTy::Adt(adt_def, args) => (adt_def.invariants().skip_binder(), &args[..]),
             BaseTy::Uint(uint_ty) => (uint_invariants(*uint_ty, overflow_mode), &[][..]),
             BaseTy::Int(int_ty) => (int_invariants(*int_ty, overflow_mode), &[][..]),
@@ -1984,6 +2041,20 @@ impl BaseTy {
         invariants
             .iter()
             .map(move |inv| EarlyBinder(inv).instantiate_ref(tcx, args, &[]))
+            .chain(self.pat_invariant(tcx))
+    }
+
+    /// The base type of a pattern type
+    fn pat_base(&self) -> Option<&BaseTy> {
+        if let BaseTy::Pat(ty, _) = self { ty.as_bty_skipping_existentials() } else { None }
+    }
+
+    /// The invariant of a pattern type saying that its value matches the pattern
+    pub fn pat_invariant(&self, tcx: TyCtxt) -> Option<Invariant> {
+        let BaseTy::Pat(_, pat) = self else { return None };
+        let sort = self.sort();
+        let pred = pattern_pred(tcx, pat, &sort, Expr::nu());
+        Some(Invariant { pred: Binder::bind_with_sort(pred, sort) })
     }
 
     pub fn to_ty(&self) -> Ty {
@@ -2072,7 +2143,7 @@ impl BaseTy {
             | BaseTy::Param(_)
             | BaseTy::Dynamic(..)
             | BaseTy::Infer(_) => None,
-            BaseTy::Pat => todo!(),
+            BaseTy::Pat(ty, _) => ty.as_bty_skipping_existentials()?.simplify_type(),
         }
     }
 }
@@ -2140,7 +2211,7 @@ impl<'tcx> ToRustc<'tcx> for BaseTy {
                     RawPtrKind::FakeForPtrMetadata.to_mutbl_lossy(),
                 )
             }
-            BaseTy::Pat => todo!(),
+            BaseTy::Pat(ty, pat) => ty::Ty::new_pat(tcx, ty.to_rustc(tcx), pat.to_rustc(tcx)),
         }
     }
 }
@@ -2582,6 +2653,7 @@ impl CoroutineObligPredicate {
                 inputs,
                 output,
                 Expr::ff(),
+                List::empty(),
                 false,
             ),
             List::from(vars),
@@ -2699,6 +2771,7 @@ impl VariantSig {
 }
 
 impl FnSig {
+    #[expect(clippy::too_many_arguments)]
     pub fn new(
         safety: Safety,
         abi: rustc_abi::ExternAbi,
@@ -2706,9 +2779,21 @@ impl FnSig {
         inputs: List<Ty>,
         output: Binder<FnOutput>,
         no_panic: Expr,
+        decreases: List<Expr>,
         lifted: bool,
     ) -> Self {
-        FnSig { safety, abi, requires, inputs, output, no_panic, lifted }
+        let annots = FnAnnots { no_panic, decreases, loop_invariants: List::empty() };
+        FnSig { safety, abi, requires, inputs, output, annots: Interned::new(annots), lifted }
+    }
+
+    pub fn with_no_panic(self, no_panic: Expr) -> Self {
+        let annots = FnAnnots { no_panic, ..FnAnnots::clone(&self.annots) };
+        FnSig { annots: Interned::new(annots), ..self }
+    }
+
+    pub fn with_loop_invariants(self, loop_invariants: List<LoopInvariant>) -> Self {
+        let annots = FnAnnots { loop_invariants, ..FnAnnots::clone(&self.annots) };
+        FnSig { annots: Interned::new(annots), ..self }
     }
 
     pub fn requires(&self) -> &[Expr] {
@@ -2720,7 +2805,15 @@ impl FnSig {
     }
 
     pub fn no_panic(&self) -> Expr {
-        self.no_panic.clone()
+        self.annots.no_panic.clone()
+    }
+
+    pub fn decreases(&self) -> &[Expr] {
+        &self.annots.decreases
+    }
+
+    pub fn loop_invariants(&self) -> &[LoopInvariant] {
+        &self.annots.loop_invariants
     }
 
     pub fn output(&self) -> Binder<FnOutput> {
@@ -2794,6 +2887,18 @@ impl AdtDef {
         self.0.rustc.is_union()
     }
 
+    /// The first field in the sort of a refined union is a tag holding the index of the active
+    /// field. This returns the tag of a union with index `idx`, or `None` if the union is not
+    /// refined.
+    pub fn union_tag(&self, idx: &Expr) -> Option<Expr> {
+        if !self.is_union() {
+            return None;
+        }
+        let sort_def = self.sort_def();
+        let proj = sort_def.struct_variant().projections(self.did()).next()?;
+        Some(idx.proj_and_reduce(proj))
+    }
+
     pub fn variants(&self) -> &IndexSlice<VariantIdx, VariantDef> {
         self.0.rustc.variants()
     }
@@ -2816,17 +2921,12 @@ impl AdtDef {
 }
 
 impl EarlyBinder<PolyVariant> {
-    // The field_idx is `Some(i)` when we have the `i`-th field of a `union`, in which case,
-    // the `inputs` are _just_ the `i`-th type (and not all the types...)
-    pub fn to_poly_fn_sig(&self, field_idx: Option<crate::FieldIdx>) -> EarlyBinder<PolyFnSig> {
+    pub fn to_poly_fn_sig(&self) -> EarlyBinder<PolyFnSig> {
         self.as_ref().map(|poly_variant| {
             poly_variant.as_ref().map(|variant| {
                 let ret = variant.ret().shift_in_escaping(1);
                 let output = Binder::bind_with_vars(FnOutput::new(ret, vec![]), List::empty());
-                let inputs = match field_idx {
-                    None => variant.fields.clone(),
-                    Some(i) => List::singleton(variant.fields[i.index()].clone()),
-                };
+                let inputs = variant.fields.clone();
                 FnSig::new(
                     Safety::Safe,
                     rustc_abi::ExternAbi::Rust,
@@ -2834,6 +2934,7 @@ impl EarlyBinder<PolyVariant> {
                     inputs,
                     output,
                     Expr::tt(),
+                    List::empty(),
                     false,
                 )
             })
@@ -2903,6 +3004,34 @@ fn uint_invariants(uint_ty: UintTy, overflow_mode: OverflowMode) -> &'static [In
     }
 }
 
+/// The predicate saying that `e`, whose sort is `sort`, matches the pattern `pat`
+fn pattern_pred(tcx: TyCtxt, pat: &Pattern, sort: &Sort, e: Expr) -> Expr {
+    match pat.kind() {
+        PatternKind::Range { start, end } => {
+            // Chars are compared through their integer value
+            let to_int =
+                |e| if let Sort::Char = sort { Expr::cast(Sort::Char, Sort::Int, e) } else { e };
+            let e = to_int(e);
+            // Bounds that are not known constants are left unconstrained
+            let bound = |c: &Const| {
+                matches!(c.kind, ConstKind::Param(_) | ConstKind::Value(..))
+                    .then(|| to_int(Expr::from_const(tcx, c)))
+            };
+            let lower = bound(start).map(|start| Expr::le(start, e.clone()));
+            let upper = bound(end).map(|end| Expr::le(e.clone(), end));
+            Expr::and_from_iter(lower.into_iter().chain(upper))
+        }
+        PatternKind::Or(pats) => {
+            Expr::or_from_iter(
+                pats.iter()
+                    .map(|pat| pattern_pred(tcx, pat, sort, e.clone())),
+            )
+        }
+        // We don't track whether raw pointers are null
+        PatternKind::NotNull => Expr::tt(),
+    }
+}
+
 fn char_invariants() -> &'static [Invariant] {
     static INVARIANTS: LazyLock<[Invariant; 2]> = LazyLock::new(|| {
         [
@@ -2958,7 +3087,7 @@ fn int_invariants(int_ty: IntTy, overflow_mode: OverflowMode) -> &'static [Invar
     }
 }
 
-impl_internable!(AdtDefData, AdtSortDefData, TyKind);
+impl_internable!(AdtDefData, AdtSortDefData, TyKind, FnAnnots);
 impl_slice_internable!(
     Ty,
     GenericArg,
@@ -2975,7 +3104,9 @@ impl_slice_internable!(
     RefineParam,
     FluxDefId,
     SortParamKind,
-    AssocReft
+    AssocReft,
+    LoopInvariant,
+    LoopLocal
 );
 
 #[macro_export]
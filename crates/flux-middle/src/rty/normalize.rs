@@ -1,17 +1,20 @@
-use std::ops::ControlFlow;
+use std::{iter, ops::ControlFlow};
 
 use itertools::Itertools;
-use rustc_data_structures::{fx::FxIndexSet, unord::UnordMap};
+use rustc_data_structures::{
+    fx::FxIndexSet,
+    graph::{scc::Sccs, vec_graph::VecGraph},
+    unord::UnordMap,
+};
 use rustc_hir::def_id::{CrateNum, DefIndex, LOCAL_CRATE};
 use rustc_macros::{TyDecodable, TyEncodable};
-use toposort_scc::IndexGraph;
 
 use super::{ESpan, fold::TypeSuperFoldable};
 use crate::{
     def_id::{FluxDefId, FluxId, FluxLocalDefId},
     global_env::GlobalEnv,
     rty::{
-        Binder, Expr, ExprKind, SortArg,
+        Binder, Expr, ExprKind, List, SortArg,
         expr::SpecFuncKind,
         fold::{TypeFoldable, TypeFolder, TypeSuperVisitable, TypeVisitable, TypeVisitor},
     },
@@ -23,12 +26,29 @@ pub struct NormalizedDefns {
     inlined_bodies: UnordMap<FluxId<DefIndex>, Binder<Expr>>,
     /// Information about all function definitions both with a body and UIF
     info: UnordMap<FluxId<DefIndex>, FuncInfo>,
+    /// The (normalized) `decreases` metric of recursive functions
+    decreases: UnordMap<FluxId<DefIndex>, Binder<List<Expr>>>,
+}
+
+/// A spec function definition before normalization
+pub struct Defn {
+    pub def_id: FluxLocalDefId,
+    /// The body of the function, `None` if the function is uninterpreted
+    pub body: Option<Binder<Expr>>,
+    /// The `decreases` metric of the function, empty if it doesn't have one
+    pub decreases: Binder<List<Expr>>,
+    pub hide: bool,
 }
 
 // This implementation is needed for `flux-metada::Tables`
 impl Default for NormalizedDefns {
     fn default() -> Self {
-        Self { krate: LOCAL_CRATE, inlined_bodies: UnordMap::default(), info: UnordMap::default() }
+        Self {
+            krate: LOCAL_CRATE,
+            inlined_bodies: UnordMap::default(),
+            info: UnordMap::default(),
+            decreases: UnordMap::default(),
+        }
     }
 }
 
@@ -42,17 +62,26 @@ impl Default for NormalizedDefns {
 ///   all *monomorphic* flux-defs un-inlined.
 /// - When the above flag is not set, we replace *every* flux-def
 ///   with its (transitively) inlined body
+///
+/// Recursive flux-defs are never inlined. Their bodies are normalized
+/// but calls to functions in the same recursive group are left in place.
+/// Every function in a recursive group must have a `decreases` metric,
+/// which is checked separately to ensure the definitions terminate.
 #[derive(Clone, TyEncodable, TyDecodable)]
 pub struct FuncInfo {
     /// Whether or not this function is inlined (i.e. NOT represented as `define-fun`).
     /// This value is irrelevant of UIFs.
     pub inline: bool,
+    /// Whether the function is (mutually) recursive. Recursive functions are encoded as
+    /// uninterpreted functions together with equations unfolding their definition.
+    pub recursive: bool,
     /// Whether or not this function is uninterpreted by default
     /// This value is irrelevant of UIFs.
     pub hide: bool,
     /// The rank of this function in the topological sort of all the flux-defs, needed so
     /// we can specify the `define-fun` in the correct order, without any "forward"
-    /// dependencies which the SMT solver cannot handle.
+    /// dependencies which the SMT solver cannot handle. Mutually recursive functions
+    /// share the same rank.
     pub rank: usize,
     /// Whether the function is a UIF
     pub uif: bool,
@@ -70,36 +99,64 @@ pub(super) struct Normalizer<'a, 'genv, 'tcx> {
 }
 
 impl NormalizedDefns {
-    pub fn new(
-        genv: GlobalEnv,
-        funs: &[(FluxLocalDefId, Option<Binder<Expr>>, bool)],
-    ) -> Result<Self, Vec<FluxLocalDefId>> {
-        // 1. Topologically sort the Defns
-        let ds = toposort(funs)?;
+    /// Normalizes the definitions in `defns`. Returns a recursive group as an error if one of its
+    /// functions doesn't have a `decreases` metric.
+    pub fn new(genv: GlobalEnv, defns: &[Defn]) -> Result<Self, Vec<FluxLocalDefId>> {
+        // 1. Topologically sort the strongly connected components of the Defns
+        let sccs = sccs(defns);
 
-        // 2. Expand each defn in the sorted order
-        let mut inlining = InliningCtxt::default();
-        for (rank, i) in ds.iter().enumerate() {
-            let (id, body, hide) = &funs[*i];
-
-            if let Some(body) = body {
-                let body = body.fold_with(&mut Normalizer::new(genv, Some(&inlining)));
+        // 2. Reject recursive groups whose termination cannot be checked. The cycle is reported
+        //    starting from the last definition without a metric, i.e., the one closing the cycle.
+        for (scc, recursive) in &sccs {
+            if *recursive
+                && let Some(i) = scc
+                    .iter()
+                    .rfind(|i| defns[**i].decreases.skip_binder_ref().is_empty())
+            {
+                let cycle = iter::once(i)
+                    .chain(scc.iter().filter(|j| *j != i))
+                    .map(|j| defns[*j].def_id)
+                    .collect();
+                return Err(cycle);
+            }
+        }
 
-                inlining.inlined_bodies.insert(*id, body);
-                inlining.info.insert(
-                    *id,
+        // 3. Expand each defn in the sorted order
+        let mut inlining = InliningCtxt::default();
+        let mut decreases = UnordMap::default();
+        for (rank, (scc, recursive)) in sccs.iter().enumerate() {
+            // Register all the functions in the component before normalizing any body so calls
+            // between them are known not to be inlined.
+            for i in scc {
+                let defn = &defns[*i];
+                let info = if defn.body.is_some() {
                     FuncInfo {
                         rank,
-                        inline: genv.should_inline_fun(id.to_def_id()),
-                        hide: *hide,
+                        inline: !recursive && genv.should_inline_fun(defn.def_id.to_def_id()),
+                        recursive: *recursive,
+                        hide: defn.hide,
                         uif: false,
-                    },
-                );
-            } else {
-                inlining
-                    .info
-                    .insert(*id, FuncInfo { rank, inline: false, hide: *hide, uif: true });
+                    }
+                } else {
+                    FuncInfo { rank, inline: false, recursive: false, hide: defn.hide, uif: true }
+                };
+                inlining.info.insert(defn.def_id, info);
             }
+            let mut normalizer = Normalizer::new(genv, Some(&inlining));
+            let bodies = scc
+                .iter()
+                .filter_map(|i| {
+                    let defn = &defns[*i];
+                    Some((defn.def_id, defn.body.as_ref()?.fold_with(&mut normalizer)))
+                })
+                .collect_vec();
+            if *recursive {
+                decreases.extend(scc.iter().map(|i| {
+                    let defn = &defns[*i];
+                    (defn.def_id.local_def_index(), defn.decreases.fold_with(&mut normalizer))
+                }));
+            }
+            inlining.inlined_bodies.extend(bodies);
         }
         Ok(Self {
             krate: LOCAL_CRATE,
@@ -113,6 +170,7 @@ impl NormalizedDefns {
                 .into_items()
                 .map(|(id, body)| (id.local_def_index(), body))
                 .collect(),
+            decreases,
         })
     }
 
@@ -125,46 +183,50 @@ impl NormalizedDefns {
         debug_assert_eq!(self.krate, did.krate());
         self.inlined_bodies.get(&did.index()).unwrap().clone()
     }
+
+    /// Returns the `decreases` metric of a recursive function.
+    pub fn decreases(&self, did: FluxDefId) -> Binder<List<Expr>> {
+        debug_assert_eq!(self.krate, did.krate());
+        self.decreases.get(&did.index()).unwrap().clone()
+    }
 }
 
-/// Returns
-/// * either Ok(d1...dn) which are topologically sorted such that
-///   forall i < j, di does not depend on i.e. "call" dj
-/// * or Err(d1...dn) where d1 'calls' d2 'calls' ... 'calls' dn 'calls' d1
-fn toposort<T>(
-    defns: &[(FluxLocalDefId, Option<Binder<Expr>>, T)],
-) -> Result<Vec<usize>, Vec<FluxLocalDefId>> {
+/// Returns the strongly connected components of the dependency graph of `defns` sorted such that
+/// a component only depends on (i.e. "calls") itself or on components appearing before it. Each
+/// component is paired with a boolean indicating whether its functions are (mutually) recursive.
+fn sccs(defns: &[Defn]) -> Vec<(Vec<usize>, bool)> {
     // 1. Make a Symbol to Index map
     let s2i: UnordMap<FluxLocalDefId, usize> = defns
         .iter()
         .enumerate()
-        .map(|(i, defn)| (defn.0, i))
+        .map(|(i, defn)| (defn.def_id, i))
         .collect();
 
     // 2. Make the dependency graph
-    let mut adj_list = Vec::with_capacity(defns.len());
-    for defn in defns {
-        if let Some(body) = &defn.1 {
-            let deps = local_deps(body)
-                .iter()
-                .filter_map(|s| s2i.get(s).copied())
-                .collect_vec();
-            adj_list.push(deps);
-        } else {
-            adj_list.push(vec![]);
+    let mut edges = vec![];
+    let mut self_loops = vec![false; defns.len()];
+    for (i, defn) in defns.iter().enumerate() {
+        let Some(body) = &defn.body else { continue };
+        for j in local_deps(body).iter().filter_map(|s| s2i.get(s).copied()) {
+            self_loops[i] |= i == j;
+            edges.push((i, j));
         }
     }
-    let mut g = IndexGraph::from_adjacency_list(&adj_list);
-    g.transpose();
+    let g = VecGraph::<usize>::new(defns.len(), edges);
 
-    // 3. Topologically sort the graph
-    match g.toposort_or_scc() {
-        Ok(is) => Ok(is),
-        Err(mut scc) => {
-            let cycle = scc.pop().unwrap();
-            Err(cycle.iter().map(|i| defns[*i].0).collect())
-        }
+    // 3. Compute the strongly connected components. They are returned in dependency order.
+    let sccs: Sccs<usize, usize> = Sccs::new(&g);
+    let mut members = vec![vec![]; sccs.num_sccs()];
+    for i in 0..defns.len() {
+        members[sccs.scc(i)].push(i);
     }
+    sccs.all_sccs()
+        .map(|scc| {
+            let scc = std::mem::take(&mut members[scc]);
+            let recursive = scc.len() > 1 || self_loops[scc[0]];
+            (scc, recursive)
+        })
+        .collect()
 }
 
 pub fn local_deps(body: &Binder<Expr>) -> FxIndexSet<FluxLocalDefId> {
@@ -102,6 +102,7 @@ impl Pretty for SortCtor {
         match self {
             SortCtor::Set => w!(cx, f, "Set"),
             SortCtor::Map => w!(cx, f, "Map"),
+            SortCtor::Seq => w!(cx, f, "Seq"),
             SortCtor::User(def_id) => w!(cx, f, "{}", ^def_id.name()),
             SortCtor::Adt(adt_sort_def) => {
                 w!(cx, f, "{:?}", adt_sort_def.did())
@@ -212,6 +213,9 @@ impl Pretty for FnSig {
         if !self.requires.is_empty() {
             w!(cx, f, " requires {:?}", join!(" ∧ ", &self.requires))?;
         }
+        if !self.decreases().is_empty() {
+            w!(cx, f, " decreases {:?}", join!(", ", self.decreases()))?;
+        }
         Ok(())
     }
 }
@@ -572,7 +576,7 @@ impl Pretty for BaseTy {
             BaseTy::Foreign(def_id) => {
                 w!(cx, f, "{:?}", def_id)
             }
-            BaseTy::Pat => todo!(),
+            BaseTy::Pat(ty, pat) => w!(cx, f, "pattern_type!({:?} is {:?})", ty, pat),
         }
     }
 }
@@ -652,6 +656,26 @@ impl Pretty for Const {
     }
 }
 
+impl Pretty for Pattern {
+    fn fmt(&self, cx: &PrettyCx, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind() {
+            PatternKind::Range { start, end } => {
+                // Print the value of the bounds instead of their bits
+                let fmt_bound = |c: &Const| {
+                    if let ConstKind::Value(..) | ConstKind::Param(_) = c.kind {
+                        format_cx!(cx, "{:?}", Expr::from_const(cx.tcx(), c))
+                    } else {
+                        format_cx!(cx, "{:?}", c)
+                    }
+                };
+                w!(cx, f, "{}..={}", ^fmt_bound(start), ^fmt_bound(end))
+            }
+            PatternKind::Or(pats) => w!(cx, f, "{:?}", join!(" | ", pats)),
+            PatternKind::NotNull => w!(cx, f, "!null"),
+        }
+    }
+}
+
 impl Pretty for GenericArg {
     fn fmt(&self, cx: &PrettyCx, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -826,7 +850,11 @@ impl PrettyNested for BaseTy {
                 let children = float_children(kidss);
                 Ok(NestedString { text, children, key: None })
             }
-            BaseTy::Pat => todo!(),
+            BaseTy::Pat(ty, pat) => {
+                let ty_d = ty.fmt_nested(cx)?;
+                let text = format_cx!(cx, "pattern_type!({} is {:?})", ^ty_d.text, pat);
+                Ok(NestedString { text, children: ty_d.children, key: None })
+            }
         }
     }
 }
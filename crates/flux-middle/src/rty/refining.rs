@@ -284,7 +284,9 @@ impl<'genv, 'tcx> Refiner<'genv, 'tcx> {
                     .try_collect()?;
                 rty::BaseTy::Dynamic(exi_preds, *r)
             }
-            ty::TyKind::Pat => rty::BaseTy::Pat,
+            ty::TyKind::Pat(ty, pat) => {
+                rty::BaseTy::Pat(ty.refine(&self.as_default())?, pat.clone())
+            }
         };
         Ok(rty::TyOrBase::Base((self.refine)(bty)))
     }
@@ -347,7 +349,16 @@ impl Refine for ty::FnSig {
         // single hole for the "requires"; then we "fill" the hole with a KVAR
         // and generate a PolyFnSig with the hoisted variables
         // see `into_bb_env` in `type_env.rs` for an example.
-        Ok(rty::FnSig::new(self.safety, self.abi, List::empty(), inputs, output, Expr::ff(), true))
+        Ok(rty::FnSig::new(
+            self.safety,
+            self.abi,
+            List::empty(),
+            inputs,
+            output,
+            Expr::ff(),
+            List::empty(),
+            true,
+        ))
     }
 }
 
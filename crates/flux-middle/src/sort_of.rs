@@ -19,7 +19,7 @@ impl GlobalEnv<'_, '_> {
         }
     }
 
-    fn sort_of_rust_ty(
+    pub fn sort_of_rust_ty(
         self,
         def_id: DefId,
         ty: rustc_middle::ty::Ty,
@@ -29,6 +29,7 @@ impl GlobalEnv<'_, '_> {
             ty::TyKind::Bool => Some(rty::Sort::Bool),
             ty::TyKind::Slice(_) | ty::TyKind::Int(_) | ty::TyKind::Uint(_) => Some(rty::Sort::Int),
             ty::TyKind::Char => Some(rty::Sort::Char),
+            ty::TyKind::Float(_) if flux_config::real_floats() => Some(rty::Sort::Real),
             ty::TyKind::Str => Some(rty::Sort::Str),
             ty::TyKind::Adt(adt_def, args) => {
                 let mut sort_args = vec![];
@@ -42,6 +43,7 @@ impl GlobalEnv<'_, '_> {
                 let ctor = rty::SortCtor::Adt(self.adt_sort_def_of(adt_def.did())?);
                 Some(rty::Sort::App(ctor, List::from_vec(sort_args)))
             }
+            ty::TyKind::Pat(base, _) => self.sort_of_rust_ty(def_id, *base)?,
             ty::TyKind::Param(p) => {
                 let param_def = self.generics_of(def_id)?.param_at(p.index as usize, self)?;
                 if let rty::GenericParamDefKind::Base { .. } = param_def.kind {
@@ -72,7 +74,7 @@ impl GlobalEnv<'_, '_> {
                     .expect_ctor()
                     .sort())
             }
-            DefKind::Struct | DefKind::Enum => {
+            DefKind::Struct | DefKind::Enum | DefKind::Union => {
                 Ok(self
                     .adt_sort_def_of(alias_ty.def_id)?
                     .to_sort(&alias_ty.args))
@@ -90,6 +92,7 @@ impl rty::BaseTy {
             rty::BaseTy::RawPtr(..) => rty::Sort::RawPtr,
             rty::BaseTy::Bool => rty::Sort::Bool,
             rty::BaseTy::Char => rty::Sort::Char,
+            rty::BaseTy::Float(_) if flux_config::real_floats() => rty::Sort::Real,
             rty::BaseTy::Adt(adt_def, args) => adt_def.sort(args),
             rty::BaseTy::Param(param_ty) => rty::Sort::Param(*param_ty),
             rty::BaseTy::Str => rty::Sort::Str,
@@ -115,7 +118,10 @@ impl rty::BaseTy {
             | rty::BaseTy::Never
             | rty::BaseTy::Foreign(..) => rty::Sort::unit(),
             rty::BaseTy::Infer(_) => tracked_span_bug!(),
-            rty::BaseTy::Pat => rty::Sort::unit()
+            rty::BaseTy::Pat(ty, _) => {
+                ty.as_bty_skipping_existentials()
+                    .map_or_else(rty::Sort::unit, rty::BaseTy::sort)
+            }
         }
     }
 }
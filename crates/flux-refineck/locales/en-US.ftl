@@ -11,6 +11,12 @@ refineck_condition_span_note =
 refineck_call_span_note =
     inside this call
 
+refineck_counterexample_note =
+    counterexample: {$model}
+
+refineck_spurious_counterexample_note =
+    possibly spurious counterexample (some hypotheses could not be encoded): {$model}
+
 refineck_refine_error =
     refinement type error
     .label = a {$cond} cannot be proved
@@ -18,6 +24,33 @@ refineck_refine_error =
 refineck_panic_error =
     call to {$callee} may panic
 
+refineck_termination_error =
+    recursive call may not terminate
+    .note = the `decreases` metric must stay non-negative and strictly decrease at every recursive call
+
+refineck_loop_termination_error =
+    loop may not terminate
+    .note = the `loop_variant` metric must stay non-negative and strictly decrease at every iteration
+
+refineck_loop_invariant_error =
+    loop invariant may not hold
+
+refineck_union_field_error =
+    union field may not be active
+
+refineck_pattern_error =
+    value may not match the pattern of the pattern type
+
+refineck_raw_deref_error =
+    raw pointer may be out of bounds
+
+refineck_ptr_offset_error =
+    pointer offset may be out of bounds
+
+refineck_loop_invariant_local =
+    local `{$name}` cannot be used in a loop invariant
+    .label = the type of this local at the head of the loop has no index of the expected sort
+
 refineck_div_error =
     possible division by zero
 
@@ -49,6 +82,14 @@ refineck_unsupported_call =
 refineck_expected_neg =
     {$def_descr} marked with `#[should_fail]` didn't produce a refinement type error
 
+refineck_timeout_error =
+    verification timed out
+    .note = the solver exceeded the limit of {$secs}s, the limit can be raised with `solver_timeout`
+
+refineck_out_of_memory_error =
+    verification ran out of memory
+    .note = the solver exceeded the limit of {$mb}MB, the limit can be raised with `solver_memory`
+
 # Invariant checking
 
 refineck_invalid_invariant =
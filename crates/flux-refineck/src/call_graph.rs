@@ -0,0 +1,84 @@
+//! Discovery of the functions that can be called recursively, used to check termination.
+//!
+//! The call graph has an edge from a function to every local function it calls and to every
+//! closure or coroutine it constructs. Calls through function pointers or trait objects are not
+//! tracked. Trusted and ignored functions are assumed to terminate, so we don't follow the calls
+//! in their bodies.
+use flux_middle::global_env::GlobalEnv;
+use flux_rustc_bridge::mir::{AggregateKind, CallKind, Rvalue, StatementKind, TerminatorKind};
+use rustc_hash::{FxHashMap, FxHashSet};
+use rustc_hir::def_id::{DefId, LocalDefId};
+
+/// Returns the functions in the same strongly connected component of the call graph as `root`,
+/// i.e., the functions that are reachable from `root` and that can reach `root` back.
+pub(crate) fn recursive_group(genv: GlobalEnv, root: LocalDefId) -> FxHashSet<DefId> {
+    // Collect the edges of the subgraph reachable from `root`
+    let mut predecessors: FxHashMap<LocalDefId, Vec<LocalDefId>> = FxHashMap::default();
+    let mut visited = FxHashSet::from_iter([root]);
+    let mut stack = vec![root];
+    while let Some(def_id) = stack.pop() {
+        for callee in callees(genv, def_id) {
+            predecessors.entry(callee).or_default().push(def_id);
+            if visited.insert(callee) {
+                stack.push(callee);
+            }
+        }
+    }
+
+    // Every function that reaches `root` within that subgraph is in the same component
+    let mut group = FxHashSet::from_iter([root]);
+    let mut stack = vec![root];
+    while let Some(def_id) = stack.pop() {
+        for &caller in predecessors.get(&def_id).into_iter().flatten() {
+            if group.insert(caller) {
+                stack.push(caller);
+            }
+        }
+    }
+    group.into_iter().map(LocalDefId::to_def_id).collect()
+}
+
+fn callees(genv: GlobalEnv, def_id: LocalDefId) -> Vec<LocalDefId> {
+    let tcx = genv.tcx();
+    if !genv.def_kind(def_id).is_fn_like()
+        || tcx.hir_maybe_body_owned_by(def_id).is_none()
+        || genv.trusted(def_id)
+        || genv.ignored(def_id)
+        || genv.is_dummy(def_id)
+    {
+        return vec![];
+    }
+    // If the body cannot be lowered, an error has already been reported
+    let Ok(body) = genv.mir(def_id) else { return vec![] };
+
+    let mut callees = vec![];
+    for data in &body.body.basic_blocks {
+        for stmt in &data.statements {
+            if let StatementKind::Assign(
+                _,
+                Rvalue::Aggregate(
+                    AggregateKind::Closure(did, _) | AggregateKind::Coroutine(did, _),
+                    _,
+                ),
+            ) = &stmt.kind
+            {
+                callees.extend(as_local(*did));
+            }
+        }
+        if let Some(terminator) = &data.terminator
+            && let TerminatorKind::Call { kind: CallKind::FnDef { resolved_id, .. }, .. } =
+                &terminator.kind
+        {
+            callees.extend(as_local(*resolved_id));
+        }
+    }
+    callees
+}
+
+#[expect(
+    clippy::disallowed_methods,
+    reason = "calls to functions with an extern spec resolve to the external definition"
+)]
+fn as_local(def_id: DefId) -> Option<LocalDefId> {
+    def_id.as_local()
+}
@@ -1,4 +1,4 @@
-use std::{collections::hash_map::Entry, iter, vec};
+use std::{cell::OnceCell, collections::hash_map::Entry, iter, slice, vec};
 
 use flux_common::{
     bug, dbg, dbg::SpanTrace, index::IndexVec, iter::IterExt, span_bug, tracked_span_bug,
@@ -7,15 +7,17 @@ use flux_common::{
 use flux_config::{self as config, InferOpts};
 use flux_infer::{
     infer::{
-        ConstrReason, GlobalEnvExt as _, InferCtxt, InferCtxtRoot, InferResult, SubtypeReason,
+        AssertReason, ConstrReason, GlobalEnvExt as _, InferCtxt, InferCtxtAt, InferCtxtRoot,
+        InferResult, SubtypeReason,
     },
     projections::NormalizeExt as _,
     refine_tree::{Marker, RefineCtxtTrace},
 };
 use flux_middle::{
+    def_id::ResolvedDefId,
     global_env::GlobalEnv,
     pretty::PrettyCx,
-    queries::{QueryResult, try_query},
+    queries::{QueryErr, QueryResult, try_query},
     query_bug,
     rty::{
         self, AdtDef, BaseTy, Binder, Bool, Clause, Constant, CoroutineObligPredicate, EarlyBinder,
@@ -29,10 +31,11 @@ use flux_middle::{
 };
 use flux_rustc_bridge::{
     self, ToRustc,
+    lowering::Lower as _,
     mir::{
         self, AggregateKind, AssertKind, BasicBlock, Body, BodyRoot, BorrowKind, CastKind,
-        ConstOperand, Location, NonDivergingIntrinsic, Operand, Place, Rvalue, START_BLOCK,
-        Statement, StatementKind, Terminator, TerminatorKind, UnOp,
+        ConstOperand, Location, NonDivergingIntrinsic, NullOp, Operand, Place, PlaceElem, Rvalue,
+        START_BLOCK, Statement, StatementKind, Terminator, TerminatorKind, UnOp,
     },
     ty::{self, GenericArgsExt as _},
 };
@@ -56,6 +59,7 @@ use rustc_span::{
 
 use self::errors::{CheckerError, ResultExt};
 use crate::{
+    call_graph,
     ghost_statements::{CheckerId, GhostStatement, GhostStatements, Point},
     primops,
     queue::WorkQueue,
@@ -77,11 +81,28 @@ pub(crate) struct Checker<'ck, 'genv, 'tcx, M> {
     /// A marker to the node in the refinement tree at the end of the basic block after applying
     /// the effects of the terminator.
     markers: IndexVec<BasicBlock, Option<Marker>>,
+    /// A marker to the node in the refinement tree right before applying the effects of the
+    /// terminator, for basic blocks whose terminator has an unwind edge that we follow. Cleanup
+    /// blocks dominated by such a block are checked under this marker because the effects of the
+    /// terminator don't hold if it unwinds.
+    unwind_markers: IndexVec<BasicBlock, Option<Marker>>,
     visited: DenseBitSet<BasicBlock>,
     queue: WorkQueue<'ck>,
     default_refiner: Refiner<'genv, 'tcx>,
     /// The templates for the promoted bodies of the current function
     promoted: &'ck IndexSlice<Promoted, Ty>,
+    /// Loop invariants indexed by the head of the loop they are attached to
+    loop_invariants: FxHashMap<BasicBlock, rty::LoopInvariant>,
+    /// Loop variants indexed by the head of the loop they are attached to
+    loop_variants: FxHashMap<BasicBlock, rty::LoopInvariant>,
+    /// The value of each loop variant at the head of its loop
+    variants_at_head: FxHashMap<BasicBlock, Expr>,
+    /// The functions that can call back the function being checked, computed lazily when
+    /// checking termination.
+    recursive_group: OnceCell<FxHashSet<DefId>>,
+    /// The locals that may hold a raw pointer into the storage of each local, computed lazily
+    /// when checking raw pointer dereferences.
+    raw_ptrs_into: OnceCell<FxHashMap<mir::Local, Vec<mir::Local>>>,
 }
 
 /// Fields shared by the top-level function and its nested closure/generators
@@ -97,9 +118,11 @@ struct Inherited<'ck, M> {
     closures: &'ck mut UnordMap<DefId, PolyFnSig>,
 }
 
-#[derive(Debug)]
-struct ResolvedCall {
+struct ResolvedCall<'a> {
     output: Ty,
+    /// The environment to check the cleanup block with if the call unwinds, together with a
+    /// marker to the node in the refinement tree before assuming the postcondition of the callee.
+    unwind: Option<(Marker, TypeEnv<'a>)>,
     /// The refine arguments given to the call
     _early_args: Vec<Expr>,
     /// The refine arguments given to the call
@@ -153,14 +176,19 @@ pub(crate) struct RefineMode {
 pub(crate) struct ShapeResult(FxHashMap<CheckerId, FxHashMap<BasicBlock, BasicBlockEnvShape>>);
 
 /// A `Guard` describes extra "control" information that holds at the start of a successor basic block
-#[derive(Debug)]
-enum Guard {
+enum Guard<'a> {
     /// No extra information holds, e.g., for a plain goto.
     None,
     /// A predicate that can be assumed, e.g., in the branches of an if-then-else.
     Pred(Expr),
     /// The corresponding place was found to be of a particular variant.
     Match(Place, VariantIdx),
+    /// The successor is a cleanup block reached when the terminator unwinds. The block must be
+    /// checked in the given environment, which doesn't include the effects of the terminator, under
+    /// the [unwind marker] of the current block.
+    ///
+    /// [unwind marker]: Checker::unwind_markers
+    Unwind(TypeEnv<'a>),
 }
 
 impl<'genv, 'tcx> Checker<'_, 'genv, 'tcx, ShapeMode> {
@@ -218,7 +246,15 @@ impl<'genv, 'tcx> Checker<'_, 'genv, 'tcx, RefineMode> {
                 .build()
         })
         .with_span(span)?;
-        let bb_envs = bb_env_shapes.into_bb_envs(&mut root_ctxt, &body.body);
+        let loop_heads = loop_invariant_heads(
+            &body.body,
+            poly_sig.skip_binder_ref().loop_invariants(),
+            rty::LoopInvariantKind::Invariant,
+        )
+        .into_keys()
+        .map(|bb| (CheckerId::DefId(local_id), bb))
+        .collect();
+        let bb_envs = bb_env_shapes.into_bb_envs(&mut root_ctxt, &body.body, &loop_heads);
 
         dbg::refine_mode_span!(genv.tcx(), def_id, bb_envs).in_scope(|| {
             // Check the body of the function def_id against its signature
@@ -474,7 +510,16 @@ fn promoted_fn_sig(ty: &Ty) -> PolyFnSig {
     let inputs = rty::List::empty();
     let output =
         Binder::bind_with_vars(FnOutput::new(ty.clone(), rty::List::empty()), rty::List::empty());
-    let fn_sig = crate::rty::FnSig::new(safety, abi, requires, inputs, output, Expr::tt(), false);
+    let fn_sig = crate::rty::FnSig::new(
+        safety,
+        abi,
+        requires,
+        inputs,
+        output,
+        Expr::tt(),
+        rty::List::empty(),
+        false,
+    );
     PolyFnSig::bind_with_vars(fn_sig, crate::rty::List::empty())
 }
 
@@ -498,6 +543,10 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         };
 
         let bb_len = body.basic_blocks.len();
+        let loop_invariants =
+            loop_invariant_heads(body, fn_sig.loop_invariants(), rty::LoopInvariantKind::Invariant);
+        let loop_variants =
+            loop_invariant_heads(body, fn_sig.loop_invariants(), rty::LoopInvariantKind::Variant);
         Ok(Self {
             checker_id,
             genv,
@@ -507,9 +556,15 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             visited: DenseBitSet::new_empty(bb_len),
             fn_sig,
             markers: IndexVec::from_fn_n(|_| None, bb_len),
+            unwind_markers: IndexVec::from_fn_n(|_| None, bb_len),
             queue: WorkQueue::empty(bb_len, &body.dominator_order_rank),
             default_refiner: Refiner::default_for_item(genv, root_id.to_def_id())?,
             promoted,
+            loop_invariants,
+            loop_variants,
+            variants_at_head: FxHashMap::default(),
+            recursive_group: OnceCell::new(),
+            raw_ptrs_into: OnceCell::new(),
         })
     }
 
@@ -552,6 +607,17 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             let mut infcx = infcx.move_to(marker, visited);
             let mut env = M::enter_basic_block(&mut ck, &mut infcx, bb);
             env.unpack(&mut infcx);
+            if let Some(invariant) = ck.loop_invariant_at(&mut infcx, &mut env, span, bb)? {
+                infcx.assume_pred(&invariant);
+            }
+            if let Some(variant) = ck.loop_variants.get(&bb) {
+                let variant = ck.instantiate_loop_invariant(&mut infcx, &mut env, span, variant)?;
+                ck.variants_at_head.insert(bb, variant);
+            } else if config::termination()
+                && let Some(size) = ck.iterator_size_at(&mut infcx, &mut env, span, bb)?
+            {
+                ck.variants_at_head.insert(bb, size);
+            }
             ck.check_basic_block(infcx, env, bb)?;
         }
         Ok(())
@@ -660,7 +726,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 dbg::terminator!("start", terminator, infcx, env);
 
                 let successors =
-                    self.check_terminator(&mut infcx, &mut env, terminator, last_stmt_span)?;
+                    self.check_terminator(&mut infcx, &mut env, bb, terminator, last_stmt_span)?;
                 dbg::terminator!("end", terminator, infcx, env);
 
                 self.markers[bb] = Some(infcx.marker());
@@ -711,6 +777,10 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 // Place mentions are a no-op used to detect uses of unsafe that would
                 // otherwise be optimized away.
             }
+            StatementKind::StorageDead(local) => {
+                self.invalidate_raw_ptrs_into(infcx, env, *local, stmt_span)
+                    .with_span(stmt_span)?;
+            }
             StatementKind::Nop => {}
             StatementKind::Intrinsic(NonDivergingIntrinsic::Assume(op)) => {
                 // Currently, we only have the `assume` intrinsic, which if we're to trust rustc should be a NOP.
@@ -737,15 +807,17 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     /// For `check_terminator`, the output `Vec<BasicBlock, Guard>` denotes,
     /// - `BasicBlock` "successors" of the current terminator, and
     /// - `Guard` are extra control information from, e.g. the `SwitchInt` (or `Assert`) you can assume when checking the corresponding successor.
-    fn check_terminator(
+    fn check_terminator<'a>(
         &mut self,
         infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
-        env: &mut TypeEnv,
+        env: &mut TypeEnv<'a>,
+        bb: BasicBlock,
         terminator: &Terminator<'tcx>,
         last_stmt_span: Option<Span>,
-    ) -> Result<Vec<(BasicBlock, Guard)>> {
+    ) -> Result<Vec<(BasicBlock, Guard<'a>)>> {
         let source_info = terminator.source_info;
         let terminator_span = source_info.span;
+        let cleanup = terminator.unwind_cleanup().filter(|_| infcx.check_unwind);
         match &terminator.kind {
             TerminatorKind::Return => {
                 self.check_ret(infcx, env, last_stmt_span.unwrap_or(terminator_span))?;
@@ -777,8 +849,10 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 let actuals = self
                     .check_operands(infcx, env, terminator_span, args)
                     .with_span(terminator_span)?;
-                let ret = match kind {
+                let resolved_call = match kind {
                     mir::CallKind::FnDef { resolved_id, resolved_args, .. } => {
+                        self.hyperlink_callee_spec(terminator_span, *resolved_id);
+                        self.check_ptr_offset_call(infcx, terminator_span, *resolved_id, &actuals);
                         let fn_sig = self.genv.fn_sig(*resolved_id).with_span(terminator_span)?;
                         let generic_args = instantiate_args_for_fun_call(
                             self.genv,
@@ -787,7 +861,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                             &resolved_args.lowered,
                         )
                         .with_span(terminator_span)?;
-                        self.check_call(
+                        let mut resolved_call = self.check_call(
                             infcx,
                             env,
                             terminator_span,
@@ -795,8 +869,17 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                             fn_sig,
                             &generic_args,
                             &actuals,
-                        )?
-                        .output
+                            cleanup.is_some(),
+                        )?;
+                        if self.genv.tcx().is_intrinsic(*resolved_id, sym::transmute)
+                            && let [from] = &actuals[..]
+                            && let [_, to] = &resolved_args.lowered[..]
+                        {
+                            resolved_call.output = self
+                                .check_transmute(infcx, terminator_span, from, to.expect_type())
+                                .with_span(terminator_span)?;
+                        }
+                        resolved_call
                     }
                     mir::CallKind::FnPtr { operand, .. } => {
                         let ty = self
@@ -812,44 +895,117 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                                 EarlyBinder(fn_sig.clone()),
                                 &[],
                                 &actuals,
+                                cleanup.is_some(),
                             )?
-                            .output
                         } else {
                             bug!("TODO: fnptr call {ty:?}")
                         }
                     }
                 };
 
+                let unwind = cleanup
+                    .zip(resolved_call.unwind)
+                    .map(|(cleanup, (marker, env))| {
+                        self.unwind_markers[bb] = Some(marker);
+                        (cleanup, Guard::Unwind(env))
+                    });
+
                 let name = destination.name(&self.body.local_names);
-                let ret = infcx.unpack_at_name(name, &ret);
+                let ret = infcx.unpack_at_name(name, &resolved_call.output);
                 infcx.assume_invariants(&ret);
 
                 env.assign(&mut infcx.at(terminator_span), destination, ret)
                     .with_span(terminator_span)?;
 
-                if let Some(target) = target {
-                    Ok(vec![(*target, Guard::None)])
-                } else {
-                    Ok(vec![])
-                }
-            }
-            TerminatorKind::Assert { cond, expected, target, msg } => {
-                Ok(vec![(
-                    *target,
-                    self.check_assert(infcx, env, terminator_span, cond, *expected, msg)
-                        .with_span(terminator_span)?,
-                )])
+                Ok(target
+                    .map(|target| (target, Guard::None))
+                    .into_iter()
+                    .chain(unwind)
+                    .collect())
+            }
+            TerminatorKind::Assert { cond, expected, target, msg, .. } => {
+                let unwind = cleanup.map(|cleanup| {
+                    self.unwind_markers[bb] = Some(infcx.marker());
+                    (cleanup, Guard::Unwind(env.clone()))
+                });
+                let guard = self
+                    .check_assert(infcx, env, terminator_span, cond, *expected, msg)
+                    .with_span(terminator_span)?;
+                Ok(iter::once((*target, guard)).chain(unwind).collect())
             }
             TerminatorKind::Drop { place, target, .. } => {
+                if infcx.check_unwind && self.body.basic_blocks[bb].is_cleanup {
+                    self.check_drop_obligations(infcx, env, place, terminator_span)?;
+                }
                 let _ = env.move_place(&mut infcx.at(terminator_span), place);
-                Ok(vec![(*target, Guard::None)])
+                if place.projection.is_empty() {
+                    self.invalidate_raw_ptrs_into(infcx, env, place.local, terminator_span)
+                        .with_span(terminator_span)?;
+                }
+                let unwind = cleanup.map(|cleanup| {
+                    self.unwind_markers[bb] = Some(infcx.marker());
+                    (cleanup, Guard::Unwind(env.clone()))
+                });
+                Ok(iter::once((*target, Guard::None)).chain(unwind).collect())
             }
             TerminatorKind::FalseEdge { real_target, .. } => Ok(vec![(*real_target, Guard::None)]),
             TerminatorKind::FalseUnwind { real_target, .. } => {
                 Ok(vec![(*real_target, Guard::None)])
             }
-            TerminatorKind::UnwindResume => bug!("TODO: implement checking of cleanup code"),
+            // We only reach cleanup blocks when checking unwind edges. The locations reachable
+            // from the arguments are folded back before resuming, so there's nothing left to check.
+            TerminatorKind::UnwindResume => Ok(vec![]),
+        }
+    }
+
+    /// When a value with a destructor is dropped during unwinding, checks the call to its
+    /// `Drop::drop` implementation as if it were an explicit call with a mutable reference to the
+    /// dropped place. We only check destructors with a flux signature, i.e., local ones or ones
+    /// declared in an extern spec.
+    fn check_drop_obligations(
+        &mut self,
+        infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
+        env: &mut TypeEnv,
+        place: &Place,
+        span: Span,
+    ) -> Result {
+        let genv = self.genv;
+        let ty = env.lookup_rust_ty(genv, place).with_span(span)?;
+        let ty::TyKind::Adt(adt_def, args) = ty.kind() else { return Ok(()) };
+        let Some(destructor) = genv.tcx().adt_destructor(adt_def.did()) else { return Ok(()) };
+        if let ResolvedDefId::Extern(_) = genv.resolve_id(destructor.did) {
+            return Ok(());
         }
+        // Drops are not elaborated yet, so the place may have been moved out before unwinding.
+        if env
+            .lookup_place(&mut infcx.at(span), place)
+            .with_span(span)?
+            .is_uninit()
+        {
+            return Ok(());
+        }
+        let fn_sig = genv.fn_sig(destructor.did).with_span(span)?;
+        let generic_args = instantiate_args_for_fun_call(
+            genv,
+            self.checker_id.root_id().to_def_id(),
+            destructor.did,
+            args,
+        )
+        .with_span(span)?;
+        let actual = env
+            .borrow(&mut infcx.at(span), rty::ReErased, Mutability::Mut, place)
+            .with_span(span)?;
+        self.check_call(
+            infcx,
+            env,
+            span,
+            Some(destructor.did),
+            fn_sig,
+            &generic_args,
+            &[actual],
+            false,
+        )?;
+        Ok(())
     }
 
     fn check_ret(
@@ -878,17 +1034,36 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         self.check_coroutine_obligations(infcx, obligations)
     }
 
+    /// Links a call to the flux signature of the callee, which may be detached or declared in an
+    /// extern spec, so editors can jump to it.
+    fn hyperlink_callee_spec(&self, span: Span, callee_id: DefId) {
+        if !config::dump_checker_trace_info() {
+            return;
+        }
+        let local_id = match self.genv.resolve_id(callee_id) {
+            ResolvedDefId::Local(local_id) | ResolvedDefId::ExternSpec(local_id, _) => local_id,
+            ResolvedDefId::Extern(_) => return,
+        };
+        if let Ok(node) = self.genv.fhir_expect_owner_node(local_id)
+            && let Some(fn_sig) = node.fn_sig()
+            && !fn_sig.decl.lifted
+        {
+            dbg::hyperlink!(self.genv.tcx(), span, fn_sig.decl.span);
+        }
+    }
+
     #[expect(clippy::too_many_arguments)]
-    fn check_call(
+    fn check_call<'a>(
         &mut self,
         infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
-        env: &mut TypeEnv,
+        env: &mut TypeEnv<'a>,
         span: Span,
         callee_def_id: Option<DefId>,
         fn_sig: EarlyBinder<PolyFnSig>,
         generic_args: &[GenericArg],
         actuals: &[Ty],
-    ) -> Result<ResolvedCall> {
+        unwind: bool,
+    ) -> Result<ResolvedCall<'a>> {
         let genv = self.genv;
         let tcx = genv.tcx();
 
@@ -961,6 +1136,10 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             at.check_pred(requires, ConstrReason::Call);
         }
 
+        if let Some(callee_def_id) = callee_def_id {
+            self.check_termination(&mut at, callee_def_id, &fn_sig);
+        }
+
         // Check arguments
         for (actual, formal) in iter::zip(actuals, fn_sig.inputs()) {
             at.subtyping_with_env(env, &actual, formal, ConstrReason::Call)
@@ -976,11 +1155,24 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 Expr::fvar(infcx.define_bound_reft_var(sort, kind))
             });
 
+        // If the call unwinds, the callee may have updated the locations in its postcondition
+        // before panicking, so we only know they still have the same base type.
+        let unwind = if unwind {
+            let mut unwind_env = env.clone();
+            unwind_env.havoc_ensures(infcx, &output.ensures, span);
+            let marker = infcx.marker();
+            fold_local_ptrs(infcx, &mut unwind_env, span).with_span(span)?;
+            Some((marker, unwind_env))
+        } else {
+            None
+        };
+
         env.assume_ensures(infcx, &output.ensures, span);
         fold_local_ptrs(infcx, env, span).with_span(span)?;
 
         Ok(ResolvedCall {
             output: output.ret,
+            unwind,
             _early_args: early_refine_args
                 .into_iter()
                 .map(|arg| infcx.fully_resolve_evars(arg))
@@ -992,6 +1184,44 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         })
     }
 
+    /// Checks that the termination metric of the function being checked decreases at a call that
+    /// may be recursive, i.e., a call to a function in the same strongly connected component of
+    /// the call graph. The metric of the callee is compared against the metric of the caller, so
+    /// mutually recursive functions must have comparable metrics. A call from a nested closure
+    /// cannot be compared against the metric at function entry, so it can never be proven to
+    /// terminate.
+    fn check_termination(
+        &self,
+        at: &mut InferCtxtAt,
+        callee_def_id: DefId,
+        callee_sig: &rty::FnSig,
+    ) {
+        let root_id = self.checker_id.root_id();
+        let item_id = self.genv.tcx().typeck_root_def_id(root_id.to_def_id());
+        let callee_metric = callee_sig.decreases();
+        if callee_metric.is_empty() && !config::termination() {
+            return;
+        }
+        if callee_def_id != item_id && !self.recursive_group().contains(&callee_def_id) {
+            return;
+        }
+        let caller_metric =
+            if root_id.to_def_id() == item_id { self.fn_sig.decreases() } else { &[] };
+        at.check_pred(metric_decreases(callee_metric, caller_metric), ConstrReason::Termination);
+    }
+
+    fn recursive_group(&self) -> &FxHashSet<DefId> {
+        self.recursive_group.get_or_init(|| {
+            let root_id = self.checker_id.root_id().to_def_id();
+            #[expect(
+                clippy::disallowed_methods,
+                reason = "the typeck root of a local item is local"
+            )]
+            let item_id = self.genv.tcx().typeck_root_def_id(root_id).expect_local();
+            call_graph::recursive_group(self.genv, item_id)
+        })
+    }
+
     fn check_coroutine_obligations(
         &mut self,
         infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
@@ -1106,30 +1336,30 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         cond: &Operand<'tcx>,
         expected: bool,
         msg: &AssertKind,
-    ) -> InferResult<Guard> {
+    ) -> InferResult<Guard<'static>> {
         let ty = self.check_operand(infcx, env, terminator_span, cond)?;
         let TyKind::Indexed(BaseTy::Bool, idx) = ty.kind() else {
             tracked_span_bug!("unexpected ty `{ty:?}`");
         };
         let pred = if expected { idx.clone() } else { idx.not() };
 
-        let msg = match msg {
-            AssertKind::DivisionByZero => "possible division by zero",
-            AssertKind::BoundsCheck => "possible out-of-bounds access",
-            AssertKind::RemainderByZero => "possible remainder with a divisor of zero",
-            AssertKind::Overflow(mir::BinOp::Div) => "possible division with overflow",
-            AssertKind::Overflow(mir::BinOp::Rem) => "possible reminder with overflow",
+        let reason = match msg {
+            AssertKind::DivisionByZero => AssertReason::DivisionByZero,
+            AssertKind::BoundsCheck => AssertReason::BoundsCheck,
+            AssertKind::RemainderByZero => AssertReason::RemainderByZero,
+            AssertKind::Overflow(mir::BinOp::Div) => AssertReason::DivisionOverflow,
+            AssertKind::Overflow(mir::BinOp::Rem) => AssertReason::RemainderOverflow,
             AssertKind::Overflow(_) => return Ok(Guard::Pred(pred)),
         };
         infcx
             .at(terminator_span)
-            .check_pred(&pred, ConstrReason::Assert(msg));
+            .check_pred(&pred, ConstrReason::Assert(reason));
         Ok(Guard::Pred(pred))
     }
 
     /// Checks conditional branching as in a `match` statement. [`SwitchTargets`](https://doc.rust-lang.org/nightly/nightly-rustc/stable_mir/mir/struct.SwitchTargets.html) contains a list of branches - the exact bit value which is being compared and the block to jump to. Using the conditionals, each branch can be checked using the new control flow information.
     /// See <https://github.com/flux-rs/flux/pull/840#discussion_r1786543174>
-    fn check_if(discr_ty: &Ty, targets: &SwitchTargets) -> Vec<(BasicBlock, Guard)> {
+    fn check_if(discr_ty: &Ty, targets: &SwitchTargets) -> Vec<(BasicBlock, Guard<'static>)> {
         let mk = |bits| {
             match discr_ty.kind() {
                 TyKind::Indexed(BaseTy::Bool, idx) => {
@@ -1164,7 +1394,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         discr_ty: &Ty,
         targets: &SwitchTargets,
         span: Span,
-    ) -> Vec<(BasicBlock, Guard)> {
+    ) -> Vec<(BasicBlock, Guard<'static>)> {
         let (adt_def, place) = discr_ty.expect_discr();
         let idx = if let Ok(ty) = env.lookup_place(&mut infcx.at(span), place)
             && let TyKind::Indexed(_, idx) = ty.kind()
@@ -1210,19 +1440,29 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         successors
     }
 
-    fn check_successors(
+    fn check_successors<'a>(
         &mut self,
         mut infcx: InferCtxt<'_, 'genv, 'tcx>,
-        env: TypeEnv,
+        env: TypeEnv<'a>,
         from: BasicBlock,
         terminator_span: Span,
-        successors: Vec<(BasicBlock, Guard)>,
+        successors: Vec<(BasicBlock, Guard<'a>)>,
     ) -> Result {
         for (target, guard) in successors {
-            let mut infcx = infcx.branch();
-            let mut env = env.clone();
+            let (mut infcx, mut env, guard) = match guard {
+                Guard::Unwind(unwind_env) => {
+                    let marker = self.unwind_markers[from]
+                        .as_ref()
+                        .unwrap_or_else(|| tracked_span_bug!());
+                    let mut infcx = infcx.move_to(marker, false);
+                    let mut unwind_env = unwind_env;
+                    unwind_env.unblock_all(&mut infcx);
+                    (infcx, unwind_env, Guard::None)
+                }
+                guard => (infcx.branch(), env.clone(), guard),
+            };
             match guard {
-                Guard::None => {}
+                Guard::None | Guard::Unwind(_) => {}
                 Guard::Pred(expr) => {
                     infcx.assume_pred(&expr);
                 }
@@ -1237,6 +1477,9 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 Point::Edge(from, target),
                 terminator_span,
             )?;
+            if self.body.is_join_point(target) && self.dominators().dominates(target, from) {
+                self.check_loop_variant(&mut infcx, &mut env, terminator_span, target)?;
+            }
             self.check_goto(infcx, env, terminator_span, target)?;
         }
         Ok(())
@@ -1270,6 +1513,11 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             )?;
             self.check_ret(&mut infcx, &mut env, span)
         } else if self.body.is_join_point(target) {
+            if let Some(invariant) = self.loop_invariant_at(&mut infcx, &mut env, span, target)? {
+                infcx
+                    .at(span)
+                    .check_pred(&invariant, ConstrReason::LoopInvariant);
+            }
             if M::check_goto_join_point(self, infcx, env, span, target)? {
                 self.queue.insert(target);
             }
@@ -1279,6 +1527,164 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         }
     }
 
+    /// If `bb` is the head of a loop with an invariant, returns the invariant instantiated with the
+    /// indices of the locals it mentions in `env`.
+    fn loop_invariant_at(
+        &self,
+        infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
+        env: &mut TypeEnv,
+        span: Span,
+        bb: BasicBlock,
+    ) -> Result<Option<Expr>> {
+        let Some(invariant) = self.loop_invariants.get(&bb) else { return Ok(None) };
+        Ok(Some(self.instantiate_loop_invariant(infcx, env, span, invariant)?))
+    }
+
+    /// Checks that the variant of the loop with head `head` decreases along a back edge. Under
+    /// `-Ftermination`, `for` loops without a variant use the size of their iterator (see
+    /// [`Checker::iterator_size_at`]) and back edges of other loops without a variant are rejected.
+    fn check_loop_variant(
+        &self,
+        infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
+        env: &mut TypeEnv,
+        span: Span,
+        head: BasicBlock,
+    ) -> Result {
+        let current = if let Some(variant) = self.loop_variants.get(&head) {
+            Some(self.instantiate_loop_invariant(infcx, env, span, variant)?)
+        } else if config::termination() {
+            self.iterator_size_at(infcx, env, span, head)?
+        } else {
+            return Ok(());
+        };
+        let pred = if let Some(current) = current
+            && let Some(at_head) = self.variants_at_head.get(&head)
+        {
+            metric_decreases(&[current], std::slice::from_ref(at_head))
+        } else {
+            Expr::ff()
+        };
+        infcx.at(span).check_pred(pred, ConstrReason::LoopVariant);
+        Ok(())
+    }
+
+    /// If `head` is the head of a `for` loop, returns the number of items left in the iterator it
+    /// advances, as given by the `size` associated refinement of `Iterator` for the type of the
+    /// iterator in `env`. For a `Range` this is the distance between its start and its end.
+    fn iterator_size_at(
+        &self,
+        infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
+        env: &mut TypeEnv,
+        span: Span,
+        head: BasicBlock,
+    ) -> Result<Option<Expr>> {
+        let tcx = self.genv.tcx();
+        let Some(local) = self.loop_iterator(head) else { return Ok(None) };
+        let Some(iterator_id) = tcx.lang_items().iterator_trait() else { return Ok(None) };
+        let Some(size) = self
+            .genv
+            .assoc_refinements_of(iterator_id)
+            .with_span(span)?
+            .find(sym::size)
+        else {
+            return Ok(None);
+        };
+        let ty = env
+            .lookup_place(&mut infcx.at(span), &Place::new(local, vec![]))
+            .with_span(span)?;
+        let ty = infcx.unpack(&ty);
+        let TyKind::Indexed(bty, idx) = ty.kind() else { return Ok(None) };
+        let alias_reft = rty::AliasReft {
+            assoc_id: size.def_id,
+            args: rty::List::singleton(GenericArg::Base(bty.to_subset_ty_ctor())),
+        };
+        let size = Expr::alias(alias_reft, rty::List::singleton(idx.clone()))
+            .deeply_normalize(&mut infcx.at(span))
+            .with_span(span)?;
+        Ok(Some(size))
+    }
+
+    /// Returns the local holding the iterator of a `for` loop with head `head`, i.e., the local
+    /// mutably (re)borrowed to call [`Iterator::next`] before any branching after the head.
+    fn loop_iterator(&self, head: BasicBlock) -> Option<mir::Local> {
+        let next_fn = self.genv.tcx().lang_items().next_fn()?;
+        let mut bb = head;
+        for _ in 0..self.body.basic_blocks.len() {
+            let data = &self.body.basic_blocks[bb];
+            match &data.terminator.as_ref()?.kind {
+                TerminatorKind::Goto { target }
+                | TerminatorKind::FalseUnwind { real_target: target, .. } => bb = *target,
+                TerminatorKind::Call {
+                    kind: mir::CallKind::FnDef { def_id, .. }, args, ..
+                } if *def_id == next_fn => {
+                    let [Operand::Copy(arg) | Operand::Move(arg)] = &args[..] else { return None };
+                    if !arg.projection.is_empty() {
+                        return None;
+                    }
+                    let mut local = arg.local;
+                    // Follow the chain of reborrows, e.g., `_12 = &mut _7; _11 = &mut (*_12)`
+                    for stmt in data.statements.iter().rev() {
+                        if let StatementKind::Assign(
+                            lhs,
+                            Rvalue::Ref(_, BorrowKind::Mut { .. }, place),
+                        ) = &stmt.kind
+                            && lhs.local == local
+                            && lhs.projection.is_empty()
+                        {
+                            match &place.projection[..] {
+                                [] => return Some(place.local),
+                                [PlaceElem::Deref] => local = place.local,
+                                _ => return None,
+                            }
+                        }
+                    }
+                    return None;
+                }
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    /// Instantiates a loop invariant or variant with the indices of the locals it mentions in
+    /// `env`.
+    fn instantiate_loop_invariant(
+        &self,
+        infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
+        env: &mut TypeEnv,
+        span: Span,
+        invariant: &rty::LoopInvariant,
+    ) -> Result<Expr> {
+        let mut args = vec![];
+        for (local, var) in iter::zip(&invariant.locals, invariant.pred.vars()) {
+            let idx = if let Some(mir_local) = self.body.find_local(local.name, local.span) {
+                let ty = env
+                    .lookup_place(&mut infcx.at(span), &Place::new(mir_local, vec![]))
+                    .with_span(span)?;
+                match infcx.unpack(&ty).kind() {
+                    TyKind::Indexed(bty, idx) if bty.sort() == *var.expect_sort() => {
+                        Some(idx.clone())
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            let Some(idx) = idx else {
+                let err = self
+                    .genv
+                    .sess()
+                    .emit_err(crate::errors::LoopInvariantLocal {
+                        span: local.span,
+                        name: local.name,
+                    });
+                return Err(QueryErr::from(err)).with_span(span);
+            };
+            args.push(idx);
+        }
+        Ok(invariant.pred.replace_bound_refts(&args))
+    }
+
     fn closure_template(
         &mut self,
         infcx: &mut InferCtxt<'_, 'genv, 'tcx>,
@@ -1449,12 +1855,11 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 let actuals = self
                     .check_operands(infcx, env, stmt_span, operands)
                     .with_span(stmt_span)?;
-                let sig = genv
+                let variant = genv
                     .variant_sig(*def_id, *variant_idx)
                     .with_span(stmt_span)?
                     .ok_or_query_err(*def_id)
-                    .with_span(stmt_span)?
-                    .to_poly_fn_sig(*field_idx);
+                    .with_span(stmt_span)?;
 
                 let args = instantiate_args_for_constructor(
                     genv,
@@ -1463,7 +1868,24 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     args,
                 )
                 .with_span(stmt_span)?;
-                self.check_call(infcx, env, stmt_span, Some(*def_id), sig, &args, &actuals)
+                if let Some(field_idx) = field_idx {
+                    // A union is initialized through a single field which becomes the active one
+                    let [actual] = &actuals[..] else {
+                        tracked_span_bug!("expected a single operand when initializing a union")
+                    };
+                    return infcx
+                        .at(stmt_span)
+                        .check_union_constructor(
+                            variant,
+                            &args,
+                            *field_idx,
+                            actual,
+                            ConstrReason::Call,
+                        )
+                        .with_span(stmt_span);
+                }
+                let sig = variant.to_poly_fn_sig();
+                self.check_call(infcx, env, stmt_span, Some(*def_id), sig, &args, &actuals, false)
                     .map(|resolved_call| resolved_call.output)
             }
             Rvalue::Aggregate(AggregateKind::Array(arr_ty), operands) => {
@@ -1498,6 +1920,24 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                     .with_span(stmt_span)?;
                 Ty::mk_box_with_default_alloc(self.genv, Ty::uninit()).with_span(stmt_span)
             }
+            Rvalue::ThreadLocalRef(def_id, ptr_ty) => {
+                // Immutable thread-locals are read through a shared reference which we type with
+                // the spec of the static if there's one. Raw pointers to mutable or extern
+                // thread-locals get the default refinement.
+                if let ty::TyKind::Ref(re, _, Mutability::Not) = ptr_ty.kind()
+                    && let rty::StaticInfo::Known(ty) =
+                        genv.static_info(*def_id).with_span(stmt_span)?
+                {
+                    Ok(Ty::mk_ref(*re, ty, Mutability::Not))
+                } else {
+                    self.refine_default(ptr_ty).with_span(stmt_span)
+                }
+            }
+            Rvalue::NullaryOp(NullOp::RuntimeChecks(_)) => {
+                // Whether runtime checks are enabled is decided when the code is generated, so we
+                // cannot assume anything about it.
+                Ok(Ty::bool())
+            }
         }
     }
 
@@ -1542,8 +1982,20 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
 
         match (ty1.kind(), ty2.kind()) {
             (TyKind::Indexed(bty1, idx1), TyKind::Indexed(bty2, idx2)) => {
-                let rule =
-                    primops::match_bin_op(bin_op, bty1, idx1, bty2, idx2, infcx.check_overflow);
+                let rule = if let mir::BinOp::Cmp = bin_op {
+                    primops::match_cmp(self.genv, bty1, idx1, bty2, idx2)?
+                } else {
+                    primops::match_bin_op(
+                        bin_op,
+                        bty1,
+                        idx1,
+                        bty2,
+                        idx2,
+                        infcx.check_overflow,
+                        infcx.uint_as_bitvec,
+                        infcx.check_raw_deref(),
+                    )
+                };
                 if let Some(pre) = rule.precondition {
                     infcx.at(stmt_span).check_pred(pre.pred, pre.reason);
                 }
@@ -1565,7 +2017,13 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         let ty = self.check_operand(infcx, env, stmt_span, op)?;
         match ty.kind() {
             TyKind::Indexed(bty, idx) => {
-                let rule = primops::match_un_op(un_op, bty, idx, infcx.check_overflow);
+                let rule = primops::match_un_op(
+                    un_op,
+                    bty,
+                    idx,
+                    infcx.check_overflow,
+                    infcx.uint_as_bitvec,
+                );
                 if let Some(pre) = rule.precondition {
                     infcx.at(stmt_span).check_pred(pre.pred, pre.reason);
                 }
@@ -1648,12 +2106,21 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
             CastKind::PointerCoercion(mir::PointerCast::Unsize) => {
                 self.check_unsize_cast(infcx, env, stmt_span, from, to)?
             }
+            CastKind::IntToFloat if config::real_floats() => {
+                match (from.kind(), to.kind()) {
+                    (Int!(_, idx) | Uint!(_, idx), RustTy::Float(float_ty)) => {
+                        int_float_cast(idx, *float_ty)
+                    }
+                    _ => self.refine_default(to)?,
+                }
+            }
             CastKind::FloatToInt
             | CastKind::IntToFloat
             | CastKind::PtrToPtr
             | CastKind::PointerCoercion(mir::PointerCast::MutToConstPointer)
             | CastKind::PointerCoercion(mir::PointerCast::ClosureFnPointer)
             | CastKind::PointerWithExposedProvenance => self.refine_default(to)?,
+            CastKind::Transmute => self.check_transmute(infcx, stmt_span, from, to)?,
             CastKind::PointerCoercion(mir::PointerCast::ReifyFnPointer) => {
                 let to = self.refine_default(to)?;
                 if let TyKind::Indexed(BaseTy::FnDef(def_id, args), _) = from.kind()
@@ -1673,6 +2140,70 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
         Ok(ty)
     }
 
+    /// In `checked` raw dereference mode, `add` and `offset` on raw pointers must keep the pointer
+    /// within its allocation, just like the `Offset` primitive operation. The specs in `flux-core`
+    /// only describe the resulting pointer, so the bounds are not required in the other modes.
+    fn check_ptr_offset_call(
+        &self,
+        infcx: &mut InferCtxt,
+        span: Span,
+        callee_id: DefId,
+        actuals: &[Ty],
+    ) {
+        let tcx = self.genv.tcx();
+        if !infcx.check_raw_deref()
+            || !matches!(tcx.item_name(callee_id), sym::add | sym::offset)
+            || !tcx
+                .inherent_impl_of_assoc(callee_id)
+                .is_some_and(|impl_id| tcx.type_of(impl_id).skip_binder().is_raw_ptr())
+        {
+            return;
+        }
+        if let [ptr, count] = actuals
+            && let TyKind::Indexed(BaseTy::RawPtr(..), ptr) = infcx.unpack(ptr).kind()
+            && let TyKind::Indexed(bty, count) = infcx.unpack(count).kind()
+            && bty.is_integral()
+        {
+            infcx
+                .at(span)
+                .check_pred(primops::ptr_offset_in_bounds(ptr, count), ConstrReason::PtrOffset);
+        }
+    }
+
+    /// Transmuting between a pattern type and its base type preserves the index. When transmuting
+    /// into a pattern type we check that the value matches the pattern. Other transmutes produce
+    /// an unrefined value.
+    fn check_transmute(
+        &self,
+        infcx: &mut InferCtxt,
+        span: Span,
+        from: &Ty,
+        to: &ty::Ty,
+    ) -> InferResult<Ty> {
+        let to = self.refine_default(to)?;
+        let from = infcx.unpack(from);
+        let TyKind::Indexed(from_bty, idx) = from.kind() else {
+            return Ok(to);
+        };
+        let Some(to_bty) = to.as_bty_skipping_existentials() else {
+            return Ok(to);
+        };
+        match (from_bty, to_bty) {
+            (BaseTy::Pat(base, _), _) if base.as_bty_skipping_existentials() == Some(to_bty) => {
+                Ok(Ty::indexed(to_bty.clone(), idx.clone()))
+            }
+            (_, BaseTy::Pat(base, _)) if base.as_bty_skipping_existentials() == Some(from_bty) => {
+                if let Some(invariant) = to_bty.pat_invariant(self.genv.tcx()) {
+                    infcx
+                        .at(span)
+                        .check_pred(invariant.apply(idx), ConstrReason::Pattern);
+                }
+                Ok(Ty::indexed(to_bty.clone(), idx.clone()))
+            }
+            _ => Ok(to),
+        }
+    }
+
     fn discr_to_int_cast(adt_def: &AdtDef, bty: BaseTy) -> Ty {
         // TODO: This could be a giant disjunction, maybe better (if less precise) to use the interval?
         let vals = adt_def
@@ -1756,12 +2287,46 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     ) -> InferResult<Ty> {
         let ty = match operand {
             Operand::Copy(p) => env.lookup_place(&mut infcx.at(span), p)?,
-            Operand::Move(p) => env.move_place(&mut infcx.at(span), p)?,
+            Operand::Move(p) => {
+                let ty = env.move_place(&mut infcx.at(span), p)?;
+                if p.projection.is_empty() {
+                    self.invalidate_raw_ptrs_into(infcx, env, p.local, span)?;
+                }
+                ty
+            }
             Operand::Constant(c) => self.check_constant(infcx, c)?,
         };
         Ok(infcx.hoister(true).hoist(&ty))
     }
 
+    /// In `checked` raw dereference mode, a raw pointer into the storage of `local` is no longer
+    /// known to be valid once the storage is deallocated or moved, so we forget everything we
+    /// know about it by giving it a fresh unconstrained index. Dereferencing it afterwards
+    /// requires proving `ptr_size(p) > 0` for an arbitrary `p`, which fails.
+    fn invalidate_raw_ptrs_into(
+        &self,
+        infcx: &mut InferCtxt,
+        env: &mut TypeEnv,
+        local: mir::Local,
+        span: Span,
+    ) -> InferResult {
+        if !infcx.check_raw_deref() {
+            return Ok(());
+        }
+        let raw_ptrs = self
+            .raw_ptrs_into
+            .get_or_init(|| raw_ptrs_into_locals(self.body));
+        for ptr in raw_ptrs.get(&local).into_iter().flatten() {
+            let place = Place::new(*ptr, vec![]);
+            let ty = env.lookup_place(&mut infcx.at(span), &place)?;
+            if let TyKind::Indexed(bty @ BaseTy::RawPtr(..), _) = ty.kind() {
+                let dangling = infcx.hoister(true).hoist(&bty.to_ty());
+                env.assign(&mut infcx.at(span), &place, dangling)?;
+            }
+        }
+        Ok(())
+    }
+
     fn check_constant(
         &mut self,
         infcx: &InferCtxt<'_, 'genv, 'tcx>,
@@ -1907,6 +2472,23 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 let idx = Expr::constant(Constant::from(scalar_to_uint(tcx, scalar, *uint_ty)));
                 Some(Ty::indexed(BaseTy::Uint(*uint_ty), idx))
             }
+            ty::Float(float_ty) if config::real_floats() => {
+                // Floats are indexed by the real number they denote. The value is taken from its
+                // shortest decimal representation, which is how it would be written in the source.
+                let idx = match float_ty {
+                    ty::FloatTy::F32 => {
+                        Expr::real_from_decimal(&f32::from_bits(scalar.to_u32()).to_string())
+                    }
+                    ty::FloatTy::F64 => {
+                        Expr::real_from_decimal(&f64::from_bits(scalar.to_u64()).to_string())
+                    }
+                    ty::FloatTy::F16 | ty::FloatTy::F128 => None,
+                };
+                match idx {
+                    Some(idx) => Some(Ty::indexed(BaseTy::Float(*float_ty), idx)),
+                    None => Some(Ty::float(*float_ty)),
+                }
+            }
             ty::Float(float_ty) => Some(Ty::float(*float_ty)),
             ty::Char => {
                 let idx = Expr::constant(Constant::Char(scalar.try_into().unwrap()));
@@ -1916,6 +2498,13 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
                 let idx = Expr::constant(Constant::Bool(scalar.try_to_bool().unwrap()));
                 Some(Ty::indexed(BaseTy::Bool, idx))
             }
+            ty::Pat(base, pat) => {
+                // A constant of pattern type is indexed by the value of its base type
+                let ty = self.check_scalar_int(scalar, *base)?;
+                let TyKind::Indexed(bty, idx) = ty.kind() else { return None };
+                let pat = pat.lower(tcx).ok()?;
+                Some(Ty::indexed(BaseTy::Pat(bty.to_ty(), pat), idx.clone()))
+            }
             // ty::Tuple(tys) if tys.is_empty() => Constant::Unit,
             _ => None,
         }
@@ -1963,7 +2552,7 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
 
     #[track_caller]
     fn marker_at_dominator(&self, bb: BasicBlock) -> &Marker {
-        marker_at_dominator(self.body, &self.markers, bb)
+        marker_at_dominator(self.body, &self.markers, &self.unwind_markers, bb)
     }
 
     fn dominators(&self) -> &'ck Dominators<BasicBlock> {
@@ -1983,6 +2572,69 @@ impl<'ck, 'genv, 'tcx, M: Mode> Checker<'ck, 'genv, 'tcx, M> {
     }
 }
 
+/// Finds the head of the loop each invariant of the given `kind` is attached to, i.e., the
+/// outermost join point inside the loop that is the target of a back edge. Invariants for loops
+/// without a back edge are dropped because there's no join point where they could be assumed.
+fn loop_invariant_heads(
+    body: &Body,
+    invariants: &[rty::LoopInvariant],
+    kind: rty::LoopInvariantKind,
+) -> FxHashMap<BasicBlock, rty::LoopInvariant> {
+    if !invariants.iter().any(|invariant| invariant.kind == kind) {
+        return FxHashMap::default();
+    }
+    let dominators = body.dominators();
+    let predecessors = body.rustc_body.basic_blocks.predecessors();
+    let heads = body
+        .basic_blocks
+        .indices()
+        .filter(|&bb| {
+            body.is_join_point(bb)
+                && predecessors[bb]
+                    .iter()
+                    .any(|&pred| dominators.dominates(bb, pred))
+        })
+        .collect_vec();
+    invariants
+        .iter()
+        .filter(|invariant| invariant.kind == kind)
+        .filter_map(|invariant| {
+            let head = heads
+                .iter()
+                .copied()
+                .filter(|&bb| {
+                    body.basic_blocks[bb]
+                        .terminator
+                        .as_ref()
+                        .is_some_and(|terminator| {
+                            invariant.span.contains(terminator.source_info.span)
+                        })
+                })
+                .min_by_key(|&bb| body.dominator_order_rank[bb])?;
+            Some((head, invariant.clone()))
+        })
+        .collect()
+}
+
+/// Returns a predicate stating that `callee` is smaller than `caller` in the lexicographic order
+/// and that all its components are non-negative. The predicate is `false` if there's no metric to
+/// compare.
+pub(crate) fn metric_decreases(callee: &[Expr], caller: &[Expr]) -> Expr {
+    if callee.is_empty() || callee.len() != caller.len() {
+        return Expr::ff();
+    }
+    let non_negative = callee.iter().map(|e| Expr::ge(e.clone(), Expr::zero()));
+    let decreases = iter::zip(callee, caller)
+        .rev()
+        .fold(Expr::ff(), |rest, (e1, e2)| {
+            Expr::or(
+                Expr::lt(e1.clone(), e2.clone()),
+                Expr::and(Expr::eq(e1.clone(), e2.clone()), rest),
+            )
+        });
+    Expr::and_from_iter(non_negative.chain([decreases]))
+}
+
 fn instantiate_args_for_fun_call(
     genv: GlobalEnv,
     caller_id: DefId,
@@ -2180,7 +2832,7 @@ impl Mode for ShapeMode {
         let modified = match bb_envs.entry(ck.checker_id).or_default().entry(target) {
             Entry::Occupied(mut entry) => entry.get_mut().join(env, span),
             Entry::Vacant(entry) => {
-                let scope = marker_at_dominator(ck.body, &ck.markers, target)
+                let scope = marker_at_dominator(ck.body, &ck.markers, &ck.unwind_markers, target)
                     .scope()
                     .unwrap_or_else(|| tracked_span_bug!());
                 entry.insert(env.into_infer(scope));
@@ -2247,6 +2899,75 @@ impl Mode for RefineMode {
     }
 }
 
+/// Maps each local to the locals that may hold a raw pointer into its storage, i.e., the locals
+/// (transitively) computed from a borrow of the local that doesn't go through a dereference. This
+/// is a flow-insensitive over-approximation. Pointers stored inside other values, e.g., in a field
+/// of a struct, are not tracked.
+fn raw_ptrs_into_locals(body: &Body) -> FxHashMap<mir::Local, Vec<mir::Local>> {
+    let mut owners: IndexVec<mir::Local, FxHashSet<mir::Local>> =
+        IndexVec::from_elem(FxHashSet::default(), &body.local_decls);
+    let borrowed = |owners: &IndexVec<_, FxHashSet<_>>, place: &Place| {
+        if place.projection.contains(&PlaceElem::Deref) {
+            owners[place.local].clone()
+        } else {
+            FxHashSet::from_iter([place.local])
+        }
+    };
+    let copied = |owners: &IndexVec<_, FxHashSet<_>>, operands: &[Operand]| {
+        operands
+            .iter()
+            .filter_map(
+                |op| if let Operand::Copy(p) | Operand::Move(p) = op { Some(p) } else { None },
+            )
+            .flat_map(|p| owners[p.local].iter().copied())
+            .collect::<FxHashSet<_>>()
+    };
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for data in &body.basic_blocks {
+            let stmts = data.statements.iter().filter_map(|stmt| {
+                if let StatementKind::Assign(dest, rvalue) = &stmt.kind {
+                    let new = match rvalue {
+                        Rvalue::Ref(_, _, place) | Rvalue::RawPtr(_, place) => {
+                            borrowed(&owners, place)
+                        }
+                        Rvalue::Use(op) | Rvalue::Cast(_, op, _) => {
+                            copied(&owners, slice::from_ref(op))
+                        }
+                        _ => return None,
+                    };
+                    Some((dest, new))
+                } else {
+                    None
+                }
+            });
+            let call = data.terminator.iter().filter_map(|terminator| {
+                if let TerminatorKind::Call { args, destination, .. } = &terminator.kind {
+                    Some((destination, copied(&owners, args)))
+                } else {
+                    None
+                }
+            });
+            let updates = stmts.chain(call).collect_vec();
+            for (dest, new) in updates {
+                if dest.projection.is_empty() {
+                    let old_len = owners[dest.local].len();
+                    owners[dest.local].extend(new);
+                    changed |= owners[dest.local].len() != old_len;
+                }
+            }
+        }
+    }
+    let mut raw_ptrs_into: FxHashMap<_, Vec<_>> = FxHashMap::default();
+    for (ptr, owners) in owners.iter_enumerated() {
+        for owner in owners {
+            raw_ptrs_into.entry(*owner).or_default().push(ptr);
+        }
+    }
+    raw_ptrs_into
+}
+
 fn bool_int_cast(b: &Expr, int_ty: IntTy) -> Ty {
     let idx = Expr::ite(b, 1, 0);
     Ty::indexed(BaseTy::Int(int_ty), idx)
@@ -2259,6 +2980,11 @@ fn uint_char_cast(idx: &Expr) -> Ty {
     Ty::indexed(BaseTy::Char, idx)
 }
 
+fn int_float_cast(idx: &Expr, float_ty: rty::FloatTy) -> Ty {
+    let idx = Expr::cast(rty::Sort::Int, rty::Sort::Real, idx.clone());
+    Ty::indexed(BaseTy::Float(float_ty), idx)
+}
+
 fn char_uint_cast(idx: &Expr, uint_ty: UintTy) -> Ty {
     let idx = Expr::cast(rty::Sort::Char, rty::Sort::Int, idx.clone());
     if uint_bit_width(uint_ty) >= 32 {
@@ -2318,17 +3044,23 @@ fn int_bit_width(int_ty: IntTy) -> u64 {
 }
 
 impl ShapeResult {
+    /// Generates the [`BasicBlockEnv`] for each join point. The blocks in `loop_heads` are the
+    /// heads of loops annotated with an invariant.
     fn into_bb_envs(
         self,
         infcx: &mut InferCtxtRoot,
         body: &Body,
+        loop_heads: &FxHashSet<(CheckerId, BasicBlock)>,
     ) -> FxHashMap<CheckerId, FxHashMap<BasicBlock, BasicBlockEnv>> {
         self.0
             .into_iter()
             .map(|(checker_id, shapes)| {
                 let bb_envs = shapes
                     .into_iter()
-                    .map(|(bb, shape)| (bb, shape.into_bb_env(infcx, body)))
+                    .map(|(bb, shape)| {
+                        let has_invariant = loop_heads.contains(&(checker_id, bb));
+                        (bb, shape.into_bb_env(infcx, body, has_invariant))
+                    })
                     .collect();
                 (checker_id, bb_envs)
             })
@@ -2339,14 +3071,17 @@ impl ShapeResult {
 fn marker_at_dominator<'a>(
     body: &Body,
     markers: &'a IndexVec<BasicBlock, Option<Marker>>,
+    unwind_markers: &'a IndexVec<BasicBlock, Option<Marker>>,
     bb: BasicBlock,
 ) -> &'a Marker {
     let dominator = body
         .dominators()
         .immediate_dominator(bb)
         .unwrap_or_else(|| tracked_span_bug!());
-    markers[dominator]
-        .as_ref()
+    let unwind_marker =
+        if body.basic_blocks[bb].is_cleanup { unwind_markers[dominator].as_ref() } else { None };
+    unwind_marker
+        .or(markers[dominator].as_ref())
         .unwrap_or_else(|| tracked_span_bug!())
 }
 
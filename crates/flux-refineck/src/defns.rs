@@ -0,0 +1,227 @@
+//! Checking that recursive spec functions terminate.
+//!
+//! Recursive spec functions are encoded as uninterpreted functions together with equations
+//! unfolding their definition, which is only sound if the definitions terminate. Every function in
+//! a recursive group must have a `decreases` metric, and we check that the metric stays non-negative
+//! and strictly decreases at every recursive call under the conditions guarding the call.
+use flux_common::{iter::IterExt, result::ResultExt};
+use flux_infer::{
+    fixpoint_encoding::FixpointQueue,
+    infer::{ConstrReason, GlobalEnvExt, InferCtxt, Tag},
+};
+use flux_middle::{
+    FixpointQueryKind,
+    def_id::{FluxDefId, MaybeExternId},
+    fhir,
+    global_env::GlobalEnv,
+    queries::try_query,
+    rty::{self, BinOp, BoundReftKind, Expr, ExprKind, FuncInfo, SpecFuncKind},
+};
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_errors::ErrorGuaranteed;
+use rustc_hir::def_id::{LOCAL_CRATE, LocalDefId};
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_middle::ty::TypingMode;
+use rustc_span::Span;
+
+use crate::checker::metric_decreases;
+
+/// Checks the termination of all the recursive spec functions defined in the crate. We issue one
+/// query for all the functions defined in the same item.
+pub fn check_defns<'genv, 'tcx>(
+    genv: GlobalEnv<'genv, 'tcx>,
+    queue: &mut FixpointQueue<'genv, 'tcx>,
+) -> Result<(), ErrorGuaranteed> {
+    let mut defns: FxIndexMap<LocalDefId, Vec<&fhir::SpecFunc>> = FxIndexMap::default();
+    for (_, item) in genv.fhir_iter_flux_items() {
+        if let fhir::FluxItem::Func(func) = item
+            && func.body.is_some()
+            && genv.normalized_info(func.def_id.to_def_id()).recursive
+        {
+            defns.entry(func.def_id.parent()).or_default().push(func);
+        }
+    }
+    defns
+        .into_iter()
+        .try_for_each_exhaust(|(parent, funcs)| check_termination(genv, queue, parent, &funcs))
+}
+
+fn check_termination<'genv, 'tcx>(
+    genv: GlobalEnv<'genv, 'tcx>,
+    queue: &mut FixpointQueue<'genv, 'tcx>,
+    parent: LocalDefId,
+    funcs: &[&fhir::SpecFunc],
+) -> Result<(), ErrorGuaranteed> {
+    let region_infercx = genv
+        .tcx()
+        .infer_ctxt()
+        .with_next_trait_solver(true)
+        .build(TypingMode::non_body_analysis());
+
+    let mut infcx_root = try_query(|| {
+        genv.infcx_root(&region_infercx, genv.infer_opts(parent))
+            .build()
+    })
+    .emit(&genv)?;
+
+    for func in funcs {
+        let mut rcx = infcx_root.infcx(parent.to_def_id(), &region_infercx);
+        let def_id = func.def_id.to_def_id();
+        let params = genv
+            .inlined_body(def_id)
+            .vars()
+            .iter()
+            .map(|var| {
+                let (sort, _, kind) = var.expect_refine();
+                Expr::fvar(rcx.define_bound_reft_var(sort, kind))
+            })
+            .collect::<Vec<_>>();
+        let body = genv.inlined_body(def_id).replace_bound_refts(&params);
+        let metric = decreases(genv, def_id).replace_bound_refts(&params);
+        TerminationCk { genv, info: genv.normalized_info(def_id), metric, span: func.ident_span }
+            .check_expr(&mut rcx, &body);
+    }
+
+    infcx_root.execute_fixpoint_query(
+        queue,
+        MaybeExternId::Local(parent),
+        FixpointQueryKind::Defns,
+        move |answer| {
+            if let Some(resource) = answer.exhausted {
+                Err(crate::report_exhausted(genv, genv.tcx().def_span(parent), resource))
+            } else {
+                crate::report_errors(genv, answer)
+            }
+        },
+    )
+}
+
+fn decreases(genv: GlobalEnv, def_id: FluxDefId) -> rty::Binder<rty::List<Expr>> {
+    genv.normalized_defns(LOCAL_CRATE).decreases(def_id)
+}
+
+struct TerminationCk<'genv, 'tcx> {
+    genv: GlobalEnv<'genv, 'tcx>,
+    /// Information about the function being checked
+    info: FuncInfo,
+    /// The metric of the function being checked instantiated with its parameters
+    metric: rty::List<Expr>,
+    /// Span used to report errors for calls without a span
+    span: Span,
+}
+
+impl TerminationCk<'_, '_> {
+    /// Checks the metric decreases at every recursive call in `expr`, assuming the conditions
+    /// under which each subexpression is evaluated.
+    fn check_expr(&self, rcx: &mut InferCtxt, expr: &Expr) {
+        match expr.kind() {
+            ExprKind::IfThenElse(p, e1, e2) => {
+                self.check_expr(rcx, p);
+                self.check_expr_assuming(rcx, p, e1);
+                self.check_expr_assuming(rcx, &p.not(), e2);
+            }
+            ExprKind::BinaryOp(BinOp::And | BinOp::Imp, e1, e2) => {
+                self.check_expr(rcx, e1);
+                self.check_expr_assuming(rcx, e1, e2);
+            }
+            ExprKind::BinaryOp(BinOp::Or, e1, e2) => {
+                self.check_expr(rcx, e1);
+                self.check_expr_assuming(rcx, &e1.not(), e2);
+            }
+            ExprKind::BinaryOp(_, e1, e2) => {
+                self.check_expr(rcx, e1);
+                self.check_expr(rcx, e2);
+            }
+            ExprKind::App(func, _, args) => {
+                for arg in args {
+                    self.check_expr(rcx, arg);
+                }
+                if let ExprKind::GlobalFunc(SpecFuncKind::Def(callee)) = func.kind()
+                    && let Some(callee) = self.recursive_callee(*callee)
+                {
+                    let metric = decreases(self.genv, callee).replace_bound_refts(args);
+                    let span = expr.span().map_or(self.span, |espan| espan.span);
+                    rcx.check_pred(
+                        metric_decreases(&metric, &self.metric),
+                        Tag::new(ConstrReason::Termination, span),
+                    );
+                } else {
+                    self.check_expr(rcx, func);
+                }
+            }
+            ExprKind::Let(init, body) => {
+                self.check_expr(rcx, init);
+                self.check_expr(rcx, &body.replace_bound_reft(init));
+            }
+            ExprKind::BoundedQuant(_, rng, body) => {
+                let mut rcx = rcx.branch();
+                let var =
+                    Expr::fvar(rcx.define_bound_reft_var(&rty::Sort::Int, BoundReftKind::Anon));
+                rcx.assume_pred(Expr::and(
+                    Expr::le(Expr::constant(rng.start.into()), &var),
+                    Expr::lt(&var, Expr::constant(rng.end.into())),
+                ));
+                self.check_expr(&mut rcx, &body.replace_bound_reft(&var));
+            }
+            ExprKind::Quant(_, body) => {
+                let mut rcx = rcx.branch();
+                let body = body.replace_bound_refts_with(|sort, _, kind| {
+                    Expr::fvar(rcx.define_bound_reft_var(sort, kind))
+                });
+                self.check_expr(&mut rcx, &body.pred);
+            }
+            ExprKind::ForAll(body) | ExprKind::Exists(body) => {
+                let mut rcx = rcx.branch();
+                let body = body.replace_bound_refts_with(|sort, _, kind| {
+                    Expr::fvar(rcx.define_bound_reft_var(sort, kind))
+                });
+                self.check_expr(&mut rcx, &body);
+            }
+            ExprKind::Abs(lam) => {
+                let mut rcx = rcx.branch();
+                let args = lam
+                    .vars()
+                    .iter()
+                    .map(|var| {
+                        let (sort, _, kind) = var.expect_refine();
+                        Expr::fvar(rcx.define_bound_reft_var(sort, kind))
+                    })
+                    .collect::<Vec<_>>();
+                self.check_expr(&mut rcx, &lam.apply(&args));
+            }
+            ExprKind::UnaryOp(_, e)
+            | ExprKind::FieldProj(e, _)
+            | ExprKind::PathProj(e, _)
+            | ExprKind::IsCtor(_, _, e) => self.check_expr(rcx, e),
+            ExprKind::Ctor(_, es) | ExprKind::Tuple(es) | ExprKind::Alias(_, es) => {
+                for e in es {
+                    self.check_expr(rcx, e);
+                }
+            }
+            ExprKind::Var(_)
+            | ExprKind::Local(_)
+            | ExprKind::Constant(_)
+            | ExprKind::ConstDefId(_)
+            | ExprKind::GlobalFunc(_)
+            | ExprKind::InternalFunc(_)
+            | ExprKind::KVar(_)
+            | ExprKind::Hole(_) => {}
+        }
+    }
+
+    fn check_expr_assuming(&self, rcx: &mut InferCtxt, assumption: &Expr, expr: &Expr) {
+        let mut rcx = rcx.branch();
+        rcx.assume_pred(assumption);
+        self.check_expr(&mut rcx, expr);
+    }
+
+    /// Returns the callee if it is in the same recursive group as the function being checked.
+    /// Functions in the same group have the same rank.
+    fn recursive_callee(&self, callee: FluxDefId) -> Option<FluxDefId> {
+        if callee.krate() != LOCAL_CRATE {
+            return None;
+        }
+        let info = self.genv.normalized_info(callee);
+        (info.recursive && info.rank == self.info.rank).then_some(callee)
+    }
+}
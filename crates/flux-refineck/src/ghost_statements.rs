@@ -110,7 +110,14 @@ impl GhostStatements {
                     Some(genv.fn_sig(def_id)?)
                 };
 
-            fold_unfold::add_ghost_statements(&mut stmts, genv, body, fn_sig.as_ref())?;
+            let check_unwind = genv.infer_opts(def_id).check_unwind;
+            fold_unfold::add_ghost_statements(
+                &mut stmts,
+                genv,
+                body,
+                fn_sig.as_ref(),
+                check_unwind,
+            )?;
             points_to::add_ghost_statements(&mut stmts, genv, &body.rustc_body, fn_sig.as_ref())?;
             // We only add unblock statements for the main body because borrows in promoted constants
             // have to be live in the main body so they never go out of scope in the promoted body.
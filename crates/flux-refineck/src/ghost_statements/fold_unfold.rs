@@ -30,11 +30,13 @@ pub(crate) fn add_ghost_statements<'tcx>(
     genv: GlobalEnv<'_, 'tcx>,
     body: &Body<'tcx>,
     fn_sig: Option<&rty::EarlyBinder<rty::PolyFnSig>>,
+    check_unwind: bool,
 ) -> QueryResult {
     let mut bb_envs = FxHashMap::default();
-    FoldUnfoldAnalysis::new(genv, body, &mut bb_envs, Infer).run(fn_sig)?;
+    FoldUnfoldAnalysis::new(genv, body, &mut bb_envs, check_unwind, Infer).run(fn_sig)?;
 
-    FoldUnfoldAnalysis::new(genv, body, &mut bb_envs, Elaboration { stmts }).run(fn_sig)
+    FoldUnfoldAnalysis::new(genv, body, &mut bb_envs, check_unwind, Elaboration { stmts })
+        .run(fn_sig)
 }
 
 #[derive(Clone)]
@@ -82,6 +84,8 @@ impl Env {
             let elem = place.projection[i];
             let (n, m) = match elem {
                 PlaceElem::Deref => node.deref(),
+                // A union is never unfolded, its fields are accessed through the union directly
+                PlaceElem::Field(_) if node.is_union() => break,
                 PlaceElem::Field(f) => node.field(genv, f)?,
                 PlaceElem::Downcast(_, idx) => node.downcast(genv, idx)?,
                 PlaceElem::Index(_) | PlaceElem::ConstantIndex { .. } => break,
@@ -125,6 +129,8 @@ struct FoldUnfoldAnalysis<'a, 'genv, 'tcx, M> {
     queue: WorkQueue<'a>,
     discriminants: UnordMap<Place, Place>,
     point: Point,
+    /// Whether to follow unwind edges into cleanup blocks
+    check_unwind: bool,
     mode: M,
 }
 
@@ -206,14 +212,13 @@ impl Mode for Elaboration<'_> {
         match env.projection(analysis.genv, place)? {
             ProjResult::None => {}
             ProjResult::Fold(place_ref) => {
-                tracked_span_assert_eq!(place_ref, place.as_ref());
-                let place = place.clone();
+                tracked_span_assert_eq!(place_ref, unfolded_prefix(analysis, place)?);
                 analysis
                     .mode
-                    .insert_at(analysis.point, GhostStatement::Fold(place));
+                    .insert_at(analysis.point, GhostStatement::Fold(place_ref.to_place()));
             }
             ProjResult::Unfold(place_ref) => {
-                tracked_span_assert_eq!(place_ref, place.as_ref());
+                tracked_span_assert_eq!(place_ref, unfolded_prefix(analysis, place)?);
                 match place_ref.last_projection() {
                     Some((base, PlaceElem::Deref | PlaceElem::Field(..))) => {
                         analysis
@@ -244,6 +249,29 @@ impl Mode for Elaboration<'_> {
     }
 }
 
+/// Returns the prefix of `place` that is folded or unfolded when accessing it, which is the whole
+/// place unless it accesses the field of a union. Unions are never unfolded, so in that case it is
+/// the prefix of `place` up to the (first) union.
+fn unfolded_prefix<'a, M>(
+    analysis: &FoldUnfoldAnalysis<M>,
+    place: &'a Place,
+) -> QueryResult<PlaceRef<'a>> {
+    for (i, elem) in place.projection.iter().enumerate() {
+        let prefix = place.as_ref().truncate(i);
+        if let PlaceElem::Field(_) = elem
+            && let TyKind::Adt(adt_def, _) = prefix
+                .to_place()
+                .ty(analysis.genv, &analysis.body.local_decls)?
+                .ty
+                .kind()
+            && adt_def.is_union()
+        {
+            return Ok(prefix);
+        }
+    }
+    Ok(place.as_ref())
+}
+
 #[derive(Clone)]
 enum PlaceNode {
     Deref(Ty, Box<PlaceNode>),
@@ -335,6 +363,7 @@ impl<M: Mode> FoldUnfoldAnalysis<'_, '_, '_, M> {
                     Rvalue::Repeat(op, _) => {
                         self.operand(op, env)?;
                     }
+                    Rvalue::ThreadLocalRef(..) | Rvalue::NullaryOp(_) => {}
                 }
                 M::projection(self, env, place)?;
             }
@@ -345,6 +374,7 @@ impl<M: Mode> FoldUnfoldAnalysis<'_, '_, '_, M> {
             | StatementKind::FakeRead(_)
             | StatementKind::AscribeUserType(_, _)
             | StatementKind::PlaceMention(_)
+            | StatementKind::StorageDead(_)
             | StatementKind::Nop => {}
         }
         Ok(())
@@ -366,6 +396,7 @@ impl<M: Mode> FoldUnfoldAnalysis<'_, '_, '_, M> {
         mut env: Env,
     ) -> QueryResult<Vec<(Env, BasicBlock)>> {
         let mut successors = vec![];
+        let cleanup = terminator.unwind_cleanup().filter(|_| self.check_unwind);
         match &terminator.kind {
             TerminatorKind::Return => {
                 M::ret(self, &env);
@@ -374,6 +405,9 @@ impl<M: Mode> FoldUnfoldAnalysis<'_, '_, '_, M> {
                 for arg in args {
                     self.operand(arg, &mut env)?;
                 }
+                if let Some(cleanup) = cleanup {
+                    successors.push((env.clone(), cleanup));
+                }
                 M::projection(self, &mut env, destination)?;
                 if let Some(target) = target {
                     successors.push((env, *target));
@@ -430,10 +464,16 @@ impl<M: Mode> FoldUnfoldAnalysis<'_, '_, '_, M> {
             }
             TerminatorKind::Drop { place, target, .. } => {
                 M::projection(self, &mut env, place)?;
+                if let Some(cleanup) = cleanup {
+                    successors.push((env.clone(), cleanup));
+                }
                 successors.push((env, *target));
             }
             TerminatorKind::Assert { cond, target, .. } => {
                 self.operand(cond, &mut env)?;
+                if let Some(cleanup) = cleanup {
+                    successors.push((env.clone(), cleanup));
+                }
                 successors.push((env, *target));
             }
             TerminatorKind::FalseEdge { real_target, .. } => {
@@ -442,9 +482,12 @@ impl<M: Mode> FoldUnfoldAnalysis<'_, '_, '_, M> {
             TerminatorKind::FalseUnwind { real_target, .. } => {
                 successors.push((env, *real_target));
             }
-            TerminatorKind::Unreachable
-            | TerminatorKind::UnwindResume
-            | TerminatorKind::CoroutineDrop => {}
+            TerminatorKind::UnwindResume => {
+                // The caller can observe the state of the locations reachable from the arguments
+                // after catching the panic, so we fold them as if returning.
+                M::ret(self, &env);
+            }
+            TerminatorKind::Unreachable | TerminatorKind::CoroutineDrop => {}
         }
         Ok(successors)
     }
@@ -466,6 +509,7 @@ impl<'a, 'genv, 'tcx, M> FoldUnfoldAnalysis<'a, 'genv, 'tcx, M> {
         genv: GlobalEnv<'genv, 'tcx>,
         body: &'a Body<'tcx>,
         bb_envs: &'a mut FxHashMap<BasicBlock, Env>,
+        check_unwind: bool,
         mode: M,
     ) -> Self {
         Self {
@@ -476,6 +520,7 @@ impl<'a, 'genv, 'tcx, M> FoldUnfoldAnalysis<'a, 'genv, 'tcx, M> {
             point: Point::FunEntry,
             visited: DenseBitSet::new_empty(body.basic_blocks.len()),
             queue: WorkQueue::empty(body.basic_blocks.len(), &body.dominator_order_rank),
+            check_unwind,
             mode,
         }
     }
@@ -812,6 +857,16 @@ impl PlaceNode {
     fn is_ty(&self) -> bool {
         matches!(self, Self::Ty(..))
     }
+
+    fn is_union(&self) -> bool {
+        if let PlaceNode::Ty(ty) = self
+            && let TyKind::Adt(adt_def, _) = ty.kind()
+        {
+            adt_def.is_union()
+        } else {
+            false
+        }
+    }
 }
 
 fn downcast(
@@ -2,7 +2,7 @@ use flux_common::{iter::IterExt, result::ResultExt};
 use flux_config::InferOpts;
 use flux_errors::ErrorGuaranteed;
 use flux_infer::{
-    fixpoint_encoding::FixQueryCache,
+    fixpoint_encoding::FixpointQueue,
     infer::{ConstrReason, GlobalEnvExt, Tag},
 };
 use flux_middle::{
@@ -17,9 +17,9 @@ use rustc_infer::infer::TyCtxtInferExt;
 use rustc_middle::ty::TypingMode;
 use rustc_span::{DUMMY_SP, Span};
 
-pub fn check_invariants(
-    genv: GlobalEnv,
-    cache: &mut FixQueryCache,
+pub fn check_invariants<'genv, 'tcx>(
+    genv: GlobalEnv<'genv, 'tcx>,
+    queue: &mut FixpointQueue<'genv, 'tcx>,
     def_id: MaybeExternId,
     invariants: &[fhir::Expr],
     adt_def: &rty::AdtDef,
@@ -39,13 +39,13 @@ pub fn check_invariants(
         .enumerate()
         .try_for_each_exhaust(|(idx, invariant)| {
             let span = invariants[idx].span;
-            check_invariant(genv, cache, def_id, adt_def, span, invariant, opts)
+            check_invariant(genv, queue, def_id, adt_def, span, invariant, opts)
         })
 }
 
-fn check_invariant(
-    genv: GlobalEnv,
-    cache: &mut FixQueryCache,
+fn check_invariant<'genv, 'tcx>(
+    genv: GlobalEnv<'genv, 'tcx>,
+    queue: &mut FixpointQueue<'genv, 'tcx>,
     def_id: MaybeExternId,
     adt_def: &rty::AdtDef,
     span: Span,
@@ -86,24 +86,24 @@ fn check_invariant(
         let pred = invariant.apply(&variant_sig.idx);
         rcx.check_pred(&pred, Tag::new(ConstrReason::Other, DUMMY_SP));
     }
-    let answer = infcx_root
-        .execute_fixpoint_query(cache, def_id, FixpointQueryKind::Invariant)
-        .emit(&genv)?;
-
-    if answer.errors.is_empty() {
-        Ok(())
-    } else {
-        Err(genv.sess().emit_err(errors::Invalid { span }))
-    }
+    infcx_root.execute_fixpoint_query(queue, def_id, FixpointQueryKind::Invariant, move |answer| {
+        if let Some(resource) = answer.exhausted {
+            Err(crate::report_exhausted(genv, span, resource))
+        } else if answer.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(genv.sess().emit_err(errors::Invalid { span }))
+        }
+    })
 }
 
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::E0997;
     use flux_macros::Diagnostic;
     use rustc_span::Span;
 
     #[derive(Diagnostic)]
-    #[diag(refineck_invalid_invariant, code = E0999)]
+    #[diag(refineck_invalid_invariant, code = E0997)]
     pub struct Invalid {
         #[primary_span]
         pub span: Span,
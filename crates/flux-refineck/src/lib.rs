@@ -21,8 +21,10 @@ extern crate rustc_mir_dataflow;
 extern crate rustc_span;
 extern crate rustc_type_ir;
 
+mod call_graph;
 mod checker;
 pub mod compare_impl_item;
+pub mod defns;
 mod ghost_statements;
 pub mod invariants;
 mod primops;
@@ -32,9 +34,10 @@ mod type_env;
 use checker::{Checker, trait_impl_subtyping};
 use flux_common::{dbg, dbg::SpanTrace, result::ResultExt as _};
 use flux_config as config;
+use flux_errors::{E0989, E0990, E0991, E0992, E0994};
 use flux_infer::{
-    fixpoint_encoding::{FixQueryCache, SolutionTrace},
-    infer::{ConstrReason, SubtypeReason, Tag},
+    fixpoint_encoding::{Answer, FixpointQueue, Resource, SolutionTrace},
+    infer::{AssertReason, ConstrReason, SubtypeReason, Tag},
 };
 use flux_macros::fluent_messages;
 use flux_middle::{
@@ -42,10 +45,11 @@ use flux_middle::{
     def_id::MaybeExternId,
     global_env::GlobalEnv,
     metrics::{self, Metric, TimingKind},
+    report,
     rty::{self, ESpan},
 };
 use rustc_data_structures::unord::UnordMap;
-use rustc_errors::ErrorGuaranteed;
+use rustc_errors::{Diag, ErrorGuaranteed};
 use rustc_hir::def_id::LocalDefId;
 use rustc_span::Span;
 
@@ -56,19 +60,35 @@ fluent_messages! { "../locales/en-US.ftl" }
 fn report_fixpoint_errors(
     genv: GlobalEnv,
     local_id: LocalDefId,
-    errors: Vec<Tag>,
+    answer: Answer<Tag>,
 ) -> Result<(), ErrorGuaranteed> {
-    #[expect(clippy::collapsible_else_if, reason = "it looks better")]
-    if genv.should_fail(local_id) {
-        if errors.is_empty() { report_expected_neg(genv, local_id) } else { Ok(()) }
+    let result = if let Some(resource) = answer.exhausted {
+        let span = genv.tcx().def_span(local_id);
+        let reason = match resource {
+            Resource::Time(_) => "timeout",
+            Resource::Memory(_) => "out_of_memory",
+        };
+        report::record_error(local_id, reason, span);
+        Err(report_exhausted(genv, span, resource))
+    } else if genv.should_fail(local_id) {
+        if answer.errors.is_empty() { report_expected_neg(genv, local_id) } else { Ok(()) }
     } else {
-        if errors.is_empty() { Ok(()) } else { report_errors(genv, errors) }
+        for err in &answer.errors {
+            report::record_error(local_id, err.reason.name(), err.src_span);
+        }
+        if answer.errors.is_empty() { Ok(()) } else { report_errors(genv, answer) }
+    };
+    // The answer may be processed after the driver recorded the status of the function if fixpoint
+    // queries are run in parallel.
+    if result.is_err() {
+        report::record_status(local_id, report::Status::Failed);
     }
+    result
 }
 
-fn check_body(
-    genv: GlobalEnv,
-    cache: &mut FixQueryCache,
+fn check_body<'genv, 'tcx>(
+    genv: GlobalEnv<'genv, 'tcx>,
+    queue: &mut FixpointQueue<'genv, 'tcx>,
     def_id: LocalDefId,
     poly_sig: &rty::PolyFnSig,
 ) -> Result<(), ErrorGuaranteed> {
@@ -102,26 +122,28 @@ fn check_body(
         || flux_config::lean().is_emit()
     {
         infcx_root
-            .execute_lean_query(cache, MaybeExternId::Local(def_id))
+            .execute_lean_query(&mut queue.cache, MaybeExternId::Local(def_id))
             .emit(&genv)
     } else {
-        let answer = infcx_root
-            .execute_fixpoint_query(cache, MaybeExternId::Local(def_id), FixpointQueryKind::Body)
-            .emit(&genv)?;
-
-        let tcx = genv.tcx();
-        let hir_id = tcx.local_def_id_to_hir_id(def_id);
-        let body_span = tcx.hir_span_with_body(hir_id);
-        dbg::solution!(genv, &answer, body_span);
-
-        let errors = answer.errors;
-        report_fixpoint_errors(genv, def_id, errors)
+        infcx_root.execute_fixpoint_query(
+            queue,
+            MaybeExternId::Local(def_id),
+            FixpointQueryKind::Body,
+            move |answer| {
+                let tcx = genv.tcx();
+                let hir_id = tcx.local_def_id_to_hir_id(def_id);
+                let body_span = tcx.hir_span_with_body(hir_id);
+                dbg::solution!(genv, &answer, body_span);
+
+                report_fixpoint_errors(genv, def_id, answer)
+            },
+        )
     }
 }
 
-pub fn check_static(
-    genv: GlobalEnv,
-    cache: &mut FixQueryCache,
+pub fn check_static<'genv, 'tcx>(
+    genv: GlobalEnv<'genv, 'tcx>,
+    queue: &mut FixpointQueue<'genv, 'tcx>,
     def_id: LocalDefId,
     ty: rty::Ty,
 ) -> Result<(), ErrorGuaranteed> {
@@ -134,17 +156,18 @@ pub fn check_static(
         rty::List::empty(),
         output,
         rty::Expr::ff(),
+        rty::List::empty(),
         false,
     );
     let poly_sig = rty::PolyFnSig::dummy(fn_sig);
 
     metrics::incr_metric(Metric::FnChecked, 1);
-    metrics::time_it(TimingKind::CheckBody(def_id), || check_body(genv, cache, def_id, &poly_sig))
+    metrics::time_it(TimingKind::CheckBody(def_id), || check_body(genv, queue, def_id, &poly_sig))
 }
 
-pub fn check_fn(
-    genv: GlobalEnv,
-    cache: &mut FixQueryCache,
+pub fn check_fn<'genv, 'tcx>(
+    genv: GlobalEnv<'genv, 'tcx>,
+    queue: &mut FixpointQueue<'genv, 'tcx>,
     def_id: LocalDefId,
 ) -> Result<(), ErrorGuaranteed> {
     let span = genv.tcx().def_span(def_id);
@@ -155,6 +178,7 @@ pub fn check_fn(
     // manually trusted or ignored.
     if !genv.tcx().def_span(def_id).ctxt().is_root() {
         metrics::incr_metric(Metric::FnTrusted, 1);
+        report::record_status(def_id, report::Status::Trusted);
         return Ok(());
     }
 
@@ -166,17 +190,19 @@ pub fn check_fn(
         .map_err(|err| err.emit(genv, def_id))?
     {
         tracing::info!("check_fn::refine-subtyping");
-        let answer = infcx_root
-            .execute_fixpoint_query(cache, MaybeExternId::Local(def_id), FixpointQueryKind::Impl)
-            .emit(&genv)?;
+        infcx_root.execute_fixpoint_query(
+            queue,
+            MaybeExternId::Local(def_id),
+            FixpointQueryKind::Impl,
+            move |answer| report_fixpoint_errors(genv, def_id, answer),
+        )?;
         tracing::info!("check_fn::fixpoint-subtyping");
-        let errors = answer.errors;
-        report_fixpoint_errors(genv, def_id, errors)?;
     }
 
     // Skip trusted functions
     if genv.trusted(def_id) {
         metrics::incr_metric(Metric::FnTrusted, 1);
+        report::record_status(def_id, report::Status::Trusted);
         return Ok(());
     }
 
@@ -189,57 +215,96 @@ pub fn check_fn(
             .instantiate_identity();
         let poly_sig = rty::auto_strong(genv, def_id, poly_sig);
 
-        check_body(genv, cache, def_id, &poly_sig)
+        check_body(genv, queue, def_id, &poly_sig)
     })?;
 
     dbg::check_fn_span!(genv.tcx(), def_id).in_scope(|| Ok(()))
 }
 
-fn call_error(genv: GlobalEnv, span: Span, dst_span: Option<ESpan>) -> ErrorGuaranteed {
+fn call_error<'a>(genv: GlobalEnv<'a, '_>, span: Span, dst_span: Option<ESpan>) -> Diag<'a> {
     genv.sess()
-        .emit_err(errors::RefineError::call(span, dst_span))
+        .create_err(errors::RefineError::call(span, dst_span))
+        .with_code(E0990)
 }
 
-fn ret_error(genv: GlobalEnv, span: Span, dst_span: Option<ESpan>) -> ErrorGuaranteed {
+fn ret_error<'a>(genv: GlobalEnv<'a, '_>, span: Span, dst_span: Option<ESpan>) -> Diag<'a> {
     genv.sess()
-        .emit_err(errors::RefineError::ret(span, dst_span))
+        .create_err(errors::RefineError::ret(span, dst_span))
+        .with_code(E0991)
 }
 
-fn report_errors(genv: GlobalEnv, errors: Vec<Tag>) -> Result<(), ErrorGuaranteed> {
+fn report_errors(genv: GlobalEnv, answer: Answer<Tag>) -> Result<(), ErrorGuaranteed> {
     let mut e = None;
-    for err in errors {
+    for err in answer.errors {
         let span = err.src_span;
-        e = Some(match err.reason {
+        let mut diag = match err.reason {
             ConstrReason::Call
             | ConstrReason::Subtype(SubtypeReason::Input)
             | ConstrReason::Subtype(SubtypeReason::Requires)
             | ConstrReason::Predicate => call_error(genv, span, err.dst_span),
-            ConstrReason::Assign => genv.sess().emit_err(errors::AssignError { span }),
+            ConstrReason::Assign => genv.sess().create_err(errors::AssignError { span }),
             ConstrReason::Ret
             | ConstrReason::Subtype(SubtypeReason::Output)
             | ConstrReason::Subtype(SubtypeReason::Ensures) => ret_error(genv, span, err.dst_span),
-            ConstrReason::Div => genv.sess().emit_err(errors::DivError { span }),
-            ConstrReason::Rem => genv.sess().emit_err(errors::RemError { span }),
-            ConstrReason::Goto(_) => genv.sess().emit_err(errors::GotoError { span }),
-            ConstrReason::Assert(msg) => genv.sess().emit_err(errors::AssertError { span, msg }),
+            ConstrReason::Div => genv.sess().create_err(errors::DivError { span }),
+            ConstrReason::Rem => genv.sess().create_err(errors::RemError { span }),
+            ConstrReason::Goto(_) => genv.sess().create_err(errors::GotoError { span }),
+            ConstrReason::Assert(reason) => {
+                let code = match reason {
+                    AssertReason::DivisionByZero | AssertReason::RemainderByZero => E0994,
+                    AssertReason::DivisionOverflow | AssertReason::RemainderOverflow => E0992,
+                    AssertReason::BoundsCheck => E0989,
+                };
+                genv.sess()
+                    .create_err(errors::AssertError { span, msg: reason.msg() })
+                    .with_code(code)
+            }
             ConstrReason::Fold | ConstrReason::FoldLocal => {
-                genv.sess().emit_err(errors::FoldError { span })
+                genv.sess().create_err(errors::FoldError { span })
+            }
+            ConstrReason::Overflow => genv.sess().create_err(errors::OverflowError { span }),
+            ConstrReason::Underflow => genv.sess().create_err(errors::UnderflowError { span }),
+            ConstrReason::Termination => genv.sess().create_err(errors::TerminationError { span }),
+            ConstrReason::LoopVariant => {
+                genv.sess()
+                    .create_err(errors::LoopTerminationError { span })
             }
-            ConstrReason::Overflow => genv.sess().emit_err(errors::OverflowError { span }),
-            ConstrReason::Underflow => genv.sess().emit_err(errors::UnderflowError { span }),
-            ConstrReason::Other => genv.sess().emit_err(errors::UnknownError { span }),
+            ConstrReason::LoopInvariant => {
+                genv.sess().create_err(errors::LoopInvariantError { span })
+            }
+            ConstrReason::UnionField => genv.sess().create_err(errors::UnionFieldError { span }),
+            ConstrReason::Pattern => genv.sess().create_err(errors::PatternError { span }),
+            ConstrReason::RawDeref => genv.sess().create_err(errors::RawDerefError { span }),
+            ConstrReason::PtrOffset => genv.sess().create_err(errors::PtrOffsetError { span }),
+            ConstrReason::Other => genv.sess().create_err(errors::UnknownError { span }),
             ConstrReason::NoPanic(callee) => {
-                genv.sess().emit_err(errors::PanicError {
+                genv.sess().create_err(errors::PanicError {
                     span,
                     callee: genv.tcx().def_path_debug_str(callee),
                 })
             }
-        });
+        };
+        if let Some(cex) = answer.counterexamples.get(&err) {
+            if cex.is_possibly_spurious() {
+                diag.subdiagnostic(errors::SpuriousCounterexampleNote { model: cex.to_string() });
+            } else {
+                diag.subdiagnostic(errors::CounterexampleNote { model: cex.to_string() });
+            }
+        }
+        e = Some(diag.emit());
     }
 
     if let Some(e) = e { Err(e) } else { Ok(()) }
 }
 
+/// Reports that fixpoint was stopped before answering the query for the item at `span`
+fn report_exhausted(genv: GlobalEnv, span: Span, resource: Resource) -> ErrorGuaranteed {
+    match resource {
+        Resource::Time(secs) => genv.sess().emit_err(errors::TimeoutError { span, secs }),
+        Resource::Memory(mb) => genv.sess().emit_err(errors::OutOfMemoryError { span, mb }),
+    }
+}
+
 fn report_expected_neg(genv: GlobalEnv, def_id: LocalDefId) -> Result<(), ErrorGuaranteed> {
     Err(genv.sess().emit_err(errors::ExpectedNeg {
         span: genv.tcx().def_span(def_id),
@@ -248,10 +313,12 @@ fn report_expected_neg(genv: GlobalEnv, def_id: LocalDefId) -> Result<(), ErrorG
 }
 
 mod errors {
-    use flux_errors::E0999;
+    use flux_errors::{
+        E0984, E0985, E0986, E0987, E0988, E0992, E0993, E0994, E0995, E0996, E0997, E0999,
+    };
     use flux_macros::{Diagnostic, Subdiagnostic};
     use flux_middle::rty::ESpan;
-    use rustc_span::Span;
+    use rustc_span::{Span, Symbol};
 
     #[derive(Diagnostic)]
     #[diag(refineck_goto_error, code = E0999)]
@@ -281,8 +348,20 @@ mod errors {
         pub span: Span,
     }
 
+    #[derive(Subdiagnostic)]
+    #[note(refineck_counterexample_note)]
+    pub(crate) struct CounterexampleNote {
+        pub model: String,
+    }
+
+    #[derive(Subdiagnostic)]
+    #[note(refineck_spurious_counterexample_note)]
+    pub(crate) struct SpuriousCounterexampleNote {
+        pub model: String,
+    }
+
     #[derive(Diagnostic)]
-    #[diag(refineck_refine_error, code = E0999)]
+    #[diag(refineck_refine_error)]
     pub struct RefineError {
         #[primary_span]
         #[label]
@@ -316,21 +395,21 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_div_error, code = E0999)]
+    #[diag(refineck_div_error, code = E0994)]
     pub struct DivError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_rem_error, code = E0999)]
+    #[diag(refineck_rem_error, code = E0994)]
     pub struct RemError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_assert_error, code = E0999)]
+    #[diag(refineck_assert_error)]
     pub struct AssertError {
         #[primary_span]
         pub span: Span,
@@ -338,21 +417,21 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_fold_error, code = E0999)]
+    #[diag(refineck_fold_error, code = E0995)]
     pub struct FoldError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_overflow_error, code = E0999)]
+    #[diag(refineck_overflow_error, code = E0992)]
     pub struct OverflowError {
         #[primary_span]
         pub span: Span,
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_underflow_error, code = E0999)]
+    #[diag(refineck_underflow_error, code = E0993)]
     pub struct UnderflowError {
         #[primary_span]
         pub span: Span,
@@ -374,7 +453,85 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(refineck_panic_error, code = E0999)]
+    #[diag(refineck_timeout_error, code = E0984)]
+    #[note]
+    pub(super) struct TimeoutError {
+        #[primary_span]
+        pub(super) span: Span,
+        pub(super) secs: u64,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_out_of_memory_error, code = E0984)]
+    #[note]
+    pub(super) struct OutOfMemoryError {
+        #[primary_span]
+        pub(super) span: Span,
+        pub(super) mb: u64,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_termination_error, code = E0988)]
+    #[note]
+    pub(super) struct TerminationError {
+        #[primary_span]
+        pub(super) span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_loop_termination_error, code = E0988)]
+    #[note]
+    pub(super) struct LoopTerminationError {
+        #[primary_span]
+        pub(super) span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_loop_invariant_error, code = E0997)]
+    pub(super) struct LoopInvariantError {
+        #[primary_span]
+        pub(super) span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_union_field_error, code = E0987)]
+    pub(super) struct UnionFieldError {
+        #[primary_span]
+        pub(super) span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_pattern_error, code = E0986)]
+    pub(super) struct PatternError {
+        #[primary_span]
+        pub(super) span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_raw_deref_error, code = E0985)]
+    pub(super) struct RawDerefError {
+        #[primary_span]
+        pub(super) span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_ptr_offset_error, code = E0985)]
+    pub(super) struct PtrOffsetError {
+        #[primary_span]
+        pub(super) span: Span,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_loop_invariant_local, code = E0999)]
+    pub(crate) struct LoopInvariantLocal {
+        #[primary_span]
+        #[label]
+        pub(crate) span: Span,
+        pub(crate) name: Symbol,
+    }
+
+    #[derive(Diagnostic)]
+    #[diag(refineck_panic_error, code = E0996)]
     pub(super) struct PanicError {
         #[primary_span]
         pub(super) span: Span,
@@ -29,13 +29,19 @@ use flux_common::tracked_span_bug;
 use flux_config::OverflowMode;
 use flux_infer::infer::ConstrReason;
 use flux_macros::primop_rules;
-use flux_middle::rty::{self, BaseTy, Expr, Sort};
+use flux_middle::{
+    global_env::GlobalEnv,
+    queries::QueryResult,
+    rty::{self, BaseTy, BvSize, Expr, Sort, SpecFuncKind},
+};
 use flux_rustc_bridge::mir;
+use liquid_fixpoint::ThyFunc;
 use rty::{
     BinOp::{BitAnd, BitOr, BitShl, BitShr, BitXor, Mod},
     Expr as E,
 };
 use rustc_data_structures::unord::UnordMap;
+use rustc_hir::LangItem;
 
 #[derive(Debug)]
 pub(crate) struct MatchedRule {
@@ -49,6 +55,7 @@ pub(crate) struct Pre {
     pub pred: Expr,
 }
 
+#[expect(clippy::too_many_arguments)]
 pub(crate) fn match_bin_op(
     op: mir::BinOp,
     bty1: &BaseTy,
@@ -56,14 +63,28 @@ pub(crate) fn match_bin_op(
     bty2: &BaseTy,
     idx2: &Expr,
     overflow_mode: OverflowMode,
+    uint_as_bitvec: bool,
+    check_raw_deref: bool,
 ) -> MatchedRule {
+    let inputs = [(bty1.clone(), idx1.clone()), (bty2.clone(), idx2.clone())];
+    if check_raw_deref && let Some(rule) = CHECKED_PTR_BIN_OPS.try_match_inputs(&op, &inputs) {
+        return rule;
+    }
+    if uint_as_bitvec && let Some(rule) = BITVEC_BIN_OPS.try_match_inputs(&op, &inputs) {
+        return rule;
+    }
+    if flux_config::real_floats()
+        && let Some(rule) = REAL_FLOAT_BIN_OPS.try_match_inputs(&op, &inputs)
+    {
+        return rule;
+    }
     let table = match overflow_mode {
         OverflowMode::Strict => &OVERFLOW_STRICT_BIN_OPS,
         OverflowMode::Lazy => &OVERFLOW_LAZY_BIN_OPS,
         OverflowMode::None => &OVERFLOW_NONE_BIN_OPS,
         OverflowMode::StrictUnder => &OVERFLOW_STRICT_UNDER_BIN_OPS,
     };
-    table.match_inputs(&op, [(bty1.clone(), idx1.clone()), (bty2.clone(), idx2.clone())])
+    table.match_inputs(&op, inputs)
 }
 
 pub(crate) fn match_un_op(
@@ -71,13 +92,23 @@ pub(crate) fn match_un_op(
     bty: &BaseTy,
     idx: &Expr,
     overflow_mode: OverflowMode,
+    uint_as_bitvec: bool,
 ) -> MatchedRule {
+    let inputs = [(bty.clone(), idx.clone())];
+    if uint_as_bitvec && let Some(rule) = BITVEC_UN_OPS.try_match_inputs(&op, &inputs) {
+        return rule;
+    }
+    if flux_config::real_floats()
+        && let Some(rule) = REAL_FLOAT_UN_OPS.try_match_inputs(&op, &inputs)
+    {
+        return rule;
+    }
     let table = match overflow_mode {
         OverflowMode::Strict => &OVERFLOW_STRICT_UN_OPS,
         OverflowMode::None => &OVERFLOW_NONE_UN_OPS,
         OverflowMode::Lazy | OverflowMode::StrictUnder => &OVERFLOW_LAZY_UN_OPS,
     };
-    table.match_inputs(&op, [(bty.clone(), idx.clone())])
+    table.match_inputs(&op, inputs)
 }
 
 struct RuleTable<Op: Eq + Hash, const N: usize> {
@@ -89,6 +120,10 @@ impl<Op: Eq + Hash + fmt::Debug, const N: usize> RuleTable<Op, N> {
         (self.rules[op])(&inputs)
             .unwrap_or_else(|| tracked_span_bug!("no primop rule for {op:?} using {inputs:?}"))
     }
+
+    fn try_match_inputs(&self, op: &Op, inputs: &[(BaseTy, Expr); N]) -> Option<MatchedRule> {
+        (self.rules.get(op)?)(inputs)
+    }
 }
 
 type RuleMatcher<const N: usize> = fn(&[(BaseTy, Expr); N]) -> Option<MatchedRule>;
@@ -117,6 +152,18 @@ static OVERFLOW_NONE_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyLock::new
             // Shifts
             (Shl, mk_shl_rules()),
             (Shr, mk_shr_rules()),
+            // Unchecked
+            (AddUnchecked, mk_add_unchecked_rules()),
+            (SubUnchecked, mk_sub_unchecked_rules()),
+            (MulUnchecked, mk_mul_unchecked_rules()),
+            (ShlUnchecked, mk_shl_unchecked_rules()),
+            (ShrUnchecked, mk_shr_unchecked_rules()),
+            // With overflow
+            (AddWithOverflow, mk_add_with_overflow_rules()),
+            (SubWithOverflow, mk_sub_with_overflow_rules()),
+            (MulWithOverflow, mk_mul_with_overflow_rules()),
+            // Pointers
+            (Offset, mk_offset_rules()),
         ]
         .into_iter()
         .collect(),
@@ -147,6 +194,18 @@ static OVERFLOW_STRICT_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyLock::n
             // Shifts
             (Shl, mk_shl_rules()),
             (Shr, mk_shr_rules()),
+            // Unchecked
+            (AddUnchecked, mk_add_unchecked_rules()),
+            (SubUnchecked, mk_sub_unchecked_rules()),
+            (MulUnchecked, mk_mul_unchecked_rules()),
+            (ShlUnchecked, mk_shl_unchecked_rules()),
+            (ShrUnchecked, mk_shr_unchecked_rules()),
+            // With overflow
+            (AddWithOverflow, mk_add_with_overflow_rules()),
+            (SubWithOverflow, mk_sub_with_overflow_rules()),
+            (MulWithOverflow, mk_mul_with_overflow_rules()),
+            // Pointers
+            (Offset, mk_offset_rules()),
         ]
         .into_iter()
         .collect(),
@@ -177,6 +236,18 @@ static OVERFLOW_LAZY_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyLock::new
             // Shifts
             (Shl, mk_shl_rules()),
             (Shr, mk_shr_rules()),
+            // Unchecked
+            (AddUnchecked, mk_add_unchecked_rules()),
+            (SubUnchecked, mk_sub_unchecked_rules()),
+            (MulUnchecked, mk_mul_unchecked_rules()),
+            (ShlUnchecked, mk_shl_unchecked_rules()),
+            (ShrUnchecked, mk_shr_unchecked_rules()),
+            // With overflow
+            (AddWithOverflow, mk_add_with_overflow_rules()),
+            (SubWithOverflow, mk_sub_with_overflow_rules()),
+            (MulWithOverflow, mk_mul_with_overflow_rules()),
+            // Pointers
+            (Offset, mk_offset_rules()),
         ]
         .into_iter()
         .collect(),
@@ -207,6 +278,18 @@ static OVERFLOW_STRICT_UNDER_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyL
             // Shifts
             (Shl, mk_shl_rules()),
             (Shr, mk_shr_rules()),
+            // Unchecked
+            (AddUnchecked, mk_add_unchecked_rules()),
+            (SubUnchecked, mk_sub_unchecked_rules()),
+            (MulUnchecked, mk_mul_unchecked_rules()),
+            (ShlUnchecked, mk_shl_unchecked_rules()),
+            (ShrUnchecked, mk_shr_unchecked_rules()),
+            // With overflow
+            (AddWithOverflow, mk_add_with_overflow_rules()),
+            (SubWithOverflow, mk_sub_with_overflow_rules()),
+            (MulWithOverflow, mk_mul_with_overflow_rules()),
+            // Pointers
+            (Offset, mk_offset_rules()),
         ]
         .into_iter()
         .collect(),
@@ -240,6 +323,64 @@ static OVERFLOW_STRICT_UN_OPS: LazyLock<RuleTable<mir::UnOp, 1>> = LazyLock::new
     }
 });
 
+/// Rules used instead of the ones above when a function opts into `uint_as_bitvec`. Bitwise
+/// operations on unsigned integers are then interpreted in the theory of bit-vectors of the
+/// integer's width, and the result is converted back to an `int` index.
+static BITVEC_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyLock::new(|| {
+    use mir::BinOp::*;
+    RuleTable {
+        rules: [
+            (BitAnd, mk_bv_bit_and_rules()),
+            (BitOr, mk_bv_bit_or_rules()),
+            (BitXor, mk_bv_bit_xor_rules()),
+            (Shl, mk_bv_shl_rules()),
+            (Shr, mk_bv_shr_rules()),
+        ]
+        .into_iter()
+        .collect(),
+    }
+});
+
+static BITVEC_UN_OPS: LazyLock<RuleTable<mir::UnOp, 1>> = LazyLock::new(|| {
+    use mir::UnOp::*;
+    RuleTable { rules: [(Not, mk_bv_not_rules())].into_iter().collect() }
+});
+
+/// Rules used instead of the ones above when raw pointer dereferences are checked
+/// (`allow_raw_deref = "checked"`). Offsetting a pointer must then keep it in bounds.
+static CHECKED_PTR_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyLock::new(|| {
+    use mir::BinOp::*;
+    RuleTable { rules: [(Offset, mk_checked_offset_rules())].into_iter().collect() }
+});
+
+/// Rules used instead of the ones above when floats are indexed by reals (`-Freal-floats`).
+/// Arithmetic and comparisons on floats are then interpreted on real numbers, ignoring rounding,
+/// infinities and `NaN`.
+static REAL_FLOAT_BIN_OPS: LazyLock<RuleTable<mir::BinOp, 2>> = LazyLock::new(|| {
+    use mir::BinOp::*;
+    RuleTable {
+        rules: [
+            (Add, mk_real_add_rules()),
+            (Sub, mk_real_sub_rules()),
+            (Mul, mk_real_mul_rules()),
+            (Div, mk_real_div_rules()),
+            (Eq, mk_real_eq_rules()),
+            (Ne, mk_real_ne_rules()),
+            (Le, mk_real_le_rules()),
+            (Ge, mk_real_ge_rules()),
+            (Lt, mk_real_lt_rules()),
+            (Gt, mk_real_gt_rules()),
+        ]
+        .into_iter()
+        .collect(),
+    }
+});
+
+static REAL_FLOAT_UN_OPS: LazyLock<RuleTable<mir::UnOp, 1>> = LazyLock::new(|| {
+    use mir::UnOp::*;
+    RuleTable { rules: [(Neg, mk_real_neg_rules())].into_iter().collect() }
+});
+
 fn valid_int(e: impl Into<Expr>, int_ty: rty::IntTy) -> rty::Expr {
     let e1 = e.into();
     let e2 = e1.clone();
@@ -540,6 +681,217 @@ fn mk_shr_rules() -> RuleMatcher<2> {
     }
 }
 
+/// `a + b` where overflow is undefined behavior (e.g., `unchecked_add`)
+fn mk_add_unchecked_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T[a + b]
+        requires valid_int(a + b, int_ty) => ConstrReason::Overflow
+        if let &BaseTy::Int(int_ty) = T
+
+        fn(a: T, b: T) -> T[a + b]
+        requires valid_uint(a + b, uint_ty) => ConstrReason::Overflow
+        if let &BaseTy::Uint(uint_ty) = T
+    }
+}
+
+/// `a - b` where overflow is undefined behavior (e.g., `unchecked_sub`)
+fn mk_sub_unchecked_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T[a - b]
+        requires valid_int(a - b, int_ty) => ConstrReason::Overflow
+        if let &BaseTy::Int(int_ty) = T
+
+        fn(a: T, b: T) -> T[a - b]
+        requires valid_uint(a - b, uint_ty) => ConstrReason::Underflow
+        if let &BaseTy::Uint(uint_ty) = T
+    }
+}
+
+/// `a * b` where overflow is undefined behavior (e.g., `unchecked_mul`)
+fn mk_mul_unchecked_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T[a * b]
+        requires valid_int(a * b, int_ty) => ConstrReason::Overflow
+        if let &BaseTy::Int(int_ty) = T
+
+        fn(a: T, b: T) -> T[a * b]
+        requires valid_uint(a * b, uint_ty) => ConstrReason::Overflow
+        if let &BaseTy::Uint(uint_ty) = T
+    }
+}
+
+/// `a << b` where shifting by `b >= bits(T)` is undefined behavior (e.g., `unchecked_shl`)
+fn mk_shl_unchecked_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: S) -> { T[E::prim_val(BitShl(Sort::Int), a, b)] | E::prim_rel(BitShl(Sort::Int), a, b) }
+        requires valid_shift(b, bits) => ConstrReason::Overflow
+        if let Some(bits) = bit_width(T)
+        if S.is_integral()
+    }
+}
+
+/// `a >> b` where shifting by `b >= bits(T)` is undefined behavior (e.g., `unchecked_shr`)
+fn mk_shr_unchecked_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: S) -> { T[E::prim_val(BitShr(Sort::Int), a, b)] | E::prim_rel(BitShr(Sort::Int), a, b) }
+        requires valid_shift(b, bits) => ConstrReason::Overflow
+        if let Some(bits) = bit_width(T)
+        if S.is_integral()
+    }
+}
+
+/// `a + b` returning the wrapped result and whether it overflowed (e.g., `overflowing_add`)
+fn mk_add_with_overflow_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> (T{v: E::implies(valid_int(a + b, int_ty), E::eq(v, a + b))}, bool[valid_int(a + b, int_ty).not()])
+        if let &BaseTy::Int(int_ty) = T
+
+        fn(a: T, b: T) -> (T{v: E::implies(valid_uint(a + b, uint_ty), E::eq(v, a + b))}, bool[valid_uint(a + b, uint_ty).not()])
+        if let &BaseTy::Uint(uint_ty) = T
+    }
+}
+
+/// `a - b` returning the wrapped result and whether it overflowed (e.g., `overflowing_sub`)
+fn mk_sub_with_overflow_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> (T{v: E::implies(valid_int(a - b, int_ty), E::eq(v, a - b))}, bool[valid_int(a - b, int_ty).not()])
+        if let &BaseTy::Int(int_ty) = T
+
+        fn(a: T, b: T) -> (T{v: E::implies(valid_uint(a - b, uint_ty), E::eq(v, a - b))}, bool[valid_uint(a - b, uint_ty).not()])
+        if let &BaseTy::Uint(uint_ty) = T
+    }
+}
+
+/// `a * b` returning the wrapped result and whether it overflowed (e.g., `overflowing_mul`)
+fn mk_mul_with_overflow_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> (T{v: E::implies(valid_int(a * b, int_ty), E::eq(v, a * b))}, bool[valid_int(a * b, int_ty).not()])
+        if let &BaseTy::Int(int_ty) = T
+
+        fn(a: T, b: T) -> (T{v: E::implies(valid_uint(a * b, uint_ty), E::eq(v, a * b))}, bool[valid_uint(a * b, uint_ty).not()])
+        if let &BaseTy::Uint(uint_ty) = T
+    }
+}
+
+/// `a.offset(b)` on raw pointers
+fn mk_offset_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: S) -> T{v: offset_ptr(v, a, b)}
+        if matches!(T, BaseTy::RawPtr(..)) && S.is_integral()
+    }
+}
+
+/// `a.offset(b)` on raw pointers when raw pointer dereferences are checked. The result must stay
+/// in bounds of the allocation (or one past its end), so `b` can be at most the number of elements
+/// accessible through `a` and, if negative, at most the distance from the start of the allocation.
+fn mk_checked_offset_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: S) -> T{v: offset_ptr(v, a, b)}
+        requires ptr_offset_in_bounds(a, b) => ConstrReason::PtrOffset
+        if matches!(T, BaseTy::RawPtr(..)) && S.is_integral()
+    }
+}
+
+/// Moving the pointer `a` by `b` elements stays within (or one past the end of) its allocation
+pub(crate) fn ptr_offset_in_bounds(a: impl Into<Expr>, b: impl Into<Expr>) -> Expr {
+    let (a, b) = (a.into(), b.into());
+    E::and(E::ge(E::ptr_size(&a) - &b, 0), E::ge(E::ptr_offset(&a) + &b, 0))
+}
+
+/// `v` is the pointer `a` moved by `b` elements
+fn offset_ptr(v: impl Into<Expr>, a: impl Into<Expr>, b: impl Into<Expr>) -> Expr {
+    let (v, a, b) = (v.into(), a.into(), b.into());
+    E::and(
+        E::eq(E::ptr_size(&v), E::ptr_size(&a) - &b),
+        E::eq(E::ptr_offset(&v), E::ptr_offset(&a) + &b),
+    )
+}
+
+/// `a <=> b`, i.e., `Ord::cmp` on primitive types. The output is an [`Ordering`] which we index
+/// with `-1`, `0` or `1` when its extern spec (in `flux-core`) refines it by an `int`.
+///
+/// [`Ordering`]: std::cmp::Ordering
+pub(crate) fn match_cmp(
+    genv: GlobalEnv,
+    bty1: &BaseTy,
+    idx1: &Expr,
+    bty2: &BaseTy,
+    idx2: &Expr,
+) -> QueryResult<MatchedRule> {
+    let Some(did) = genv.tcx().lang_items().get(LangItem::OrderingEnum) else {
+        tracked_span_bug!("missing lang item for `Ordering`")
+    };
+    let adt_def = genv.adt_def(did)?;
+    let bty = BaseTy::adt(adt_def.clone(), rty::List::empty());
+    let is_refined_by_int = adt_def
+        .sort_def()
+        .opt_struct_variant()
+        .is_some_and(|variant| variant.field_sorts(&[])[..] == [Sort::Int]);
+    let output_type = if bty1 == bty2 && bty1.is_integral() && is_refined_by_int {
+        let (a, b) = (idx1.clone(), idx2.clone());
+        let val = E::ite(E::lt(a.clone(), b.clone()), -1, E::ite(E::eq(a, b), 0, 1));
+        rty::Ty::indexed(bty, E::ctor_struct(did, rty::List::from_arr([val])))
+    } else {
+        bty.to_ty()
+    };
+    Ok(MatchedRule { precondition: None, output_type })
+}
+
+fn bit_width(bty: &BaseTy) -> Option<u64> {
+    let bit_width = match bty {
+        BaseTy::Int(int_ty) => int_ty.bit_width(),
+        BaseTy::Uint(uint_ty) => uint_ty.bit_width(),
+        _ => return None,
+    };
+    Some(bit_width.unwrap_or(flux_config::pointer_width().bits()))
+}
+
+fn uint_bit_width(bty: &BaseTy) -> Option<u32> {
+    if let BaseTy::Uint(_) = bty { bit_width(bty).map(|bits| bits as u32) } else { None }
+}
+
+fn bv_conv(bits: u32, to_bv: bool) -> Expr {
+    let itf = match (bits, to_bv) {
+        (8, true) => ThyFunc::IntToBv8,
+        (8, false) => ThyFunc::Bv8ToInt,
+        (16, true) => ThyFunc::IntToBv16,
+        (16, false) => ThyFunc::Bv16ToInt,
+        (32, true) => ThyFunc::IntToBv32,
+        (32, false) => ThyFunc::Bv32ToInt,
+        (64, true) => ThyFunc::IntToBv64,
+        (64, false) => ThyFunc::Bv64ToInt,
+        (128, true) => ThyFunc::IntToBv128,
+        (128, false) => ThyFunc::Bv128ToInt,
+        _ => tracked_span_bug!("unsupported bit-vector width `{bits}`"),
+    };
+    E::global_func(SpecFuncKind::Thy(itf))
+}
+
+fn int_to_bv(bits: u32, e: impl Into<Expr>) -> Expr {
+    E::app(bv_conv(bits, true), rty::List::empty(), rty::List::from_arr([e.into()]))
+}
+
+fn bv_to_int(bits: u32, e: impl Into<Expr>) -> Expr {
+    E::app(bv_conv(bits, false), rty::List::empty(), rty::List::from_arr([e.into()]))
+}
+
+/// The value of `a op b` where `a` and `b` are interpreted as bit-vectors of size `bits`
+fn bv_bin_op(
+    op: fn(Sort) -> rty::BinOp,
+    bits: u32,
+    a: impl Into<Expr>,
+    b: impl Into<Expr>,
+) -> Expr {
+    let sort = Sort::BitVec(BvSize::Fixed(bits));
+    bv_to_int(bits, E::binary_op(op(sort), int_to_bv(bits, a), int_to_bv(bits, b)))
+}
+
+fn valid_shift(e: impl Into<Expr>, bits: u64) -> rty::Expr {
+    let e1 = e.into();
+    let e2 = e1.clone();
+    E::and(E::ge(e1, 0), E::lt(e2, E::constant(rty::Constant::from(bits))))
+}
+
 /// `-a`
 fn mk_neg_rules(overflow_mode: OverflowMode) -> RuleMatcher<1> {
     match overflow_mode {
@@ -583,3 +935,148 @@ fn mk_not_rules() -> RuleMatcher<1> {
         if T.is_integral()
     }
 }
+
+/// `a & b` on unsigned integers interpreted as bit-vectors
+fn mk_bv_bit_and_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T[bv_bin_op(BitAnd, bits, a, b)]
+        if let Some(bits) = uint_bit_width(T)
+    }
+}
+
+/// `a | b` on unsigned integers interpreted as bit-vectors
+fn mk_bv_bit_or_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T[bv_bin_op(BitOr, bits, a, b)]
+        if let Some(bits) = uint_bit_width(T)
+    }
+}
+
+/// `a ^ b` on unsigned integers interpreted as bit-vectors
+fn mk_bv_bit_xor_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T[bv_bin_op(BitXor, bits, a, b)]
+        if let Some(bits) = uint_bit_width(T)
+    }
+}
+
+/// `a << b` on unsigned integers interpreted as bit-vectors. The shift amount is converted to a
+/// bit-vector of the same size as `a`.
+fn mk_bv_shl_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: S) -> T[bv_bin_op(BitShl, bits, a, b)]
+        if let Some(bits) = uint_bit_width(T)
+        if S.is_unsigned()
+    }
+}
+
+/// `a >> b` on unsigned integers interpreted as bit-vectors (i.e., a logical shift)
+fn mk_bv_shr_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: S) -> T[bv_bin_op(BitShr, bits, a, b)]
+        if let Some(bits) = uint_bit_width(T)
+        if S.is_unsigned()
+    }
+}
+
+/// `!a` on unsigned integers interpreted as bit-vectors
+fn mk_bv_not_rules() -> RuleMatcher<1> {
+    primop_rules! {
+        fn(a: T) -> T[bv_to_int(bits, E::app(E::global_func(SpecFuncKind::Thy(ThyFunc::BvNot)), rty::List::empty(), rty::List::from_arr([int_to_bv(bits, a)])))]
+        if let Some(bits) = uint_bit_width(T)
+    }
+}
+
+/// Floats are indexed by the real number they denote, so arithmetic on floats is arithmetic on reals.
+fn real_op(op: fn(Sort) -> rty::BinOp, a: impl Into<Expr>, b: impl Into<Expr>) -> Expr {
+    E::binary_op(op(Sort::Real), a, b)
+}
+
+/// `a + b` on floats interpreted as reals
+fn mk_real_add_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T[real_op(rty::BinOp::Add, a, b)]
+        if T.is_float()
+    }
+}
+
+/// `a - b` on floats interpreted as reals
+fn mk_real_sub_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T[real_op(rty::BinOp::Sub, a, b)]
+        if T.is_float()
+    }
+}
+
+/// `a * b` on floats interpreted as reals
+fn mk_real_mul_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T[real_op(rty::BinOp::Mul, a, b)]
+        if T.is_float()
+    }
+}
+
+/// `a / b` on floats interpreted as reals. Dividing by zero is not an error, but the result is
+/// only known when `b` is non-zero.
+fn mk_real_div_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> T{v: E::implies(E::ne(b, E::real(0, 1)), E::eq(v, real_op(rty::BinOp::Div, a, b))) }
+        if T.is_float()
+    }
+}
+
+/// `a == b` on floats interpreted as reals
+fn mk_real_eq_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> bool[E::eq(a, b)]
+        if T.is_float()
+    }
+}
+
+/// `a != b` on floats interpreted as reals
+fn mk_real_ne_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> bool[E::ne(a, b)]
+        if T.is_float()
+    }
+}
+
+/// `a <= b` on floats interpreted as reals
+fn mk_real_le_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> bool[real_op(rty::BinOp::Le, a, b)]
+        if T.is_float()
+    }
+}
+
+/// `a >= b` on floats interpreted as reals
+fn mk_real_ge_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> bool[real_op(rty::BinOp::Ge, a, b)]
+        if T.is_float()
+    }
+}
+
+/// `a < b` on floats interpreted as reals
+fn mk_real_lt_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> bool[real_op(rty::BinOp::Lt, a, b)]
+        if T.is_float()
+    }
+}
+
+/// `a > b` on floats interpreted as reals
+fn mk_real_gt_rules() -> RuleMatcher<2> {
+    primop_rules! {
+        fn(a: T, b: T) -> bool[real_op(rty::BinOp::Gt, a, b)]
+        if T.is_float()
+    }
+}
+
+/// `-a` on floats interpreted as reals
+fn mk_real_neg_rules() -> RuleMatcher<1> {
+    primop_rules! {
+        fn(a: T) -> T[a.neg()]
+        if T.is_float()
+    }
+}
@@ -19,9 +19,9 @@ use flux_middle::{
     pretty::{PrettyCx, PrettyNested},
     queries::QueryResult,
     rty::{
-        BaseTy, Binder, BoundReftKind, Ctor, Ensures, Expr, ExprKind, FnSig, GenericArg, HoleKind,
-        INNERMOST, Lambda, List, Loc, Mutability, Path, PtrKind, Region, SortCtor, SubsetTy, Ty,
-        TyKind, VariantIdx,
+        BaseTy, Binder, BoundReftKind, Ctor, Ensures, Expr, ExprKind, FIRST_VARIANT, FnSig,
+        GenericArg, HoleKind, INNERMOST, Lambda, List, Loc, Mutability, Path, PtrKind, Region,
+        SortCtor, SubsetTy, Ty, TyKind, VariantIdx,
         canonicalize::{Hoister, LocalHoister},
         fold::{FallibleTypeFolder, TypeFoldable, TypeVisitable, TypeVisitor},
         region_matching::{rty_match_regions, ty_match_regions},
@@ -29,7 +29,7 @@ use flux_middle::{
 };
 use flux_rustc_bridge::{
     self,
-    mir::{BasicBlock, Body, Local, LocalDecl, LocalDecls, Place, PlaceElem},
+    mir::{BasicBlock, Body, FieldIdx, Local, LocalDecl, LocalDecls, Place, PlaceElem},
     ty,
 };
 use itertools::{Itertools, izip};
@@ -256,6 +256,13 @@ impl<'a> TypeEnv<'a> {
     ) -> InferResult {
         let rustc_ty = place.ty(infcx.genv, self.local_decls)?.ty;
         let new_ty = ty_match_regions(&new_ty, &rustc_ty);
+        if let Some((base, PlaceElem::Field(f))) = place.as_ref().last_projection()
+            && let base = base.to_place()
+            && let ty::TyKind::Adt(adt_def, _) = base.ty(infcx.genv, self.local_decls)?.ty.kind()
+            && adt_def.is_union()
+        {
+            return self.assign_union_field(infcx, &base, f, new_ty);
+        }
         let span = infcx.span;
         let result = self.bindings.lookup_unfolding(infcx, place, span)?;
         if result.is_strg {
@@ -266,6 +273,35 @@ impl<'a> TypeEnv<'a> {
         Ok(())
     }
 
+    /// Writing to a field of a union makes it the active field, so instead of updating the field we
+    /// assign a new value to the whole union.
+    fn assign_union_field(
+        &mut self,
+        infcx: &mut InferCtxtAt,
+        place: &Place,
+        f: FieldIdx,
+        new_ty: Ty,
+    ) -> InferResult {
+        let span = infcx.span;
+        let result = self.bindings.lookup_unfolding(infcx, place, span)?;
+        let union_ty = infcx.unpack(&result.ty);
+        let TyKind::Indexed(BaseTy::Adt(adt, args), _) = union_ty.kind() else {
+            tracked_span_bug!("expected union type found `{union_ty:?}`")
+        };
+        let variant = infcx
+            .genv
+            .variant_sig(adt.did(), FIRST_VARIANT)?
+            .ok_or_query_err(adt.did())?;
+        let union_ty =
+            infcx.check_union_constructor(variant, args, f, &new_ty, ConstrReason::Assign)?;
+        if result.is_strg {
+            result.update(union_ty);
+        } else if !place.behind_raw_ptr(infcx.genv, self.local_decls)? {
+            infcx.subtyping(&union_ty, &result.ty, ConstrReason::Assign)?;
+        }
+        Ok(())
+    }
+
     pub(crate) fn move_place(&mut self, infcx: &mut InferCtxtAt, place: &Place) -> InferResult<Ty> {
         let span = infcx.span;
         let result = self.bindings.lookup_unfolding(infcx, place, span)?;
@@ -288,6 +324,18 @@ impl<'a> TypeEnv<'a> {
         self.bindings.unblock(infcx, place);
     }
 
+    /// Unblocks all blocked locations. This is used when unwinding, where a blocked location keeps
+    /// the type of the bound it was blocked with.
+    pub(crate) fn unblock_all(&mut self, infcx: &mut InferCtxt) {
+        self.bindings.fmap_mut(|_, ty| {
+            if let TyKind::Blocked(_) = ty.kind() {
+                infcx.hoister(true).hoist(&ty.unblocked())
+            } else {
+                ty.clone()
+            }
+        });
+    }
+
     pub(crate) fn check_goto(
         self,
         infcx: &mut InferCtxtAt,
@@ -399,6 +447,19 @@ impl<'a> TypeEnv<'a> {
         }
     }
 
+    /// Forgets the refinements of the locations updated by `ensures`, keeping only their base type.
+    pub(crate) fn havoc_ensures(&mut self, infcx: &mut InferCtxt, ensures: &[Ensures], span: Span) {
+        for ensure in ensures {
+            if let Ensures::Type(path, updated_ty) = ensure
+                && let Some(bty) = updated_ty.as_bty_skipping_existentials()
+            {
+                let ty = infcx.unpack(&bty.to_ty());
+                infcx.assume_invariants(&ty);
+                self.update_path(path, ty, span);
+            }
+        }
+    }
+
     pub(crate) fn check_ensures(
         &mut self,
         at: &mut InferCtxtAt,
@@ -515,6 +576,7 @@ impl BasicBlockEnvShape {
             BaseTy::Slice(ty) => BaseTy::Slice(Self::pack_ty(scope, ty)),
             BaseTy::Ref(r, ty, mutbl) => BaseTy::Ref(*r, Self::pack_ty(scope, ty), *mutbl),
             BaseTy::Array(ty, c) => BaseTy::Array(Self::pack_ty(scope, ty), c.clone()),
+            BaseTy::Pat(ty, pat) => BaseTy::Pat(Self::pack_ty(scope, ty), pat.clone()),
             BaseTy::Int(_)
             | BaseTy::Param(_)
             | BaseTy::Uint(_)
@@ -537,9 +599,6 @@ impl BasicBlockEnvShape {
                     bty.clone()
                 }
             }
-            BaseTy::Pat => {
-                todo!()
-            }
             BaseTy::Infer(..) => {
                 tracked_span_bug!("unexpected infer type")
             }
@@ -746,7 +805,15 @@ impl BasicBlockEnvShape {
         }
     }
 
-    pub fn into_bb_env(self, infcx: &mut InferCtxtRoot, body: &Body) -> BasicBlockEnv {
+    /// Generalizes the shape into a [`BasicBlockEnv`] with a fresh kvar constraining the values of
+    /// the locals. If the block is the head of a loop with an invariant, the invariant is assumed
+    /// when entering the block so no kvar is generated.
+    pub fn into_bb_env(
+        self,
+        infcx: &mut InferCtxtRoot,
+        body: &Body,
+        has_invariant: bool,
+    ) -> BasicBlockEnv {
         let mut delegate = LocalHoister::default();
         let mut hoister = Hoister::with_delegate(&mut delegate).transparent();
 
@@ -770,12 +837,14 @@ impl BasicBlockEnvShape {
                     .into_iter()
                     .filter(|pred| !matches!(pred.kind(), ExprKind::Hole(HoleKind::Pred)))
                     .collect_vec();
-                let kvar = infcx.fresh_kvar_in_scope(
-                    std::slice::from_ref(&vars),
-                    &self.scope,
-                    KVarEncoding::Conj,
-                );
-                constrs.push(kvar);
+                if !has_invariant {
+                    let kvar = infcx.fresh_kvar_in_scope(
+                        std::slice::from_ref(&vars),
+                        &self.scope,
+                        KVarEncoding::Conj,
+                    );
+                    constrs.push(kvar);
+                }
 
                 // Replace remaining holes by fresh kvars
                 let mut kvar_gen = |binders: &[_], kind| {
@@ -9,8 +9,9 @@ use flux_middle::{
     global_env::GlobalEnv,
     queries::QueryResult,
     rty::{
-        AdtDef, BaseTy, Binder, EarlyBinder, Expr, FIRST_VARIANT, GenericArg, GenericArgsExt, List,
-        Loc, Mutability, Path, PtrKind, Ref, Sort, Ty, TyKind, VariantIdx, VariantSig,
+        AdtDef, BaseTy, Binder, Constant, EarlyBinder, Expr, FIRST_VARIANT, GenericArg,
+        GenericArgsExt, List, Loc, Mutability, Path, PtrKind, Ref, Sort, Ty, TyKind, VariantIdx,
+        VariantSig,
         fold::{FallibleTypeFolder, TypeFoldable, TypeVisitable, TypeVisitor},
     },
 };
@@ -84,6 +85,16 @@ pub(crate) trait LookupMode {
         args: &[GenericArg],
         idx: &Expr,
     ) -> Result<Vec<Ty>, Self::Error>;
+
+    fn union_field(
+        &mut self,
+        adt: &AdtDef,
+        args: &[GenericArg],
+        idx: &Expr,
+        f: FieldIdx,
+    ) -> Result<Ty, Self::Error>;
+
+    fn raw_deref(&mut self, idx: &Expr);
 }
 
 struct Unfold<'a, 'infcx, 'genv, 'tcx>(&'a mut InferCtxt<'infcx, 'genv, 'tcx>, Span);
@@ -103,6 +114,37 @@ impl LookupMode for Unfold<'_, '_, '_, '_> {
     ) -> Result<Vec<Ty>, Self::Error> {
         downcast_struct(self.0, adt, args, idx, self.1)
     }
+
+    /// Unions are never unfolded, a field is accessed directly from the type of the union which
+    /// requires the field to be the active one.
+    fn union_field(
+        &mut self,
+        adt: &AdtDef,
+        args: &[GenericArg],
+        idx: &Expr,
+        f: FieldIdx,
+    ) -> Result<Ty, Self::Error> {
+        if let Some(tag) = adt.union_tag(idx) {
+            let active = Expr::eq(tag, Expr::constant(Constant::from(f.as_usize())));
+            self.0
+                .at(self.1)
+                .check_pred(active, ConstrReason::UnionField);
+        }
+        let mut fields = downcast_struct(self.0, adt, args, idx, self.1)?;
+        Ok(fields.swap_remove(f.as_usize()))
+    }
+
+    /// When raw derefs are checked, the pointer must be in bounds of its allocation, i.e., there
+    /// must be at least one element we can access through it. Pointers into a local that is
+    /// deallocated or moved are invalidated by the checker, so they fail this check.
+    fn raw_deref(&mut self, idx: &Expr) {
+        if self.0.check_raw_deref() {
+            let in_bounds = Expr::gt(Expr::ptr_size(idx.clone()), Expr::zero());
+            self.0
+                .at(self.1)
+                .check_pred(in_bounds, ConstrReason::RawDeref);
+        }
+    }
 }
 
 struct NoUnfold;
@@ -112,6 +154,18 @@ impl LookupMode for NoUnfold {
         tracked_span_bug!("cannot unfold in `NoUnfold` mode")
     }
 
+    fn union_field(
+        &mut self,
+        _: &AdtDef,
+        _: &[GenericArg],
+        _: &Expr,
+        _: FieldIdx,
+    ) -> Result<Ty, !> {
+        tracked_span_bug!("cannot access union field in `NoUnfold` mode")
+    }
+
+    fn raw_deref(&mut self, _: &Expr) {}
+
     fn unpack(&mut self, ty: &Ty) -> Ty {
         ty.clone()
     }
@@ -149,6 +203,8 @@ impl PlacesTree {
                         | TyKind::Downcast(.., fields) => {
                             ty = fields[f.as_usize()].clone();
                         }
+                        // Fields of unions are never blocked because unions are never unfolded
+                        TyKind::Indexed(BaseTy::Adt(adt, _), _) if adt.is_union() => return,
                         _ => tracked_span_bug!("invalid field access `Field({f:?})` and `{ty:?}`"),
                     };
                 }
@@ -179,8 +235,9 @@ impl PlacesTree {
                         TyKind::Indexed(BaseTy::Adt(adt, args), _) if adt.is_box() => {
                             ty = args.box_args().0.clone();
                         }
-                        TyKind::Indexed(BaseTy::RawPtr(deref_ty, _), _) => {
+                        TyKind::Indexed(BaseTy::RawPtr(deref_ty, _), idx) => {
                             is_strg = false;
+                            mode.raw_deref(idx);
                             ty = deref_ty.clone();
                         }
                         TyKind::Ptr(_, path) => {
@@ -206,6 +263,12 @@ impl PlacesTree {
                         | TyKind::Downcast(.., fields) => {
                             ty = fields[f.as_usize()].clone();
                         }
+                        TyKind::Indexed(BaseTy::Adt(adt, args), idx) if adt.is_union() => {
+                            // Writing to a field of a union changes the whole union so we cannot
+                            // update the field in place.
+                            is_strg = false;
+                            ty = mode.union_field(adt, args, idx, f)?;
+                        }
                         TyKind::Indexed(BaseTy::Adt(adt, args), idx) => {
                             ty = mode.downcast_struct(adt, args, idx)?[f.as_usize()].clone();
                         }
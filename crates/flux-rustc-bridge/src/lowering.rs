@@ -18,7 +18,7 @@ use rustc_middle::{
         adjustment as rustc_adjustment,
     },
 };
-use rustc_span::Span;
+use rustc_span::{Span, sym};
 use rustc_trait_selection::traits::SelectionContext;
 
 use super::{
@@ -31,7 +31,8 @@ use super::{
         AdtDef, AdtDefData, AliasKind, Binder, BoundRegion, BoundVariableKind, Clause, ClauseKind,
         Const, ConstKind, ExistentialPredicate, ExistentialProjection, FieldDef, FnSig, GenericArg,
         GenericParamDef, GenericParamDefKind, GenericPredicates, Generics, OutlivesPredicate,
-        TraitPredicate, TraitRef, Ty, TypeOutlivesPredicate, UnevaluatedConst, VariantDef,
+        Pattern, PatternKind, TraitPredicate, TraitRef, Ty, TypeOutlivesPredicate,
+        UnevaluatedConst, VariantDef,
     },
 };
 use crate::{
@@ -200,20 +201,77 @@ impl<'sess, 'tcx> MirLoweringCtxt<'_, 'sess, 'tcx> {
         &mut self,
         data: &rustc_mir::BasicBlockData<'tcx>,
     ) -> Result<BasicBlockData<'tcx>, ErrorGuaranteed> {
-        let data = BasicBlockData {
-            statements: data
-                .statements
-                .iter()
-                .map(|stmt| self.lower_statement(stmt))
-                .try_collect()?,
-            terminator: data
-                .terminator
-                .as_ref()
-                .map(|terminator| self.lower_terminator(terminator))
-                .transpose()?,
-            is_cleanup: data.is_cleanup,
+        let mut statements: Vec<_> = data
+            .statements
+            .iter()
+            .map(|stmt| self.lower_statement(stmt))
+            .try_collect()?;
+        let terminator = match &data.terminator {
+            Some(terminator) => {
+                if let Some((stmt, terminator)) = self.lower_intrinsic_bin_op(terminator)? {
+                    statements.push(stmt);
+                    Some(terminator)
+                } else {
+                    Some(self.lower_terminator(terminator)?)
+                }
+            }
+            None => None,
         };
-        Ok(data)
+        Ok(BasicBlockData { statements, terminator, is_cleanup: data.is_cleanup })
+    }
+
+    /// Calls to intrinsics corresponding to a binary operation are lowered to an assignment
+    /// followed by a `goto`, the same as rustc does later in the pipeline. This lets us check them
+    /// with the rules for the binary operation instead of the (unrefined) signature of the
+    /// intrinsic.
+    fn lower_intrinsic_bin_op(
+        &self,
+        terminator: &rustc_mir::Terminator<'tcx>,
+    ) -> Result<Option<(Statement<'tcx>, Terminator<'tcx>)>, ErrorGuaranteed> {
+        let rustc_mir::TerminatorKind::Call {
+            func, args, destination, target: Some(target), ..
+        } = &terminator.kind
+        else {
+            return Ok(None);
+        };
+        let rustc_middle::ty::TyKind::FnDef(def_id, _) = func.ty(self.rustc_mir, self.tcx).kind()
+        else {
+            return Ok(None);
+        };
+        let Some(intrinsic) = self.tcx.intrinsic(*def_id) else { return Ok(None) };
+        let bin_op = match intrinsic.name {
+            sym::unchecked_add => BinOp::AddUnchecked,
+            sym::unchecked_sub => BinOp::SubUnchecked,
+            sym::unchecked_mul => BinOp::MulUnchecked,
+            sym::unchecked_shl => BinOp::ShlUnchecked,
+            sym::unchecked_shr => BinOp::ShrUnchecked,
+            sym::add_with_overflow => BinOp::AddWithOverflow,
+            sym::sub_with_overflow => BinOp::SubWithOverflow,
+            sym::mul_with_overflow => BinOp::MulWithOverflow,
+            sym::three_way_compare => BinOp::Cmp,
+            sym::offset => BinOp::Offset,
+            _ => return Ok(None),
+        };
+        let span = terminator.source_info.span;
+        let [op1, op2] = &args[..] else { return Ok(None) };
+        let lower_operand = |op: &rustc_mir::Operand<'tcx>| {
+            self.lower_operand(op)
+                .map_err(|reason| errors::UnsupportedMir::new(span, "intrinsic args", reason))
+                .emit(self.sess)
+        };
+        let rvalue = Rvalue::BinaryOp(bin_op, lower_operand(&op1.node)?, lower_operand(&op2.node)?);
+        let place = lower_place(self.tcx, destination)
+            .map_err(|reason| errors::UnsupportedMir::new(span, "intrinsic destination", reason))
+            .emit(self.sess)?;
+        let stmt = Statement {
+            kind: StatementKind::Assign(place, rvalue),
+            source_info: terminator.source_info,
+        };
+        let terminator = Terminator {
+            kind: TerminatorKind::Goto { target: *target },
+            source_info: terminator.source_info,
+        };
+        Ok(Some((stmt, terminator)))
     }
 
     fn lower_local_decl(
@@ -269,9 +327,10 @@ impl<'sess, 'tcx> MirLoweringCtxt<'_, 'sess, 'tcx> {
                         .emit(self.sess)?,
                 )
             }
-            rustc_mir::StatementKind::Nop
-            | rustc_mir::StatementKind::StorageLive(_)
-            | rustc_mir::StatementKind::StorageDead(_) => StatementKind::Nop,
+            rustc_mir::StatementKind::StorageDead(local) => StatementKind::StorageDead(*local),
+            rustc_mir::StatementKind::Nop | rustc_mir::StatementKind::StorageLive(_) => {
+                StatementKind::Nop
+            }
             rustc_mir::StatementKind::AscribeUserType(
                 box (place, rustc_mir::UserTypeProjection { projs, .. }),
                 variance,
@@ -405,7 +464,7 @@ impl<'sess, 'tcx> MirLoweringCtxt<'_, 'sess, 'tcx> {
                     unwind: *unwind,
                 }
             }
-            rustc_mir::TerminatorKind::Assert { cond, target, expected, msg, .. } => {
+            rustc_mir::TerminatorKind::Assert { cond, target, expected, msg, unwind } => {
                 TerminatorKind::Assert {
                     cond: self
                         .lower_operand(cond)
@@ -417,6 +476,7 @@ impl<'sess, 'tcx> MirLoweringCtxt<'_, 'sess, 'tcx> {
                         .lower_assert_msg(msg)
                         .ok_or_else(|| errors::UnsupportedMir::from(terminator))
                         .emit(self.sess)?,
+                    unwind: *unwind,
                 }
             }
             rustc_mir::TerminatorKind::Unreachable => TerminatorKind::Unreachable,
@@ -511,10 +571,16 @@ impl<'sess, 'tcx> MirLoweringCtxt<'_, 'sess, 'tcx> {
             rustc_mir::Rvalue::ShallowInitBox(op, ty) => {
                 Ok(Rvalue::ShallowInitBox(self.lower_operand(op)?, ty.lower(self.tcx)?))
             }
-            rustc_mir::Rvalue::ThreadLocalRef(_)
-            | rustc_mir::Rvalue::NullaryOp(..)
-            | rustc_mir::Rvalue::CopyForDeref(_)
-            | rustc_mir::Rvalue::WrapUnsafeBinder(..) => {
+            rustc_mir::Rvalue::ThreadLocalRef(def_id) => {
+                let ty = self.tcx.thread_local_ptr_ty(*def_id).lower(self.tcx)?;
+                Ok(Rvalue::ThreadLocalRef(*def_id, ty))
+            }
+            rustc_mir::Rvalue::NullaryOp(null_op) => Ok(Rvalue::NullaryOp(*null_op)),
+            // A `CopyForDeref` is a copy whose result is only used to deref through it
+            rustc_mir::Rvalue::CopyForDeref(place) => {
+                Ok(Rvalue::Use(Operand::Copy(lower_place(self.tcx, place)?)))
+            }
+            rustc_mir::Rvalue::WrapUnsafeBinder(..) => {
                 Err(UnsupportedReason::new(format!("unsupported rvalue `{rvalue:?}`")))
             }
         }
@@ -552,6 +618,7 @@ impl<'sess, 'tcx> MirLoweringCtxt<'_, 'sess, 'tcx> {
             rustc_mir::CastKind::PointerWithExposedProvenance => {
                 Some(CastKind::PointerWithExposedProvenance)
             }
+            rustc_mir::CastKind::Transmute => Some(CastKind::Transmute),
             _ => None,
         }
     }
@@ -613,18 +680,16 @@ impl<'sess, 'tcx> MirLoweringCtxt<'_, 'sess, 'tcx> {
             rustc_mir::BinOp::BitXor => Ok(BinOp::BitXor),
             rustc_mir::BinOp::Shl => Ok(BinOp::Shl),
             rustc_mir::BinOp::Shr => Ok(BinOp::Shr),
-            rustc_mir::BinOp::AddUnchecked
-            | rustc_mir::BinOp::SubUnchecked
-            | rustc_mir::BinOp::MulUnchecked
-            | rustc_mir::BinOp::ShlUnchecked
-            | rustc_mir::BinOp::ShrUnchecked
-            | rustc_mir::BinOp::AddWithOverflow
-            | rustc_mir::BinOp::SubWithOverflow
-            | rustc_mir::BinOp::MulWithOverflow
-            | rustc_mir::BinOp::Cmp
-            | rustc_mir::BinOp::Offset => {
-                Err(UnsupportedReason::new(format!("unsupported binary op `{bin_op:?}`")))
-            }
+            rustc_mir::BinOp::AddUnchecked => Ok(BinOp::AddUnchecked),
+            rustc_mir::BinOp::SubUnchecked => Ok(BinOp::SubUnchecked),
+            rustc_mir::BinOp::MulUnchecked => Ok(BinOp::MulUnchecked),
+            rustc_mir::BinOp::ShlUnchecked => Ok(BinOp::ShlUnchecked),
+            rustc_mir::BinOp::ShrUnchecked => Ok(BinOp::ShrUnchecked),
+            rustc_mir::BinOp::AddWithOverflow => Ok(BinOp::AddWithOverflow),
+            rustc_mir::BinOp::SubWithOverflow => Ok(BinOp::SubWithOverflow),
+            rustc_mir::BinOp::MulWithOverflow => Ok(BinOp::MulWithOverflow),
+            rustc_mir::BinOp::Cmp => Ok(BinOp::Cmp),
+            rustc_mir::BinOp::Offset => Ok(BinOp::Offset),
         }
     }
 
@@ -757,6 +822,26 @@ impl<'tcx> Lower<'tcx> for rustc_ty::Const<'tcx> {
     }
 }
 
+impl<'tcx> Lower<'tcx> for rustc_ty::Pattern<'tcx> {
+    type R = Result<Pattern, UnsupportedReason>;
+
+    fn lower(self, tcx: TyCtxt<'tcx>) -> Self::R {
+        match *self {
+            rustc_ty::PatternKind::Range { start, end } => {
+                Ok(Pattern::new(PatternKind::Range {
+                    start: start.lower(tcx)?,
+                    end: end.lower(tcx)?,
+                }))
+            }
+            rustc_ty::PatternKind::Or(pats) => {
+                let pats = pats.iter().map(|pat| pat.lower(tcx)).try_collect()?;
+                Ok(Pattern::new(PatternKind::Or(List::from_vec(pats))))
+            }
+            rustc_ty::PatternKind::NotNull => Ok(Pattern::new(PatternKind::NotNull)),
+        }
+    }
+}
+
 impl<'tcx, T, S> Lower<'tcx> for rustc_ty::Binder<'tcx, T>
 where
     T: Lower<'tcx, R = Result<S, UnsupportedReason>>,
@@ -838,7 +923,7 @@ impl<'tcx> Lower<'tcx> for rustc_ty::Ty<'tcx> {
                 Ok(Ty::mk_dynamic(exi_preds, region))
             }
             rustc_ty::Foreign(def_id) => Ok(Ty::mk_foreign(*def_id)),
-            rustc_ty::Pat(..) => Ok(Ty::mk_pat()),
+            rustc_ty::Pat(ty, pat) => Ok(Ty::mk_pat(ty.lower(tcx)?, pat.lower(tcx)?)),
             _ => Err(UnsupportedReason::new(format!("unsupported type `{self:?}`"))),
         }
     }
@@ -1103,7 +1188,7 @@ impl<'tcx> Lower<'tcx> for rustc_ty::RegionOutlivesPredicate<'tcx> {
 mod errors {
     use std::path::PathBuf;
 
-    use flux_errors::E0999;
+    use flux_errors::E0998;
     use flux_macros::Diagnostic;
     use rustc_middle::mir as rustc_mir;
     use rustc_span::Span;
@@ -1111,7 +1196,7 @@ mod errors {
     use super::UnsupportedReason;
 
     #[derive(Diagnostic)]
-    #[diag(rustc_bridge_unsupported_local_decl, code = E0999)]
+    #[diag(rustc_bridge_unsupported_local_decl, code = E0998)]
     pub(super) struct UnsupportedLocalDecl<'tcx> {
         #[primary_span]
         #[label]
@@ -1129,7 +1214,7 @@ mod errors {
     }
 
     #[derive(Diagnostic)]
-    #[diag(rustc_bridge_unsupported_mir, code = E0999)]
+    #[diag(rustc_bridge_unsupported_mir, code = E0998)]
     #[note]
     pub(super) struct UnsupportedMir {
         #[primary_span]
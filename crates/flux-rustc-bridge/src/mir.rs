@@ -18,7 +18,7 @@ use rustc_macros::{TyDecodable, TyEncodable};
 use rustc_middle::mir::{Promoted, VarDebugInfoContents};
 pub use rustc_middle::{
     mir::{
-        BasicBlock, BorrowKind, FakeBorrowKind, FakeReadCause, Local, LocalKind, Location,
+        BasicBlock, BorrowKind, FakeBorrowKind, FakeReadCause, Local, LocalKind, Location, NullOp,
         RETURN_PLACE, RawPtrKind, START_BLOCK, SourceInfo, SwitchTargets, UnOp, UnwindAction,
     },
     ty::{UserTypeAnnotationIndex, Variance},
@@ -181,6 +181,7 @@ pub enum TerminatorKind<'tcx> {
         expected: bool,
         target: BasicBlock,
         msg: AssertKind,
+        unwind: UnwindAction,
     },
     Unreachable,
     FalseEdge {
@@ -230,6 +231,7 @@ pub enum StatementKind<'tcx> {
     AscribeUserType(Place, Variance),
     Intrinsic(NonDivergingIntrinsic<'tcx>),
     PlaceMention(Place),
+    StorageDead(Local),
     Nop,
 }
 
@@ -245,6 +247,10 @@ pub enum Rvalue<'tcx> {
     Discriminant(Place),
     Aggregate(AggregateKind, Vec<Operand<'tcx>>),
     ShallowInitBox(Operand<'tcx>, Ty),
+    /// A pointer to a thread-local static together with the type of the pointer, i.e., `&T` for
+    /// an immutable static and a raw pointer for mutable or extern ones.
+    ThreadLocalRef(DefId, Ty),
+    NullaryOp(NullOp),
 }
 
 #[derive(Copy, Clone)]
@@ -256,6 +262,7 @@ pub enum CastKind {
     PointerCoercion(PointerCast),
     PointerExposeProvenance,
     PointerWithExposedProvenance,
+    Transmute,
 }
 
 #[derive(Copy, Clone)]
@@ -293,6 +300,16 @@ pub enum BinOp {
     BitXor,
     Shl,
     Shr,
+    AddUnchecked,
+    SubUnchecked,
+    MulUnchecked,
+    ShlUnchecked,
+    ShrUnchecked,
+    AddWithOverflow,
+    SubWithOverflow,
+    MulWithOverflow,
+    Cmp,
+    Offset,
 }
 
 pub enum Operand<'tcx> {
@@ -392,11 +409,30 @@ impl Terminator<'_> {
     pub fn is_return(&self) -> bool {
         matches!(self.kind, TerminatorKind::Return)
     }
+
+    /// The cleanup block control flows to if this terminator unwinds. We don't consider the
+    /// unwind edge of [`TerminatorKind::FalseUnwind`] because it is never taken at runtime.
+    pub fn unwind_cleanup(&self) -> Option<BasicBlock> {
+        match &self.kind {
+            TerminatorKind::Call { unwind, .. }
+            | TerminatorKind::Drop { unwind, .. }
+            | TerminatorKind::Assert { unwind, .. } => {
+                if let UnwindAction::Cleanup(bb) = unwind {
+                    Some(*bb)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Statement<'_> {
+    /// Whether the statement has no effect on the types of places. A `StorageDead` only affects
+    /// raw pointers into the local when raw pointer dereferences are checked.
     pub fn is_nop(&self) -> bool {
-        matches!(self.kind, StatementKind::Nop)
+        matches!(self.kind, StatementKind::Nop | StatementKind::StorageDead(_))
     }
 }
 
@@ -468,6 +504,24 @@ impl<'tcx> Body<'tcx> {
         self.rustc_body.basic_blocks.dominators()
     }
 
+    /// Finds the local for the user variable `name` declared by the binding at `span`. If no local
+    /// is declared exactly at `span`, we fall back to a local with the same name whose declaration
+    /// overlaps with it.
+    pub fn find_local(&self, name: Symbol, span: Span) -> Option<Local> {
+        let candidates: Vec<_> = self
+            .local_decls
+            .iter_enumerated()
+            .filter(|(local, decl)| {
+                self.local_names.get(local) == Some(&name) && decl.source_info.span.overlaps(span)
+            })
+            .collect();
+        candidates
+            .iter()
+            .find(|(_, decl)| decl.source_info.span == span)
+            .or_else(|| candidates.first())
+            .map(|(local, _)| *local)
+    }
+
     #[inline]
     pub fn args_iter(&self) -> impl ExactSizeIterator<Item = Local> {
         (1..self.rustc_body.arg_count + 1).map(Local::new)
@@ -536,6 +590,7 @@ impl fmt::Debug for Statement<'_> {
         match &self.kind {
             StatementKind::Assign(place, rvalue) => write!(f, "{place:?} = {rvalue:?}"),
             StatementKind::Nop => write!(f, "nop"),
+            StatementKind::StorageDead(local) => write!(f, "StorageDead({local:?})"),
             StatementKind::PlaceMention(place) => {
                 write!(f, "PlaceMention({place:?})")
             }
@@ -600,7 +655,7 @@ impl fmt::Debug for Terminator<'_> {
             TerminatorKind::Drop { place, target, unwind } => {
                 write!(f, "drop({place:?}) -> [{target:?}, unwind: {unwind:?}]",)
             }
-            TerminatorKind::Assert { cond, target, expected, msg } => {
+            TerminatorKind::Assert { cond, target, expected, msg, .. } => {
                 write!(
                     f,
                     "assert({cond:?} is expected to be {expected:?}, \"{msg:?}\") -> {target:?}"
@@ -730,6 +785,10 @@ impl fmt::Debug for Rvalue<'_> {
             Rvalue::Cast(kind, op, ty) => write!(f, "{op:?} as {ty:?} [{kind:?}]"),
             Rvalue::Repeat(op, c) => write!(f, "[{op:?}; {c:?}]"),
             Rvalue::ShallowInitBox(op, ty) => write!(f, "ShallowInitBox({op:?}, {ty:?})"),
+            Rvalue::ThreadLocalRef(def_id, _) => {
+                write!(f, "&/*tls*/ {}", def_id_to_string(*def_id))
+            }
+            Rvalue::NullaryOp(null_op) => write!(f, "{null_op:?}"),
         }
     }
 }
@@ -755,6 +814,7 @@ impl fmt::Debug for CastKind {
             CastKind::PointerCoercion(c) => write!(f, "Pointer({c:?})"),
             CastKind::PointerExposeProvenance => write!(f, "PointerExposeProvenance"),
             CastKind::PointerWithExposedProvenance => write!(f, "PointerWithExposedProvenance"),
+            CastKind::Transmute => write!(f, "Transmute"),
         }
     }
 }
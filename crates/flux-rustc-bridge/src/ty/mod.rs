@@ -235,7 +235,24 @@ pub enum TyKind {
     RawPtr(Ty, Mutability),
     Dynamic(List<Binder<ExistentialPredicate>>, Region),
     Foreign(DefId),
-    Pat,
+    /// A pattern type, e.g., `pattern_type!(u32 is 1..=10)`
+    Pat(Ty, Pattern),
+}
+
+/// The pattern in a pattern type
+#[derive(Clone, PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
+pub struct Pattern(Interned<PatternKind>);
+
+#[derive(PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
+pub enum PatternKind {
+    /// An inclusive range `start..=end`
+    Range {
+        start: Const,
+        end: Const,
+    },
+    Or(List<Pattern>),
+    /// A raw pointer that is not null
+    NotNull,
 }
 
 #[derive(PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
@@ -344,6 +361,25 @@ impl<'tcx> ToRustc<'tcx> for Const {
     }
 }
 
+impl<'tcx> ToRustc<'tcx> for Pattern {
+    type T = rustc_ty::Pattern<'tcx>;
+
+    fn to_rustc(&self, tcx: TyCtxt<'tcx>) -> Self::T {
+        let kind = match self.kind() {
+            PatternKind::Range { start, end } => {
+                rustc_ty::PatternKind::Range { start: start.to_rustc(tcx), end: end.to_rustc(tcx) }
+            }
+            PatternKind::Or(pats) => {
+                rustc_ty::PatternKind::Or(
+                    tcx.mk_patterns_from_iter(pats.iter().map(|pat| pat.to_rustc(tcx))),
+                )
+            }
+            PatternKind::NotNull => rustc_ty::PatternKind::NotNull,
+        };
+        tcx.mk_pat(kind)
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, TyEncodable, TyDecodable)]
 pub struct UnevaluatedConst {
     pub def: DefId,
@@ -729,6 +765,16 @@ impl TyKind {
     }
 }
 
+impl Pattern {
+    pub fn new(kind: PatternKind) -> Pattern {
+        Pattern(Interned::new(kind))
+    }
+
+    pub fn kind(&self) -> &PatternKind {
+        &self.0
+    }
+}
+
 impl Ty {
     pub fn mk_adt(adt_def: AdtDef, args: impl Into<GenericArgs>) -> Ty {
         let args = args.into();
@@ -820,8 +866,8 @@ impl Ty {
         TyKind::Foreign(def_id).intern()
     }
 
-    pub fn mk_pat() -> Ty {
-        TyKind::Pat.intern()
+    pub fn mk_pat(ty: Ty, pat: Pattern) -> Ty {
+        TyKind::Pat(ty, pat).intern()
     }
 
     pub fn deref(&self) -> Ty {
@@ -975,7 +1021,7 @@ impl<'tcx> ToRustc<'tcx> for Ty {
                 let preds = tcx.mk_poly_existential_predicates(&preds);
                 rustc_ty::Ty::new_dynamic(tcx, preds, re.to_rustc(tcx))
             }
-            TyKind::Pat => todo!(),
+            TyKind::Pat(ty, pat) => rustc_ty::Ty::new_pat(tcx, ty.to_rustc(tcx), pat.to_rustc(tcx)),
             TyKind::Coroutine(_, _) | TyKind::CoroutineWitness(_, _) => {
                 bug!("TODO: to_rustc for `{self:?}`")
             }
@@ -983,9 +1029,10 @@ impl<'tcx> ToRustc<'tcx> for Ty {
     }
 }
 
-impl_internable!(TyS, AdtDefData);
+impl_internable!(TyS, AdtDefData, PatternKind);
 impl_slice_internable!(
     Ty,
+    Pattern,
     GenericArg,
     GenericParamDef,
     BoundVariableKind,
@@ -1137,7 +1184,17 @@ impl fmt::Debug for Ty {
             TyKind::Foreign(def_id) => {
                 write!(f, "Foreign {def_id:?}")
             }
-            TyKind::Pat => todo!(),
+            TyKind::Pat(ty, pat) => write!(f, "pattern_type!({ty:?} is {pat:?})"),
+        }
+    }
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind() {
+            PatternKind::Range { start, end } => write!(f, "{start:?}..={end:?}"),
+            PatternKind::Or(pats) => write!(f, "{:?}", pats.iter().format(" | ")),
+            PatternKind::NotNull => write!(f, "!null"),
         }
     }
 }
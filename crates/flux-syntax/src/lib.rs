@@ -100,6 +100,14 @@ impl ParseSess {
         parser::parse_expr(&mut self.cx(tokens, span), true)
     }
 
+    pub fn parse_decreases(
+        &mut self,
+        tokens: &TokenStream,
+        span: Span,
+    ) -> ParseResult<Vec<surface::Expr>> {
+        parser::parse_decreases_list(&mut self.cx(tokens, span))
+    }
+
     pub fn parse_static_info(
         &mut self,
         tokens: &TokenStream,
@@ -23,8 +23,8 @@ use crate::{
         GenericParam, Generics, Ident, ImplAssocReft, Indices, LetDecl, LitKind, Mutability,
         ParamMode, Path, PathSegment, PrimOpProp, Qualifier, QualifierKind, QuantKind, RefineArg,
         RefineParam, RefineParams, Requires, Sort, SortDecl, SortPath, SpecFunc, Spread,
-        StaticInfo, StructDef, TraitAssocReft, TraitRef, Trusted, Ty, TyAlias, TyKind, UnOp,
-        VariantDef, VariantRet, WhereBoundPredicate,
+        StaticInfo, StructDef, TraitAssocReft, TraitRef, Trigger, Trusted, Ty, TyAlias, TyKind,
+        UnOp, VariantDef, VariantRet, WhereBoundPredicate,
     },
     symbols::{kw, sym},
     token::{self, Comma, Delimiter::*, IdentIsRaw, Or, Token, TokenKind},
@@ -463,6 +463,8 @@ fn parse_attrs(cx: &mut ParseCtxt) -> ParseResult<ParsedAttrs> {
 ///               ( ⟨refine_param⟩,* )
 ///               ->
 ///               ⟨sort⟩
+///               ⟨ decreases ⟨expr⟩,+ ⟩?
+///               ⟨ { ⟨expr⟩ } | ; ⟩
 /// ```
 fn parse_reft_func(cx: &mut ParseCtxt) -> ParseResult<SpecFunc> {
     let attrs = parse_attrs(cx)?;
@@ -473,13 +475,19 @@ fn parse_reft_func(cx: &mut ParseCtxt) -> ParseResult<SpecFunc> {
     let params = parens(cx, Comma, |cx| parse_refine_param(cx, RequireSort::Yes))?;
     cx.expect(token::RArrow)?;
     let output = parse_sort(cx)?;
+    // Struct expressions are not allowed in the metric because they would be ambiguous with the body
+    let decreases = if cx.advance_if(kw::Decreases) {
+        sep1(cx, Comma, |cx| parse_expr(cx, false))?
+    } else {
+        vec![]
+    };
     let body = if cx.peek(token::OpenBrace) {
         Some(parse_block(cx)?)
     } else {
         cx.expect(token::Semi)?;
         None
     };
-    Ok(SpecFunc { name, sort_vars, params, output, body, hide })
+    Ok(SpecFunc { name, sort_vars, params, output, decreases, body, hide })
 }
 
 /// ```text
@@ -771,7 +779,7 @@ fn mut_as_strg(inputs: Vec<FnInput>, ensures: &[Ensures]) -> ParseResult<Vec<FnI
 ///             ⟨ [ ⟨refine_param⟩,* ] ⟩?
 ///             ( ⟨fn_inputs⟩,* )
 ///             ⟨-> ⟨ty⟩⟩?
-///             ⟨requires⟩ ⟨ensures⟩ ⟨where⟩
+///             ⟨requires⟩ ⟨ensures⟩ ⟨decreases⟩ ⟨where⟩
 /// ```
 pub(crate) fn parse_fn_sig<T: PeekExpected>(cx: &mut ParseCtxt, end: T) -> ParseResult<FnSig> {
     let lo = cx.lo();
@@ -788,6 +796,7 @@ pub(crate) fn parse_fn_sig<T: PeekExpected>(cx: &mut ParseCtxt, end: T) -> Parse
     let returns = parse_fn_ret(cx)?;
     let requires = parse_opt_requires(cx)?;
     let ensures = parse_opt_ensures(cx)?;
+    let decreases = parse_opt_decreases(cx)?;
     let inputs = mut_as_strg(inputs, &ensures)?;
     generics.predicates = parse_opt_where(cx)?;
     cx.expect(end)?;
@@ -803,6 +812,8 @@ pub(crate) fn parse_fn_sig<T: PeekExpected>(cx: &mut ParseCtxt, end: T) -> Parse
         node_id: cx.next_node_id(),
         span: cx.mk_span(lo, hi),
         no_panic: None, // We attach the `no_panic` expr later
+        decreases,
+        loop_invariants: vec![], // Loop invariants are collected from the body later
     })
 }
 
@@ -816,7 +827,12 @@ fn parse_opt_requires(cx: &mut ParseCtxt) -> ParseResult<Vec<Requires>> {
     punctuated_until(
         cx,
         Comma,
-        |t: TokenKind| t.is_keyword(kw::Ensures) || t.is_keyword(kw::Where) || t.is_eof(),
+        |t: TokenKind| {
+            t.is_keyword(kw::Ensures)
+                || t.is_keyword(kw::Decreases)
+                || t.is_keyword(kw::Where)
+                || t.is_eof()
+        },
         parse_requires_clause,
     )
 }
@@ -844,7 +860,7 @@ fn parse_opt_ensures(cx: &mut ParseCtxt) -> ParseResult<Vec<Ensures>> {
     punctuated_until(
         cx,
         Comma,
-        |t: TokenKind| t.is_keyword(kw::Where) || t.is_eof(),
+        |t: TokenKind| t.is_keyword(kw::Decreases) || t.is_keyword(kw::Where) || t.is_eof(),
         parse_ensures_clause,
     )
 }
@@ -866,6 +882,23 @@ fn parse_ensures_clause(cx: &mut ParseCtxt) -> ParseResult<Ensures> {
     }
 }
 
+/// ```text
+/// ⟨decreases⟩ := ⟨decreases ⟨decreases_list⟩⟩?
+/// ```
+fn parse_opt_decreases(cx: &mut ParseCtxt) -> ParseResult<Vec<Expr>> {
+    if !cx.advance_if(kw::Decreases) {
+        return Ok(vec![]);
+    }
+    parse_decreases_list(cx)
+}
+
+/// ```text
+/// ⟨decreases_list⟩ := ⟨expr⟩,+
+/// ```
+pub(crate) fn parse_decreases_list(cx: &mut ParseCtxt) -> ParseResult<Vec<Expr>> {
+    sep1(cx, Comma, |cx| parse_expr(cx, true))
+}
+
 fn parse_opt_where(cx: &mut ParseCtxt) -> ParseResult<Option<Vec<WhereBoundPredicate>>> {
     if !cx.advance_if(kw::Where) {
         return Ok(None);
@@ -1440,12 +1473,13 @@ fn parse_trailer_expr(cx: &mut ParseCtxt, allow_struct: bool) -> ParseResult<Exp
 ///         | ( ⟨expr⟩ )
 ///         | ( ⟨expr⟩,* )
 ///         | ⟨epath⟩
-///         | ⟨bounded_quant⟩
+///         | ⟨quant⟩
 ///         |  <⟨ty⟩ as ⟨path⟩> :: ⟨ident⟩
 ///         | [binop]
 ///         | ⟨epath⟩ { ⟨constructor_arg⟩,* }    if allow_struct
 ///         | { ⟨constructor_arg⟩,* }            if allow_struct
 ///         | #{ ⟨expr⟩,* }
+///         | #[ ⟨expr⟩,* ]
 /// ```
 fn parse_atom(cx: &mut ParseCtxt, allow_struct: bool) -> ParseResult<Expr> {
     let lo = cx.lo();
@@ -1471,11 +1505,15 @@ fn parse_atom(cx: &mut ParseCtxt, allow_struct: bool) -> ParseResult<Expr> {
             })
         }
     } else if lookahead.advance_if(token::Pound) {
-        // #{ ⟨expr⟩,* }
         let lo = cx.lo();
-        let exprs = braces(cx, Comma, |cx| parse_expr(cx, true))?;
+        let kind = if cx.peek(token::OpenBracket) {
+            // #[ ⟨expr⟩,* ]
+            ExprKind::SeqLiteral(brackets(cx, Comma, |cx| parse_expr(cx, true))?)
+        } else {
+            // #{ ⟨expr⟩,* }
+            ExprKind::SetLiteral(braces(cx, Comma, |cx| parse_expr(cx, true))?)
+        };
         let hi = cx.hi();
-        let kind = ExprKind::SetLiteral(exprs);
         Ok(Expr { kind, node_id: cx.next_node_id(), span: cx.mk_span(lo, hi) })
     } else if lookahead.peek(NonReserved) {
         let path = parse_expr_path(cx)?;
@@ -1513,7 +1551,7 @@ fn parse_atom(cx: &mut ParseCtxt, allow_struct: bool) -> ParseResult<Expr> {
     } else if lookahead.peek(token::OpenBracket) {
         parse_prim_uif(cx)
     } else if lookahead.peek(kw::Exists) || lookahead.peek(kw::Forall) {
-        parse_bounded_quantifier(cx)
+        parse_quantifier(cx)
     } else {
         Err(lookahead.into_error())
     }
@@ -1529,10 +1567,12 @@ fn parse_prim_uif(cx: &mut ParseCtxt) -> ParseResult<Expr> {
 }
 
 /// ```text
-/// ⟨bounded_quant⟩ := forall ⟨refine_param⟩ in ⟨int⟩..⟨int⟩ ⟨block⟩
-///                  | exists ⟨refine_param⟩ in ⟨int⟩..⟨int⟩ ⟨block⟩
+/// ⟨quant⟩ := ⟨quant_kind⟩ ⟨refine_param⟩ in ⟨int⟩..⟨int⟩ ⟨block⟩
+///          | ⟨quant_kind⟩ ⟨refine_param⟩,+ ⟨trigger⟩* . ⟨expr⟩
+///
+/// ⟨quant_kind⟩ := forall | exists
 /// ```
-fn parse_bounded_quantifier(cx: &mut ParseCtxt) -> ParseResult<Expr> {
+fn parse_quantifier(cx: &mut ParseCtxt) -> ParseResult<Expr> {
     let lo = cx.lo();
     let mut lookahead = cx.lookahead1();
     let quant = if lookahead.advance_if(kw::Forall) {
@@ -1542,18 +1582,35 @@ fn parse_bounded_quantifier(cx: &mut ParseCtxt) -> ParseResult<Expr> {
     } else {
         return Err(lookahead.into_error());
     };
-    let param = parse_refine_param(cx, RequireSort::Maybe)?;
-    cx.expect(kw::In)?;
-    let start = parse_int(cx)?;
-    cx.expect(token::DotDot)?;
-    let end = parse_int(cx)?;
-    let body = parse_block(cx)?;
+    let mut params = sep1(cx, Comma, |cx| parse_refine_param(cx, RequireSort::Maybe))?;
+    let kind = if params.len() == 1 && cx.advance_if(kw::In) {
+        let start = parse_int(cx)?;
+        cx.expect(token::DotDot)?;
+        let end = parse_int(cx)?;
+        let body = parse_block(cx)?;
+        ExprKind::BoundedQuant(quant, params.remove(0), start..end, Box::new(body))
+    } else {
+        let triggers = repeat_while(cx, token::Pound, parse_trigger)?;
+        cx.expect(token::Dot)?;
+        let body = parse_expr(cx, true)?;
+        ExprKind::Quant(quant, params, triggers, Box::new(body))
+    };
     let hi = cx.hi();
-    Ok(Expr {
-        kind: ExprKind::BoundedQuant(quant, param, start..end, Box::new(body)),
-        node_id: cx.next_node_id(),
-        span: cx.mk_span(lo, hi),
-    })
+    Ok(Expr { kind, node_id: cx.next_node_id(), span: cx.mk_span(lo, hi) })
+}
+
+/// ```text
+/// ⟨trigger⟩ := #[trigger(⟨expr⟩,+)]
+/// ```
+fn parse_trigger(cx: &mut ParseCtxt) -> ParseResult<Trigger> {
+    let lo = cx.lo();
+    cx.expect(token::Pound)?;
+    cx.expect(token::OpenBracket)?;
+    cx.expect(sym::trigger)?;
+    let exprs = delimited(cx, Parenthesis, |cx| sep1(cx, Comma, |cx| parse_expr(cx, true)))?;
+    cx.expect(token::CloseBracket)?;
+    let hi = cx.hi();
+    Ok(Trigger { exprs, span: cx.mk_span(lo, hi) })
 }
 
 /// ```text
@@ -72,6 +72,8 @@ pub struct SpecFunc {
     pub sort_vars: Vec<Ident>,
     pub params: RefineParams,
     pub output: Sort,
+    /// Metric used to check termination of recursive definitions, e.g., `decreases n`.
+    pub decreases: Vec<Expr>,
     /// Body of the function. If not present this definition corresponds to an uninterpreted function.
     pub body: Option<Expr>,
     /// Is this function "hidden" i.e. to be considered
@@ -362,6 +364,32 @@ pub struct FnSig {
     pub span: Span,
     pub node_id: NodeId,
     pub no_panic: Option<Expr>,
+    /// example: `decreases n`
+    pub decreases: Vec<Expr>,
+    /// Invariants attached to loops in the body of the function with `#[flux::loop_invariant]`.
+    pub loop_invariants: Vec<LoopInvariant>,
+}
+
+/// A loop invariant, e.g., `#[flux::loop_invariant(i <= n)]`, or a loop variant, e.g.,
+/// `#[flux::loop_variant(n - i)]`. Rust locals mentioned in the annotation are bound as parameters
+/// denoting the value of the local at the head of the loop.
+#[derive(Debug)]
+pub struct LoopInvariant {
+    pub kind: LoopInvariantKind,
+    /// The locals mentioned in the invariant. The span of each parameter is the span of the
+    /// binding introducing the local.
+    pub params: RefineParams,
+    pub pred: Expr,
+    /// The span of the loop
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopInvariantKind {
+    /// A boolean predicate that must hold at the head of the loop
+    Invariant,
+    /// An integer metric that must stay non-negative and strictly decrease at every iteration
+    Variant,
 }
 
 #[derive(Debug)]
@@ -726,13 +754,25 @@ pub enum ExprKind {
     IfThenElse(Box<[Expr; 3]>),
     Constructor(Option<ExprPath>, Vec<ConstructorArg>),
     BoundedQuant(QuantKind, RefineParam, Range<usize>, Box<Expr>),
+    /// Unbounded quantifier `forall x: int, y: int #[trigger(f(x, y))]. p(x, y)`
+    Quant(QuantKind, Vec<RefineParam>, Vec<Trigger>, Box<Expr>),
     Block(Vec<LetDecl>, Box<Expr>),
     /// Set expression `#{ e1, e2, ..., en }`
     SetLiteral(Vec<Expr>),
+    /// Sequence expression `#[ e1, e2, ..., en ]`
+    SeqLiteral(Vec<Expr>),
     /// Tuple expression `(e1, e2, ..., en)`
     Tuple(Vec<Expr>),
 }
 
+/// A trigger pattern for a quantifier `#[trigger(e1, ..., en)]`. A trigger with more than one
+/// expression is a multi-pattern: all expressions must be matched to instantiate the quantifier.
+#[derive(Debug)]
+pub struct Trigger {
+    pub exprs: Vec<Expr>,
+    pub span: Span,
+}
+
 #[derive(Debug)]
 pub struct LetDecl {
     pub param: RefineParam,
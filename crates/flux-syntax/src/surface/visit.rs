@@ -11,9 +11,9 @@ use super::{
     Async, BaseSort, BaseTy, BaseTyKind, ConstArg, ConstantInfo, ConstructorArg, Ensures, EnumDef,
     Expr, ExprKind, ExprPath, ExprPathSegment, FieldExpr, FnInput, FnOutput, FnRetTy, FnSig,
     GenericArg, GenericArgKind, GenericParam, Generics, Impl, ImplAssocReft, Indices, ItemKind,
-    Lit, Path, PathSegment, Qualifier, RefineArg, RefineParam, Sort, SortPath, SpecFunc, StructDef,
-    Trait, TraitAssocReft, TraitRef, Ty, TyAlias, TyKind, VariantDef, VariantRet,
-    WhereBoundPredicate,
+    Lit, LoopInvariant, Path, PathSegment, Qualifier, RefineArg, RefineParam, Sort, SortPath,
+    SpecFunc, StructDef, Trait, TraitAssocReft, TraitRef, Ty, TyAlias, TyKind, VariantDef,
+    VariantRet, WhereBoundPredicate,
 };
 use crate::surface::{FluxItem, ImplItemFn, Item, PrimOpProp, SortDecl, TraitItemFn};
 
@@ -130,6 +130,10 @@ pub trait Visitor: Sized {
         walk_fn_sig(self, fn_sig);
     }
 
+    fn visit_loop_invariant(&mut self, invariant: &LoopInvariant) {
+        walk_loop_invariant(self, invariant);
+    }
+
     fn visit_fn_output(&mut self, fn_output: &FnOutput) {
         walk_fn_output(self, fn_output);
     }
@@ -235,6 +239,7 @@ pub fn walk_defn<V: Visitor>(vis: &mut V, defn: &SpecFunc) {
     walk_list!(vis, visit_ident, defn.sort_vars.iter().copied());
     walk_list!(vis, visit_refine_param, &defn.params);
     vis.visit_sort(&defn.output);
+    walk_list!(vis, visit_expr, &defn.decreases);
     if let Some(body) = &defn.body {
         vis.visit_expr(body);
     }
@@ -418,9 +423,16 @@ pub fn walk_fn_sig<V: Visitor>(vis: &mut V, fn_sig: &FnSig) {
     if let Some(no_panic_expr) = &fn_sig.no_panic {
         vis.visit_expr(no_panic_expr);
     }
+    walk_list!(vis, visit_expr, &fn_sig.decreases);
+    walk_list!(vis, visit_loop_invariant, &fn_sig.loop_invariants);
     vis.visit_fn_output(&fn_sig.output);
 }
 
+pub fn walk_loop_invariant<V: Visitor>(vis: &mut V, invariant: &LoopInvariant) {
+    walk_list!(vis, visit_refine_param, &invariant.params);
+    vis.visit_expr(&invariant.pred);
+}
+
 pub fn walk_fn_output<V: Visitor>(vis: &mut V, fn_output: &FnOutput) {
     vis.visit_fn_ret_ty(&fn_output.returns);
     walk_list!(vis, visit_ensures, &fn_output.ensures);
@@ -615,6 +627,13 @@ pub fn walk_expr<V: Visitor>(vis: &mut V, expr: &Expr) {
             vis.visit_refine_param(i);
             vis.visit_expr(e);
         }
+        ExprKind::Quant(_, params, triggers, e) => {
+            walk_list!(vis, visit_refine_param, params);
+            for trigger in triggers {
+                walk_list!(vis, visit_expr, &trigger.exprs);
+            }
+            vis.visit_expr(e);
+        }
         ExprKind::Block(decls, body) => {
             for decl in decls {
                 // the order here is important because the parameter is not in scope
@@ -624,7 +643,7 @@ pub fn walk_expr<V: Visitor>(vis: &mut V, expr: &Expr) {
             }
             vis.visit_expr(body);
         }
-        ExprKind::SetLiteral(exprs) => {
+        ExprKind::SetLiteral(exprs) | ExprKind::SeqLiteral(exprs) => {
             walk_list!(vis, visit_expr, exprs);
         }
         ExprKind::Tuple(exprs) => {
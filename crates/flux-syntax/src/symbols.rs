@@ -18,16 +18,20 @@ symbols! {
         Reft: "reft",
         Invariant: "invariant",
         RefinedBy: "refined_by",
+        Decreases: "decreases",
     }
 
     Symbols {
         Map,
+        Seq,
         Set,
         int,
         no_panic,
         no_panic_if,
+        ptr_offset,
         ptr_size,
         real,
+        trigger,
     }
 }
 
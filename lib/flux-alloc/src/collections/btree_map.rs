@@ -0,0 +1,96 @@
+use std::{
+    alloc::{Allocator, Global},
+    borrow::Borrow,
+    collections::{
+        BTreeMap,
+        btree_map::{Entry, OccupiedEntry, VacantEntry},
+    },
+};
+
+use flux_attrs::*;
+
+//---------------------------------------------------------------------------------------
+#[extern_spec]
+#[refined_by(len: int, keys: Set<K>, vals: Map<K, V>)]
+#[invariant(0 <= len)]
+struct BTreeMap<K, V, A: Allocator + Clone = Global>;
+
+#[extern_spec]
+#[refined_by(occupied: bool)]
+enum Entry<'a, K, V, A: Allocator + Clone = Global> {
+    #[variant((VacantEntry<K, V, A>) -> Entry<K, V, A>[false])]
+    Vacant(VacantEntry<'a, K, V, A>),
+    #[variant((OccupiedEntry<K, V, A>) -> Entry<K, V, A>[true])]
+    Occupied(OccupiedEntry<'a, K, V, A>),
+}
+
+//---------------------------------------------------------------------------------------
+
+#[extern_spec]
+impl<K, V> BTreeMap<K, V> {
+    #[spec(fn() -> BTreeMap<K, V>{m: m.len == 0 && m.keys == set_empty(0)})]
+    fn new() -> BTreeMap<K, V>;
+}
+
+#[extern_spec]
+impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
+    #[spec(fn(&BTreeMap<K, V, A>[@m]) -> usize[m.len])]
+    fn len(&self) -> usize;
+
+    #[spec(fn(&BTreeMap<K, V, A>[@m]) -> bool[m.len == 0])]
+    fn is_empty(&self) -> bool;
+}
+
+#[extern_spec]
+impl<K, V, A: Allocator + Clone> BTreeMap<K, V, A> {
+    #[spec(fn(self: &mut BTreeMap<K, V, A>[@m], k: K)
+           -> Entry<K, V, A>{e: <K as Eq>::injective() => (e <=> set_is_in(k, m.keys))}
+           ensures self: BTreeMap<K, V, A>{n:
+               m.len <= n.len && n.len <= m.len + 1 &&
+               set_subset(m.keys, n.keys) && set_subset(n.keys, set_union(m.keys, set_singleton(k)))
+           })]
+    fn entry(&mut self, key: K) -> Entry<'_, K, V, A>
+    where
+        K: Ord;
+
+    #[spec(fn(&BTreeMap<K, V, A>[@m], &Q[@q])
+           -> Option<&V{v: <K as Eq>::injective() => v == map_select(m.vals, <K as Borrow<Q>>::to_owned(q))}>
+                {b: <K as Eq>::injective() => (b <=> set_is_in(<K as Borrow<Q>>::to_owned(q), m.keys))})]
+    fn get<'a, Q: ?Sized + Ord>(&'a self, key: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q> + Ord;
+
+    #[spec(fn(&BTreeMap<K, V, A>[@m], &Q[@q])
+           -> bool{b: <K as Eq>::injective() => (b <=> set_is_in(<K as Borrow<Q>>::to_owned(q), m.keys))})]
+    fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q> + Ord;
+
+    #[spec(fn(self: &mut BTreeMap<K, V, A>[@m], k: K, v: V)
+           -> Option<V{r: <K as Eq>::injective() => r == map_select(m.vals, k)}>
+                {b: <K as Eq>::injective() => (b <=> set_is_in(k, m.keys))}
+           ensures self: BTreeMap<K, V, A>{n:
+               m.len <= n.len && n.len <= m.len + 1 &&
+               (<K as Eq>::injective() =>
+                   n.len == (if set_is_in(k, m.keys) { m.len } else { m.len + 1 }) &&
+                   n.keys == set_union(m.keys, set_singleton(k)) &&
+                   n.vals == map_store(m.vals, k, v))
+           })]
+    fn insert(&mut self, key: K, value: V) -> Option<V>
+    where
+        K: Ord;
+
+    #[spec(fn(self: &mut BTreeMap<K, V, A>[@m], &Q[@q])
+           -> Option<V{r: <K as Eq>::injective() => r == map_select(m.vals, <K as Borrow<Q>>::to_owned(q))}>
+                {b: <K as Eq>::injective() => (b <=> set_is_in(<K as Borrow<Q>>::to_owned(q), m.keys))}
+           ensures self: BTreeMap<K, V, A>{n:
+               n.len <= m.len && m.len <= n.len + 1 &&
+               (<K as Eq>::injective() =>
+                   n.len == (if set_is_in(<K as Borrow<Q>>::to_owned(q), m.keys) { m.len - 1 } else { m.len }) &&
+                   n.keys == set_difference(m.keys, set_singleton(<K as Borrow<Q>>::to_owned(q))) &&
+                   n.vals == m.vals)
+           })]
+    fn remove<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q> + Ord;
+}
@@ -0,0 +1,60 @@
+use std::{
+    alloc::{Allocator, Global},
+    borrow::Borrow,
+    collections::BTreeSet,
+};
+
+use flux_attrs::*;
+
+//---------------------------------------------------------------------------------------
+#[extern_spec]
+#[refined_by(len: int, elems: Set<T>)]
+#[invariant(0 <= len)]
+struct BTreeSet<T, A: Allocator + Clone = Global>;
+
+//---------------------------------------------------------------------------------------
+
+#[extern_spec]
+impl<T> BTreeSet<T> {
+    #[spec(fn() -> BTreeSet<T>{s: s.len == 0 && s.elems == set_empty(0)})]
+    fn new() -> BTreeSet<T>;
+}
+
+#[extern_spec]
+impl<T, A: Allocator + Clone> BTreeSet<T, A> {
+    #[spec(fn(&BTreeSet<T, A>[@s]) -> usize[s.len])]
+    fn len(&self) -> usize;
+
+    #[spec(fn(&BTreeSet<T, A>[@s]) -> bool[s.len == 0])]
+    fn is_empty(&self) -> bool;
+
+    #[spec(fn(&BTreeSet<T, A>[@s], &Q[@q])
+           -> bool{b: <T as Eq>::injective() => (b <=> set_is_in(<T as Borrow<Q>>::to_owned(q), s.elems))})]
+    fn contains<Q: ?Sized + Ord>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q> + Ord;
+
+    #[spec(fn(self: &mut BTreeSet<T, A>[@s], x: T)
+           -> bool{b: <T as Eq>::injective() => (b <=> !set_is_in(x, s.elems))}
+           ensures self: BTreeSet<T, A>{n:
+               s.len <= n.len && n.len <= s.len + 1 &&
+               (<T as Eq>::injective() =>
+                   n.len == (if set_is_in(x, s.elems) { s.len } else { s.len + 1 }) &&
+                   n.elems == set_union(s.elems, set_singleton(x)))
+           })]
+    fn insert(&mut self, value: T) -> bool
+    where
+        T: Ord;
+
+    #[spec(fn(self: &mut BTreeSet<T, A>[@s], &Q[@q])
+           -> bool{b: <T as Eq>::injective() => (b <=> set_is_in(<T as Borrow<Q>>::to_owned(q), s.elems))}
+           ensures self: BTreeSet<T, A>{n:
+               n.len <= s.len && s.len <= n.len + 1 &&
+               (<T as Eq>::injective() =>
+                   n.len == (if set_is_in(<T as Borrow<Q>>::to_owned(q), s.elems) { s.len - 1 } else { s.len }) &&
+                   n.elems == set_difference(s.elems, set_singleton(<T as Borrow<Q>>::to_owned(q))))
+           })]
+    fn remove<Q: ?Sized + Ord>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q> + Ord;
+}
@@ -0,0 +1,92 @@
+use std::{
+    borrow::Borrow,
+    collections::{
+        HashMap,
+        hash_map::{Entry, OccupiedEntry, VacantEntry},
+    },
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use flux_attrs::*;
+
+//---------------------------------------------------------------------------------------
+#[extern_spec]
+#[refined_by(len: int, keys: Set<K>, vals: Map<K, V>)]
+#[invariant(0 <= len)]
+struct HashMap<K, V, S = RandomState>;
+
+#[extern_spec]
+#[refined_by(occupied: bool)]
+enum Entry<'a, K, V> {
+    #[variant((OccupiedEntry<K, V>) -> Entry<K, V>[true])]
+    Occupied(OccupiedEntry<'a, K, V>),
+    #[variant((VacantEntry<K, V>) -> Entry<K, V>[false])]
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+//---------------------------------------------------------------------------------------
+
+#[extern_spec]
+impl<K, V> HashMap<K, V, RandomState> {
+    #[spec(fn() -> HashMap<K, V, RandomState>{m: m.len == 0 && m.keys == set_empty(0)})]
+    fn new() -> HashMap<K, V, RandomState>;
+}
+
+#[extern_spec]
+impl<K, V, S> HashMap<K, V, S> {
+    #[spec(fn(&HashMap<K, V, S>[@m]) -> usize[m.len])]
+    fn len(&self) -> usize;
+
+    #[spec(fn(&HashMap<K, V, S>[@m]) -> bool[m.len == 0])]
+    fn is_empty(&self) -> bool;
+}
+
+#[extern_spec]
+impl<K: Eq + Hash, V, S: BuildHasher> HashMap<K, V, S> {
+    #[spec(fn(self: &mut HashMap<K, V, S>[@m], k: K)
+           -> Entry<K, V>{e: <K as Eq>::injective() => (e <=> set_is_in(k, m.keys))}
+           ensures self: HashMap<K, V, S>{n:
+               m.len <= n.len && n.len <= m.len + 1 &&
+               set_subset(m.keys, n.keys) && set_subset(n.keys, set_union(m.keys, set_singleton(k)))
+           })]
+    fn entry(&mut self, key: K) -> Entry<'_, K, V>;
+
+    #[spec(fn(&HashMap<K, V, S>[@m], &Q[@q])
+           -> Option<&V{v: <K as Eq>::injective() => v == map_select(m.vals, <K as Borrow<Q>>::to_owned(q))}>
+                {b: <K as Eq>::injective() => (b <=> set_is_in(<K as Borrow<Q>>::to_owned(q), m.keys))})]
+    fn get<'a, Q: ?Sized + Hash + Eq>(&'a self, k: &Q) -> Option<&'a V>
+    where
+        K: Borrow<Q>;
+
+    #[spec(fn(&HashMap<K, V, S>[@m], &Q[@q])
+           -> bool{b: <K as Eq>::injective() => (b <=> set_is_in(<K as Borrow<Q>>::to_owned(q), m.keys))})]
+    fn contains_key<Q: ?Sized + Hash + Eq>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>;
+
+    #[spec(fn(self: &mut HashMap<K, V, S>[@m], k: K, v: V)
+           -> Option<V{r: <K as Eq>::injective() => r == map_select(m.vals, k)}>
+                {b: <K as Eq>::injective() => (b <=> set_is_in(k, m.keys))}
+           ensures self: HashMap<K, V, S>{n:
+               m.len <= n.len && n.len <= m.len + 1 &&
+               (<K as Eq>::injective() =>
+                   n.len == (if set_is_in(k, m.keys) { m.len } else { m.len + 1 }) &&
+                   n.keys == set_union(m.keys, set_singleton(k)) &&
+                   n.vals == map_store(m.vals, k, v))
+           })]
+    fn insert(&mut self, k: K, v: V) -> Option<V>;
+
+    #[spec(fn(self: &mut HashMap<K, V, S>[@m], &Q[@q])
+           -> Option<V{r: <K as Eq>::injective() => r == map_select(m.vals, <K as Borrow<Q>>::to_owned(q))}>
+                {b: <K as Eq>::injective() => (b <=> set_is_in(<K as Borrow<Q>>::to_owned(q), m.keys))}
+           ensures self: HashMap<K, V, S>{n:
+               n.len <= m.len && m.len <= n.len + 1 &&
+               (<K as Eq>::injective() =>
+                   n.len == (if set_is_in(<K as Borrow<Q>>::to_owned(q), m.keys) { m.len - 1 } else { m.len }) &&
+                   n.keys == set_difference(m.keys, set_singleton(<K as Borrow<Q>>::to_owned(q))) &&
+                   n.vals == m.vals)
+           })]
+    fn remove<Q: ?Sized + Hash + Eq>(&mut self, k: &Q) -> Option<V>
+    where
+        K: Borrow<Q>;
+}
@@ -0,0 +1,61 @@
+use std::{
+    borrow::Borrow,
+    collections::HashSet,
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use flux_attrs::*;
+
+//---------------------------------------------------------------------------------------
+#[extern_spec]
+#[refined_by(len: int, elems: Set<T>)]
+#[invariant(0 <= len)]
+struct HashSet<T, S = RandomState>;
+
+//---------------------------------------------------------------------------------------
+
+#[extern_spec]
+impl<T> HashSet<T, RandomState> {
+    #[spec(fn() -> HashSet<T, RandomState>{s: s.len == 0 && s.elems == set_empty(0)})]
+    fn new() -> HashSet<T, RandomState>;
+}
+
+#[extern_spec]
+impl<T, S> HashSet<T, S> {
+    #[spec(fn(&HashSet<T, S>[@s]) -> usize[s.len])]
+    fn len(&self) -> usize;
+
+    #[spec(fn(&HashSet<T, S>[@s]) -> bool[s.len == 0])]
+    fn is_empty(&self) -> bool;
+}
+
+#[extern_spec]
+impl<T: Eq + Hash, S: BuildHasher> HashSet<T, S> {
+    #[spec(fn(&HashSet<T, S>[@s], &Q[@q])
+           -> bool{b: <T as Eq>::injective() => (b <=> set_is_in(<T as Borrow<Q>>::to_owned(q), s.elems))})]
+    fn contains<Q: ?Sized + Hash + Eq>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>;
+
+    #[spec(fn(self: &mut HashSet<T, S>[@s], x: T)
+           -> bool{b: <T as Eq>::injective() => (b <=> !set_is_in(x, s.elems))}
+           ensures self: HashSet<T, S>{n:
+               s.len <= n.len && n.len <= s.len + 1 &&
+               (<T as Eq>::injective() =>
+                   n.len == (if set_is_in(x, s.elems) { s.len } else { s.len + 1 }) &&
+                   n.elems == set_union(s.elems, set_singleton(x)))
+           })]
+    fn insert(&mut self, value: T) -> bool;
+
+    #[spec(fn(self: &mut HashSet<T, S>[@s], &Q[@q])
+           -> bool{b: <T as Eq>::injective() => (b <=> set_is_in(<T as Borrow<Q>>::to_owned(q), s.elems))}
+           ensures self: HashSet<T, S>{n:
+               n.len <= s.len && s.len <= n.len + 1 &&
+               (<T as Eq>::injective() =>
+                   n.len == (if set_is_in(<T as Borrow<Q>>::to_owned(q), s.elems) { s.len - 1 } else { s.len }) &&
+                   n.elems == set_difference(s.elems, set_singleton(<T as Borrow<Q>>::to_owned(q))))
+           })]
+    fn remove<Q: ?Sized + Hash + Eq>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>;
+}
@@ -0,0 +1,17 @@
+//! Specifications for maps and sets in `std::collections`.
+//!
+//! Maps are refined by their length, the set of keys they contain, and a logical map from keys to
+//! values. Sets are refined by their length and the logical set of their elements. Methods taking a
+//! borrowed key `&Q` refer to the stored key through `<K as Borrow<Q>>::to_owned`, which is the
+//! identity when `Q` and `K` are the same type.
+//!
+//! Keys are tracked by their refinement index, so membership, lookups and the exact effect of
+//! `insert`/`remove` are only known when the key type declares `<K as Eq>::injective()`, i.e.,
+//! equal indices imply equal keys. This holds for the primitive integers, `bool`, `char` and
+//! `String`; other key types (e.g., an unrefined struct, whose values all share one index) only get
+//! bounds on the length.
+
+pub mod btree_map;
+pub mod btree_set;
+pub mod hash_map;
+pub mod hash_set;
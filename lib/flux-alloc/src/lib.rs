@@ -13,6 +13,9 @@ pub mod string;
 #[cfg(flux)]
 pub mod rc;
 
+#[cfg(flux)]
+pub mod collections;
+
 // TODO(RJ): I get an "unused extern crate" warning here,
 // but without it, `in_bounds` is not in scope in `lib/vec/mod.rs`.
 #[cfg(flux)]
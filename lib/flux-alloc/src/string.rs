@@ -14,6 +14,10 @@ impl PartialEq for String {
     fn eq(&self, other: &String) -> bool;
 }
 
+#[extern_spec]
+#[assoc(fn injective() -> bool { true })]
+impl Eq for String {}
+
 #[extern_spec]
 #[assoc(fn cloned(old: Self, new: Self) -> bool { old == new } )]
 impl Clone for String {
@@ -0,0 +1,77 @@
+use std::{
+    alloc::{Allocator, Global},
+    ops::{Index, IndexMut},
+    slice::SliceIndex,
+};
+
+use flux_attrs::*;
+
+//---------------------------------------------------------------------------------------
+#[extern_spec]
+#[refined_by(len: int, elems: Seq<T>)]
+#[invariant(0 <= len && len == seq_len(elems))]
+struct Vec<T, A: Allocator = Global>;
+
+//---------------------------------------------------------------------------------------
+
+#[extern_spec]
+impl<T> Vec<T> {
+    #[flux::sig(fn() -> Vec<T>[0, #[]])]
+    fn new() -> Vec<T>;
+}
+
+#[extern_spec]
+impl<T, A: Allocator> Vec<T, A> {
+    #[spec(fn(self: &mut Vec<T, A>[@n, @s], x: T) ensures self: Vec<T, A>[n+1, seq_concat(s, #[x])])]
+    fn push(v: &mut Vec<T, A>, value: T);
+
+    #[spec(fn(&Vec<T, A>[@n, @s]) -> usize[n])]
+    fn len(v: &Vec<T, A>) -> usize;
+
+    #[spec(
+        fn(self: &mut Vec<T, A>[@n, @s]) -> Option<T[seq_nth(s, n-1)]>[n > 0]
+        ensures self: Vec<T, A>[if n > 0 { n-1 } else { 0 }, if n > 0 { seq_sub(s, 0, n-1) } else { s }]
+    )]
+    fn pop(&mut self) -> Option<T>;
+
+    #[spec(
+        fn(self: &mut Vec<T, A>[@n, @s], i: usize, x: T)
+        requires i <= n
+        ensures self: Vec<T, A>[n+1, seq_concat(seq_concat(seq_sub(s, 0, i), #[x]), seq_sub(s, i, n-i))]
+    )]
+    fn insert(&mut self, index: usize, element: T);
+
+    #[spec(fn(self: &Vec<T, A>[@n, @s]) -> bool[n == 0])]
+    fn is_empty(&self) -> bool;
+}
+
+//---------------------------------------------------------------------------------------
+
+// The output of indexing depends on `I` (an element for `usize`, a slice for ranges), so its
+// refinement can't mention `elems` and we only check the bounds.
+#[extern_spec]
+impl<T, I: SliceIndex<[T]>, A: Allocator> Index<I> for Vec<T, A> {
+    #[spec(fn(&Vec<T, A>[@len, @s], {I[@idx] | <I as SliceIndex<[T]>>::in_bounds(idx, len)}) -> _)]
+    fn index(z: &Vec<T, A>, index: I) -> &<I as SliceIndex<[T]>>::Output;
+}
+
+// Writes through the returned reference can change any element, so we only keep the length.
+#[extern_spec]
+impl<T, I: SliceIndex<[T]>, A: Allocator> IndexMut<I> for Vec<T, A> {
+    #[spec(
+        fn(self: &mut Vec<T, A>[@len, @s], {I[@idx] | <I as SliceIndex<[T]>>::in_bounds(idx, len)}) -> _
+        ensures self: Vec<T, A>{v: v.len == len}
+    )]
+    fn index_mut(z: &mut Vec<T, A>, index: I) -> &mut <I as SliceIndex<[T]>>::Output;
+}
+
+//---------------------------------------------------------------------------------------
+#[extern_spec]
+impl<'a, T, A: Allocator> IntoIterator for &'a Vec<T, A> {
+    #[spec(fn (&Vec<T, A>[@n, @s]) -> <&Vec<T, A> as IntoIterator>::IntoIter[0,n])]
+    fn into_iter(v: &'a Vec<T, A>) -> <&'a Vec<T, A> as IntoIterator>::IntoIter;
+}
+
+#[extern_spec]
+#[assoc(fn with_size(self: Self, n:int) -> bool { self.len == n })]
+impl<T> FromIterator<T> for Vec<T> {}
@@ -124,6 +124,21 @@ pub fn no_panic_if(attrs: TokenStream, tokens: TokenStream) -> TokenStream {
     attr_impl::no_panic_if(attrs, tokens)
 }
 
+#[proc_macro_attribute]
+pub fn decreases(attrs: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::decreases(attrs, tokens)
+}
+
+#[proc_macro_attribute]
+pub fn loop_invariant(attrs: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::loop_invariant(attrs, tokens)
+}
+
+#[proc_macro_attribute]
+pub fn loop_variant(attrs: TokenStream, tokens: TokenStream) -> TokenStream {
+    attr_impl::loop_variant(attrs, tokens)
+}
+
 #[proc_macro_attribute]
 pub fn reft(attrs: TokenStream, tokens: TokenStream) -> TokenStream {
     attr_impl::reft(attrs, tokens)
@@ -177,6 +192,9 @@ mod attr_sysroot {
         reft,
         no_panic,
         no_panic_if,
+        decreases,
+        loop_invariant,
+        loop_variant,
     );
 }
 
@@ -227,6 +245,9 @@ mod attr_dummy {
         should_fail,
         no_panic,
         no_panic_if,
+        decreases,
+        loop_invariant,
+        loop_variant,
         reft,
     );
 }
@@ -0,0 +1,12 @@
+use flux_attrs::*;
+
+/// `to_owned(x)` is the value of type `Self` that borrows as `x`. For the blanket implementation
+/// `T: Borrow<T>` this is the identity, which allows specifications of collections that are indexed
+/// by borrowed keys (e.g., `HashMap::get`) to talk about the key stored in the collection.
+#[extern_spec(core::borrow)]
+#[assoc(fn to_owned(x: Borrowed) -> Self)]
+trait Borrow<Borrowed> {}
+
+#[extern_spec(core::borrow)]
+#[assoc(fn to_owned(x: T) -> T { x })]
+impl<T> Borrow<T> for T {}
@@ -14,3 +14,48 @@ trait PartialEq<Rhs: PointeeSized = Self>: PointeeSized {
     #[spec(fn(&Self[@s], &Rhs[@t]) -> bool{v: Self::is_ne(s, t, v)})]
     fn ne(&self, other: &Rhs) -> bool;
 }
+
+/// `injective()` holds when two values of `Self` are equal according to `Eq` exactly when their
+/// refinement indices are equal. Specifications that track values by their index (e.g., the keys
+/// of a map) only use the index when `injective()` holds. The default is `false`; a type opts in
+/// by implementing the associated refinement on its `impl Eq`.
+#[extern_spec]
+#[assoc(
+    fn injective() -> bool { false }
+)]
+trait Eq {}
+
+macro_rules! injective_eq {
+    ($($ty:ident),*) => {$(
+        #[extern_spec(core::cmp)]
+        #[assoc(fn injective() -> bool { true })]
+        impl Eq for $ty {}
+    )*};
+}
+
+injective_eq!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, bool, char);
+
+#[extern_spec(core::cmp)]
+#[refined_by(val: int)]
+enum Ordering {
+    #[variant(Ordering[-1])]
+    Less,
+    #[variant(Ordering[0])]
+    Equal,
+    #[variant(Ordering[1])]
+    Greater,
+}
+
+/// `Ord::cmp` on primitive integers, matching the rule for `BinOp::Cmp` in `flux-refineck`
+macro_rules! int_ord {
+    ($($ty:ident),*) => {$(
+        #[extern_spec(core::cmp)]
+        impl Ord for $ty {
+            #[no_panic]
+            #[spec(fn(&$ty[@a], &$ty[@b]) -> Ordering[if a < b { -1 } else if a == b { 0 } else { 1 }])]
+            fn cmp(&self, other: &$ty) -> Ordering;
+        }
+    )*};
+}
+
+int_ord!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
@@ -29,6 +29,9 @@ mod ptr;
 #[cfg(flux)]
 mod convert;
 
+#[cfg(flux)]
+mod borrow;
+
 // -------------------------------------------------------------------
 
 #[macro_export]
@@ -1,7 +1,41 @@
 use flux_attrs::*;
 
-#[extern_spec(core::num)]
-impl usize {
-    #[no_panic]
-    fn saturating_sub(self, other: usize) -> usize;
+/// The checked and overflowing arithmetic operations return the mathematical result when it's in
+/// the range of the type, and report whether it is otherwise. These mirror the rules for the
+/// overflow-reporting binary ops in `flux-refineck`. There can only be one extern spec for the
+/// methods of each integer type, so they are all here.
+macro_rules! int_ops {
+    ($($ty:ident),*) => {$(
+        #[extern_spec(core::num)]
+        impl $ty {
+            #[no_panic]
+            fn saturating_sub(self, other: $ty) -> $ty;
+
+            #[no_panic]
+            #[sig(fn(a: $ty, b: $ty) -> Option<$ty[a + b]>[$ty::MIN <= a + b && a + b <= $ty::MAX])]
+            fn checked_add(self, rhs: $ty) -> Option<$ty>;
+
+            #[no_panic]
+            #[sig(fn(a: $ty, b: $ty) -> Option<$ty[a - b]>[$ty::MIN <= a - b && a - b <= $ty::MAX])]
+            fn checked_sub(self, rhs: $ty) -> Option<$ty>;
+
+            #[no_panic]
+            #[sig(fn(a: $ty, b: $ty) -> Option<$ty[a * b]>[$ty::MIN <= a * b && a * b <= $ty::MAX])]
+            fn checked_mul(self, rhs: $ty) -> Option<$ty>;
+
+            #[no_panic]
+            #[sig(fn(a: $ty, b: $ty) -> ($ty{v: $ty::MIN <= a + b && a + b <= $ty::MAX => v == a + b}, bool[a + b < $ty::MIN || $ty::MAX < a + b]))]
+            fn overflowing_add(self, rhs: $ty) -> ($ty, bool);
+
+            #[no_panic]
+            #[sig(fn(a: $ty, b: $ty) -> ($ty{v: $ty::MIN <= a - b && a - b <= $ty::MAX => v == a - b}, bool[a - b < $ty::MIN || $ty::MAX < a - b]))]
+            fn overflowing_sub(self, rhs: $ty) -> ($ty, bool);
+
+            #[no_panic]
+            #[sig(fn(a: $ty, b: $ty) -> ($ty{v: $ty::MIN <= a * b && a * b <= $ty::MAX => v == a * b}, bool[a * b < $ty::MIN || $ty::MAX < a * b]))]
+            fn overflowing_mul(self, rhs: $ty) -> ($ty, bool);
+        }
+    )*};
 }
+
+int_ops!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
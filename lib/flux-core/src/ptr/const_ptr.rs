@@ -2,6 +2,13 @@ use flux_attrs::*;
 
 #[extern_spec(core::ptr)]
 impl<T> *const T {
-    #[spec(fn (me: *const[@src] T, count: usize) -> *const{p: ptr_size(p) == ptr_size(src) - count} T)]
+    #[spec(
+        fn (me: *const[@src] T, count: usize) -> *const{p: ptr_size(p) == ptr_size(src) - count && ptr_offset(p) == ptr_offset(src) + count} T
+    )]
     unsafe fn add(self, count: usize) -> Self;
+
+    #[spec(
+        fn (me: *const[@src] T, count: isize) -> *const{p: ptr_size(p) == ptr_size(src) - count && ptr_offset(p) == ptr_offset(src) + count} T
+    )]
+    unsafe fn offset(self, count: isize) -> Self;
 }
@@ -2,6 +2,13 @@ use flux_attrs::*;
 
 #[extern_spec(core::ptr)]
 impl<T> *mut T {
-    #[spec(fn (me: *mut [@src] T, count: usize) -> *mut{p: ptr_size(p) == ptr_size(src) - count} T)]
+    #[spec(
+        fn (me: *mut [@src] T, count: usize) -> *mut{p: ptr_size(p) == ptr_size(src) - count && ptr_offset(p) == ptr_offset(src) + count} T
+    )]
     unsafe fn add(self, count: usize) -> Self;
+
+    #[spec(
+        fn (me: *mut [@src] T, count: isize) -> *mut{p: ptr_size(p) == ptr_size(src) - count && ptr_offset(p) == ptr_offset(src) + count} T
+    )]
+    unsafe fn offset(self, count: isize) -> Self;
 }
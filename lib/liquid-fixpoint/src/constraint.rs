@@ -128,8 +128,10 @@ pub enum Sort<T: Types> {
 impl<T: Types> Sort<T> {
     pub fn deps(&self, acc: &mut Vec<T::Sort>) {
         match self {
-            Sort::App(SortCtor::Data(dt_name), args) => {
-                acc.push(dt_name.clone());
+            Sort::App(ctor, args) => {
+                if let SortCtor::Data(dt_name) = ctor {
+                    acc.push(dt_name.clone());
+                }
                 for arg in args {
                     arg.deps(acc);
                 }
@@ -233,6 +235,7 @@ impl<T: Types> FunSort<T> {
 pub enum SortCtor<T: Types> {
     Set,
     Map,
+    Seq,
     Data(T::Sort),
 }
 
@@ -330,6 +333,9 @@ pub enum Expr<T: Types> {
     ThyFunc(ThyFunc),
     IsCtor(T::Var, Box<Self>),
     Exists(Vec<(T::Var, Sort<T>)>, Box<Self>),
+    /// Universal quantification with a (possibly empty) list of triggers. Each trigger is a
+    /// multi-pattern, i.e., a list of terms that must all be matched to instantiate the quantifier.
+    ForAll(Vec<(T::Var, Sort<T>)>, Vec<Vec<Self>>, Box<Self>),
 }
 
 impl<T: Types> From<Constant<T>> for Expr<T> {
@@ -404,6 +410,15 @@ impl<T: Types> Expr<T> {
                 }
                 expr.var_sorts_to_int();
             }
+            Expr::ForAll(binder, triggers, expr) => {
+                for (_, sort) in binder {
+                    sort.free_var_sorts_to_int();
+                }
+                for term in triggers.iter_mut().flatten() {
+                    term.var_sorts_to_int();
+                }
+                expr.var_sorts_to_int();
+            }
         }
     }
 
@@ -463,6 +478,16 @@ impl<T: Types> Expr<T> {
                 }
                 vars.extend(inner);
             }
+            Expr::ForAll(binder, triggers, expr) => {
+                let mut inner = expr.free_vars();
+                for term in triggers.iter().flatten() {
+                    inner.extend(term.free_vars());
+                }
+                for (var, _sort) in binder {
+                    inner.swap_remove(var);
+                }
+                vars.extend(inner);
+            }
         };
         vars
     }
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, iter};
+use std::{borrow::Cow, collections::HashMap, iter};
 
 use itertools::Itertools;
 
@@ -262,8 +262,13 @@ impl<T: Types> Constraint<T> {
     }
 
     fn elim1(&self, var: &T::KVar) -> Self {
-        let solution = self.scope(var).sol1(var);
-        self.do_elim(var, &solution)
+        self.do_elim(var, &self.elim_solution(var))
+    }
+
+    /// The solution used to eliminate `var`, i.e., the paths (binders and arguments) leading to
+    /// the occurrences of `var` in head position.
+    pub(crate) fn elim_solution(&self, var: &T::KVar) -> Vec<Solution<T>> {
+        self.scope(var).sol1(var)
     }
 
     fn do_elim(&self, var: &T::KVar, solution: &[Solution<T>]) -> Self {
@@ -343,19 +348,7 @@ impl<T: Types> Pred<T> {
                 Pred::and(
                     qualifiers
                         .iter()
-                        .map(|qualifier| {
-                            Pred::Expr(
-                                qualifier
-                                    .0
-                                    .args
-                                    .iter()
-                                    .map(|arg| &arg.0)
-                                    .zip(qualifier.1.iter().map(|arg_idx| &args[*arg_idx]))
-                                    .fold(qualifier.0.body.clone(), |acc, e| {
-                                        acc.substitute(e.0, e.1)
-                                    }),
-                            )
-                        })
+                        .map(|qualifier| Pred::Expr(qualifier.0.instantiate(&qualifier.1, args)))
                         .collect(),
                 )
             }
@@ -375,17 +368,7 @@ impl<T: Types> Pred<T> {
     pub(crate) fn sub_head(&self, assignment: &(&Qualifier<T>, Vec<usize>)) -> Self {
         match self {
             Pred::Expr(expr) => Pred::Expr(expr.clone()),
-            Pred::KVar(_kvid, args) => {
-                Pred::Expr(
-                    assignment
-                        .0
-                        .args
-                        .iter()
-                        .map(|arg| &arg.0)
-                        .zip(assignment.1.iter().map(|arg_idx| &args[*arg_idx]))
-                        .fold(assignment.0.body.clone(), |acc, e| acc.substitute(e.0, e.1)),
-                )
-            }
+            Pred::KVar(_kvid, args) => Pred::Expr(assignment.0.instantiate(&assignment.1, args)),
             _ => panic!("Conjunctions should not occur here"),
         }
     }
@@ -420,11 +403,22 @@ impl<T: Types> Pred<T> {
     }
 }
 
+impl<T: Types> Qualifier<T> {
+    /// Instantiates the body of the qualifier, mapping its `i`-th argument to `args[arg_idxs[i]]`.
+    pub(crate) fn instantiate(&self, arg_idxs: &[usize], args: &[Expr<T>]) -> Expr<T> {
+        let subst = iter::zip(&self.args, arg_idxs)
+            .map(|((name, _), arg_idx)| (name.clone(), args[*arg_idx].clone()))
+            .collect();
+        self.body.substitute_all(&subst)
+    }
+}
+
 impl<T: Types> Expr<T> {
-    fn substitute_in_place(&mut self, v_from: &T::Var, v_to: &Expr<T>) {
+    /// Simultaneously replaces every free variable in the domain of `subst` by its image.
+    fn substitute_in_place(&mut self, subst: &HashMap<T::Var, Expr<T>>) {
         match self {
             Expr::Var(v) => {
-                if v == v_from {
+                if let Some(v_to) = subst.get(v) {
                     *self = v_to.clone();
                 }
             }
@@ -433,44 +427,65 @@ impl<T: Types> Expr<T> {
             | Expr::BinaryOp(_, exprs)
             | Expr::Atom(_, exprs) => {
                 let [e1, e2] = &mut **exprs;
-                e1.substitute_in_place(v_from, v_to);
-                e2.substitute_in_place(v_from, v_to);
+                e1.substitute_in_place(subst);
+                e2.substitute_in_place(subst);
             }
             Expr::Let(_, exprs) => {
                 // We are assuming there's no shadowing here.
                 let [e1, e2] = &mut **exprs;
-                e1.substitute_in_place(v_from, v_to);
-                e2.substitute_in_place(v_from, v_to);
+                e1.substitute_in_place(subst);
+                e2.substitute_in_place(subst);
             }
             Expr::And(exprs) | Expr::Or(exprs) => {
                 exprs
                     .iter_mut()
-                    .for_each(|expr| expr.substitute_in_place(v_from, v_to));
+                    .for_each(|expr| expr.substitute_in_place(subst));
             }
             Expr::App(func, _sort_args, args, _out_sort) => {
-                func.substitute_in_place(v_from, v_to);
+                func.substitute_in_place(subst);
                 args.iter_mut()
-                    .for_each(|expr| expr.substitute_in_place(v_from, v_to));
+                    .for_each(|expr| expr.substitute_in_place(subst));
             }
             Expr::IsCtor(_, e) | Expr::Neg(e) | Expr::Not(e) => {
-                e.substitute_in_place(v_from, v_to);
+                e.substitute_in_place(subst);
             }
             Expr::IfThenElse(exprs) => {
                 let [p, e1, e2] = &mut **exprs;
-                p.substitute_in_place(v_from, v_to);
-                e1.substitute_in_place(v_from, v_to);
-                e2.substitute_in_place(v_from, v_to);
+                p.substitute_in_place(subst);
+                e1.substitute_in_place(subst);
+                e2.substitute_in_place(subst);
             }
             Expr::Constant(_) | Expr::ThyFunc(_) => {}
-            Expr::Exists(..) => {
-                todo!("unexpected! exists")
+            Expr::Exists(binders, body) => {
+                if binders.iter().any(|(name, _)| subst.contains_key(name)) {
+                    let mut subst = subst.clone();
+                    for (name, _) in binders.iter() {
+                        subst.remove(name);
+                    }
+                    body.substitute_in_place(&subst);
+                } else {
+                    body.substitute_in_place(subst);
+                }
+            }
+            Expr::ForAll(binders, triggers, body) => {
+                let mut inner = Cow::Borrowed(subst);
+                if binders.iter().any(|(name, _)| subst.contains_key(name)) {
+                    let inner = inner.to_mut();
+                    for (name, _) in binders.iter() {
+                        inner.remove(name);
+                    }
+                }
+                for term in triggers.iter_mut().flatten() {
+                    term.substitute_in_place(&inner);
+                }
+                body.substitute_in_place(&inner);
             }
         }
     }
 
-    pub(crate) fn substitute(&self, v_from: &T::Var, v_to: &Expr<T>) -> Self {
+    pub(crate) fn substitute_all(&self, subst: &HashMap<T::Var, Expr<T>>) -> Self {
         let mut new_expr = self.clone();
-        new_expr.substitute_in_place(v_from, v_to);
+        new_expr.substitute_in_place(subst);
         new_expr
     }
 }
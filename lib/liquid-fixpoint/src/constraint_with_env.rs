@@ -2,12 +2,19 @@ use derive_where::derive_where;
 #[cfg(feature = "rust-fixpoint")]
 use {
     crate::{
-        FixpointStatus, Sort, SortCtor,
-        cstr2smt2::{Env, is_constraint_satisfiable, new_binding, new_datatype},
+        CrashInfo, Expr, FixpointStatus, Identifier, KVarBind, Pred, Sort, SortCtor,
+        constraint_solving::Solution,
+        cstr2smt2::{
+            Env, check_elaborated, check_expr_elaborated, is_constraint_satisfiable, new_binding,
+            new_datatype,
+        },
         graph,
     },
     itertools::Itertools,
-    std::collections::{HashMap, VecDeque},
+    std::{
+        collections::{BTreeSet, HashMap},
+        time::Instant,
+    },
     z3::Solver,
 };
 
@@ -79,12 +86,31 @@ impl<T: Types> ConstraintWithEnv<T> {
         assignments
     }
 
-    fn solve_for_kvars(&self, solver: &Solver, env: &mut Env<T>) -> Assignments<'_, T> {
+    fn solve_for_kvars(
+        &self,
+        solver: &Solver,
+        env: &mut Env<T>,
+        deadline: Option<Instant>,
+    ) -> Assignments<'_, T> {
         let mut assignments = self.compute_initial_assignments();
-        let kvars_to_fragments = self.constraint.kvar_mappings();
         let topo_order_fragments = self.constraint.topo_order_fragments();
-        let mut work_list = VecDeque::from_iter(topo_order_fragments.iter());
-        while let Some(fragment) = work_list.pop_front() {
+        // The fragments that have to be checked again when the assignment of a kvar is weakened,
+        // i.e., the ones mentioning the kvar in their hypotheses.
+        let mut dependents: HashMap<T::KVar, Vec<usize>> = HashMap::new();
+        for (i, fragment) in topo_order_fragments.iter().enumerate() {
+            for kvar in fragment.kvar_deps().into_iter().unique() {
+                dependents.entry(kvar).or_default().push(i);
+            }
+        }
+        // Fragments are always picked in topological order so the assignment of a kvar is weakened
+        // as much as possible before checking the fragments depending on it.
+        let mut work_list = BTreeSet::from_iter(0..topo_order_fragments.len());
+        while let Some(i) = work_list.pop_first() {
+            // Once the deadline passes every check fails, so there is no point in weakening further
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+            let fragment = &topo_order_fragments[i];
             if let Some(kvar_name) = fragment.fragment_kvar_head()
                 && let subbed = fragment.sub_kvars_except_head(&assignments)
                 && let Some(assignment) = assignments.get_mut(&kvar_name)
@@ -92,26 +118,31 @@ impl<T: Types> ConstraintWithEnv<T> {
                 let initial_length = assignment.len();
                 assignment.retain(|assignment| {
                     let vc = subbed.sub_head(assignment);
-                    is_constraint_satisfiable(&vc, solver, env).is_safe()
+                    is_constraint_satisfiable(&vc, solver, env, deadline).is_safe()
                 });
                 if initial_length > assignment.len() {
-                    work_list.extend(
-                        fragment
-                            .kvar_deps()
-                            .iter()
-                            .flat_map(|kvar| kvars_to_fragments.get(kvar).unwrap().iter()),
-                    );
+                    work_list.extend(dependents.get(&kvar_name).into_iter().flatten());
                 }
             }
         }
         assignments
     }
 
-    pub fn is_satisfiable(&mut self) -> FixpointStatus<T::Tag> {
-        self.solve_by_fusion()
+    /// Checks the constraint returning its status together with the solution for the kvars solved
+    /// by predicate abstraction (the cuts) and the solution for the kvars eliminated beforehand.
+    /// Checks that don't finish before `deadline` fail.
+    pub fn is_satisfiable(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> (FixpointStatus<T::Tag>, Vec<KVarBind>, Vec<KVarBind>) {
+        self.solve_by_fusion(deadline)
     }
 
-    pub(crate) fn eliminate_acyclic_kvars(&mut self) {
+    /// Eliminates kvars that don't depend on other kvars until none is left, returning the
+    /// solution of the eliminated kvars. Variables bound outside the scope of an eliminated kvar
+    /// may occur free in its solution; they are in scope at every occurrence of the kvar.
+    pub(crate) fn eliminate_acyclic_kvars(&mut self) -> Vec<KVarBind> {
+        let mut eliminated = vec![];
         let mut dep_graph = self.constraint.kvar_dep_graph();
         let mut acyclic_kvars: Vec<T::KVar> = dep_graph
             .into_iter()
@@ -119,6 +150,15 @@ impl<T: Types> ConstraintWithEnv<T> {
             .map(|(kvar, _)| kvar)
             .collect();
         while !acyclic_kvars.is_empty() {
+            for kvar in &acyclic_kvars {
+                if let Some(decl) = self.kvar_decls.iter().find(|decl| &decl.kvid == kvar) {
+                    let solution = self.constraint.elim_solution(kvar);
+                    eliminated.push(KVarBind {
+                        kvar: kvar.display().to_string(),
+                        val: elim_solution_to_string(decl, &solution),
+                    });
+                }
+            }
             self.constraint = self.constraint.elim(&acyclic_kvars);
             dep_graph = self.constraint.kvar_dep_graph();
             acyclic_kvars = dep_graph
@@ -127,19 +167,36 @@ impl<T: Types> ConstraintWithEnv<T> {
                 .map(|(kvar, _)| kvar)
                 .collect();
         }
+        eliminated
     }
 
     fn simplify(&mut self) {
         self.constraint.simplify();
     }
 
-    pub fn solve_by_fusion(&mut self) -> FixpointStatus<T::Tag> {
+    pub fn solve_by_fusion(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> (FixpointStatus<T::Tag>, Vec<KVarBind>, Vec<KVarBind>) {
         self.simplify();
-        self.eliminate_acyclic_kvars();
-        self.solve_by_predicate_abstraction()
+        let eliminated = self.eliminate_acyclic_kvars();
+        let (status, solution) = self.solve_by_predicate_abstraction(deadline);
+        (status, solution, eliminated)
     }
 
-    pub fn solve_by_predicate_abstraction(&mut self) -> FixpointStatus<T::Tag> {
+    pub fn solve_by_predicate_abstraction(
+        &mut self,
+        deadline: Option<Instant>,
+    ) -> (FixpointStatus<T::Tag>, Vec<KVarBind>) {
+        // Like the fixpoint binary, we report a constraint we cannot encode as a crash
+        let elaborated = check_elaborated(&self.constraint).and_then(|()| {
+            self.qualifiers
+                .iter()
+                .try_for_each(|qualifier| check_expr_elaborated(&qualifier.body))
+        });
+        if let Err(err) = elaborated {
+            return (FixpointStatus::Crash(CrashInfo(vec![err.into()])), vec![]);
+        }
         let solver = Solver::new();
         let mut vars: Env<T> = Env::new();
         self.constants.iter().for_each(|const_decl| {
@@ -152,9 +209,27 @@ impl<T: Types> ConstraintWithEnv<T> {
             let datatype_sort = new_datatype(&data_decl.name, data_decl, &mut vars);
             vars.insert_data_decl(data_decl.name.clone(), datatype_sort);
         });
-        let kvar_assignment = self.solve_for_kvars(&solver, &mut vars);
+        let kvar_assignment = self.solve_for_kvars(&solver, &mut vars, deadline);
+        let solution = self.kvar_solution(&kvar_assignment);
         self.constraint = self.constraint.sub_all_kvars(&kvar_assignment);
-        is_constraint_satisfiable(&self.constraint, &solver, &mut vars)
+        (is_constraint_satisfiable(&self.constraint, &solver, &mut vars, deadline), solution)
+    }
+
+    /// Renders the assignment of the kvars solved by predicate abstraction, i.e., the ones that
+    /// were not eliminated, in the same format used by `fixpoint --sortedsolution`.
+    fn kvar_solution(&self, assignments: &Assignments<'_, T>) -> Vec<KVarBind> {
+        let kvars = self.constraint.kvar_dep_graph();
+        self.kvar_decls
+            .iter()
+            .filter(|decl| kvars.contains_key(&decl.kvid))
+            .filter_map(|decl| {
+                let qualifiers = assignments.get(&decl.kvid)?;
+                Some(KVarBind {
+                    kvar: decl.kvid.display().to_string(),
+                    val: kvar_solution_to_string(decl, qualifiers),
+                })
+            })
+            .collect()
     }
 
     fn topo_sort_data_declarations(datatype_decls: Vec<DataDecl<T>>) -> Vec<DataDecl<T>> {
@@ -209,3 +284,101 @@ fn type_signature_matches<T: Types>(
 
     true
 }
+
+/// Renders a solution as `(lambda ((karg$0 s0) ... (karg$n sn)) body)`, i.e., the format used by
+/// `fixpoint --sortedsolution`. The parameters are named after their position, which cannot clash
+/// with the names of variables in the constraint.
+#[cfg(feature = "rust-fixpoint")]
+fn solution_to_string<T: Types>(decl: &KVarDecl<T>, body: &str) -> String {
+    let params = decl
+        .sorts
+        .iter()
+        .enumerate()
+        .format_with(" ", |(i, sort), f| f(&format_args!("({} {sort})", kvar_param(i))));
+    format!("(lambda ({params}) {body})")
+}
+
+#[cfg(feature = "rust-fixpoint")]
+fn kvar_param(i: usize) -> String {
+    format!("karg${i}")
+}
+
+/// Renders the conjunction of the qualifiers assigned to a kvar. Each qualifier is instantiated by
+/// binding its arguments to the corresponding parameters of the kvar with a `let`.
+#[cfg(feature = "rust-fixpoint")]
+fn kvar_solution_to_string<T: Types>(
+    decl: &KVarDecl<T>,
+    qualifiers: &[(&Qualifier<T>, Vec<usize>)],
+) -> String {
+    let conjuncts = qualifiers
+        .iter()
+        .map(|(qualifier, arg_idxs)| {
+            std::iter::zip(&qualifier.args, arg_idxs).rev().fold(
+                qualifier.body.to_string(),
+                |body, ((name, _), arg_idx)| {
+                    format!("(let (({} {})) {body})", name.display(), kvar_param(*arg_idx))
+                },
+            )
+        })
+        .collect_vec();
+    solution_to_string(decl, &and_to_string(conjuncts))
+}
+
+/// Renders the solution of an eliminated kvar, i.e., the disjunction over the paths leading to
+/// the kvar of the existential closure of the hypotheses in the path together with the equalities
+/// between the parameters and the arguments of the kvar.
+#[cfg(feature = "rust-fixpoint")]
+fn elim_solution_to_string<T: Types>(decl: &KVarDecl<T>, solution: &[Solution<T>]) -> String {
+    let disjuncts = solution
+        .iter()
+        .map(|Solution { binders, args }| {
+            let mut conjuncts = binders
+                .iter()
+                .rev()
+                .map(|bind| pred_to_expr(&bind.pred).to_string())
+                .collect_vec();
+            conjuncts.extend(
+                args.iter()
+                    .enumerate()
+                    .map(|(i, arg)| format!("(= {} {arg})", kvar_param(i))),
+            );
+            let body = and_to_string(conjuncts);
+            if binders.is_empty() {
+                body
+            } else {
+                let vars = binders.iter().rev().format_with(" ", |bind, f| {
+                    f(&format_args!("({} {})", bind.name.display(), bind.sort))
+                });
+                format!("(exists ({vars}) {body})")
+            }
+        })
+        .collect_vec();
+    let body = match &disjuncts[..] {
+        [] => "false".to_string(),
+        [disjunct] => disjunct.clone(),
+        disjuncts => format!("(or {})", disjuncts.join(" ")),
+    };
+    solution_to_string(decl, &body)
+}
+
+#[cfg(feature = "rust-fixpoint")]
+fn and_to_string(conjuncts: Vec<String>) -> String {
+    match &conjuncts[..] {
+        [] => "true".to_string(),
+        [conjunct] => conjunct.clone(),
+        conjuncts => format!("(and {})", conjuncts.join(" ")),
+    }
+}
+
+/// Converts a kvar-free predicate into an expression. The paths leading to an eliminated kvar
+/// don't mention other kvars because only kvars without dependencies are eliminated.
+#[cfg(feature = "rust-fixpoint")]
+fn pred_to_expr<T: Types>(pred: &Pred<T>) -> Expr<T> {
+    match pred {
+        Pred::Expr(expr) => expr.clone(),
+        Pred::And(preds) => Expr::and(preds.iter().map(pred_to_expr).collect()),
+        Pred::KVar(kvar, _) => {
+            panic!("unexpected kvar `{}` in the solution of an eliminated kvar", kvar.display())
+        }
+    }
+}
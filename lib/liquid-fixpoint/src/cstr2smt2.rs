@@ -1,9 +1,9 @@
 use core::panic;
-use std::{collections::HashMap, iter, str::FromStr, vec};
+use std::{collections::HashMap, iter, str::FromStr, time::Instant, vec};
 
 use itertools::Itertools as _;
 use z3::{
-    FuncDecl, SatResult, Solver, SortKind,
+    FuncDecl, Params, Pattern, RecFuncDecl, SatResult, Solver, SortKind,
     ast::{self, Ast},
 };
 
@@ -27,11 +27,20 @@ impl From<ast::Dynamic> for Binding {
 pub(crate) struct Env<T: Types> {
     bindings: HashMap<T::Var, Vec<Binding>>,
     data_types: HashMap<T::Sort, z3::Sort>,
+    /// Maps the name of each data constructor to its tester, i.e., `is$ctor`
+    testers: HashMap<T::Var, FuncDecl>,
+    /// The recursive definition of `seq.extract` for each sequence sort, see [`seq_extract`]
+    seq_extracts: Vec<(z3::Sort, RecFuncDecl)>,
 }
 
 impl<T: Types> Env<T> {
     pub(crate) fn new() -> Self {
-        Self { bindings: HashMap::new(), data_types: HashMap::new() }
+        Self {
+            bindings: HashMap::new(),
+            data_types: HashMap::new(),
+            testers: HashMap::new(),
+            seq_extracts: vec![],
+        }
     }
 
     pub(crate) fn insert<B: Into<Binding>>(&mut self, name: T::Var, value: B) {
@@ -76,6 +85,10 @@ impl<T: Types> Env<T> {
     fn datatype_lookup(&self, name: &T::Sort) -> Option<&z3::Sort> {
         self.data_types.get(name)
     }
+
+    fn tester_lookup(&self, ctor: &T::Var) -> Option<&FuncDecl> {
+        self.testers.get(ctor)
+    }
 }
 
 fn const_to_z3<T: Types>(cnst: &Constant<T>) -> ast::Dynamic {
@@ -192,7 +205,11 @@ fn binop_to_z3<T: Types>(
                 BinOp::Sub => ast::Real::sub(&[&l, &r]),
                 BinOp::Mul => ast::Real::mul(&[&l, &r]),
                 BinOp::Div => l.div(&r),
-                BinOp::Mod => panic!("modulo is not defined on Real numbers"),
+                // There's no modulo on reals in SMT-LIB, so we define it as `l - r * floor(l / r)`
+                BinOp::Mod => {
+                    let quot = l.div(&r).to_int().to_real();
+                    ast::Real::sub(&[&l, &ast::Real::mul(&[&r, &quot])])
+                }
             };
             res.into()
         }
@@ -203,7 +220,9 @@ fn binop_to_z3<T: Types>(
 
 fn thy_func_application_to_z3<T: Types>(
     func: ThyFunc,
+    sort_args: Option<&[Sort<T>]>,
     args: &[Expr<T>],
+    out_sort: Option<&Sort<T>>,
     env: &mut Env<T>,
 ) -> ast::Dynamic {
     match func {
@@ -280,6 +299,14 @@ fn thy_func_application_to_z3<T: Types>(
             let arg = expr_to_z3(&args[0], env).as_int().unwrap();
             ast::BV::from_int(&arg, 8).into()
         }
+        ThyFunc::IntToBv16 => {
+            let arg = expr_to_z3(&args[0], env).as_int().unwrap();
+            ast::BV::from_int(&arg, 16).into()
+        }
+        ThyFunc::IntToBv128 => {
+            let arg = expr_to_z3(&args[0], env).as_int().unwrap();
+            ast::BV::from_int(&arg, 128).into()
+        }
         ThyFunc::Bv32ToInt => {
             let arg = expr_to_z3(&args[0], env).as_bv().unwrap();
             arg.to_int(false).into()
@@ -292,6 +319,14 @@ fn thy_func_application_to_z3<T: Types>(
             let arg = expr_to_z3(&args[0], env).as_bv().unwrap();
             arg.to_int(false).into()
         }
+        ThyFunc::Bv16ToInt => {
+            let arg = expr_to_z3(&args[0], env).as_bv().unwrap();
+            arg.to_int(false).into()
+        }
+        ThyFunc::Bv128ToInt => {
+            let arg = expr_to_z3(&args[0], env).as_bv().unwrap();
+            arg.to_int(false).into()
+        }
         ThyFunc::BvAdd => {
             let arg1 = expr_to_z3(&args[0], env).as_bv().unwrap();
             let arg2 = expr_to_z3(&args[1], env).as_bv().unwrap();
@@ -421,13 +456,122 @@ fn thy_func_application_to_z3<T: Types>(
         ThyFunc::MapStore => {
             let map = expr_to_z3(&args[0], env).as_array().unwrap();
             let idx = expr_to_z3(&args[1], env);
-            let val = expr_to_z3(&args[1], env);
+            let val = expr_to_z3(&args[2], env);
             map.store(&idx, &val).into()
         }
-        ThyFunc::MapDefault => todo!("map default needs the elaborated domain sort"),
+        ThyFunc::MapDefault => {
+            // The value alone does not determine the sort of the keys, so we need the sort of
+            // the application which is filled in during elaboration.
+            let Some(Sort::App(SortCtor::Map, sorts)) = out_sort else {
+                unreachable!("unelaborated map defaults are rejected by `check_elaborated`")
+            };
+            let val = expr_to_z3(&args[0], env);
+            ast::Array::const_array(&z3_sort(&sorts[0], env), &val).into()
+        }
+        ThyFunc::SeqEmpty => {
+            // As with default maps, there are no arguments to determine the sort of the elements
+            // so we use the instantiated sort argument.
+            let elem_sort = match (sort_args, out_sort) {
+                (Some([elem_sort]), _) => elem_sort,
+                (_, Some(Sort::App(SortCtor::Seq, sorts))) => &sorts[0],
+                _ => panic!("cannot infer empty sequence element sort"),
+            };
+            ast::Seq::empty(&z3_sort(elem_sort, env)).into()
+        }
+        ThyFunc::SeqUnit => {
+            let arg = expr_to_z3(&args[0], env);
+            ast::Seq::unit(&arg).into()
+        }
+        ThyFunc::SeqLen => {
+            let seq = expr_to_z3(&args[0], env).as_seq().unwrap();
+            seq.length().into()
+        }
+        ThyFunc::SeqNth => {
+            let seq = expr_to_z3(&args[0], env).as_seq().unwrap();
+            let idx = expr_to_z3(&args[1], env).as_int().unwrap();
+            seq.nth(idx)
+        }
+        ThyFunc::SeqConcat => {
+            let arg1 = expr_to_z3(&args[0], env).as_seq().unwrap();
+            let arg2 = expr_to_z3(&args[1], env).as_seq().unwrap();
+            ast::Seq::concat(&[&arg1, &arg2]).into()
+        }
+        ThyFunc::SeqExtract => {
+            let seq = expr_to_z3(&args[0], env).as_seq().unwrap();
+            let offset = expr_to_z3(&args[1], env).as_int().unwrap();
+            let len = expr_to_z3(&args[2], env).as_int().unwrap();
+            seq_extract(&seq, &offset, &len, env).into()
+        }
+        ThyFunc::SeqUpdate => {
+            // `seq.update` is not available through the C API, so we split the sequence around
+            // the updated index. As in SMT-LIB, updating an index out of bounds is a no-op.
+            let seq = expr_to_z3(&args[0], env).as_seq().unwrap();
+            let idx = expr_to_z3(&args[1], env).as_int().unwrap();
+            let val = expr_to_z3(&args[2], env);
+            let zero = ast::Int::from_i64(0);
+            let one = ast::Int::from_i64(1);
+            let len = seq.length();
+            let prefix = seq_extract(&seq, &zero, &idx, env);
+            let suffix = seq_extract(&seq, &(&idx + &one), &(&len - &idx - &one), env);
+            let updated = ast::Seq::concat(&[&prefix, &ast::Seq::unit(&val), &suffix]);
+            let in_bounds = ast::Bool::and(&[&idx.ge(&zero), &idx.lt(&len)]);
+            in_bounds.ite(&updated, &seq).into()
+        }
     }
 }
 
+/// The subsequence of `seq` starting at `offset` of length `len`. The `z3` crate doesn't expose
+/// `seq.extract`, so we define it recursively (once per sequence sort) following its SMT-LIB
+/// semantics: the result is empty if `offset` is out of bounds or `len` is not positive, and it is
+/// truncated at the end of `seq`.
+fn seq_extract<T: Types>(
+    seq: &ast::Seq,
+    offset: &ast::Int,
+    len: &ast::Int,
+    env: &mut Env<T>,
+) -> ast::Seq {
+    let seq_sort = seq.get_sort();
+    let i = match env
+        .seq_extracts
+        .iter()
+        .position(|(sort, _)| *sort == seq_sort)
+    {
+        Some(i) => i,
+        None => {
+            let fun = mk_seq_extract(&seq_sort);
+            env.seq_extracts.push((seq_sort, fun));
+            env.seq_extracts.len() - 1
+        }
+    };
+    env.seq_extracts[i]
+        .1
+        .apply(&[seq, offset, len])
+        .as_seq()
+        .unwrap()
+}
+
+fn mk_seq_extract(seq_sort: &z3::Sort) -> RecFuncDecl {
+    let int = z3::Sort::int();
+    let fun =
+        RecFuncDecl::new(format!("seq_extract_{seq_sort}"), &[seq_sort, &int, &int], seq_sort);
+    let seq = ast::Seq::new_const("seq", seq_sort);
+    let offset = ast::Int::new_const("offset");
+    let len = ast::Int::new_const("len");
+    let zero = ast::Int::from_i64(0);
+    let one = ast::Int::from_i64(1);
+    let seq_len = seq.length();
+    // `seq.at` is the empty sequence when the index is out of bounds
+    let empty = seq.at(&seq_len);
+    let rest = fun
+        .apply(&[&seq, &(&offset + &one), &(&len - &one)])
+        .as_seq()
+        .unwrap();
+    let out_of_bounds = ast::Bool::or(&[&len.le(&zero), &offset.lt(&zero), &offset.ge(&seq_len)]);
+    let body = out_of_bounds.ite(&empty, &ast::Seq::concat(&[&seq.at(&offset), &rest]));
+    fun.add_def(&[&seq, &offset, &len], &body);
+    fun
+}
+
 fn expr_to_z3<T: Types>(expr: &Expr<T>, env: &mut Env<T>) -> ast::Dynamic {
     match expr {
         Expr::Var(var) => env.var_lookup(var).cloned().expect("error if not present"),
@@ -492,7 +636,7 @@ fn expr_to_z3<T: Types>(expr: &Expr<T>, env: &mut Env<T>) -> ast::Dynamic {
             let if_false = expr_to_z3(&exprs[2], env);
             ast::Bool::ite(&condition, &if_true, &if_false)
         }
-        Expr::App(fun, _sort_args, args, _out_sort) => {
+        Expr::App(fun, sort_args, args, out_sort) => {
             match &**fun {
                 Expr::Var(var) => {
                     let arg_asts = args.iter().map(|arg| expr_to_z3(arg, env)).collect_vec();
@@ -502,15 +646,58 @@ fn expr_to_z3<T: Types>(expr: &Expr<T>, env: &mut Env<T>) -> ast::Dynamic {
                         .unwrap_or_else(|| panic!("error if function not present {var:#?}"));
                     fun_decl.apply(&arg_refs)
                 }
-                Expr::ThyFunc(func) => thy_func_application_to_z3(*func, args, env),
+                Expr::ThyFunc(func) => {
+                    thy_func_application_to_z3(
+                        *func,
+                        sort_args.as_deref(),
+                        args,
+                        out_sort.as_ref(),
+                        env,
+                    )
+                }
                 _ => panic!("encountered function application but no function"),
             }
         }
-        Expr::IsCtor(..) => todo!("testers not yet implemented"),
+        Expr::IsCtor(ctor, arg) => {
+            let arg = expr_to_z3(arg, env);
+            let tester = env
+                .tester_lookup(ctor)
+                .unwrap_or_else(|| panic!("tester for constructor not present {ctor:#?}"));
+            tester.apply(&[&arg])
+        }
         Expr::ThyFunc(_) => {
             unreachable!("Should not encounter theory func outside of an application")
         }
-        Expr::Exists(..) => todo!("exists not yet implemented"),
+        Expr::Exists(binders, body) => {
+            let bounds = quantified_vars_to_z3(binders, env);
+            let body = expr_to_z3(body, env).as_bool().unwrap();
+            for (name, _) in binders {
+                env.pop(name);
+            }
+            let bound_refs = bounds.iter().map(|b| b as &dyn Ast).collect_vec();
+            ast::exists_const(&bound_refs, &[], &body).into()
+        }
+        Expr::ForAll(binders, triggers, body) => {
+            let bounds = quantified_vars_to_z3(binders, env);
+            let body = expr_to_z3(body, env).as_bool().unwrap();
+            let patterns = triggers
+                .iter()
+                .map(|trigger| {
+                    let terms = trigger
+                        .iter()
+                        .map(|term| expr_to_z3(term, env))
+                        .collect_vec();
+                    let term_refs = terms.iter().map(|t| t as &dyn Ast).collect_vec();
+                    Pattern::new(&term_refs)
+                })
+                .collect_vec();
+            for (name, _) in binders {
+                env.pop(name);
+            }
+            let bound_refs = bounds.iter().map(|b| b as &dyn Ast).collect_vec();
+            let pattern_refs = patterns.iter().collect_vec();
+            ast::forall_const(&bound_refs, &pattern_refs, &body).into()
+        }
     }
 }
 
@@ -525,10 +712,41 @@ fn pred_to_z3<T: Types>(pred: &Pred<T>, env: &mut Env<T>) -> ast::Bool {
             let bool_refs = bools.iter().collect_vec();
             ast::Bool::and(&bool_refs)
         }
-        Pred::KVar(_kvar, _vars) => panic!("Kvars not supported yet"),
+        // Kvars are eliminated or substituted by their solution before encoding. Treating a
+        // leftover kvar as an unknown proposition would silently weaken the check.
+        Pred::KVar(kvar, _vars) => {
+            panic!(
+                "kvar `{}` should be substituted by its solution before encoding",
+                kvar.display()
+            )
+        }
     }
 }
 
+/// Binds the variables of a quantifier in `env` and returns the corresponding z3 constants. The
+/// caller is responsible for popping the variables after encoding the body.
+///
+/// Variables bound by a `let` are substituted by their z3 term, and z3 abstracts every occurrence
+/// of a constant when building a quantifier. We thus bind each variable to a fresh constant instead
+/// of the constant with its name, so that a term substituted under the quantifier that mentions an
+/// outer variable with the same name is not captured.
+fn quantified_vars_to_z3<T: Types>(
+    binders: &[(T::Var, Sort<T>)],
+    env: &mut Env<T>,
+) -> Vec<ast::Dynamic> {
+    binders
+        .iter()
+        .map(|(name, sort)| {
+            let Binding::Variable(bound) = new_binding(name, sort, env) else {
+                panic!("cannot quantify over function `{}`", name.display())
+            };
+            let bound = ast::Dynamic::fresh_const(&name.display().to_string(), &bound.get_sort());
+            env.insert(name.clone(), bound.clone());
+            bound
+        })
+        .collect_vec()
+}
+
 pub(crate) fn new_datatype<T: Types>(
     name: &T::Sort,
     data_decl: &DataDecl<T>,
@@ -552,7 +770,8 @@ pub(crate) fn new_datatype<T: Types>(
     }
     let z3::DatatypeSort { sort, variants } = builder.finish();
     for (data_ctor, variant) in iter::zip(&data_decl.ctors, variants) {
-        let z3::DatatypeVariant { constructor, accessors, tester: _ } = variant;
+        let z3::DatatypeVariant { constructor, accessors, tester } = variant;
+        env.testers.insert(data_ctor.name.clone(), tester);
         env.insert(
             data_ctor.name.clone(),
             Binding::Function(constructor, ast::Int::new_const(name.display().to_string()).into()),
@@ -616,6 +835,12 @@ pub(crate) fn new_binding<T: Types>(name: &T::Var, sort: &Sort<T>, env: &Env<T>)
                         .into(),
                     )
                 }
+                SortCtor::Seq => {
+                    Binding::Variable(
+                        ast::Seq::new_const(name.display().to_string(), &z3_sort(&args[0], env))
+                            .into(),
+                    )
+                }
                 SortCtor::Data(data_ctor) => {
                     Binding::Variable(
                         ast::Datatype::new_const(
@@ -647,6 +872,7 @@ fn z3_sort<T: Types>(s: &Sort<T>, env: &Env<T>) -> z3::Sort {
             match sort_ctor {
                 SortCtor::Set => z3::Sort::set(&z3_sort(&args[0], env)),
                 SortCtor::Map => z3::Sort::array(&z3_sort(&args[0], env), &z3_sort(&args[1], env)),
+                SortCtor::Seq => z3::Sort::seq(&z3_sort(&args[0], env)),
                 SortCtor::Data(sort) => env.datatype_lookup(sort).unwrap().clone(),
             }
         }
@@ -654,16 +880,75 @@ fn z3_sort<T: Types>(s: &Sort<T>, env: &Env<T>) -> z3::Sort {
     }
 }
 
+/// Checks that the constraint can be encoded, returning an error otherwise. Some theory functions
+/// don't determine their sort from their arguments, so we need the sort filled in during
+/// elaboration to encode their applications.
+pub(crate) fn check_elaborated<T: Types>(cstr: &Constraint<T>) -> Result<(), String> {
+    match cstr {
+        Constraint::Pred(pred, _) => check_pred_elaborated(pred),
+        Constraint::Conj(conjuncts) => conjuncts.iter().try_for_each(check_elaborated),
+        Constraint::ForAll(bind, inner) => {
+            check_pred_elaborated(&bind.pred)?;
+            check_elaborated(inner)
+        }
+    }
+}
+
+fn check_pred_elaborated<T: Types>(pred: &Pred<T>) -> Result<(), String> {
+    match pred {
+        Pred::And(preds) => preds.iter().try_for_each(check_pred_elaborated),
+        Pred::KVar(_, args) => args.iter().try_for_each(check_expr_elaborated),
+        Pred::Expr(expr) => check_expr_elaborated(expr),
+    }
+}
+
+pub(crate) fn check_expr_elaborated<T: Types>(expr: &Expr<T>) -> Result<(), String> {
+    match expr {
+        Expr::Constant(_) | Expr::Var(_) | Expr::ThyFunc(_) => Ok(()),
+        Expr::App(fun, _sort_args, args, out_sort) => {
+            if let Expr::ThyFunc(ThyFunc::MapDefault) = **fun
+                && !matches!(out_sort, Some(Sort::App(SortCtor::Map, _)))
+            {
+                return Err(format!(
+                    "map default requires an elaborated map sort, found `{out_sort:?}`"
+                ));
+            }
+            check_expr_elaborated(fun)?;
+            args.iter().try_for_each(check_expr_elaborated)
+        }
+        Expr::Neg(e) | Expr::Not(e) | Expr::IsCtor(_, e) | Expr::Exists(_, e) => {
+            check_expr_elaborated(e)
+        }
+        Expr::BinaryOp(_, exprs)
+        | Expr::Imp(exprs)
+        | Expr::Iff(exprs)
+        | Expr::Atom(_, exprs)
+        | Expr::Let(_, exprs) => exprs.iter().try_for_each(check_expr_elaborated),
+        Expr::IfThenElse(exprs) => exprs.iter().try_for_each(check_expr_elaborated),
+        Expr::And(exprs) | Expr::Or(exprs) => exprs.iter().try_for_each(check_expr_elaborated),
+        Expr::ForAll(_, triggers, body) => {
+            triggers
+                .iter()
+                .flatten()
+                .try_for_each(check_expr_elaborated)?;
+            check_expr_elaborated(body)
+        }
+    }
+}
+
+/// Checks `cstr` with `solver`. A check that doesn't finish before `deadline` fails, and once the
+/// deadline passes the remaining checks fail without running.
 pub(crate) fn is_constraint_satisfiable<T: Types>(
     cstr: &Constraint<T>,
     solver: &Solver,
     env: &mut Env<T>,
+    deadline: Option<Instant>,
 ) -> FixpointStatus<T::Tag> {
     solver.push();
     let res = match cstr {
         Constraint::Pred(pred, tag) => {
             solver.assert(pred_to_z3(pred, env).not());
-            if solver.check() == SatResult::Unsat {
+            if set_remaining_time(solver, deadline) && solver.check() == SatResult::Unsat {
                 FixpointStatus::Safe(Stats { num_cstr: 1, num_iter: 0, num_chck: 0, num_vald: 0 })
             } else {
                 FixpointStatus::Unsafe(
@@ -678,14 +963,14 @@ pub(crate) fn is_constraint_satisfiable<T: Types>(
         Constraint::Conj(conjuncts) => {
             conjuncts.iter().fold(
                 FixpointStatus::Safe(Stats { num_cstr: 0, num_iter: 0, num_chck: 0, num_vald: 0 }),
-                |acc, cstr| is_constraint_satisfiable(cstr, solver, env).merge(acc),
+                |acc, cstr| is_constraint_satisfiable(cstr, solver, env, deadline).merge(acc),
             )
         }
 
         Constraint::ForAll(bind, inner) => {
             env.insert(bind.name.clone(), new_binding(&bind.name, &bind.sort, env));
             solver.assert(pred_to_z3(&bind.pred, env));
-            let inner_soln = is_constraint_satisfiable(inner, solver, env);
+            let inner_soln = is_constraint_satisfiable(inner, solver, env, deadline);
             env.pop(&bind.name);
             inner_soln
         }
@@ -693,3 +978,23 @@ pub(crate) fn is_constraint_satisfiable<T: Types>(
     solver.pop(1);
     res
 }
+
+/// Sets the timeout of `solver` to the time left until `deadline`, so a check that doesn't finish
+/// in time returns `unknown`. Returns `false` if the deadline already passed.
+fn set_remaining_time(solver: &Solver, deadline: Option<Instant>) -> bool {
+    let Some(deadline) = deadline else { return true };
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() {
+        return false;
+    }
+    let mut params = Params::new();
+    // A timeout of 0 means no timeout, so we round up to 1ms
+    params.set_u32(
+        "timeout",
+        u32::try_from(remaining.as_millis())
+            .unwrap_or(u32::MAX)
+            .max(1),
+    );
+    solver.set_params(&params);
+    true
+}
@@ -218,6 +218,7 @@ impl<T: Types> fmt::Display for SortCtor<T> {
         match self {
             SortCtor::Set => write!(f, "Set_Set"),
             SortCtor::Map => write!(f, "Map_t"),
+            SortCtor::Seq => write!(f, "Seq_t"),
             SortCtor::Data(name) => write!(f, "{}", name.display()),
         }
     }
@@ -336,6 +337,18 @@ impl<T: Types> fmt::Display for Expr<T> {
                     body
                 )
             }
+            Expr::ForAll(binders, _triggers, body) => {
+                // Fixpoint doesn't support trigger annotations, so we leave quantifier
+                // instantiation to the solver.
+                write!(
+                    f,
+                    "(forall ({}) {})",
+                    binders.iter().format_with(" ", |(var, sort), f| {
+                        f(&format_args!("({} {sort})", var.display()))
+                    }),
+                    body
+                )
+            }
         }
     }
 }
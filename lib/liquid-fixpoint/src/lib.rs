@@ -21,6 +21,7 @@ mod cstr2smt2;
 mod format;
 #[cfg(feature = "rust-fixpoint")]
 mod graph;
+mod model;
 pub mod parser;
 pub mod sexp;
 
@@ -29,12 +30,16 @@ use std::{
     fmt::{self, Debug},
     hash::{Hash, Hasher},
     io,
+    io::Read,
+    process::{Child, Command, Output},
     str::FromStr,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
 #[cfg(not(feature = "rust-fixpoint"))]
 use std::{
     io::{BufWriter, Write as IOWrite},
-    process::{Command, Stdio},
+    process::Stdio,
 };
 
 pub use constraint::{
@@ -42,6 +47,7 @@ pub use constraint::{
     FunSort, Pred, Qualifier, Sort, SortCtor, SortDecl,
 };
 use derive_where::derive_where;
+pub use model::{Model, ModelQuery};
 #[cfg(feature = "nightly")]
 use rustc_macros::{Decodable, Encodable};
 use serde::{Deserialize, Serialize, de};
@@ -170,6 +176,9 @@ pub struct FunDef<T: Types> {
 pub struct FunBody<T: Types> {
     pub args: Vec<T::Var>,
     pub expr: Expr<T>,
+    /// Termination metric of a recursive function, empty if the function is not recursive. It is
+    /// not part of the fixpoint query, but backends that must prove termination themselves use it.
+    pub decreases: Vec<Expr<T>>,
 }
 
 #[derive_where(Hash)]
@@ -184,6 +193,116 @@ pub struct Task<T: Types> {
     pub qualifiers: Vec<Qualifier<T>>,
     pub scrape_quals: bool,
     pub solver: SmtSolver,
+    /// The limits are not part of the hash because they don't change the answer of a query that
+    /// finishes within them.
+    #[derive_where(skip)]
+    pub limits: Limits,
+}
+
+/// Resource limits for a single run of fixpoint. The same limits apply to every other solver
+/// process spawned for the query, i.e., the SMT solver computing counterexamples and Lean. When
+/// the constraint is solved in-process (with the `rust-fixpoint` feature) only the timeout is
+/// enforced.
+///
+/// The timeout applies to a process together with the processes it spawns, which are killed as a
+/// group. The memory limit, on the other hand, is set with `RLIMIT_AS` and thus applies to each
+/// process separately: the processes spawned by a solver (e.g., the SMT solver spawned by
+/// fixpoint) inherit the limit, but they are not capped together.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Limits {
+    /// Maximum number of seconds fixpoint can run before being stopped
+    pub timeout: Option<u64>,
+    /// Maximum amount of memory, in megabytes, each solver process can use
+    pub memory: Option<u64>,
+}
+
+impl Limits {
+    /// Spawns `cmd` within the limits, returning the exhausted resource if the process can't even
+    /// be started because the memory limit is too low to load it.
+    pub fn spawn(&self, cmd: &mut Command) -> io::Result<Result<Child, Resource>> {
+        self.apply(cmd);
+        let err = match cmd.spawn() {
+            Ok(child) => return Ok(Ok(child)),
+            Err(err) => err,
+        };
+        #[cfg(unix)]
+        if let Some(mb) = self.memory
+            && err.raw_os_error() == Some(libc::ENOMEM)
+        {
+            return Ok(Err(Resource::Memory(mb)));
+        }
+        Err(err)
+    }
+
+    /// Limits the memory of each process spawned by `cmd` and, if there's a timeout, puts it in
+    /// its own process group so the processes it spawns (e.g., the SMT solver spawned by
+    /// fixpoint) can be killed together with it.
+    fn apply(&self, cmd: &mut Command) {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+
+            if self.timeout.is_some() {
+                cmd.process_group(0);
+            }
+            if let Some(mb) = self.memory {
+                let bytes = mb.saturating_mul(1024 * 1024) as libc::rlim_t;
+                // SAFETY: `setrlimit` is async-signal-safe and we don't allocate in the closure.
+                unsafe {
+                    cmd.pre_exec(move || {
+                        let limit = libc::rlimit { rlim_cur: bytes, rlim_max: bytes };
+                        if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                            return Err(io::Error::last_os_error());
+                        }
+                        Ok(())
+                    });
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = cmd;
+    }
+
+    /// Waits for `child`, which must have been spawned with [`Limits::spawn`], and collects its output. Returns the exhausted resource if the child
+    /// didn't exit before the timeout or if it reported running out of memory.
+    pub fn wait_with_output(&self, child: Child) -> io::Result<Result<Output, Resource>> {
+        let out = if let Some(secs) = self.timeout {
+            match wait_with_timeout(child, Duration::from_secs(secs))? {
+                Some(out) => out,
+                None => return Ok(Err(Resource::Time(secs))),
+            }
+        } else {
+            child.wait_with_output()?
+        };
+        if let Some(mb) = self.memory
+            && !out.status.success()
+            && is_out_of_memory(&out)
+        {
+            return Ok(Err(Resource::Memory(mb)));
+        }
+        Ok(Ok(out))
+    }
+}
+
+/// A resource that was exhausted before fixpoint could answer a query, together with the limit
+/// that was exceeded.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum Resource {
+    /// The query didn't finish within the given number of seconds
+    Time(u64),
+    /// The query ran out of memory with a limit of the given number of megabytes
+    Memory(u64),
+}
+
+impl Resource {
+    /// Whether running the query again with `limits` would exhaust the resource again, i.e.,
+    /// whether the limit on the resource is not larger than the one that was exceeded.
+    pub fn is_exhausted_with(self, limits: Limits) -> bool {
+        match self {
+            Resource::Time(secs) => limits.timeout.is_some_and(|timeout| timeout <= secs),
+            Resource::Memory(mb) => limits.memory.is_some_and(|memory| memory <= mb),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Hash)]
@@ -211,6 +330,8 @@ pub enum FixpointStatus<Tag> {
     Safe(Stats),
     Unsafe(Stats, Vec<Error<Tag>>),
     Crash(CrashInfo),
+    /// Fixpoint was stopped because it exceeded one of the [`Limits`] of the task
+    Exhausted(Resource),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -238,6 +359,17 @@ pub struct KVarBind {
     pub val: String,
 }
 
+impl<Tag> VerificationResult<Tag> {
+    pub fn exhausted(resource: Resource) -> Self {
+        VerificationResult {
+            status: FixpointStatus::Exhausted(resource),
+            solution: vec![],
+            non_cuts_solution: vec![],
+            lean_status: LeanStatus::default(),
+        }
+    }
+}
+
 impl KVarBind {
     pub fn dump(&self) -> String {
         format!("{} := {}", self.kvar, self.val)
@@ -266,6 +398,7 @@ impl<Tag> FixpointStatus<Tag> {
             (FR::Crash(info1), FR::Crash(info2)) => FR::Crash(info1.merge(info2)),
             (FR::Crash(info), _) => FR::Crash(info),
             (_, FR::Crash(info)) => FR::Crash(info),
+            (FR::Exhausted(resource), _) | (_, FR::Exhausted(resource)) => FR::Exhausted(resource),
         }
     }
 }
@@ -324,24 +457,83 @@ impl<T: Types> Task<T> {
 
     #[cfg(feature = "rust-fixpoint")]
     pub fn run(&self) -> io::Result<VerificationResult<T::Tag>> {
+        // The timeout applies to the whole query rather than to each check
+        let start = Instant::now();
+        let deadline = self
+            .limits
+            .timeout
+            .map(|secs| start + Duration::from_secs(secs));
+        // Functions without a body (e.g., recursive functions) are declared as constants.
+        let constants = self
+            .constants
+            .iter()
+            .cloned()
+            .chain(
+                self.define_funs
+                    .iter()
+                    .filter(|fun| fun.body.is_none())
+                    .map(|fun| {
+                        ConstDecl {
+                            name: fun.name.clone(),
+                            sort: Sort::mk_func(
+                                fun.sort.params,
+                                fun.sort.inputs.iter().cloned(),
+                                fun.sort.output.clone(),
+                            ),
+                            comment: None,
+                        }
+                    }),
+            )
+            .collect();
         let mut cstr_with_env = ConstraintWithEnv::new(
             self.data_decls.clone(),
             self.kvars.clone(),
             self.qualifiers.clone(),
-            self.constants.clone(),
+            constants,
             self.constraint.clone(),
         );
+        let (status, solution, non_cuts_solution) = cstr_with_env.is_satisfiable(deadline);
+        if let Some(secs) = self.limits.timeout
+            && !matches!(status, FixpointStatus::Safe(_))
+            && start.elapsed() >= Duration::from_secs(secs)
+        {
+            return Ok(VerificationResult::exhausted(Resource::Time(secs)));
+        }
         Ok(VerificationResult {
-            status: cstr_with_env.is_satisfiable(),
-            solution: vec![],
-            non_cuts_solution: vec![],
+            status,
+            solution,
+            non_cuts_solution,
+            lean_status: LeanStatus::default(),
         })
     }
 
     #[cfg(not(feature = "rust-fixpoint"))]
     pub fn run(&self) -> io::Result<VerificationResult<T::Tag>> {
-        let mut child = Command::new("fixpoint")
-            .arg("-q")
+        self.render().run()
+    }
+
+    /// Renders the task in the input format of the fixpoint binary.
+    #[cfg(not(feature = "rust-fixpoint"))]
+    pub fn render(&self) -> RenderedTask {
+        RenderedTask { input: self.to_string(), solver: self.solver, limits: self.limits }
+    }
+}
+
+/// A [`Task`] rendered in the input format of the fixpoint binary. Contrary to a [`Task`], it
+/// doesn't refer to the types used to instantiate the constraint, so it can be sent to another
+/// thread to be run.
+#[cfg(not(feature = "rust-fixpoint"))]
+pub struct RenderedTask {
+    input: String,
+    solver: SmtSolver,
+    limits: Limits,
+}
+
+#[cfg(not(feature = "rust-fixpoint"))]
+impl RenderedTask {
+    pub fn run<Tag: FromStr>(&self) -> io::Result<VerificationResult<Tag>> {
+        let mut cmd = Command::new("fixpoint");
+        cmd.arg("-q")
             .arg("--stdin")
             .arg("--sortedsolution")
             .arg("--json")
@@ -350,17 +542,34 @@ impl<T: Types> Task<T> {
             .arg(format!("--solver={}", self.solver))
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()?;
+            .stderr(Stdio::piped());
+        let mut child = match self.limits.spawn(&mut cmd)? {
+            Ok(child) => child,
+            Err(resource) => return Ok(VerificationResult::exhausted(resource)),
+        };
         let mut stdin = None;
         std::mem::swap(&mut stdin, &mut child.stdin);
         {
             let mut w = BufWriter::new(stdin.unwrap());
-            writeln!(w, "{self}")?;
+            writeln!(w, "{}", self.input)?;
         }
-        let out = child.wait_with_output()?;
-
-        serde_json::from_slice(&out.stdout).map_err(|err| {
+        let out = if let Some(secs) = self.limits.timeout {
+            match wait_with_timeout(child, Duration::from_secs(secs))? {
+                Some(out) => out,
+                None => return Ok(VerificationResult::exhausted(Resource::Time(secs))),
+            }
+        } else {
+            child.wait_with_output()?
+        };
+
+        let result = serde_json::from_slice::<VerificationResult<Tag>>(&out.stdout);
+        if let Some(mb) = self.limits.memory
+            && !matches!(&result, Ok(res) if !matches!(res.status, FixpointStatus::Crash(_)))
+            && is_out_of_memory(&out)
+        {
+            return Ok(VerificationResult::exhausted(Resource::Memory(mb)));
+        }
+        result.map_err(|err| {
             // If we fail to parse stdout fixpoint may have outputed something to stderr
             // so use that for the error instead
             if !out.stderr.is_empty() {
@@ -374,6 +583,84 @@ impl<T: Types> Task<T> {
     }
 }
 
+/// Waits for `child` to exit and collects its output. If the child doesn't exit within `timeout`,
+/// it is killed and `None` is returned.
+fn wait_with_timeout(mut child: Child, timeout: Duration) -> io::Result<Option<Output>> {
+    // The output is read in separate threads so the child doesn't block on a full pipe while we
+    // are waiting for it.
+    let stdout = read_in_thread(child.stdout.take());
+    let stderr = read_in_thread(child.stderr.take());
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            kill(&mut child);
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    let join = |handle: JoinHandle<io::Result<Vec<u8>>>| {
+        handle
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("failed to read the output of the solver")))
+    };
+    Ok(Some(Output { status, stdout: join(stdout)?, stderr: join(stderr)? }))
+}
+
+fn read_in_thread(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut buf = vec![];
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut buf)?;
+        }
+        Ok(buf)
+    })
+}
+
+/// Kills `child`. On unix, this also kills the processes in its process group, i.e., the SMT solver.
+fn kill(child: &mut Child) {
+    #[cfg(unix)]
+    // SAFETY: `kill` has no memory safety requirements
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    let _ = child.kill();
+}
+
+/// Whether the solver ran out of memory. We rely on how the process exited when possible: the
+/// kernel kills processes with `SIGKILL` when the system runs out of memory, the GHC runtime used
+/// by fixpoint exits with code 251 when it exhausts its heap, and z3 exits with code 101 when it
+/// can't allocate. The exit status of the SMT solver spawned by fixpoint isn't visible to us
+/// though, so we fall back to the message fixpoint forwards when the SMT solver crashes.
+fn is_out_of_memory(out: &Output) -> bool {
+    const GHC_HEAP_OVERFLOW: i32 = 251;
+    const Z3_MEMOUT: i32 = 101;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+
+        if out.status.signal() == Some(libc::SIGKILL) {
+            return true;
+        }
+    }
+    if let Some(code) = out.status.code()
+        && [GHC_HEAP_OVERFLOW, Z3_MEMOUT].contains(&code)
+    {
+        return true;
+    }
+    [&out.stdout, &out.stderr].into_iter().any(|output| {
+        let output = String::from_utf8_lossy(output).to_lowercase();
+        ["out of memory", "heap exhausted", "cannot allocate memory"]
+            .iter()
+            .any(|msg| output.contains(msg))
+    })
+}
+
 impl<T: Types> KVarDecl<T> {
     pub fn new(kvid: T::KVar, sorts: Vec<Sort<T>>, comment: String) -> Self {
         Self { kvid, sorts, comment }
@@ -420,10 +707,14 @@ pub enum ThyFunc {
     BvSignExtend(u8),
     IntToBv8,
     Bv8ToInt,
+    IntToBv16,
+    Bv16ToInt,
     IntToBv32,
     Bv32ToInt,
     IntToBv64,
     Bv64ToInt,
+    IntToBv128,
+    Bv128ToInt,
     BvUle,
     BvSle,
     BvUge,
@@ -471,10 +762,26 @@ pub enum ThyFunc {
     MapSelect,
     /// Store a key value pair in a map
     MapStore,
+
+    // SEQUENCES
+    /// Make an empty sequence
+    SeqEmpty,
+    /// Make a sequence with a single element
+    SeqUnit,
+    /// Length of a sequence
+    SeqLen,
+    /// Element at an index of a sequence
+    SeqNth,
+    /// Sequence concatenation
+    SeqConcat,
+    /// Subsequence starting at an offset with a given length
+    SeqExtract,
+    /// Replace the element at an index of a sequence
+    SeqUpdate,
 }
 
 impl ThyFunc {
-    pub const ALL: [ThyFunc; 44] = [
+    pub const ALL: [ThyFunc; 55] = [
         ThyFunc::StrLen,
         ThyFunc::StrConcat,
         ThyFunc::StrPrefixOf,
@@ -482,10 +789,14 @@ impl ThyFunc {
         ThyFunc::StrContains,
         ThyFunc::IntToBv8,
         ThyFunc::Bv8ToInt,
+        ThyFunc::IntToBv16,
+        ThyFunc::Bv16ToInt,
         ThyFunc::IntToBv32,
         ThyFunc::Bv32ToInt,
         ThyFunc::IntToBv64,
         ThyFunc::Bv64ToInt,
+        ThyFunc::IntToBv128,
+        ThyFunc::Bv128ToInt,
         ThyFunc::BvAdd,
         ThyFunc::BvNeg,
         ThyFunc::BvSub,
@@ -519,6 +830,13 @@ impl ThyFunc {
         ThyFunc::MapDefault,
         ThyFunc::MapSelect,
         ThyFunc::MapStore,
+        ThyFunc::SeqEmpty,
+        ThyFunc::SeqUnit,
+        ThyFunc::SeqLen,
+        ThyFunc::SeqNth,
+        ThyFunc::SeqConcat,
+        ThyFunc::SeqExtract,
+        ThyFunc::SeqUpdate,
     ];
 }
 
@@ -539,8 +857,12 @@ impl fmt::Display for ThyFunc {
             ThyFunc::Bv32ToInt => write!(f, "bv32_to_int"),
             ThyFunc::IntToBv8 => write!(f, "int_to_bv8"),
             ThyFunc::Bv8ToInt => write!(f, "bv8_to_int"),
+            ThyFunc::IntToBv16 => write!(f, "int_to_bv16"),
+            ThyFunc::Bv16ToInt => write!(f, "bv16_to_int"),
             ThyFunc::IntToBv64 => write!(f, "int_to_bv64"),
             ThyFunc::Bv64ToInt => write!(f, "bv64_to_int"),
+            ThyFunc::IntToBv128 => write!(f, "int_to_bv128"),
+            ThyFunc::Bv128ToInt => write!(f, "bv128_to_int"),
             ThyFunc::BvUle => write!(f, "bvule"),
             ThyFunc::BvSle => write!(f, "bvsle"),
             ThyFunc::BvUge => write!(f, "bvuge"),
@@ -574,6 +896,13 @@ impl fmt::Display for ThyFunc {
             ThyFunc::MapDefault => write!(f, "Map_default"),
             ThyFunc::MapSelect => write!(f, "Map_select"),
             ThyFunc::MapStore => write!(f, "Map_store"),
+            ThyFunc::SeqEmpty => write!(f, "Seq_empty"),
+            ThyFunc::SeqUnit => write!(f, "Seq_unit"),
+            ThyFunc::SeqLen => write!(f, "Seq_len"),
+            ThyFunc::SeqNth => write!(f, "Seq_nth"),
+            ThyFunc::SeqConcat => write!(f, "Seq_concat"),
+            ThyFunc::SeqExtract => write!(f, "Seq_extract"),
+            ThyFunc::SeqUpdate => write!(f, "Seq_update"),
         }
     }
 }
@@ -0,0 +1,696 @@
+//! Extraction of counterexample models for failing constraints.
+//!
+//! Fixpoint only reports *which* constraints failed, not *why*. To give users a concrete witness
+//! we take the (kvar-free) path leading to a failing head, hand it directly to an SMT solver and
+//! ask for a model of the hypotheses together with the negated goal. The query is written in
+//! SMT-LIB2 and only supports a first-order fragment of the constraint language (integers,
+//! booleans, reals and bitvectors). Hypotheses outside that fragment are dropped, which can only
+//! make the query weaker, i.e., the returned model is always consistent with the hypotheses we
+//! keep but it may not be reproducible once the dropped hypotheses are taken into account. Such
+//! models are marked as [possibly spurious](Model::possibly_spurious).
+//!
+//! The query is sent to an external `z3` or `cvc5` process, except with the `rust-fixpoint`
+//! feature where it's solved in-process with the same z3 context used to solve the constraint.
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write as _,
+    io,
+};
+#[cfg(not(feature = "rust-fixpoint"))]
+use std::{
+    io::Write as _,
+    process::{Command, Stdio},
+    thread,
+};
+
+#[cfg(not(feature = "rust-fixpoint"))]
+use itertools::Itertools as _;
+
+use crate::{
+    BinOp, BinRel, ConstDecl, Constant, DataDecl, Expr, FunDef, Identifier, Limits, SmtSolver,
+    Sort, SortCtor, ThyFunc, Types,
+    sexp::{Atom, Parser, Sexp},
+};
+
+/// A query asking for a model of `hyps ∧ ¬goal`.
+pub struct ModelQuery<'a, T: Types> {
+    pub constants: &'a [ConstDecl<T>],
+    pub data_decls: &'a [DataDecl<T>],
+    pub define_funs: &'a [FunDef<T>],
+    /// Universally quantified variables in scope at the failing head, in binding order.
+    pub binds: Vec<(T::Var, Sort<T>)>,
+    pub hyps: Vec<Expr<T>>,
+    pub goal: Expr<T>,
+    /// Expressions whose value we want to know in the model.
+    pub observations: Vec<Expr<T>>,
+    pub solver: SmtSolver,
+    pub limits: Limits,
+}
+
+/// A model for a [`ModelQuery`].
+pub struct Model {
+    /// The values of the observations (in the same order as in the query). The value of an
+    /// observation is `None` if it could not be encoded. Values are rendered using the surface
+    /// syntax of the constraint language, e.g., `-1`, `true` or `1/2`.
+    pub values: Vec<Option<String>>,
+    /// Whether some hypotheses were dropped because they could not be encoded, in which case
+    /// the model may not satisfy them.
+    pub possibly_spurious: bool,
+}
+
+impl<T: Types> ModelQuery<'_, T> {
+    /// Runs the query returning `Ok(None)` if the goal cannot be encoded or the solver did not
+    /// find a model within the limits of the query.
+    pub fn run(&self) -> io::Result<Option<Model>> {
+        let Some(script) = self.to_smt2() else {
+            return Ok(None);
+        };
+        if script.observed.is_empty() {
+            return Ok(None);
+        }
+        let Some(values) = self.get_values(&script)? else { return Ok(None) };
+        if values.len() != script.observed.len() {
+            return Ok(None);
+        }
+        let mut model = vec![None; self.observations.len()];
+        for (observed, value) in script.observed.iter().zip(values) {
+            model[observed.idx] = value.as_ref().and_then(render_value);
+        }
+        Ok(Some(Model { values: model, possibly_spurious: script.possibly_spurious }))
+    }
+
+    /// Asks the SMT solver for the value of each observation in a model of the script, returning
+    /// `None` if it doesn't find a model. The solver runs in a separate process.
+    #[cfg(not(feature = "rust-fixpoint"))]
+    fn get_values(&self, script: &Script) -> io::Result<Option<Vec<Option<Sexp>>>> {
+        let mut cmd = match self.solver {
+            SmtSolver::Z3 => {
+                let mut cmd = Command::new("z3");
+                cmd.arg("-in");
+                cmd
+            }
+            SmtSolver::CVC5 => {
+                let mut cmd = Command::new("cvc5");
+                cmd.arg("--lang=smt2").arg("--produce-models");
+                cmd
+            }
+        };
+        cmd.stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let Ok(mut child) = self.limits.spawn(&mut cmd)? else { return Ok(None) };
+
+        let mut input = String::new();
+        writeln!(input, "(set-option :produce-models true)").unwrap();
+        input.push_str(&script.assertions);
+        writeln!(input, "(check-sat)").unwrap();
+        let exprs = script
+            .observed
+            .iter()
+            .map(|observed| &observed.expr)
+            .join(" ");
+        writeln!(input, "(get-value ({exprs}))").unwrap();
+
+        // The script is written from another thread, otherwise a solver blocked on a full stdout
+        // pipe would never read the rest of a large script.
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::other("failed to open solver stdin"))?;
+        let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let out = self.limits.wait_with_output(child)?;
+        writer.join().unwrap_or_else(|_| {
+            Err(io::Error::other("failed to write the script to the solver"))
+        })?;
+        let Ok(out) = out else { return Ok(None) };
+        let stdout = String::from_utf8_lossy(&out.stdout);
+
+        let Ok(sexps) = Parser::new(&stdout).parse_all() else { return Ok(None) };
+        let mut sexps = sexps.into_iter();
+        if !matches!(sexps.next(), Some(Sexp::Atom(Atom::S(s))) if s == "sat") {
+            return Ok(None);
+        }
+        let Some(Sexp::List(values)) = sexps.next() else { return Ok(None) };
+        let values = values
+            .into_iter()
+            .map(|value| {
+                match value {
+                    Sexp::List(pair) => pair.into_iter().nth(1),
+                    Sexp::Atom(_) => None,
+                }
+            })
+            .collect();
+        Ok(Some(values))
+    }
+
+    /// Asks the SMT solver for the value of each observation in a model of the script, returning
+    /// `None` if it doesn't find a model. The script is solved in the same (thread-local) z3
+    /// context used to solve the constraint, where only the timeout of the query is enforced.
+    #[cfg(feature = "rust-fixpoint")]
+    fn get_values(&self, script: &Script) -> io::Result<Option<Vec<Option<Sexp>>>> {
+        use z3::{Params, SatResult, Solver};
+
+        // The value of an observation is read from a fresh constant equal to it
+        let mut input = script.assertions.clone();
+        for (i, observed) in script.observed.iter().enumerate() {
+            let Some(sort) = &observed.sort else { continue };
+            writeln!(input, "(declare-fun |observation${i}| () {sort})").unwrap();
+            writeln!(input, "(assert (= |observation${i}| {}))", observed.expr).unwrap();
+        }
+
+        let solver = Solver::new();
+        if let Some(secs) = self.limits.timeout {
+            let mut params = Params::new();
+            params.set_u32("timeout", u32::try_from(secs.saturating_mul(1000)).unwrap_or(u32::MAX));
+            solver.set_params(&params);
+        }
+        solver.from_string(input);
+        if solver.check() != SatResult::Sat {
+            return Ok(None);
+        }
+        let Some(model) = solver.get_model() else { return Ok(None) };
+        let constants: HashMap<_, _> = model
+            .iter()
+            .filter(|decl| decl.arity() == 0)
+            .map(|decl| (decl.name(), decl))
+            .collect();
+        let values = (0..script.observed.len())
+            .map(|i| {
+                let decl = constants.get(&format!("observation${i}"))?;
+                let value = model.eval(&decl.apply(&[]), true)?.to_string();
+                Parser::new(&value).parse_all().ok()?.into_iter().next()
+            })
+            .collect();
+        Ok(Some(values))
+    }
+
+    /// Generates the SMT-LIB2 script for the query.
+    fn to_smt2(&self) -> Option<Script> {
+        let mut cx = SmtCx::<T>::default();
+        let mut out = String::new();
+
+        if let Some(decls) = cx.declare_datatypes(self.data_decls) {
+            writeln!(out, "{decls}").ok()?;
+        }
+        for decl in self.constants {
+            if let Some(decl) = cx.declare(&decl.name, &decl.sort) {
+                writeln!(out, "{decl}").ok()?;
+            }
+        }
+        for fun in self.define_funs {
+            if let Some(def) = cx.define_fun(fun) {
+                writeln!(out, "{def}").ok()?;
+            }
+        }
+        for (name, sort) in &self.binds {
+            if let Some(decl) = cx.declare(name, sort) {
+                writeln!(out, "{decl}").ok()?;
+            }
+        }
+        let mut possibly_spurious = false;
+        for hyp in &self.hyps {
+            if let Some(hyp) = cx.expr(hyp) {
+                writeln!(out, "(assert {hyp})").ok()?;
+            } else {
+                possibly_spurious = true;
+            }
+        }
+        let goal = cx.expr(&self.goal)?;
+        writeln!(out, "(assert (not {goal}))").ok()?;
+
+        let observed = self
+            .observations
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, e)| {
+                Some(Observed {
+                    idx,
+                    expr: cx.expr(e)?,
+                    #[cfg(feature = "rust-fixpoint")]
+                    sort: cx.sort_of(e).and_then(|sort| cx.smt_sort(&sort)),
+                })
+            })
+            .collect();
+        Some(Script { assertions: out, observed, possibly_spurious })
+    }
+}
+
+/// The SMT-LIB2 encoding of a [`ModelQuery`].
+struct Script {
+    /// The declarations and assertions of the query, i.e., the hypotheses and the negated goal.
+    assertions: String,
+    /// The observations that could be encoded, in the order they appear in the query.
+    observed: Vec<Observed>,
+    /// Whether some hypotheses were dropped because they could not be encoded.
+    possibly_spurious: bool,
+}
+
+/// An observation of a [`ModelQuery`] encoded in SMT-LIB2.
+struct Observed {
+    /// The index of the observation in the query
+    idx: usize,
+    expr: String,
+    /// The sort of the observation if it can be encoded. The in-process solver needs it to bind
+    /// the observation to a constant.
+    #[cfg(feature = "rust-fixpoint")]
+    sort: Option<String>,
+}
+
+/// Renders a value returned by `get-value` in the surface syntax of the constraint language.
+fn render_value(value: &Sexp) -> Option<String> {
+    match value {
+        Sexp::Atom(Atom::I(n)) => Some(format!("{n}")),
+        Sexp::Atom(Atom::B(b)) => Some(format!("{b}")),
+        Sexp::Atom(Atom::F(f)) => Some(format!("{f}")),
+        Sexp::Atom(Atom::S(s)) => {
+            // Bitvector literals are returned as `#b0101` or `#x0f`
+            if let Some(bits) = s.strip_prefix("#b") {
+                u128::from_str_radix(bits, 2).ok().map(|n| format!("{n}"))
+            } else if let Some(hex) = s.strip_prefix("#x") {
+                u128::from_str_radix(hex, 16).ok().map(|n| format!("{n}"))
+            } else {
+                Some(s.clone())
+            }
+        }
+        Sexp::Atom(Atom::Q(_)) => None,
+        Sexp::List(items) => {
+            match &items[..] {
+                [Sexp::Atom(Atom::S(op)), e] if op == "-" => Some(format!("-{}", render_value(e)?)),
+                [Sexp::Atom(Atom::S(op)), e1, e2] if op == "/" => {
+                    Some(format!("{}/{}", render_value(e1)?, render_value(e2)?))
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+struct SmtCx<T: Types> {
+    /// Sorts of all the symbols declared so far. Symbols that could not be declared (because
+    /// their sort is not supported) are not in the map and expressions mentioning them cannot
+    /// be encoded.
+    sorts: HashMap<T::Var, Sort<T>>,
+    /// Data sorts declared with `declare-datatypes`.
+    datatypes: HashSet<T::Sort>,
+}
+
+impl<T: Types> Default for SmtCx<T> {
+    fn default() -> Self {
+        Self { sorts: HashMap::default(), datatypes: HashSet::default() }
+    }
+}
+
+impl<T: Types> SmtCx<T> {
+    /// Declares all data decls in a single (mutually recursive) `declare-datatypes` command. If
+    /// any of them cannot be encoded, none of them is declared.
+    fn declare_datatypes(&mut self, decls: &[DataDecl<T>]) -> Option<String> {
+        if decls.is_empty() {
+            return None;
+        }
+        self.datatypes = decls.iter().map(|decl| decl.name.clone()).collect();
+
+        let mut arities = vec![];
+        let mut bodies = vec![];
+        let mut symbols = vec![];
+        for decl in decls {
+            let name = decl.name.display();
+            arities.push(format!("(|{name}| {})", decl.vars));
+            let vars = (0..decl.vars).map(Sort::Var).collect::<Vec<_>>();
+            let data_sort = Sort::App(SortCtor::Data(decl.name.clone()), vars);
+            let mut ctors = vec![];
+            for ctor in &decl.ctors {
+                let mut fields = vec![];
+                for field in &ctor.fields {
+                    let Some(sort) = self.smt_sort(&field.sort) else {
+                        self.datatypes.clear();
+                        return None;
+                    };
+                    fields.push(format!("(|{}| {sort})", field.name.display()));
+                    symbols.push((
+                        field.name.clone(),
+                        Sort::mk_func(decl.vars, [data_sort.clone()], field.sort.clone()),
+                    ));
+                }
+                ctors.push(format!("(|{}| {})", ctor.name.display(), fields.join(" ")));
+                symbols.push((
+                    ctor.name.clone(),
+                    Sort::mk_func(
+                        decl.vars,
+                        ctor.fields.iter().map(|field| field.sort.clone()),
+                        data_sort.clone(),
+                    ),
+                ));
+            }
+            if decl.vars > 0 {
+                let params = (0..decl.vars)
+                    .map(|i| format!("T{i}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                bodies.push(format!("(par ({params}) ({}))", ctors.join(" ")));
+            } else {
+                bodies.push(format!("({})", ctors.join(" ")));
+            }
+        }
+        self.sorts.extend(symbols);
+        Some(format!("(declare-datatypes ({}) ({}))", arities.join(" "), bodies.join(" ")))
+    }
+
+    fn declare(&mut self, name: &T::Var, sort: &Sort<T>) -> Option<String> {
+        if self.sorts.contains_key(name) {
+            return None;
+        }
+        let (inputs, output) = uncurry(sort)?;
+        let inputs = inputs
+            .into_iter()
+            .map(|sort| self.smt_sort(sort))
+            .collect::<Option<Vec<_>>>()?;
+        let output = self.smt_sort(output)?;
+        self.sorts.insert(name.clone(), sort.clone());
+        Some(format!("(declare-fun |{}| ({}) {output})", name.display(), inputs.join(" ")))
+    }
+
+    fn define_fun(&mut self, fun: &FunDef<T>) -> Option<String> {
+        if fun.sort.params > 0 {
+            return None;
+        }
+        let inputs = fun
+            .sort
+            .inputs
+            .iter()
+            .map(|sort| self.smt_sort(sort))
+            .collect::<Option<Vec<_>>>()?;
+        let output = self.smt_sort(&fun.sort.output)?;
+        let name = fun.name.display();
+        let declare = format!("(declare-fun |{name}| ({}) {output})", inputs.join(" "));
+        let sort = Sort::mk_func(0, fun.sort.inputs.clone(), fun.sort.output.clone());
+        let Some(body) = &fun.body else {
+            self.sorts.insert(fun.name.clone(), sort);
+            return Some(declare);
+        };
+
+        // Arguments shadow any global symbol with the same name while encoding the body
+        let saved = body
+            .args
+            .iter()
+            .zip(&fun.sort.inputs)
+            .map(|(arg, sort)| (arg.clone(), self.sorts.insert(arg.clone(), sort.clone())))
+            .collect::<Vec<_>>();
+        let expr = self.expr(&body.expr);
+        for (arg, prev) in saved.into_iter().rev() {
+            match prev {
+                Some(sort) => self.sorts.insert(arg, sort),
+                None => self.sorts.remove(&arg),
+            };
+        }
+        self.sorts.insert(fun.name.clone(), sort);
+
+        let Some(expr) = expr else { return Some(declare) };
+        let params = body
+            .args
+            .iter()
+            .zip(&inputs)
+            .map(|(arg, sort)| format!("(|{}| {sort})", arg.display()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(format!("(define-fun |{name}| ({params}) {output} {expr})"))
+    }
+
+    fn expr(&mut self, e: &Expr<T>) -> Option<String> {
+        let s = match e {
+            Expr::Constant(c) => constant(c)?,
+            Expr::Var(x) => {
+                self.sorts.get(x)?;
+                format!("|{}|", x.display())
+            }
+            Expr::App(func, sort_args, args, _) => {
+                if sort_args.as_ref().is_some_and(|sorts| !sorts.is_empty()) {
+                    return None;
+                }
+                let func = match &**func {
+                    Expr::Var(f) => {
+                        self.sorts.get(f)?;
+                        format!("|{}|", f.display())
+                    }
+                    Expr::ThyFunc(thy_func) => thy_func_name(*thy_func)?,
+                    _ => return None,
+                };
+                let args = args
+                    .iter()
+                    .map(|arg| self.expr(arg))
+                    .collect::<Option<Vec<_>>>()?;
+                format!("({func} {})", args.join(" "))
+            }
+            Expr::Neg(e) => format!("(- {})", self.expr(e)?),
+            Expr::BinaryOp(op, exprs) => {
+                let [e1, e2] = &**exprs;
+                let op = match op {
+                    BinOp::Add => "+",
+                    BinOp::Sub => "-",
+                    BinOp::Mul => "*",
+                    BinOp::Div if self.is_real(e1) => "/",
+                    BinOp::Div => "div",
+                    BinOp::Mod => "mod",
+                };
+                format!("({op} {} {})", self.expr(e1)?, self.expr(e2)?)
+            }
+            Expr::IfThenElse(exprs) => {
+                let [p, e1, e2] = &**exprs;
+                format!("(ite {} {} {})", self.expr(p)?, self.expr(e1)?, self.expr(e2)?)
+            }
+            Expr::And(exprs) => self.nary("and", "true", exprs)?,
+            Expr::Or(exprs) => self.nary("or", "false", exprs)?,
+            Expr::Not(e) => format!("(not {})", self.expr(e)?),
+            Expr::Imp(exprs) => {
+                let [e1, e2] = &**exprs;
+                format!("(=> {} {})", self.expr(e1)?, self.expr(e2)?)
+            }
+            Expr::Iff(exprs) => {
+                let [e1, e2] = &**exprs;
+                format!("(= {} {})", self.expr(e1)?, self.expr(e2)?)
+            }
+            Expr::Atom(rel, exprs) => {
+                let [e1, e2] = &**exprs;
+                let rel = match rel {
+                    BinRel::Eq => "=",
+                    BinRel::Ne => "distinct",
+                    BinRel::Gt => ">",
+                    BinRel::Ge => ">=",
+                    BinRel::Lt => "<",
+                    BinRel::Le => "<=",
+                };
+                format!("({rel} {} {})", self.expr(e1)?, self.expr(e2)?)
+            }
+            Expr::Let(x, exprs) => {
+                let [init, body] = &**exprs;
+                let init_str = self.expr(init)?;
+                let sort = self.sort_of(init)?;
+                let prev = self.sorts.insert(x.clone(), sort);
+                let body = self.expr(body);
+                match prev {
+                    Some(sort) => self.sorts.insert(x.clone(), sort),
+                    None => self.sorts.remove(x),
+                };
+                format!("(let ((|{}| {init_str})) {})", x.display(), body?)
+            }
+            Expr::Exists(binder, body) => self.quantifier("exists", binder, body)?,
+            Expr::ForAll(binder, _, body) => self.quantifier("forall", binder, body)?,
+            Expr::ThyFunc(_) | Expr::IsCtor(..) => {
+                return None;
+            }
+        };
+        Some(s)
+    }
+
+    fn smt_sort(&self, sort: &Sort<T>) -> Option<String> {
+        match sort {
+            Sort::Int => Some("Int".to_string()),
+            Sort::Bool => Some("Bool".to_string()),
+            Sort::Real => Some("Real".to_string()),
+            Sort::BitVec(size) => {
+                let Sort::BvSize(size) = **size else { return None };
+                Some(format!("(_ BitVec {size})"))
+            }
+            // Sort variables only appear inside `par` declarations of data decls
+            Sort::Var(i) => Some(format!("T{i}")),
+            Sort::App(SortCtor::Data(name), args) if self.datatypes.contains(name) => {
+                if args.is_empty() {
+                    return Some(format!("|{}|", name.display()));
+                }
+                let args = args
+                    .iter()
+                    .map(|arg| self.smt_sort(arg))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(format!("(|{}| {})", name.display(), args.join(" ")))
+            }
+            Sort::Str | Sort::BvSize(_) | Sort::Func(_) | Sort::Abs(..) | Sort::App(..) => None,
+        }
+    }
+
+    fn nary(&mut self, op: &str, unit: &str, exprs: &[Expr<T>]) -> Option<String> {
+        if exprs.is_empty() {
+            return Some(unit.to_string());
+        }
+        let exprs = exprs
+            .iter()
+            .map(|e| self.expr(e))
+            .collect::<Option<Vec<_>>>()?;
+        Some(format!("({op} {})", exprs.join(" ")))
+    }
+
+    /// Encodes a quantified expression. Triggers are dropped since they only guide instantiation.
+    fn quantifier(
+        &mut self,
+        kw: &str,
+        binder: &[(T::Var, Sort<T>)],
+        body: &Expr<T>,
+    ) -> Option<String> {
+        let vars = binder
+            .iter()
+            .map(|(x, sort)| Some(format!("(|{}| {})", x.display(), self.smt_sort(sort)?)))
+            .collect::<Option<Vec<_>>>()?;
+        let prevs = binder
+            .iter()
+            .map(|(x, sort)| (x.clone(), self.sorts.insert(x.clone(), sort.clone())))
+            .collect::<Vec<_>>();
+        let body = self.expr(body);
+        for (x, prev) in prevs.into_iter().rev() {
+            match prev {
+                Some(sort) => self.sorts.insert(x, sort),
+                None => self.sorts.remove(&x),
+            };
+        }
+        Some(format!("({kw} ({}) {})", vars.join(" "), body?))
+    }
+
+    fn is_real(&self, e: &Expr<T>) -> bool {
+        matches!(self.sort_of(e), Some(Sort::Real))
+    }
+
+    /// A best-effort approximation of the sort of an expression. This is only used to decide
+    /// between integer and real division and to declare `let` binders.
+    fn sort_of(&self, e: &Expr<T>) -> Option<Sort<T>> {
+        match e {
+            Expr::Constant(Constant::Numeral(_)) => Some(Sort::Int),
+            Expr::Constant(Constant::Real(_)) => Some(Sort::Real),
+            Expr::Constant(Constant::Boolean(_)) => Some(Sort::Bool),
+            Expr::Constant(Constant::String(_)) => Some(Sort::Str),
+            Expr::Constant(Constant::BitVec(_, size)) => {
+                Some(Sort::BitVec(Box::new(Sort::BvSize(*size))))
+            }
+            Expr::Var(x) => self.sorts.get(x).cloned(),
+            Expr::App(func, _, _, out) => {
+                if let Some(out) = out {
+                    return Some(out.clone());
+                }
+                let Expr::Var(f) = &**func else { return None };
+                let (_, output) = uncurry(self.sorts.get(f)?)?;
+                Some(output.clone())
+            }
+            Expr::Neg(e) => self.sort_of(e),
+            Expr::BinaryOp(_, exprs) => self.sort_of(&exprs[0]),
+            Expr::IfThenElse(exprs) => self.sort_of(&exprs[1]),
+            Expr::Let(_, exprs) => self.sort_of(&exprs[1]),
+            Expr::And(_)
+            | Expr::Or(_)
+            | Expr::Not(_)
+            | Expr::Imp(_)
+            | Expr::Iff(_)
+            | Expr::Atom(..)
+            | Expr::IsCtor(..)
+            | Expr::Exists(..)
+            | Expr::ForAll(..) => Some(Sort::Bool),
+            Expr::ThyFunc(_) => None,
+        }
+    }
+}
+
+/// Splits a (monomorphic) function sort into its inputs and output. Non-function sorts are
+/// returned as nullary functions.
+fn uncurry<T: Types>(sort: &Sort<T>) -> Option<(Vec<&Sort<T>>, &Sort<T>)> {
+    let mut inputs = vec![];
+    let mut curr = sort;
+    loop {
+        match curr {
+            Sort::Func(input_and_output) => {
+                let [input, output] = &**input_and_output;
+                inputs.push(input);
+                curr = output;
+            }
+            Sort::Abs(..) => return None,
+            _ => return Some((inputs, curr)),
+        }
+    }
+}
+
+fn constant<T: Types>(c: &Constant<T>) -> Option<String> {
+    match c {
+        Constant::Numeral(n) => Some(format!("{n}")),
+        Constant::Real(n) => Some(format!("{n}.0")),
+        Constant::Boolean(b) => Some(format!("{b}")),
+        Constant::BitVec(n, size) => Some(format!("(_ bv{n} {size})")),
+        Constant::String(_) => None,
+    }
+}
+
+fn thy_func_name(thy_func: ThyFunc) -> Option<String> {
+    let name = match thy_func {
+        ThyFunc::IntToBv8 => "(_ int2bv 8)".to_string(),
+        ThyFunc::IntToBv16 => "(_ int2bv 16)".to_string(),
+        ThyFunc::IntToBv32 => "(_ int2bv 32)".to_string(),
+        ThyFunc::IntToBv64 => "(_ int2bv 64)".to_string(),
+        ThyFunc::IntToBv128 => "(_ int2bv 128)".to_string(),
+        ThyFunc::Bv8ToInt
+        | ThyFunc::Bv16ToInt
+        | ThyFunc::Bv32ToInt
+        | ThyFunc::Bv64ToInt
+        | ThyFunc::Bv128ToInt => "bv2nat".to_string(),
+        ThyFunc::BvZeroExtend(size) => format!("(_ zero_extend {size})"),
+        ThyFunc::BvSignExtend(size) => format!("(_ sign_extend {size})"),
+        ThyFunc::BvUle
+        | ThyFunc::BvSle
+        | ThyFunc::BvUge
+        | ThyFunc::BvSge
+        | ThyFunc::BvUdiv
+        | ThyFunc::BvSdiv
+        | ThyFunc::BvSrem
+        | ThyFunc::BvUrem
+        | ThyFunc::BvLshr
+        | ThyFunc::BvAshr
+        | ThyFunc::BvAnd
+        | ThyFunc::BvOr
+        | ThyFunc::BvXor
+        | ThyFunc::BvNot
+        | ThyFunc::BvAdd
+        | ThyFunc::BvNeg
+        | ThyFunc::BvSub
+        | ThyFunc::BvMul
+        | ThyFunc::BvShl
+        | ThyFunc::BvUgt
+        | ThyFunc::BvSgt
+        | ThyFunc::BvUlt
+        | ThyFunc::BvSlt => format!("{thy_func}"),
+        ThyFunc::StrLen
+        | ThyFunc::StrConcat
+        | ThyFunc::StrPrefixOf
+        | ThyFunc::StrSuffixOf
+        | ThyFunc::StrContains
+        | ThyFunc::SetEmpty
+        | ThyFunc::SetSng
+        | ThyFunc::SetCup
+        | ThyFunc::SetCap
+        | ThyFunc::SetDif
+        | ThyFunc::SetSub
+        | ThyFunc::SetMem
+        | ThyFunc::MapDefault
+        | ThyFunc::MapSelect
+        | ThyFunc::MapStore
+        | ThyFunc::SeqEmpty
+        | ThyFunc::SeqUnit
+        | ThyFunc::SeqLen
+        | ThyFunc::SeqNth
+        | ThyFunc::SeqConcat
+        | ThyFunc::SeqExtract
+        | ThyFunc::SeqUpdate => return None,
+    };
+    Some(name)
+}
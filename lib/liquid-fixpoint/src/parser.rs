@@ -441,6 +441,9 @@ where
         if (ctor == "Map_t" || ctor == "Array_t") && args.len() == 2 {
             return Ok(Sort::App(SortCtor::Map, args));
         }
+        if ctor == "Seq_t" && args.len() == 1 {
+            return Ok(Sort::App(SortCtor::Seq, args));
+        }
         if ctor == "BitVec" && args.len() == 1 {
             return parse_bitvec_sort(sexp);
         }
@@ -618,10 +621,14 @@ fn parse_thy_func(name: &str) -> Option<ThyFunc> {
         // BIT VECTORS - conversions
         "int_to_bv8" => Some(ThyFunc::IntToBv8),
         "bv8_to_int" => Some(ThyFunc::Bv8ToInt),
+        "int_to_bv16" => Some(ThyFunc::IntToBv16),
+        "bv16_to_int" => Some(ThyFunc::Bv16ToInt),
         "int_to_bv32" => Some(ThyFunc::IntToBv32),
         "bv32_to_int" => Some(ThyFunc::Bv32ToInt),
         "int_to_bv64" => Some(ThyFunc::IntToBv64),
         "bv64_to_int" => Some(ThyFunc::Bv64ToInt),
+        "int_to_bv128" => Some(ThyFunc::IntToBv128),
+        "bv128_to_int" => Some(ThyFunc::Bv128ToInt),
 
         // BIT VECTORS - comparisons
         "bvule" => Some(ThyFunc::BvUle),
@@ -664,6 +671,15 @@ fn parse_thy_func(name: &str) -> Option<ThyFunc> {
         "Map_select" | "arr_select_m" => Some(ThyFunc::MapSelect),
         "Map_store" | "arr_store_m" => Some(ThyFunc::MapStore),
 
+        // SEQUENCES
+        "Seq_empty" => Some(ThyFunc::SeqEmpty),
+        "Seq_unit" => Some(ThyFunc::SeqUnit),
+        "Seq_len" => Some(ThyFunc::SeqLen),
+        "Seq_nth" => Some(ThyFunc::SeqNth),
+        "Seq_concat" => Some(ThyFunc::SeqConcat),
+        "Seq_extract" => Some(ThyFunc::SeqExtract),
+        "Seq_update" => Some(ThyFunc::SeqUpdate),
+
         // Note: BvZeroExtend and BvSignExtend have parametric forms like "app (_ zero_extend N)"
         // These would need special parsing in the caller
         _ => None,
@@ -1,3 +1,13 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+use serde_json::Value;
+
 pub const FLUX_SYSROOT: &str = "FLUX_SYSROOT";
 pub const FLUX_SYSROOT_TEST: &str = "FLUX_SYSROOT_TEST";
 
@@ -5,3 +15,106 @@ pub const FLUX_SYSROOT_TEST: &str = "FLUX_SYSROOT_TEST";
 pub fn default_flags() -> Vec<String> {
     vec!["--crate-type=rlib".to_string(), "--edition=2021".to_string()]
 }
+
+/// Arguments passed by `cargo xtask test` to the test runners
+#[derive(Debug)]
+pub struct Args {
+    pub filters: Vec<String>,
+    pub flux: PathBuf,
+    pub sysroot: PathBuf,
+    /// Whether flux was built with the `rust-fixpoint` feature
+    pub rust_fixpoint: bool,
+    /// Whether flux-alloc was built with the `seq` feature
+    pub vec_seq: bool,
+}
+
+impl Args {
+    pub fn parse() -> Args {
+        let mut filters = vec![];
+        let mut sysroot = None;
+        let mut flux = None;
+        for (arg, val) in env::args().tuple_windows() {
+            match &arg[..] {
+                "--filter" => {
+                    filters.push(val);
+                }
+                "--flux" => {
+                    if flux.is_some() {
+                        panic!("option '--flux' given more than once");
+                    }
+                    flux = Some(val);
+                }
+                "--sysroot" => {
+                    if sysroot.is_some() {
+                        panic!("option '--sysroot' given more than once");
+                    }
+                    sysroot = Some(val);
+                }
+                _ => {}
+            }
+        }
+        let Some(flux) = flux else {
+            panic!("option '--flux' must be provided");
+        };
+        let Some(sysroot) = sysroot else {
+            panic!("option '--sysroot' must be provided");
+        };
+        let rust_fixpoint = env::args().any(|arg| arg == "--rust-fixpoint");
+        let vec_seq = env::args().any(|arg| arg == "--vec-seq");
+        Args {
+            filters,
+            flux: PathBuf::from(flux),
+            sysroot: PathBuf::from(sysroot),
+            rust_fixpoint,
+            vec_seq,
+        }
+    }
+
+    /// Whether a test with the given name should run according to the filters
+    pub fn matches(&self, name: &str) -> bool {
+        self.filters.is_empty() || self.filters.iter().any(|filter| name.contains(filter))
+    }
+}
+
+/// Writes an executable shell script at `path` that discards its standard input and then runs
+/// `body`. Tests use it to replace external tools like `fixpoint`.
+#[cfg(unix)]
+pub fn write_script(path: &Path, body: &str) {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::write(path, format!("#!/bin/sh\ncat > /dev/null\n{body}\n")).unwrap();
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+#[cfg(not(unix))]
+pub fn write_script(_: &Path, _: &str) {}
+
+/// Writes a fake `fixpoint` binary running `body` into `dir` and returns a `PATH` where it shadows
+/// the real one.
+pub fn fake_fixpoint(dir: &Path, body: &str) -> OsString {
+    let bin = dir.join("bin");
+    fs::create_dir_all(&bin).unwrap();
+    write_script(&bin.join("fixpoint"), body);
+    env::join_paths(
+        std::iter::once(bin).chain(env::split_paths(&env::var_os("PATH").unwrap_or_default())),
+    )
+    .unwrap()
+}
+
+/// Reads a JSON report written with `-Freport`
+pub fn read_report(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// Returns the items in a JSON report written with `-Freport`
+pub fn report_items(report: &Value) -> &[Value] {
+    report["items"].as_array().unwrap()
+}
+
+/// Returns the item with the given def path in a JSON report written with `-Freport`
+pub fn report_item<'a>(report: &'a Value, def_path: &str) -> &'a Value {
+    report_items(report)
+        .iter()
+        .find(|item| item["def_path"] == def_path)
+        .unwrap_or_else(|| panic!("missing item `{def_path}` in report: {report:#}"))
+}
@@ -0,0 +1,88 @@
+#![feature(custom_test_frameworks)]
+#![test_runner(test_runner)]
+
+use std::{fs, path::Path, process::Command};
+
+use flux_bin::audit::{self, AuditEntry, CrateAudit, Origin};
+use serde_json::Value;
+use tests::{Args, FLUX_SYSROOT, default_flags};
+
+/// Checks the audit written with `-Faudit` for a crate with an entry of each kind, and that it can
+/// be read back by `cargo flux audit` without losing anything.
+fn test_runner(_: &[&()]) {
+    let args = Args::parse();
+    if !args.matches("audit") {
+        return;
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    // A trailing separator makes flux write the audit in the directory as `cargo flux audit` does
+    let audit_dir = format!("{}/", tmp.path().display());
+    let output = Command::new(&args.flux)
+        .env(FLUX_SYSROOT, &args.sysroot)
+        .args(default_flags())
+        .arg("-Fsummary=off")
+        .arg(format!("-Faudit={audit_dir}"))
+        .arg("--out-dir")
+        .arg(tmp.path())
+        .arg(Path::new("tests/audit/audit00.rs"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let crate_names = ["audit00".to_string()];
+    let raw: Value = serde_json::from_str(
+        &fs::read_to_string(audit::audit_file(tmp.path(), &crate_names[0])).unwrap(),
+    )
+    .unwrap();
+    let audits = audit::read(tmp.path(), &crate_names).unwrap();
+    let [audit] = &audits[..] else { panic!("expected a single audit") };
+    assert_eq!(serde_json::to_value(audit).unwrap(), raw, "the audit changed after a round trip");
+
+    assert_eq!(audit.crate_name, "audit00");
+    assert_eq!(audit.unchecked.len(), 3, "{raw:#}");
+    assert_eq!(audit.assumptions.len(), 2, "{raw:#}");
+
+    let ignored = entry(audit, "ignored");
+    assert_eq!(ignored.kind, "ignored");
+    assert_attribute(ignored, "ignored");
+
+    let trusted = entry(audit, "trusted");
+    assert_eq!(trusted.kind, "trusted");
+    assert_attribute(trusted, "trusted");
+
+    let generated = entry(audit, "generated");
+    assert_eq!(generated.kind, "macro_generated");
+    assert!(matches!(&generated.origin, Origin::Macro { name } if name == "make_fn!"));
+
+    let incr = entry(audit, "<i32 as Incr>::incr");
+    assert_eq!(incr.kind, "trusted_impl");
+    assert_attribute(incr, "<i32 as Incr>::incr");
+
+    let raw_deref = entry(audit, "raw_deref");
+    assert_eq!(raw_deref.kind, "allow_raw_deref");
+    assert_attribute(raw_deref, "raw_deref");
+
+    let [extern_spec] = &audit.extern_specs[..] else { panic!("expected a single extern spec") };
+    assert_eq!(extern_spec.def_path, "std::mem::swap");
+    assert_eq!(extern_spec.crate_name, "audit00");
+
+    println!("test audit ... ok");
+}
+
+fn entry<'a>(audit: &'a CrateAudit, def_path: &str) -> &'a AuditEntry {
+    audit
+        .unchecked
+        .iter()
+        .chain(&audit.assumptions)
+        .find(|entry| entry.def_path == def_path)
+        .unwrap_or_else(|| panic!("missing entry for `{def_path}`"))
+}
+
+fn assert_attribute(entry: &AuditEntry, item: &str) {
+    let Origin::Attribute { item: found, span } = &entry.origin else {
+        panic!("expected `{}` to come from an attribute", entry.def_path)
+    };
+    assert_eq!(found, item);
+    assert_eq!(span.start_line, entry.span.start_line);
+}
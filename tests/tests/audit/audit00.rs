@@ -0,0 +1,39 @@
+#[flux_rs::extern_spec(std::mem)]
+#[flux_rs::sig(fn(&mut T, &mut T))]
+fn swap<T>(a: &mut T, b: &mut T);
+
+#[flux::sig(fn(x: i32) -> i32[x])]
+pub fn checked(x: i32) -> i32 {
+    x
+}
+
+#[flux::ignore]
+pub fn ignored() {}
+
+#[flux::trusted]
+pub fn trusted() {}
+
+macro_rules! make_fn {
+    () => {
+        pub fn generated() {}
+    };
+}
+
+make_fn!();
+
+pub trait Incr {
+    fn incr(&mut self);
+}
+
+impl Incr for i32 {
+    #[flux::trusted_impl]
+    #[flux::sig(fn(self: &strg i32[@n]) ensures self: i32[n + 1])]
+    fn incr(&mut self) {
+        *self += 1;
+    }
+}
+
+#[flux::opts(allow_raw_deref = "ok")]
+pub fn raw_deref(ptr: *const i32) -> i32 {
+    unsafe { *ptr }
+}
@@ -0,0 +1,85 @@
+#![feature(custom_test_frameworks)]
+#![test_runner(test_runner)]
+
+use std::{fs, process::Command};
+
+use tests::{Args, FLUX_SYSROOT, default_flags, read_report, report_item};
+
+const CALLEE_V1: &str = r#"
+#[flux::sig(fn() -> i32[1])]
+pub fn callee() -> i32 {
+    1
+}
+"#;
+
+const CALLEE_V2: &str = r#"
+#[flux::sig(fn() -> i32[2])]
+pub fn callee() -> i32 {
+    2
+}
+"#;
+
+const REST: &str = r#"
+#[flux::sig(fn() -> i32[1])]
+pub fn caller() -> i32 {
+    callee()
+}
+
+#[flux::sig(fn(x: i32) -> i32[x])]
+pub fn other(x: i32) -> i32 {
+    x
+}
+"#;
+
+/// Checks that functions are skipped with `-Fcache` only if nothing they depend on changed: running
+/// flux again skips every function, changing the signature of a function checks its callers again,
+/// and changing a global flag checks every function again.
+fn test_runner(_: &[&()]) {
+    let args = Args::parse();
+    if !args.matches("cache") {
+        return;
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let src = tmp.path().join("cache00.rs");
+    let run = |callee: &str, flags: &[&str]| {
+        fs::write(&src, format!("{callee}{REST}")).unwrap();
+        let report = tmp.path().join("report.json");
+        let output = Command::new(&args.flux)
+            .env(FLUX_SYSROOT, &args.sysroot)
+            .args(default_flags())
+            .arg("-Fsummary=off")
+            .arg(format!("-Fcache={}", tmp.path().join("FLUXCACHE").display()))
+            .arg(format!("-Freport={}", report.display()))
+            .args(flags)
+            .arg("--out-dir")
+            .arg(tmp.path())
+            .arg(&src)
+            .output()
+            .unwrap();
+        let report = read_report(&report);
+        let status = |name: &str| {
+            report_item(&report, name)["status"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        (output.status.success(), [status("callee"), status("caller"), status("other")])
+    };
+
+    let statuses = |statuses: [&str; 3]| statuses.map(String::from);
+    assert_eq!(run(CALLEE_V1, &[]), (true, statuses(["verified", "verified", "verified"])));
+    assert_eq!(run(CALLEE_V1, &[]), (true, statuses(["cached", "cached", "cached"])));
+    assert_eq!(
+        run(CALLEE_V2, &[]),
+        (false, statuses(["verified", "failed", "cached"])),
+        "changing the signature of `callee` must check `caller` again"
+    );
+    assert_eq!(
+        run(CALLEE_V2, &["-Ftermination"]),
+        (false, statuses(["verified", "failed", "verified"])),
+        "changing a global flag must check every function again"
+    );
+
+    println!("test cache ... ok");
+}
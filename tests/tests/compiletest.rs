@@ -4,50 +4,7 @@
 use std::{env, path::PathBuf};
 
 use compiletest_rs::{Config, common::Mode};
-use itertools::Itertools;
-use tests::{FLUX_SYSROOT, default_flags};
-
-#[derive(Debug)]
-struct Args {
-    filters: Vec<String>,
-    flux: PathBuf,
-    sysroot: PathBuf,
-}
-
-impl Args {
-    fn parse() -> Args {
-        let mut filters = vec![];
-        let mut sysroot = None;
-        let mut flux = None;
-        for (arg, val) in env::args().tuple_windows() {
-            match &arg[..] {
-                "--filter" => {
-                    filters.push(val);
-                }
-                "--flux" => {
-                    if flux.is_some() {
-                        panic!("option '--flux' given more than once");
-                    }
-                    flux = Some(val);
-                }
-                "--sysroot" => {
-                    if sysroot.is_some() {
-                        panic!("option '--sysroot' given more than once");
-                    }
-                    sysroot = Some(val);
-                }
-                _ => {}
-            }
-        }
-        let Some(flux) = flux else {
-            panic!("option '--flux' must be provided");
-        };
-        let Some(sysroot) = sysroot else {
-            panic!("option '--sysroot' must be provided");
-        };
-        Args { filters, flux: PathBuf::from(flux), sysroot: PathBuf::from(sysroot) }
-    }
-}
+use tests::{Args, FLUX_SYSROOT, default_flags};
 
 fn test_runner(_: &[&()]) {
     let args = Args::parse();
@@ -74,6 +31,21 @@ fn test_runner(_: &[&()]) {
         env::set_var(FLUX_SYSROOT, args.sysroot);
     }
 
+    // With the `seq` feature, `Vec` is refined by its length and elements, so the rest of the suite,
+    // which assumes `Vec` is refined by its length alone, doesn't apply.
+    if args.vec_seq {
+        let path: PathBuf = ["tests", "vec-seq", "pos"].iter().collect();
+        config.mode = Mode::Ui;
+        config.src_base = path;
+        compiletest_rs::run_tests(&config);
+
+        let path: PathBuf = ["tests", "vec-seq", "neg"].iter().collect();
+        config.mode = Mode::CompileFail;
+        config.src_base = path;
+        compiletest_rs::run_tests(&config);
+        return;
+    }
+
     let path: PathBuf = ["tests", "pos"].iter().collect();
     if path.exists() {
         config.mode = Mode::Ui;
@@ -87,4 +59,17 @@ fn test_runner(_: &[&()]) {
         config.src_base = path;
         compiletest_rs::run_tests(&config);
     }
+
+    // Tests using the theory of sequences are only supported by the rust implementation of fixpoint
+    if args.rust_fixpoint {
+        let path: PathBuf = ["tests", "rust-fixpoint", "pos"].iter().collect();
+        config.mode = Mode::Ui;
+        config.src_base = path;
+        compiletest_rs::run_tests(&config);
+
+        let path: PathBuf = ["tests", "rust-fixpoint", "neg"].iter().collect();
+        config.mode = Mode::CompileFail;
+        config.src_base = path;
+        compiletest_rs::run_tests(&config);
+    }
 }
@@ -0,0 +1,88 @@
+#![feature(custom_test_frameworks)]
+#![test_runner(test_runner)]
+
+use std::{fs, process::Command};
+
+use tests::{Args, FLUX_SYSROOT, default_flags, read_report, report_item};
+
+const POS_V1: &str = r#"
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 0})]
+pub fn pos(x: i32) -> i32 {
+    x
+}
+"#;
+
+const POS_V2: &str = r#"
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 0})]
+pub fn pos(x: i32) -> i32 {
+    let mut y = x;
+    if y < 10 {
+        y += 1;
+    }
+    y
+}
+"#;
+
+const REST: &str = r#"
+pub fn might_panic() -> i32 {
+    let v = vec![1, 2, 3];
+    v[0]
+}
+
+#[flux::no_panic]
+pub fn no_panic() -> i32 {
+    might_panic()
+}
+"#;
+
+/// Checks flux in an incremental session. The second run changes the body of `pos` only, so the
+/// MIR of the other functions is loaded from the incremental cache: flux must still find their
+/// bodies and report the same errors.
+fn test_runner(_: &[&()]) {
+    let args = Args::parse();
+    if !args.matches("incremental") {
+        return;
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let src = tmp.path().join("incremental00.rs");
+    let run = |pos: &str| {
+        fs::write(&src, format!("{pos}{REST}")).unwrap();
+        let report = tmp.path().join("report.json");
+        let output = Command::new(&args.flux)
+            .env(FLUX_SYSROOT, &args.sysroot)
+            .args(default_flags())
+            .arg("-Fsummary=off")
+            .arg(format!("-Freport={}", report.display()))
+            .arg(format!("-Cincremental={}", tmp.path().join("incremental").display()))
+            .arg("--out-dir")
+            .arg(tmp.path())
+            .arg(&src)
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(!stderr.contains("internal compiler error"), "{stderr}");
+
+        let report = read_report(&report);
+        for name in ["pos", "might_panic", "no_panic"] {
+            let item = report_item(&report, name);
+            assert!(
+                item["status"] == "verified" || item["status"] == "failed",
+                "`{name}` was not checked: {report:#}"
+            );
+        }
+
+        let errors = stderr
+            .lines()
+            .filter(|line| line.starts_with("error"))
+            .map(String::from)
+            .collect::<Vec<_>>();
+        (output.status.success(), errors)
+    };
+
+    let first = run(POS_V1);
+    assert_eq!(first, (false, vec!["error[E0996]: may panic".to_string()]));
+    assert_eq!(run(POS_V2), first, "the second run must report the same errors");
+
+    println!("test incremental ... ok");
+}
@@ -0,0 +1,78 @@
+#![feature(custom_test_frameworks)]
+#![test_runner(test_runner)]
+
+use std::{fs, process::Command};
+
+use tests::{Args, FLUX_SYSROOT, default_flags, fake_fixpoint};
+
+const SRC: &str = r#"
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn first(x: i32) -> i32 {
+    x
+}
+
+#[flux::sig(fn(x: i32{x + 1}) -> i32)]
+pub fn second(x: i32) -> i32 {
+    x
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn third(x: i32) -> i32 {
+    x
+}
+"#;
+
+/// Checks that errors are reported in the order items are defined with `-Fjobs=4`, where the
+/// errors for `first` and `third` are found by fixpoint queries running in parallel while the
+/// error in the signature of `second` is reported right away. We replace the fixpoint binary with
+/// a script rejecting every query, so the test doesn't depend on the solver.
+fn test_runner(_: &[&()]) {
+    let args = Args::parse();
+    // The in-process solver doesn't run queries in parallel
+    if !args.matches("jobs") || args.rust_fixpoint || !cfg!(unix) {
+        return;
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let src = tmp.path().join("jobs00.rs");
+    fs::write(&src, SRC).unwrap();
+
+    let path = fake_fixpoint(
+        tmp.path(),
+        r#"echo '{"status":{"tag":"Unsafe","contents":[{"numCstr":0,"numIter":0,"numChck":0,"numVald":0},[[0,"0"]]]},"solution":[],"nonCutsSolution":[]}'"#,
+    );
+
+    let run = |jobs: usize| {
+        let output = Command::new(&args.flux)
+            .env(FLUX_SYSROOT, &args.sysroot)
+            .env("PATH", &path)
+            .args(default_flags())
+            .arg("-Fsummary=off")
+            .arg(format!("-Fjobs={jobs}"))
+            .arg("--out-dir")
+            .arg(tmp.path())
+            .arg(&src)
+            .output()
+            .unwrap();
+        assert!(!output.status.success(), "expected every function to be rejected");
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    let stderr = run(4);
+    let lines: Vec<&str> = stderr
+        .lines()
+        .filter(|line| line.starts_with("error["))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            "error[E0991]: refinement type error",
+            "error[E0999]: mismatched sorts",
+            "error[E0991]: refinement type error",
+        ],
+        "{stderr}"
+    );
+    assert_eq!(stderr, run(1), "errors must be the same regardless of the number of jobs");
+
+    println!("test jobs ... ok");
+}
@@ -0,0 +1,15 @@
+// Test spec on `core::mem::align_of`
+
+extern crate flux_core;
+
+use std::mem::align_of;
+
+struct S {
+    x: u8,
+    y: u32,
+}
+
+fn test() {
+    flux_rs::assert(align_of::<u16>() == 1); //~ ERROR refinement type
+    flux_rs::assert(align_of::<S>() == 8); //~ ERROR refinement type
+}
@@ -0,0 +1,6 @@
+#![flux::opts(solver_timeout = "soon")] //~ ERROR invalid attribute: incorrect type in value for setting `solver_timeout`, expected u64
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 1})]
+pub fn inc(x: i32) -> i32 {
+    x + 1
+}
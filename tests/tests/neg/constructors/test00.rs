@@ -10,5 +10,5 @@ pub struct X {
 fn f1(mut x: X) -> X {
     x.x = 1;
     x.y = 2;
-    x //~ ERROR refinement type error [E0999]
+    x //~ ERROR refinement type error [E0991]
 }
@@ -0,0 +1,35 @@
+//@compile-flags: -Fcounterexamples
+
+#[flux::sig(fn(n: i32{0 <= n && n <= 1}) -> i32)]
+pub fn test00(n: i32) -> i32 {
+    10 / n //~ ERROR possible division by zero
+           //~| NOTE counterexample: n = 0
+}
+
+#[flux::sig(fn(n: i32{n == 5}, m: i32{m < n}) -> i32{v: v > 0})] //~ NOTE this is the condition
+pub fn test01(n: i32, m: i32) -> i32 {
+    n - m - 4 //~ ERROR refinement type
+              //~| NOTE a postcondition cannot be proved
+              //~| NOTE counterexample: n = 5
+}
+
+#[flux::opaque]
+#[flux::refined_by(elems: Set<int>)]
+pub struct Bag;
+
+// The hypothesis about the set cannot be encoded so the counterexample may not satisfy it
+#[flux::sig(fn(b: &Bag[@s], n: i32{set_is_in(n, s) && n > -10}) -> i32{v: v > 0})] //~ NOTE this is the condition
+pub fn test02(_b: &Bag, n: i32) -> i32 {
+    n //~ ERROR refinement type
+      //~| NOTE a postcondition cannot be proved
+      //~| NOTE possibly spurious counterexample
+}
+
+// The value of `x` is constrained by the solution of the kvar at the join point
+#[flux::sig(fn(b: bool, n: i32{n > 0}) -> i32{v: v > 5})] //~ NOTE this is the condition
+pub fn test03(b: bool, n: i32) -> i32 {
+    let x = if b { n } else { n + 1 };
+    x //~ ERROR refinement type
+      //~| NOTE a postcondition cannot be proved
+      //~| NOTE counterexample: n = 1, b = false, x = 2
+}
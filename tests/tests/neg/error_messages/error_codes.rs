@@ -0,0 +1,63 @@
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 0})]
+pub fn pos(x: i32) -> i32 {
+    x
+}
+
+pub fn precondition() -> i32 {
+    pos(0) //~ ERROR refinement type error [E0990]
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn postcondition(x: i32) -> i32 {
+    x //~ ERROR refinement type error [E0991]
+}
+
+#[flux::opts(check_overflow = "strict")]
+pub fn overflow(x: u32, y: u32) -> u32 {
+    x + y //~ ERROR arithmetic operation may overflow [E0992]
+}
+
+#[flux::opts(check_overflow = "none")]
+pub fn underflow(n: u32) -> u32 {
+    n - 1 //~ ERROR arithmetic operation may underflow [E0993]
+}
+
+pub fn division_by_zero(x: u32, y: u32) -> u32 {
+    x / y //~ ERROR possible division by zero [E0994]
+}
+
+pub struct S {
+    #[flux::field(i32{v : v > 0})]
+    a: i32,
+}
+
+pub fn consume(_: S) {}
+
+pub fn fold() {
+    let mut x = S { a: 1 };
+    x.a = 0;
+    consume(x); //~ ERROR type invariant may not hold (when place is folded) [E0995]
+}
+
+#[flux::refined_by(n: int)]
+#[flux::invariant(n > 0)] //~ ERROR invariant cannot be proven [E0997]
+pub enum Nat {
+    #[flux::variant(Nat[0])]
+    Zero,
+    #[flux::variant({Box<Nat[@n]>} -> Nat[n + 1])]
+    Succ(Box<Nat>),
+}
+
+pub fn might_panic() -> i32 {
+    let v = vec![1, 2, 3];
+    v[0]
+}
+
+#[flux::no_panic]
+pub fn no_panic() -> i32 {
+    might_panic() //~ ERROR may panic [E0996]
+}
+
+pub fn out_of_bounds(xs: &[i32], i: usize) -> i32 {
+    xs[i] //~ ERROR possible out-of-bounds access [E0989]
+}
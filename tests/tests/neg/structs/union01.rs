@@ -0,0 +1,54 @@
+#![allow(unused)]
+
+#[flux::refined_by(tag: int, n: int)]
+pub union IntOrBool {
+    #[flux::field(i32[n])]
+    int: i32,
+    #[flux::field(bool)]
+    boolean: bool,
+}
+
+#[flux::sig(fn(x: i32) -> IntOrBool[1, x])]
+pub fn mk_int(x: i32) -> IntOrBool {
+    IntOrBool { int: x } //~ ERROR refinement type
+}
+
+#[flux::sig(fn(u: IntOrBool) -> i32)]
+pub fn get_int(u: IntOrBool) -> i32 {
+    unsafe { u.int } //~ ERROR union field may not be active [E0987]
+}
+
+#[flux::sig(fn(u: &IntOrBool{v: v.tag == 0}) -> bool)]
+pub fn get_bool(u: &IntOrBool) -> bool {
+    unsafe { u.boolean } //~ ERROR union field
+}
+
+#[flux::sig(fn(u: &strg IntOrBool, x: i32) ensures u: IntOrBool[0, x + 1])]
+pub fn set_int(u: &mut IntOrBool, x: i32) { //~ ERROR refinement type
+    u.int = x;
+}
+
+#[flux::sig(fn() -> i32)]
+pub fn switch() -> i32 {
+    let mut u = IntOrBool { int: 5 };
+    u.boolean = true;
+    unsafe { u.int } //~ ERROR union field
+}
+
+#[flux::sig(fn(u: &mut IntOrBool{v: v.tag == 0 && v.n > 0}))]
+pub fn decr(u: &mut IntOrBool) {
+    unsafe {
+        u.int -= 1; //~ ERROR assignment
+    }
+}
+
+#[flux::sig(fn(u: &mut IntOrBool{v: v.tag == 0 && v.n > 0}))]
+pub fn write_bool(u: &mut IntOrBool) {
+    u.boolean = true; //~ ERROR assignment
+}
+
+#[flux::refined_by(tag: bool)]
+pub union Bad { //~ ERROR invalid tag [E0987]
+    a: i32,
+    b: u32,
+}
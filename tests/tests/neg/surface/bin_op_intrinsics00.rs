@@ -0,0 +1,50 @@
+// Calls to these intrinsics are checked with the rules for the corresponding binary operation
+#![feature(core_intrinsics)]
+#![allow(internal_features)]
+
+use std::{cmp::Ordering, intrinsics};
+
+extern crate flux_core;
+
+#[flux::sig(fn(a: u8, b: u8) -> u8)]
+pub fn unchecked_add(a: u8, b: u8) -> u8 {
+    unsafe { intrinsics::unchecked_add(a, b) } //~ ERROR arithmetic operation may overflow
+}
+
+#[flux::sig(fn(a: u32, b: u32) -> u32)]
+pub fn unchecked_sub(a: u32, b: u32) -> u32 {
+    unsafe { intrinsics::unchecked_sub(a, b) } //~ ERROR arithmetic operation may underflow
+}
+
+#[flux::sig(fn(a: i32{a > 0}, b: i32{b > 0}) -> i32)]
+pub fn unchecked_mul(a: i32, b: i32) -> i32 {
+    unsafe { intrinsics::unchecked_mul(a, b) } //~ ERROR arithmetic operation may overflow
+}
+
+#[flux::sig(fn(a: u32, b: u32{b <= 32}) -> u32)]
+pub fn unchecked_shl(a: u32, b: u32) -> u32 {
+    unsafe { intrinsics::unchecked_shl(a, b) } //~ ERROR arithmetic operation may overflow
+}
+
+#[flux::sig(fn(a: u8{a < 200}, b: u8{b < 100}) -> (u8, bool[false]))]
+pub fn add_with_overflow(a: u8, b: u8) -> (u8, bool) {
+    intrinsics::add_with_overflow(a, b) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(a: u8, b: u8) -> (u8[a * b], bool))]
+pub fn mul_with_overflow(a: u8, b: u8) -> (u8, bool) {
+    intrinsics::mul_with_overflow(a, b) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(a: i32, b: i32{a <= b}) -> Ordering[-1])]
+pub fn cmp_less(a: i32, b: i32) -> Ordering {
+    intrinsics::three_way_compare(a, b) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(a: u8, b: u8) -> bool[a >= b])]
+pub fn cmp_match(a: u8, b: u8) -> bool {
+    match intrinsics::three_way_compare(a, b) {
+        Ordering::Greater => true,
+        Ordering::Less | Ordering::Equal => false, //~ ERROR refinement type
+    }
+}
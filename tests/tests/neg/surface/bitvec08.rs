@@ -0,0 +1,32 @@
+use flux_rs::{
+    attrs::*,
+    bitvec::{BV16, BV64},
+};
+
+#[spec(fn (x:BV16[1]) -> BV16[4])]
+pub fn test_shl_16(x: BV16) -> BV16 {
+    let three: u16 = 3;
+    x << three //~ ERROR refinement type
+}
+
+#[spec(fn (x:u64[12]) -> u64[8])]
+pub fn test_and_64(x: u64) -> u64 {
+    (BV64::from(x) & 6).into() //~ ERROR refinement type
+}
+
+#[spec(fn (x:u64{x < 256}) -> u64[0])]
+pub fn test_uint_mask_without_opt(x: u64) -> u64 {
+    x & 0xff00 //~ ERROR refinement type
+}
+
+#[opts(uint_as_bitvec = true)]
+#[spec(fn (x:u64{x < 512}) -> u64[0])]
+pub fn test_uint_mask(x: u64) -> u64 {
+    x & 0xff00 //~ ERROR refinement type
+}
+
+#[opts(uint_as_bitvec = true)]
+#[spec(fn (x:u8) -> u8{v: v <= 7})]
+pub fn test_uint_low_nibble(x: u8) -> u8 {
+    x & 0x0f //~ ERROR refinement type
+}
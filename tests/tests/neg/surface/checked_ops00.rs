@@ -0,0 +1,23 @@
+use std::cmp::Ordering;
+
+extern crate flux_core;
+
+#[flux::sig(fn(a: u8, b: u8) -> u8)]
+pub fn checked_add(a: u8, b: u8) -> u8 {
+    a.checked_add(b).unwrap() //~ ERROR refinement type
+}
+
+#[flux::sig(fn(a: u32, b: u32{b >= a}) -> bool[true])]
+pub fn checked_sub_underflow(a: u32, b: u32) -> bool {
+    a.checked_sub(b).is_none() //~ ERROR refinement type
+}
+
+#[flux::sig(fn(a: u8, b: u8) -> (u8[a + b], bool))]
+pub fn overflowing_add(a: u8, b: u8) -> (u8, bool) {
+    a.overflowing_add(b) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(a: i32, b: i32{a <= b}) -> Ordering[-1])]
+pub fn cmp_less(a: i32, b: i32) -> Ordering {
+    a.cmp(&b) //~ ERROR refinement type
+}
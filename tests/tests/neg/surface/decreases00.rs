@@ -0,0 +1,14 @@
+#[flux::sig(fn(n: i32) -> i32 decreases n)]
+pub fn no_progress(n: i32) -> i32 {
+    if n == 0 { 0 } else { no_progress(n) } //~ ERROR recursive call may not terminate [E0988]
+}
+
+#[flux::sig(fn(n: i32) -> i32 decreases n)]
+pub fn negative(n: i32) -> i32 {
+    if n == 0 { 0 } else { negative(n - 1) } //~ ERROR recursive call may not terminate
+}
+
+#[flux::sig(fn(m: u64, n: u64) -> u64 decreases m, n)]
+pub fn swapped(m: u64, n: u64) -> u64 {
+    if n == 0 { m } else { swapped(m + 1, n - 1) } //~ ERROR recursive call may not terminate
+}
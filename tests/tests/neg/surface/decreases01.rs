@@ -0,0 +1,11 @@
+//@compile-flags: -Ftermination
+
+#[flux::sig(fn(n: u32) -> u32)]
+pub fn missing(n: u32) -> u32 {
+    if n == 0 { 0 } else { missing(n - 1) } //~ ERROR recursive call may not terminate
+}
+
+#[flux::sig(fn(n: u32) -> u32 decreases n)]
+pub fn present(n: u32) -> u32 {
+    if n == 0 { 0 } else { present(n - 1) }
+}
@@ -0,0 +1,54 @@
+//@compile-flags: -Ftermination
+#![feature(stmt_expr_attributes)]
+
+#[flux::sig(fn(n: u32) -> bool)]
+pub fn is_even(n: u32) -> bool {
+    if n == 0 { true } else { is_odd(n - 1) } //~ ERROR recursive call may not terminate
+}
+
+#[flux::sig(fn(n: u32) -> bool)]
+pub fn is_odd(n: u32) -> bool {
+    if n == 0 { false } else { is_even(n - 1) } //~ ERROR recursive call may not terminate
+}
+
+#[flux::sig(fn(n: u32) -> u32 decreases n)]
+pub fn ping(n: u32) -> u32 {
+    if n == 0 { 0 } else { pong(n) } //~ ERROR recursive call may not terminate
+}
+
+#[flux::sig(fn(n: u32) -> u32 decreases n)]
+pub fn pong(n: u32) -> u32 {
+    ping(n) //~ ERROR recursive call may not terminate
+}
+
+#[flux::sig(fn(n: usize) -> usize)]
+pub fn no_variant(n: usize) -> usize {
+    let mut i = 0;
+    while i < n {
+        //~^ ERROR loop may not terminate [E0988]
+        i += 1;
+    }
+    i
+}
+
+#[flux::sig(fn(n: usize) -> usize)]
+pub fn no_progress(n: usize) -> usize {
+    let mut i = 0;
+    #[flux::loop_variant(n - i)]
+    while i < n {
+        //~^ ERROR loop may not terminate
+        i += 0;
+    }
+    i
+}
+
+// The iterator of a `for` loop is opaque, so its termination cannot be proven with a variant
+#[flux::sig(fn(n: usize) -> usize)]
+pub fn for_loop(n: usize) -> usize {
+    let mut res = 0;
+    for _ in 0..n {
+        //~^ ERROR loop may not terminate
+        res += 1;
+    }
+    res
+}
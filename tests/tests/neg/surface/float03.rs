@@ -0,0 +1,16 @@
+//@compile-flags: -Freal-floats
+
+#[flux::sig(fn(p: f64{0.0 <= p && p <= 1.0}) -> f64{v: 0.0 <= v && v <= 1.0})]
+pub fn double(p: f64) -> f64 {
+    2.0 * p //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: f64, y: f64) -> f64[x / y])]
+pub fn div(x: f64, y: f64) -> f64 {
+    x / y //~ ERROR refinement type
+}
+
+#[flux::sig(fn(x: f32) -> f32{v: v > 0.0})]
+pub fn abs(x: f32) -> f32 {
+    if x < 0.0 { -x } else { x } //~ ERROR refinement type
+}
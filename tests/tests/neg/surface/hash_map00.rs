@@ -0,0 +1,60 @@
+extern crate flux_alloc;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+};
+
+use flux_rs::assert;
+
+pub fn test_contains() {
+    let mut m = HashMap::new();
+    let k1 = 1;
+    let k2 = 2;
+    m.insert(k1, 10);
+    assert(m.contains_key(&k2)); //~ ERROR refinement type
+}
+
+pub fn test_get() {
+    let mut m = HashMap::new();
+    let k = 1;
+    m.insert(k, 10);
+    if let Some(v) = m.get(&k) {
+        assert(*v == 20); //~ ERROR refinement type
+    }
+}
+
+pub fn test_len() {
+    let mut m = HashMap::new();
+    let k = 1;
+    m.insert(k, 10);
+    m.insert(k, 20);
+    assert(m.len() == 2); //~ ERROR refinement type
+}
+
+pub fn test_remove() {
+    let mut m = BTreeMap::new();
+    let k = 1;
+    m.insert(k, 10);
+    m.remove(&k);
+    assert(m.contains_key(&k)); //~ ERROR refinement type
+}
+
+pub fn test_param(m: HashMap<i32, i32>, k: i32) {
+    assert(m.contains_key(&k)); //~ ERROR refinement type
+}
+
+pub fn test_generic<K: Eq + Hash + Copy>(mut m: HashMap<K, i32>, k: K) {
+    m.insert(k, 1);
+    assert(m.contains_key(&k)); //~ ERROR refinement type
+}
+
+#[derive(PartialEq, Eq, Hash)]
+pub struct Name(String);
+
+pub fn test_unrefined_keys(a: String, b: String) {
+    let mut m = HashMap::new();
+    m.insert(Name(a), 1);
+    m.insert(Name(b), 2);
+    assert(m.len() == 1); //~ ERROR refinement type
+}
@@ -0,0 +1,29 @@
+extern crate flux_alloc;
+
+use std::collections::{BTreeSet, HashSet};
+
+use flux_rs::assert;
+
+pub fn test_insert() {
+    let mut s = HashSet::new();
+    let x = 1;
+    s.insert(x);
+    assert(s.insert(x)); //~ ERROR refinement type
+}
+
+pub fn test_remove() {
+    let mut s = BTreeSet::new();
+    let x = 1;
+    s.insert(x);
+    s.remove(&x);
+    assert(s.contains(&x)); //~ ERROR refinement type
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub struct Name(String);
+
+pub fn test_unrefined_elems(a: String, b: String) {
+    let mut s = BTreeSet::new();
+    s.insert(Name(a));
+    assert(!s.insert(Name(b))); //~ ERROR refinement type
+}
@@ -0,0 +1,35 @@
+#![feature(stmt_expr_attributes)]
+
+#[flux::sig(fn(n: usize) -> usize[n])]
+pub fn not_preserved(n: usize) -> usize {
+    let mut i = 0;
+    let mut res = 0;
+    #[flux::loop_invariant(res == i && i <= n)]
+    while i < n { //~ ERROR loop invariant may not hold
+        i += 1;
+        res += 2;
+    }
+    res
+}
+
+#[flux::sig(fn(n: usize) -> usize)]
+pub fn not_established(n: usize) -> usize {
+    let mut i = 0;
+    let mut res = 1; //~ ERROR loop invariant may not hold
+    #[flux::loop_invariant(res == i)]
+    while i < n {
+        i += 1;
+        res += 1;
+    }
+    res
+}
+
+#[flux::sig(fn(n: usize) -> usize[n])]
+pub fn too_weak(n: usize) -> usize {
+    let mut i = 0;
+    #[flux::loop_invariant(i <= n + 1)]
+    while i < n {
+        i += 1;
+    }
+    i //~ ERROR refinement type
+}
@@ -0,0 +1,29 @@
+#![feature(pattern_types, pattern_type_macro)]
+#![allow(internal_features)]
+
+use std::{mem::transmute, pat::pattern_type};
+
+type Small = pattern_type!(u32 is 1..=10);
+type Letter = pattern_type!(char is 'a'..='z');
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+pub fn to_small(x: u32) -> Small {
+    unsafe { transmute::<u32, Small>(x) } //~ ERROR may not match the pattern [E0986]
+}
+
+pub fn from_small(p: Small) -> u32 {
+    let x = unsafe { transmute::<Small, u32>(p) };
+    assert(x < 10); //~ ERROR refinement type
+    x
+}
+
+pub fn constant() -> Small {
+    unsafe { transmute::<u32, Small>(11) } //~ ERROR pattern
+}
+
+#[flux::sig(fn(c: char{'a' <= c}) -> Letter)]
+pub fn to_letter(c: char) -> Letter {
+    unsafe { transmute::<char, Letter>(c) } //~ ERROR pattern
+}
@@ -0,0 +1,5 @@
+#![flux::defs {
+    fn bad_trigger(m: Map<int, int>) -> bool {
+        forall k: int, j: int #[trigger(map_select(m, k))]. map_select(m, k) <= map_select(m, j) //~ ERROR trigger does not mention quantified variable `j`
+    }
+}]
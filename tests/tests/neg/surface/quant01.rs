@@ -0,0 +1,40 @@
+#![flux::defs {
+    fn all_pos(m: Map<int, int>) -> bool {
+        forall k: int #[trigger(map_select(m, k))]. map_select(m, k) > 0
+    }
+}]
+
+#[flux::opaque]
+#[flux::refined_by(vals: Map<int, int>)]
+pub struct IntMap {
+    inner: std::collections::HashMap<i32, i32>,
+}
+
+impl IntMap {
+    #[flux::trusted]
+    #[flux::sig(fn(&IntMap[@m], k: i32) -> i32[map_select(m.vals, k)])]
+    pub fn get(&self, k: i32) -> i32 {
+        *self.inner.get(&k).unwrap_or(&0)
+    }
+
+    #[flux::trusted]
+    #[flux::sig(fn(self: &mut IntMap[@m], k: i32, v: i32) ensures self: IntMap[map_store(m.vals, k, v)])]
+    pub fn set(&mut self, k: i32, v: i32) {
+        self.inner.insert(k, v);
+    }
+}
+
+#[flux::sig(fn(&IntMap, k: i32) -> i32{v: v > 0})]
+pub fn get_pos(m: &IntMap, k: i32) -> i32 {
+    m.get(k) //~ ERROR refinement type
+}
+
+#[flux::sig(fn(self: &strg IntMap{m: all_pos(m.vals)}, k: i32, v: i32) ensures self: IntMap{m: all_pos(m.vals)})]
+pub fn set_pos(m: &mut IntMap, k: i32, v: i32) {
+    m.set(k, v);
+} //~ ERROR refinement type
+
+#[flux::sig(fn(x: i32) -> bool[true])]
+pub fn not_square(x: i32) -> bool {
+    x != 4 //~ ERROR refinement type
+}
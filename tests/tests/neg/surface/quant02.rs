@@ -0,0 +1,19 @@
+#![flux::defs {
+    fn f(x: int) -> int;
+
+    fn bare_var() -> bool {
+        forall k: int #[trigger(k)]. f(k) > 0 //~ ERROR invalid trigger
+    }
+
+    fn arith(m: Map<int, int>) -> bool {
+        forall k: int #[trigger(map_select(m, k + 1))]. map_select(m, k + 1) > 0 //~ ERROR invalid trigger
+    }
+
+    fn equality() -> bool {
+        forall k: int #[trigger(f(k) == 0)]. f(k) == 0 //~ ERROR invalid trigger
+    }
+
+    fn negation() -> bool {
+        forall k: int #[trigger(f(-k))]. f(-k) > 0 //~ ERROR invalid trigger
+    }
+}]
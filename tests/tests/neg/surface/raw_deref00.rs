@@ -0,0 +1,49 @@
+#![flux::opts(allow_raw_deref = "checked")]
+#![feature(core_intrinsics)]
+#![allow(internal_features)]
+
+extern crate flux_core;
+
+use std::intrinsics;
+
+fn deref(ptr: *const i32) -> i32 {
+    unsafe { *ptr } //~ ERROR raw pointer may be out of bounds [E0985]
+}
+
+#[flux::spec(fn (ptr: *mut{v: ptr_size(v) >= 0} i32))]
+fn deref_mut(ptr: *mut i32) {
+    unsafe {
+        *ptr = 10; //~ ERROR raw pointer may be out of bounds
+    }
+}
+
+#[flux::spec(fn (ptr: *const{v: ptr_size(v) == 2} i32) -> i32)]
+fn deref_past_end(ptr: *const i32) -> i32 {
+    unsafe { *ptr.add(2) } //~ ERROR raw pointer may be out of bounds
+}
+
+#[flux::spec(fn (ptr: *const{v: ptr_size(v) == 2} i32) -> i32)]
+fn add_out_of_bounds(ptr: *const i32) -> i32 {
+    unsafe {
+        let _p = ptr.add(3); //~ ERROR pointer offset may be out of bounds
+        0
+    }
+}
+
+pub fn offset(p: *const u8) -> *const u8 {
+    unsafe { intrinsics::offset(p, 1isize) } //~ ERROR pointer offset may be out of bounds [E0985]
+}
+
+pub fn offset_before_start(buf: &[u8; 2]) -> *const u8 {
+    let slice: &[u8] = buf;
+    let p = unsafe { slice.as_ptr().add(1) };
+    unsafe { intrinsics::offset(p, -2isize) } //~ ERROR pointer offset may be out of bounds
+}
+
+#[flux::spec(fn (ptr: *const{v: ptr_size(v) == 2} i32) -> i32)]
+fn offset_method_before_start(ptr: *const i32) -> i32 {
+    unsafe {
+        let _p = ptr.offset(-1); //~ ERROR pointer offset may be out of bounds
+        0
+    }
+}
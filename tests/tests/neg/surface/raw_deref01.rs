@@ -0,0 +1,29 @@
+#![flux::opts(allow_raw_deref = "checked")]
+
+extern crate flux_alloc;
+extern crate flux_core;
+
+pub fn use_after_scope() -> i32 {
+    let p;
+    {
+        let buf = [1, 2];
+        let slice: &[i32] = &buf;
+        p = slice.as_ptr();
+        let _ = unsafe { *p };
+    }
+    unsafe { *p } //~ ERROR raw pointer may be out of bounds
+}
+
+pub fn use_after_drop(v: Vec<i32>) -> i32 {
+    let p = v[..].as_ptr();
+    drop(v);
+    unsafe { *p } //~ ERROR raw pointer may be out of bounds
+}
+
+pub fn use_after_move(buf: [String; 1]) -> usize {
+    let p = buf.as_ptr();
+    let moved = buf;
+    let n = unsafe { (&*p).len() }; //~ ERROR raw pointer may be out of bounds
+    drop(moved);
+    n
+}
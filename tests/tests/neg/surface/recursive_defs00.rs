@@ -0,0 +1,26 @@
+#![flux::defs {
+    fn sum(n: int) -> int decreases n { if n <= 0 { 0 } else { n + sum(n - 1) } }
+
+    fn is_even(n: int) -> bool decreases n { if n <= 0 { true } else { is_odd(n - 1) } }
+
+    fn is_odd(n: int) -> bool decreases n { if n <= 0 { false } else { is_even(n - 1) } }
+}]
+
+#[flux::sig(fn(n: i32{n >= 0}) -> i32[sum(n)])]
+pub fn sum_to(n: i32) -> i32 {
+    if n == 0 {
+        1 //~ ERROR refinement type
+    } else {
+        n + sum_to(n - 1)
+    }
+}
+
+#[flux::sig(fn() -> i32[sum(1)])]
+pub fn two() -> i32 {
+    2 //~ ERROR refinement type
+}
+
+#[flux::sig(fn(n: i32{n >= 0}) -> bool[is_even(n)])]
+pub fn even(n: i32) -> bool {
+    if n == 0 { true } else { even(n - 1) } //~ ERROR refinement type
+}
@@ -0,0 +1,22 @@
+#![flux::defs {
+    fn sum(n: int) -> int decreases n {
+        if n == 0 { 0 } else { n + sum(n - 1) } //~ ERROR recursive call may not terminate
+    }
+
+    fn inc(n: int) -> int decreases n {
+        inc(n) + 1 //~ ERROR recursive call may not terminate
+    }
+
+    fn ping(n: int) -> bool decreases n {
+        if n <= 0 { true } else { pong(n) } //~ ERROR recursive call may not terminate
+    }
+
+    fn pong(n: int) -> bool decreases n {
+        if n <= 0 { false } else { ping(n - 1) }
+    }
+}]
+
+#[flux::sig(fn(n: i32{n >= 0}) -> i32[sum(n)])]
+pub fn sum_to(n: i32) -> i32 {
+    if n == 0 { 0 } else { n + sum_to(n - 1) }
+}
@@ -0,0 +1,8 @@
+// Initializers of statics are always compiled with overflow checks, so the arithmetic below is
+// lowered to `AddWithOverflow` and `MulWithOverflow` followed by an assert.
+
+#[flux::spec(u32[68])]
+pub static SUM: u32 = 60 + 7; //~ ERROR refinement type
+
+#[flux::spec(i64{v: v > 0})]
+pub static PROD: i64 = -3 * 4; //~ ERROR refinement type
@@ -0,0 +1,9 @@
+#![feature(thread_local)]
+
+#[thread_local]
+static UNKNOWN: i32 = 10;
+
+#[flux::sig(fn() -> i32{v: v > 0})]
+pub fn read_unknown() -> i32 {
+    UNKNOWN //~ ERROR refinement type
+}
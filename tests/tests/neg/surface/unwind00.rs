@@ -0,0 +1,48 @@
+//@compile-flags: -Fcheck-unwind
+
+#[flux::refined_by(lo: int, hi: int)]
+#[flux::invariant(lo <= hi)]
+pub struct Range {
+    #[flux::field(i32[lo])]
+    lo: i32,
+    #[flux::field(i32[hi])]
+    hi: i32,
+}
+
+#[flux::refined_by(n: int)]
+pub struct Guard {
+    #[flux::field(i32[n])]
+    n: i32,
+}
+
+impl Drop for Guard {
+    #[flux::trusted_impl]
+    #[flux::sig(fn(self: &mut Guard{v: v.n > 0}))]
+    fn drop(&mut self) {}
+}
+
+#[flux::trusted]
+fn may_panic() {}
+
+#[flux::trusted]
+#[flux::sig(fn(g: &strg Guard[@n]) ensures g: Guard[n])]
+fn update(_g: &mut Guard) {}
+
+// The invariant is broken if `may_panic` unwinds
+pub fn swap(r: &mut Range) { //~ ERROR type invariant may not hold
+    let hi = r.hi;
+    r.hi = r.lo - 1;
+    may_panic();
+    r.hi = hi;
+}
+
+pub fn unguarded() {
+    let _g = Guard { n: 0 };
+    may_panic();
+} //~ ERROR refinement type error
+
+// The callee may update the guard before unwinding
+pub fn through_strg() {
+    let mut g = Guard { n: 1 };
+    update(&mut g);
+} //~ ERROR refinement type error
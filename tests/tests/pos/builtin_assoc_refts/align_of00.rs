@@ -0,0 +1,21 @@
+// Test spec on `core::mem::align_of`
+
+extern crate flux_core;
+
+use std::mem::align_of;
+
+struct S {
+    x: u8,
+    y: u32,
+}
+
+fn test() {
+    flux_rs::assert(align_of::<u8>() == 1);
+    flux_rs::assert(align_of::<u64>() == 8);
+    flux_rs::assert(align_of::<S>() == 4);
+}
+
+#[flux_rs::spec(fn() -> usize{v: v > 0})]
+fn size_and_align() -> usize {
+    size_of::<S>() / align_of::<S>()
+}
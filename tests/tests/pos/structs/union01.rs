@@ -0,0 +1,54 @@
+#![allow(unused)]
+
+#[flux::refined_by(tag: int, n: int)]
+pub union IntOrBool {
+    #[flux::field(i32[n])]
+    int: i32,
+    #[flux::field(bool)]
+    boolean: bool,
+}
+
+#[flux::sig(fn(x: i32) -> IntOrBool[0, x])]
+pub fn mk_int(x: i32) -> IntOrBool {
+    IntOrBool { int: x }
+}
+
+#[flux::sig(fn(b: bool) -> IntOrBool{v: v.tag == 1})]
+pub fn mk_bool(b: bool) -> IntOrBool {
+    IntOrBool { boolean: b }
+}
+
+#[flux::sig(fn(u: IntOrBool[0, @n]) -> i32[n])]
+pub fn get_int(u: IntOrBool) -> i32 {
+    unsafe { u.int }
+}
+
+#[flux::sig(fn(u: &IntOrBool{v: v.tag == 1}) -> bool)]
+pub fn get_bool(u: &IntOrBool) -> bool {
+    unsafe { u.boolean }
+}
+
+#[flux::sig(fn(u: &strg IntOrBool, x: i32) ensures u: IntOrBool[0, x])]
+pub fn set_int(u: &mut IntOrBool, x: i32) {
+    u.int = x;
+}
+
+#[flux::sig(fn() -> i32[5])]
+pub fn switch() -> i32 {
+    let mut u = IntOrBool { boolean: true };
+    u.int = 5;
+    unsafe { u.int }
+}
+
+#[flux::sig(fn(u: &mut IntOrBool{v: v.tag == 0 && v.n > 0}))]
+pub fn incr(u: &mut IntOrBool) {
+    unsafe {
+        u.int += 1;
+    }
+}
+
+pub fn uninit() -> i32 {
+    let u: IntOrBool;
+    u = IntOrBool { int: 1 };
+    unsafe { u.int }
+}
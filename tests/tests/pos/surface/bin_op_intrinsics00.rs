@@ -0,0 +1,77 @@
+// Calls to these intrinsics are checked with the rules for the corresponding binary operation
+#![feature(core_intrinsics)]
+#![allow(internal_features)]
+
+use std::{cmp::Ordering, intrinsics};
+
+extern crate flux_core;
+
+#[flux::sig(fn(a: u8{a < 100}, b: u8{b < 100}) -> u8[a + b])]
+pub fn unchecked_add(a: u8, b: u8) -> u8 {
+    unsafe { intrinsics::unchecked_add(a, b) }
+}
+
+#[flux::sig(fn(a: u32{a < 1000}, b: u32{b <= a}) -> u32[a - b])]
+pub fn unchecked_sub(a: u32, b: u32) -> u32 {
+    unsafe { intrinsics::unchecked_sub(a, b) }
+}
+
+#[flux::sig(fn(a: i32{-100 < a && a < 100}, b: i32{-100 < b && b < 100}) -> i32[a * b])]
+pub fn unchecked_mul(a: i32, b: i32) -> i32 {
+    unsafe { intrinsics::unchecked_mul(a, b) }
+}
+
+#[flux::sig(fn(a: u32, b: u32{b < 32}) -> u32)]
+pub fn unchecked_shl(a: u32, b: u32) -> u32 {
+    unsafe { intrinsics::unchecked_shl(a, b) }
+}
+
+#[flux::sig(fn(a: u64, b: u32{b < 64}) -> u64)]
+pub fn unchecked_shr(a: u64, b: u32) -> u64 {
+    unsafe { intrinsics::unchecked_shr(a, b) }
+}
+
+#[flux::sig(fn(a: u8{a < 200}, b: u8{b < 50}) -> (u8[a + b], bool[false]))]
+pub fn add_with_overflow(a: u8, b: u8) -> (u8, bool) {
+    intrinsics::add_with_overflow(a, b)
+}
+
+#[flux::sig(fn(a: u8, b: u8{b > a}) -> (u8, bool[true]))]
+pub fn sub_with_overflow(a: u8, b: u8) -> (u8, bool) {
+    intrinsics::sub_with_overflow(a, b)
+}
+
+#[flux::sig(fn(a: u8{a < 16}, b: u8{b < 16}) -> (u8[a * b], bool[false]))]
+pub fn mul_with_overflow(a: u8, b: u8) -> (u8, bool) {
+    intrinsics::mul_with_overflow(a, b)
+}
+
+#[flux::sig(fn(a: i64{a > 1000}) -> bool[true])]
+pub fn mul_with_overflow_flag(a: i64) -> bool {
+    let (_, overflow) = intrinsics::mul_with_overflow(a, i64::MAX);
+    overflow
+}
+
+#[flux::sig(fn(a: i32, b: i32{a < b}) -> Ordering[-1])]
+pub fn cmp_less(a: i32, b: i32) -> Ordering {
+    intrinsics::three_way_compare(a, b)
+}
+
+#[flux::sig(fn(a: usize) -> Ordering[0])]
+pub fn cmp_equal(a: usize) -> Ordering {
+    intrinsics::three_way_compare(a, a)
+}
+
+#[flux::sig(fn(a: u8, b: u8) -> bool[a > b])]
+pub fn cmp_match(a: u8, b: u8) -> bool {
+    match intrinsics::three_way_compare(a, b) {
+        Ordering::Greater => true,
+        Ordering::Less | Ordering::Equal => false,
+    }
+}
+
+// The bounds of offset pointers are tested together with raw pointer dereferences
+#[flux::sig(fn(p: *const u8) -> *const u8)]
+pub fn offset(p: *const u8) -> *const u8 {
+    unsafe { intrinsics::offset(p, 1isize) }
+}
@@ -0,0 +1,66 @@
+use flux_rs::{
+    attrs::*,
+    bitvec::{BV16, BV64, BV128},
+};
+
+#[spec(fn (x:BV16[1]) -> BV16[8])]
+pub fn test_shl_16(x: BV16) -> BV16 {
+    let three: u16 = 3;
+    x << three
+}
+
+#[spec(fn (x:BV64[8]) -> BV64[1])]
+pub fn test_shr_64(x: BV64) -> BV64 {
+    x >> 3u8
+}
+
+#[spec(fn (x:BV128[4]) -> BV128[5])]
+pub fn test_or_128(x: BV128) -> BV128 {
+    x | BV128::new(1)
+}
+
+#[spec(fn (x:u64[12]) -> u64[4])]
+pub fn test_and_64(x: u64) -> u64 {
+    (BV64::from(x) & 6).into()
+}
+
+#[spec(fn (x:u16{x < 256}) -> u16[0])]
+pub fn test_mask_16(x: u16) -> u16 {
+    (BV16::new(x) & BV16::new(0xff00)).into()
+}
+
+#[opts(uint_as_bitvec = true)]
+#[spec(fn (x:u64{x < 256}) -> u64[0])]
+pub fn test_uint_mask(x: u64) -> u64 {
+    x & 0xff00
+}
+
+#[opts(uint_as_bitvec = true)]
+#[spec(fn (x:u8) -> u8{v: v <= 15})]
+pub fn test_uint_low_nibble(x: u8) -> u8 {
+    x & 0x0f
+}
+
+#[opts(uint_as_bitvec = true)]
+#[spec(fn (x:u8[0xf0]) -> u8[0x0f])]
+pub fn test_uint_not(x: u8) -> u8 {
+    !x
+}
+
+#[opts(uint_as_bitvec = true)]
+#[spec(fn (x:u32[1]) -> u32[0x80000000])]
+pub fn test_uint_shl(x: u32) -> u32 {
+    x << 31u32
+}
+
+#[opts(uint_as_bitvec = true)]
+#[spec(fn (x:u8[0b1010], y:u8[0b0110]) -> u8[0b1100])]
+pub fn test_uint_xor(x: u8, y: u8) -> u8 {
+    x ^ y
+}
+
+#[opts(uint_as_bitvec = true)]
+#[spec(fn (x:u64, y:u64) -> u64{v: v < 16})]
+pub fn test_uint_or(x: u64, y: u64) -> u64 {
+    (x & 7) | (y & 8)
+}
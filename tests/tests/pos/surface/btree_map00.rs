@@ -0,0 +1,29 @@
+extern crate flux_alloc;
+
+use std::collections::{BTreeMap, btree_map::Entry};
+
+use flux_rs::assert;
+
+pub fn test_insert_get() {
+    let mut m = BTreeMap::new();
+    let k = 1;
+    assert(m.is_empty());
+    m.insert(k, 10);
+    assert(m.len() == 1);
+    assert(m.contains_key(&k));
+    if let Some(v) = m.get(&k) {
+        assert(*v == 10);
+    }
+    assert(m.remove(&k).is_some());
+    assert(!m.contains_key(&k));
+    assert(m.is_empty());
+}
+
+pub fn test_entry(mut m: BTreeMap<i32, i32>, k: i32) {
+    if !m.contains_key(&k) {
+        match m.entry(k) {
+            Entry::Vacant(_) => {}
+            Entry::Occupied(_) => assert(false),
+        }
+    }
+}
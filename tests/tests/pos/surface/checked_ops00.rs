@@ -0,0 +1,53 @@
+// The checked and overflowing arithmetic methods and `Ord::cmp` on integers have extern specs in
+// `flux-core` matching the rules for the corresponding binary operations.
+
+use std::cmp::Ordering;
+
+extern crate flux_core;
+
+#[flux::sig(fn(a: u8{a < 100}, b: u8{b < 100}) -> u8[a + b])]
+pub fn checked_add(a: u8, b: u8) -> u8 {
+    a.checked_add(b).unwrap()
+}
+
+#[flux::sig(fn(a: u32, b: u32{b > a}) -> bool[true])]
+pub fn checked_sub_underflow(a: u32, b: u32) -> bool {
+    a.checked_sub(b).is_none()
+}
+
+#[flux::sig(fn(a: i32{-1000 < a && a < 1000}) -> i32[a * a])]
+pub fn checked_mul(a: i32) -> i32 {
+    match a.checked_mul(a) {
+        Some(v) => v,
+        None => 0,
+    }
+}
+
+#[flux::sig(fn(a: u8{a < 100}, b: u8{b < 100}) -> (u8[a + b], bool[false]))]
+pub fn overflowing_add(a: u8, b: u8) -> (u8, bool) {
+    a.overflowing_add(b)
+}
+
+#[flux::sig(fn(a: u8, b: u8{b > a}) -> bool[true])]
+pub fn overflowing_sub(a: u8, b: u8) -> bool {
+    let (_, overflow) = a.overflowing_sub(b);
+    overflow
+}
+
+#[flux::sig(fn(a: i64{a > 1000}) -> bool[true])]
+pub fn overflowing_mul(a: i64) -> bool {
+    a.overflowing_mul(i64::MAX).1
+}
+
+#[flux::sig(fn(a: i32, b: i32{a < b}) -> Ordering[-1])]
+pub fn cmp_less(a: i32, b: i32) -> Ordering {
+    a.cmp(&b)
+}
+
+#[flux::sig(fn(a: u64, b: u64) -> u64{v: v >= a && v >= b})]
+pub fn max(a: u64, b: u64) -> u64 {
+    match a.cmp(&b) {
+        Ordering::Less => b,
+        Ordering::Equal | Ordering::Greater => a,
+    }
+}
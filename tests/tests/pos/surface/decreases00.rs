@@ -0,0 +1,21 @@
+#[flux::sig(fn(n: u32) -> u32 decreases n)]
+pub fn sum(n: u32) -> u32 {
+    if n == 0 { 0 } else { n + sum(n - 1) }
+}
+
+#[flux::sig(fn(m: u64, n: u64) -> u64 decreases m, n)]
+pub fn ackermann(m: u64, n: u64) -> u64 {
+    if m == 0 {
+        n + 1
+    } else if n == 0 {
+        ackermann(m - 1, 1)
+    } else {
+        ackermann(m - 1, ackermann(m, n - 1))
+    }
+}
+
+#[flux::sig(fn(i: usize, n: usize{i <= n}) -> usize[n])]
+#[flux::decreases(n - i)]
+pub fn count_up(i: usize, n: usize) -> usize {
+    if i < n { count_up(i + 1, n) } else { i }
+}
@@ -0,0 +1,41 @@
+//@compile-flags: -Ftermination
+#![feature(stmt_expr_attributes)]
+
+#[flux::sig(fn(n: u32) -> bool decreases n)]
+pub fn is_even(n: u32) -> bool {
+    if n == 0 { true } else { is_odd(n - 1) }
+}
+
+#[flux::sig(fn(n: u32) -> bool decreases n)]
+pub fn is_odd(n: u32) -> bool {
+    if n == 0 { false } else { is_even(n - 1) }
+}
+
+#[flux::sig(fn(n: usize) -> usize[n])]
+pub fn count(n: usize) -> usize {
+    let mut i = 0;
+    #[flux::loop_invariant(i <= n)]
+    #[flux::loop_variant(n - i)]
+    while i < n {
+        i += 1;
+    }
+    i
+}
+
+#[flux::sig(fn(n: usize) -> usize)]
+pub fn count_down(n: usize) -> usize {
+    let mut i = n;
+    let mut res = 0;
+    #[flux::loop_variant(i)]
+    while i > 0 {
+        i -= 1;
+        res += 1;
+    }
+    res
+}
+
+// Calls to non-recursive functions don't need a metric
+#[flux::sig(fn(n: u32) -> bool)]
+pub fn is_zero(n: u32) -> bool {
+    is_even(n) && n == 0
+}
@@ -0,0 +1,34 @@
+//@compile-flags: -Ftermination
+
+extern crate flux_core;
+
+// `for` loops over ranges terminate without a `loop_variant`
+
+#[flux::sig(fn(n: usize) -> usize)]
+pub fn sum(n: usize) -> usize {
+    let mut res = 0;
+    for i in 0..n {
+        res += i % 2;
+    }
+    res
+}
+
+#[flux::sig(fn(lo: i32, hi: i32) -> i32)]
+pub fn count(lo: i32, hi: i32) -> i32 {
+    let mut res = 0;
+    for _ in lo..hi {
+        res = 1;
+    }
+    res
+}
+
+#[flux::sig(fn(n: usize) -> usize)]
+pub fn nested(n: usize) -> usize {
+    let mut res = 0;
+    for i in 0..n {
+        for j in 0..i {
+            res = j;
+        }
+    }
+    res
+}
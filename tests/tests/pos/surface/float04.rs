@@ -0,0 +1,26 @@
+//@compile-flags: -Freal-floats
+
+#[flux::sig(fn(p: f64{0.0 <= p && p <= 1.0}) -> f64{v: 0.0 <= v && v <= 1.0})]
+pub fn complement(p: f64) -> f64 {
+    1.0 - p
+}
+
+#[flux::sig(fn(x: f64, y: f64{y != 0.0}) -> f64[x / y])]
+pub fn div(x: f64, y: f64) -> f64 {
+    x / y
+}
+
+#[flux::sig(fn(x: f32) -> f32{v: v >= 0.0})]
+pub fn abs(x: f32) -> f32 {
+    if x < 0.0 { -x } else { x }
+}
+
+#[flux::sig(fn(x: f64{x >= 1.5e1}) -> f64{v: v > 1.0})]
+pub fn scale(x: f64) -> f64 {
+    x * 0.1
+}
+
+#[flux::sig(fn(f64[@a], f64[@b]) -> f64[(a + b) / 2.0])]
+pub fn mean(a: f64, b: f64) -> f64 {
+    (a + b) / 2.0
+}
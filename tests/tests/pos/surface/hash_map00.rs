@@ -0,0 +1,77 @@
+extern crate flux_alloc;
+
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    hash::Hash,
+};
+
+use flux_rs::assert;
+
+pub fn test_insert_get() {
+    let mut m = HashMap::new();
+    let k = 1;
+    assert(m.is_empty());
+    assert(m.insert(k, 10).is_none());
+    assert(m.len() == 1);
+    assert(m.contains_key(&k));
+    if let Some(v) = m.get(&k) {
+        assert(*v == 10);
+    }
+    assert(m.insert(k, 20).is_some());
+    assert(m.len() == 1);
+    if let Some(v) = m.get(&k) {
+        assert(*v == 20);
+    }
+}
+
+pub fn test_remove() {
+    let mut m = HashMap::new();
+    let k1 = 1;
+    let k2 = 2;
+    m.insert(k1, 10);
+    m.insert(k2, 20);
+    assert(m.len() == 2);
+    if let Some(v) = m.remove(&k1) {
+        assert(v == 10);
+    }
+    assert(!m.contains_key(&k1));
+    assert(m.contains_key(&k2));
+    assert(m.len() == 1);
+    assert(m.remove(&k1).is_none());
+}
+
+pub fn test_entry(mut m: HashMap<i32, i32>, k: i32) {
+    if m.contains_key(&k) {
+        match m.entry(k) {
+            Entry::Occupied(_) => {}
+            Entry::Vacant(_) => assert(false),
+        }
+    }
+    let n = m.len();
+    *m.entry(k).or_insert(0) += 1;
+    assert(n <= m.len());
+}
+
+pub fn test_generic<K: Eq + Hash + Copy, V>(mut m: HashMap<K, V>, k: K, v: V) {
+    m.insert(k, v);
+    assert(!m.is_empty());
+}
+
+#[derive(PartialEq, Hash, Clone, Copy)]
+#[flux_rs::refined_by(id: int)]
+pub struct Key {
+    #[flux_rs::field(i32[id])]
+    id: i32,
+}
+
+#[flux_rs::assoc(fn injective() -> bool { true })]
+impl Eq for Key {}
+
+pub fn test_injective_key(mut m: HashMap<Key, i32>, k: Key) {
+    m.insert(k, 1);
+    assert(m.contains_key(&k));
+}
+
+pub fn test_borrowed(m: &HashMap<String, i32>, k: &str) -> bool {
+    m.contains_key(k)
+}
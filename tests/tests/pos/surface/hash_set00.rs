@@ -0,0 +1,33 @@
+extern crate flux_alloc;
+
+use std::collections::{BTreeSet, HashSet};
+
+use flux_rs::assert;
+
+pub fn test_hash_set() {
+    let mut s = HashSet::new();
+    let x = 1;
+    let y = 2;
+    assert(s.insert(x));
+    assert(!s.insert(x));
+    assert(s.len() == 1);
+    assert(s.contains(&x));
+    assert(!s.contains(&y));
+    assert(s.remove(&x));
+    assert(!s.contains(&x));
+    assert(s.is_empty());
+}
+
+pub fn test_btree_set() {
+    let mut s = BTreeSet::new();
+    let x = 1;
+    let y = 2;
+    s.insert(x);
+    assert(s.contains(&x));
+    assert(s.len() == 1);
+    assert(!s.remove(&y));
+}
+
+pub fn test_borrowed(s: &HashSet<String>, x: &str) -> bool {
+    s.contains(x)
+}
@@ -0,0 +1,40 @@
+#![feature(stmt_expr_attributes)]
+
+#[flux::sig(fn(n: usize) -> usize[n])]
+pub fn count(n: usize) -> usize {
+    let mut i = 0;
+    let mut res = 0;
+    #[flux::loop_invariant(res == i && i <= n)]
+    while i < n {
+        i += 1;
+        res += 1;
+    }
+    res
+}
+
+#[flux::sig(fn(n: usize) -> usize{v: v % 2 == 0})]
+pub fn evens(n: usize) -> usize {
+    let mut count = 0;
+    #[flux::loop_invariant(count % 2 == 0)]
+    for _ in 0..n {
+        count += 2;
+    }
+    count
+}
+
+#[flux::sig(fn(n: usize, m: usize) -> usize[n * m])]
+pub fn mul(n: usize, m: usize) -> usize {
+    let mut res = 0;
+    let mut i = 0;
+    #[flux::loop_invariant(res == i * m && i <= n)]
+    while i < n {
+        let mut j = 0;
+        #[flux::loop_invariant(res == i * m + j && j <= m && i < n)]
+        while j < m {
+            res += 1;
+            j += 1;
+        }
+        i += 1;
+    }
+    res
+}
@@ -0,0 +1,48 @@
+// `Ordering` is refined by an `int` in `flux-core`. Code using it without refinements should keep
+// checking.
+
+use std::cmp::Ordering;
+
+extern crate flux_core;
+
+pub struct Entry {
+    pub key: i32,
+    pub order: Ordering,
+}
+
+pub fn cmp_keys(a: &Entry, b: &Entry) -> Ordering {
+    a.key.cmp(&b.key)
+}
+
+pub fn max(a: i32, b: i32) -> i32 {
+    match a.cmp(&b) {
+        Ordering::Less => b,
+        Ordering::Equal | Ordering::Greater => a,
+    }
+}
+
+pub fn flip(ord: Ordering) -> Ordering {
+    if ord == Ordering::Less { Ordering::Greater } else { ord.reverse() }
+}
+
+pub fn sort(v: &mut Vec<i32>) {
+    v.sort_by(|a, b| b.cmp(a));
+}
+
+pub fn then(a: &Entry, b: &Entry) -> Ordering {
+    cmp_keys(a, b).then(a.order)
+}
+
+#[flux::sig(fn() -> Ordering[-1])]
+pub fn less() -> Ordering {
+    Ordering::Less
+}
+
+#[flux::sig(fn(Ordering[@ord]) -> i32[ord.val])]
+pub fn to_int(ord: Ordering) -> i32 {
+    match ord {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
+}
@@ -0,0 +1,48 @@
+#![feature(pattern_types, pattern_type_macro)]
+#![allow(internal_features)]
+
+use std::{mem::transmute, pat::pattern_type};
+
+type Small = pattern_type!(u32 is 1..=10);
+type Letter = pattern_type!(char is 'a'..='z');
+
+#[flux::sig(fn(bool[true]))]
+fn assert(_: bool) {}
+
+#[flux::sig(fn(x: u32{1 <= x && x <= 10}) -> Small)]
+pub fn to_small(x: u32) -> Small {
+    unsafe { transmute::<u32, Small>(x) }
+}
+
+#[flux::sig(fn(Small) -> u32{v: 1 <= v && v <= 10})]
+pub fn from_small(p: Small) -> u32 {
+    let x = unsafe { transmute::<Small, u32>(p) };
+    assert(1 <= x && x <= 10);
+    x
+}
+
+#[flux::sig(fn(x: u32{1 <= x && x <= 10}) -> u32[x])]
+pub fn roundtrip(x: u32) -> u32 {
+    let p = unsafe { transmute::<u32, Small>(x) };
+    unsafe { transmute::<Small, u32>(p) }
+}
+
+pub fn constant() -> u32 {
+    const FIVE: Small = unsafe { transmute::<u32, Small>(5) };
+    let x = unsafe { transmute::<Small, u32>(FIVE) };
+    assert(x == 5);
+    x
+}
+
+#[flux::sig(fn(Option<Small>) -> u32{v: v <= 10})]
+pub fn get_or_zero(p: Option<Small>) -> u32 {
+    match p {
+        Some(p) => from_small(p),
+        None => 0,
+    }
+}
+
+#[flux::sig(fn(c: char{'a' <= c && c <= 'z'}) -> Letter)]
+pub fn to_letter(c: char) -> Letter {
+    unsafe { transmute::<char, Letter>(c) }
+}
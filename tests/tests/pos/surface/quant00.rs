@@ -0,0 +1,49 @@
+#![flux::defs {
+    fn all_pos(m: Map<int, int>) -> bool {
+        forall k: int #[trigger(map_select(m, k))]. map_select(m, k) > 0
+    }
+
+    fn is_even(n: int) -> bool {
+        exists k: int. n == 2 * k
+    }
+}]
+
+#[flux::opaque]
+#[flux::refined_by(vals: Map<int, int>)]
+pub struct IntMap {
+    inner: std::collections::HashMap<i32, i32>,
+}
+
+impl IntMap {
+    #[flux::trusted]
+    #[flux::sig(fn(&IntMap[@m], k: i32) -> i32[map_select(m.vals, k)])]
+    pub fn get(&self, k: i32) -> i32 {
+        *self.inner.get(&k).unwrap_or(&0)
+    }
+
+    #[flux::trusted]
+    #[flux::sig(fn(self: &mut IntMap[@m], k: i32, v: i32) ensures self: IntMap[map_store(m.vals, k, v)])]
+    pub fn set(&mut self, k: i32, v: i32) {
+        self.inner.insert(k, v);
+    }
+}
+
+#[flux::sig(fn(&IntMap{m: all_pos(m.vals)}, k: i32) -> i32{v: v > 0})]
+pub fn get_pos(m: &IntMap, k: i32) -> i32 {
+    m.get(k)
+}
+
+#[flux::sig(fn(self: &strg IntMap{m: all_pos(m.vals)}, k: i32, v: i32{v > 0}) ensures self: IntMap{m: all_pos(m.vals)})]
+pub fn set_pos(m: &mut IntMap, k: i32, v: i32) {
+    m.set(k, v);
+}
+
+#[flux::sig(fn(x: i32{x < 1000 && x > -1000}) -> i32{v: is_even(v)})]
+pub fn double(x: i32) -> i32 {
+    x + x
+}
+
+#[flux::sig(fn(x: i32{forall y: int. y * y != x}) -> bool[true])]
+pub fn not_square(x: i32) -> bool {
+    x != 4
+}
@@ -0,0 +1,51 @@
+#![flux::opts(allow_raw_deref = "checked")]
+#![feature(core_intrinsics)]
+#![allow(internal_features)]
+
+extern crate flux_core;
+
+use std::intrinsics;
+
+#[flux::spec(fn (ptr: *const{v: ptr_size(v) > 0} i32) -> i32)]
+fn deref(ptr: *const i32) -> i32 {
+    unsafe { *ptr }
+}
+
+#[flux::spec(fn (ptr: *mut{v: ptr_size(v) > 0} i32))]
+fn deref_mut(ptr: *mut i32) {
+    unsafe {
+        *ptr = 10;
+    }
+}
+
+#[flux::spec(fn (ptr: *const{v: ptr_size(v) == 3} i32) -> i32)]
+fn deref_add(ptr: *const i32) -> i32 {
+    unsafe { *ptr + *ptr.add(1) + *ptr.add(2) }
+}
+
+#[flux::spec(fn (ptr: *mut{v: ptr_size(v) >= 2} i32))]
+fn deref_add_mut(ptr: *mut i32) {
+    unsafe {
+        let p = ptr.add(1);
+        *p = 20;
+    }
+}
+
+pub fn test(buf: &[i32; 2]) -> i32 {
+    let slice: &[i32] = buf;
+    deref(slice.as_ptr())
+}
+
+pub fn offset_back(buf: &[u8; 2]) -> u8 {
+    let slice: &[u8] = buf;
+    let p = unsafe { slice.as_ptr().add(2) };
+    unsafe { *intrinsics::offset(p, -2isize) }
+}
+
+#[flux::spec(fn (ptr: *mut{v: ptr_size(v) == 2} i32))]
+fn deref_offset_mut(ptr: *mut i32) {
+    unsafe {
+        let p = ptr.offset(2);
+        *p.offset(-1) = 20;
+    }
+}
@@ -0,0 +1,13 @@
+#![flux::opts(allow_raw_deref = "ok")]
+
+extern crate flux_core;
+
+// Pointer bounds are only checked with `allow_raw_deref = "checked"`
+pub fn add_past_end(buf: &[i32; 2]) -> *const i32 {
+    let slice: &[i32] = buf;
+    unsafe { slice.as_ptr().add(3) }
+}
+
+pub fn offset_before_start(ptr: *const i32) -> i32 {
+    unsafe { *ptr.offset(-1) }
+}
@@ -0,0 +1,12 @@
+//@compile-flags: -Fsolver-timeout=60 -Fsolver-memory=4096
+
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 1})]
+pub fn inc(x: i32) -> i32 {
+    x + 1
+}
+
+#[flux::opts(solver_timeout = "120", solver_memory = "8192")]
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 2})]
+pub fn inc2(x: i32) -> i32 {
+    x + 2
+}
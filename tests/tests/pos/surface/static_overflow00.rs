@@ -0,0 +1,24 @@
+// Initializers of statics are always compiled with overflow checks, so the arithmetic below is
+// lowered to `AddWithOverflow`, `SubWithOverflow` and `MulWithOverflow` followed by an assert.
+
+#[flux::spec(u32[67])]
+pub static SUM: u32 = 60 + 7;
+
+#[flux::spec(u8[0])]
+pub static DIFF: u8 = 255 - 255;
+
+#[flux::spec(i64{v: v < 0})]
+pub static PROD: i64 = -3 * 4;
+
+#[flux::spec(usize[42])]
+pub static NESTED: usize = (1 + 2) * (10 + 4);
+
+#[flux::sig(fn() -> u32[67])]
+pub fn test_sum() -> u32 {
+    SUM
+}
+
+#[flux::sig(fn() -> usize{v: v > 40})]
+pub fn test_nested() -> usize {
+    NESTED
+}
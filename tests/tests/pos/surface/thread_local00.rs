@@ -0,0 +1,36 @@
+#![feature(thread_local)]
+
+use std::cell::Cell;
+
+#[flux::spec(i32{v: v > 0})]
+#[thread_local]
+static POS: i32 = 10;
+
+#[flux::sig(fn() -> i32{v: v > 0})]
+pub fn read_pos() -> i32 {
+    POS
+}
+
+#[thread_local]
+static mut COUNTER: u32 = 0;
+
+pub fn bump() -> u32 {
+    unsafe {
+        let ptr = &raw mut COUNTER;
+        *ptr += 1;
+        *ptr
+    }
+}
+
+thread_local! {
+    static CELL: Cell<u32> = const { Cell::new(0) };
+}
+
+pub fn read_cell() -> u32 {
+    CELL.with(|c| c.get())
+}
+
+#[flux::sig(fn(Box<Box<i32{v: v > 0}>>) -> i32{v: v > 0})]
+pub fn deref_box(b: Box<Box<i32>>) -> i32 {
+    **b
+}
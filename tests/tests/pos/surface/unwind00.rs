@@ -0,0 +1,70 @@
+//@compile-flags: -Fcheck-unwind
+
+#[flux::refined_by(lo: int, hi: int)]
+#[flux::invariant(lo <= hi)]
+pub struct Range {
+    #[flux::field(i32[lo])]
+    lo: i32,
+    #[flux::field(i32[hi])]
+    hi: i32,
+}
+
+#[flux::refined_by(n: int)]
+pub struct Guard {
+    #[flux::field(i32[n])]
+    n: i32,
+}
+
+impl Drop for Guard {
+    #[flux::trusted_impl]
+    #[flux::sig(fn(self: &mut Guard{v: v.n > 0}))]
+    fn drop(&mut self) {}
+}
+
+#[flux::trusted]
+fn may_panic() {}
+
+#[flux::trusted]
+#[flux::sig(fn(g: &mut Guard{v: v.n > 0}))]
+fn update(_g: &mut Guard) {}
+
+// The invariant holds at every call that may unwind
+pub fn collapse(r: &mut Range) {
+    r.lo = r.hi;
+    may_panic();
+    r.lo = r.lo - 1;
+}
+
+pub fn guarded(x: &mut i32) {
+    let _g = Guard { n: 1 };
+    *x += 1;
+    may_panic();
+}
+
+// The guard is moved before calling a function that may unwind
+pub fn moved() {
+    let g = Guard { n: 0 };
+    std::mem::forget(g);
+    may_panic();
+}
+
+// The callee can only write values satisfying the bound of the mutable reference
+pub fn through_ref() {
+    let mut g = Guard { n: 1 };
+    update(&mut g);
+    update(&mut g);
+}
+
+#[flux::sig(fn(x: &strg i32[@n]) ensures x: i32[n + 1])]
+pub fn incr(x: &mut i32) {
+    *x += 1;
+    may_panic();
+}
+
+#[flux::sig(fn(i32{v: v > 0}) -> i32)]
+pub fn div(x: i32) -> i32 {
+    let v = vec![1, 2];
+    let r = 10 / x;
+    may_panic();
+    r + v[0]
+}
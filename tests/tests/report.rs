@@ -0,0 +1,61 @@
+#![feature(custom_test_frameworks)]
+#![test_runner(test_runner)]
+
+use std::{path::Path, process::Command};
+
+use serde_json::Value;
+use tests::{Args, FLUX_SYSROOT, default_flags, read_report, report_item, report_items};
+
+/// Checks the JSON file written with `-Freport` for a crate containing a verified, a failed, a
+/// trusted, and an ignored function. Other items, e.g., ignored modules and structs, are not reported.
+fn test_runner(_: &[&()]) {
+    let args = Args::parse();
+    if !args.matches("report") {
+        return;
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let report = tmp.path().join("report.json");
+    let output = Command::new(&args.flux)
+        .env(FLUX_SYSROOT, &args.sysroot)
+        .args(default_flags())
+        .arg("-Fsummary=off")
+        .arg(format!("-Freport={}", report.display()))
+        .arg("--out-dir")
+        .arg(tmp.path())
+        .arg(Path::new("tests/report/report00.rs"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "expected `failed` to be rejected");
+
+    let report = read_report(&report);
+    assert_eq!(report["crate_name"], "report00");
+
+    let item = |name: &str| report_item(&report, name);
+
+    let verified = item("verified");
+    assert_eq!(verified["status"], "verified");
+    assert_eq!(verified["errors"], Value::Array(vec![]));
+
+    let failed = item("failed");
+    assert_eq!(failed["status"], "failed");
+    let errors = failed["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["span"]["start_line"], 8);
+
+    let trusted = item("trusted");
+    assert_eq!(trusted["status"], "trusted");
+    assert_eq!(trusted["errors"], Value::Array(vec![]));
+
+    assert_eq!(item("ignored::ignored")["status"], "ignored");
+    for name in ["ignored", "ignored::Ignored"] {
+        assert!(
+            !report_items(&report)
+                .iter()
+                .any(|item| item["def_path"] == name),
+            "only functions and statics are reported, found `{name}` in report: {report:#}"
+        );
+    }
+
+    println!("test report ... ok");
+}
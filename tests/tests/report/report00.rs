@@ -0,0 +1,22 @@
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 0})]
+pub fn verified(x: i32) -> i32 {
+    x
+}
+
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn failed(x: i32) -> i32 {
+    x
+}
+
+#[flux::trusted]
+#[flux::sig(fn(x: i32) -> i32{v: v > 0})]
+pub fn trusted(x: i32) -> i32 {
+    x
+}
+
+#[flux::ignore]
+pub mod ignored {
+    pub struct Ignored;
+
+    pub fn ignored() {}
+}
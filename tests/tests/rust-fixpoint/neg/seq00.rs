@@ -0,0 +1,16 @@
+use flux_rs::attrs::*;
+
+#[spec(fn(x: i32, y: i32) -> i32[seq_nth(#[x, y], 0)])]
+pub fn test_nth(_x: i32, y: i32) -> i32 {
+    y //~ ERROR refinement type
+}
+
+#[spec(fn(x: i32) -> i32[seq_len(seq_concat(#[x], #[x, x]))])]
+pub fn test_concat(_x: i32) -> i32 {
+    2 //~ ERROR refinement type
+}
+
+#[spec(fn() -> bool[seq_sub(#[1, 2, 3], 1, 2) == #[1, 2]])]
+pub fn test_sub() -> bool {
+    true //~ ERROR refinement type
+}
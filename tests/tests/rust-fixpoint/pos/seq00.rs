@@ -0,0 +1,21 @@
+use flux_rs::attrs::*;
+
+#[spec(fn(x: i32, y: i32) -> i32[seq_nth(#[x, y], 1)])]
+pub fn test_nth(_x: i32, y: i32) -> i32 {
+    y
+}
+
+#[spec(fn(x: i32) -> i32[seq_len(seq_concat(#[x], #[x, x]))])]
+pub fn test_concat(_x: i32) -> i32 {
+    3
+}
+
+#[spec(fn() -> bool[seq_sub(#[1, 2, 3], 1, 2) == #[2, 3]])]
+pub fn test_sub() -> bool {
+    true
+}
+
+#[spec(fn(x: i32) -> bool[seq_update(#[x, 1], 0, 2) == #[2, 1]])]
+pub fn test_update(_x: i32) -> bool {
+    true
+}
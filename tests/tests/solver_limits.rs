@@ -0,0 +1,64 @@
+#![feature(custom_test_frameworks)]
+#![test_runner(test_runner)]
+
+use std::{fs, process::Command};
+
+use tests::{Args, FLUX_SYSROOT, default_flags, fake_fixpoint};
+
+const SRC: &str = r#"
+#[flux::sig(fn(x: i32{x > 0}) -> i32{v: v > 0})]
+pub fn pos(x: i32) -> i32 {
+    let mut y = x;
+    if y < 10 {
+        y += 1;
+    }
+    y
+}
+"#;
+
+/// Checks the errors reported when the solver exceeds the limits set with `-Fsolver-timeout` and
+/// `-Fsolver-memory`. We replace the fixpoint binary with scripts that never finish or that fail
+/// like a process running out of memory, so the test doesn't depend on how fast the solver is.
+fn test_runner(_: &[&()]) {
+    let args = Args::parse();
+    // The in-process solver doesn't run the fixpoint binary
+    if !args.matches("solver_limits") || args.rust_fixpoint || !cfg!(unix) {
+        return;
+    }
+
+    let tmp = tempfile::tempdir().unwrap();
+    let src = tmp.path().join("solver_limits00.rs");
+    fs::write(&src, SRC).unwrap();
+
+    let run = |fixpoint: &str, flag: &str| {
+        let path = fake_fixpoint(tmp.path(), fixpoint);
+        let output = Command::new(&args.flux)
+            .env(FLUX_SYSROOT, &args.sysroot)
+            .env("PATH", path)
+            .args(default_flags())
+            .arg("-Fsummary=off")
+            .arg(flag)
+            .arg("--out-dir")
+            .arg(tmp.path())
+            .arg(&src)
+            .output()
+            .unwrap();
+        assert!(!output.status.success(), "expected the query to exhaust the solver limits");
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    let stderr = run("exec sleep 60", "-Fsolver-timeout=1");
+    assert!(stderr.contains("error[E0984]: verification timed out"), "{stderr}");
+    assert!(stderr.contains("the solver exceeded the limit of 1s"), "{stderr}");
+
+    // The GHC runtime exits with code 251 when fixpoint exhausts its heap
+    let stderr = run("exit 251", "-Fsolver-memory=512");
+    assert!(stderr.contains("error[E0984]: verification ran out of memory"), "{stderr}");
+    assert!(stderr.contains("the solver exceeded the limit of 512MB"), "{stderr}");
+
+    // The kernel kills processes with `SIGKILL` when the system runs out of memory
+    let stderr = run("kill -KILL $$", "-Fsolver-memory=512");
+    assert!(stderr.contains("error[E0984]: verification ran out of memory"), "{stderr}");
+
+    println!("test solver_limits ... ok");
+}
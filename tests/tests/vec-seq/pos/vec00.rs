@@ -0,0 +1,36 @@
+extern crate flux_alloc;
+extern crate flux_core;
+
+use flux_rs::{assert, attrs::*};
+
+#[spec(fn() -> Vec<i32>[3, #[1, 2, 3]])]
+pub fn test_push() -> Vec<i32> {
+    let mut v = Vec::new();
+    v.push(1);
+    v.push(2);
+    v.push(3);
+    v
+}
+
+pub fn test_pop() {
+    let mut v = test_push();
+    let x = v.pop().unwrap();
+    assert(x == 3);
+    assert(v.len() == 2);
+}
+
+#[spec(fn(v: Vec<i32>[@n, @s]) -> Vec<i32>[n + 1, seq_concat(#[5], s)])]
+pub fn test_insert(mut v: Vec<i32>) -> Vec<i32> {
+    v.insert(0, 5);
+    v
+}
+
+#[spec(fn(v: &Vec<i32>{v: v.len > 0}) -> i32)]
+pub fn test_index(v: &Vec<i32>) -> i32 {
+    v[v.len() - 1]
+}
+
+#[spec(fn(v: &mut Vec<i32>[@n, @s]) requires n > 0 ensures v: Vec<i32>{w: w.len == n})]
+pub fn test_index_mut(v: &mut Vec<i32>) {
+    v[0] = 7;
+}
@@ -21,6 +21,8 @@ xflags::xflags! {
         optional --offline
         /// If true, run cargo build commands with --features rust-fixpiont
         optional --rust-fixpoint
+        /// If true, build flux-alloc with the `seq` feature, refining `Vec` by its elements. Requires --rust-fixpoint
+        optional --vec-seq
 
         /// Run regression tests
         cmd test {
@@ -112,16 +114,20 @@ fn main() -> anyhow::Result<()> {
     if cmd.offline {
         extra.push("--offline");
     }
+    if cmd.vec_seq && !cmd.rust_fixpoint {
+        return Err(anyhow!("`--vec-seq` requires `--rust-fixpoint`"));
+    }
     match cmd.subcommand {
-        XtaskCmd::Test(args) => test(args, cmd.rust_fixpoint),
+        XtaskCmd::Test(args) => test(args, cmd.rust_fixpoint, cmd.vec_seq),
         XtaskCmd::LeanBench(args) => lean_bench(args, cmd.rust_fixpoint),
-        XtaskCmd::Run(args) => run(args, cmd.rust_fixpoint),
-        XtaskCmd::Install(args) => install(&args, &extra, cmd.rust_fixpoint),
+        XtaskCmd::Run(args) => run(args, cmd.rust_fixpoint, cmd.vec_seq),
+        XtaskCmd::Install(args) => install(&args, &extra, cmd.rust_fixpoint, cmd.vec_seq),
         XtaskCmd::Doc(args) => doc(args),
         XtaskCmd::BuildSysroot(_) => {
             let config = SysrootConfig {
                 profile: Profile::Dev,
                 rust_fixpoint: cmd.rust_fixpoint,
+                vec_seq: cmd.vec_seq,
                 dst: local_sysroot_dir()?,
                 build_libs: BuildLibs { force: true, tests: true, libs: FluxLib::ALL },
             };
@@ -133,10 +139,11 @@ fn main() -> anyhow::Result<()> {
     }
 }
 
-fn test(args: Test, rust_fixpoint: bool) -> anyhow::Result<()> {
+fn test(args: Test, rust_fixpoint: bool, vec_seq: bool) -> anyhow::Result<()> {
     let config = SysrootConfig {
         profile: Profile::Dev,
         rust_fixpoint,
+        vec_seq,
         dst: local_sysroot_dir()?,
         build_libs: BuildLibs { force: false, tests: !args.no_lib_tests, libs: FluxLib::ALL },
     };
@@ -150,6 +157,8 @@ fn test(args: Test, rust_fixpoint: bool) -> anyhow::Result<()> {
         .map_opt(args.filter.as_ref(), |filter, cmd| {
             cmd.args(["--filter", filter]);
         })
+        .args(rust_fixpoint.then_some("--rust-fixpoint"))
+        .args(vec_seq.then_some("--vec-seq"))
         .run()
 }
 
@@ -159,6 +168,7 @@ fn lean_bench(args: LeanBench, rust_fixpoint: bool) -> anyhow::Result<()> {
     let config = SysrootConfig {
         profile: Profile::Dev,
         rust_fixpoint,
+        vec_seq: false,
         dst: local_sysroot_dir()?,
         build_libs: BuildLibs { force: false, tests: false, libs: FluxLib::ALL },
     };
@@ -281,7 +291,7 @@ fn lean_bench(args: LeanBench, rust_fixpoint: bool) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn run(args: Run, rust_fixpoint: bool) -> anyhow::Result<()> {
+fn run(args: Run, rust_fixpoint: bool, vec_seq: bool) -> anyhow::Result<()> {
     let libs = if args.no_extern_specs { &[FluxLib::FluxRs] } else { FluxLib::ALL };
     run_inner(
         args.input,
@@ -290,6 +300,7 @@ fn run(args: Run, rust_fixpoint: bool) -> anyhow::Result<()> {
             .into_iter()
             .chain(args.opts),
         rust_fixpoint,
+        vec_seq,
     )?;
     Ok(())
 }
@@ -300,6 +311,7 @@ fn expand(args: Expand) -> Result<(), anyhow::Error> {
         BuildLibs { force: false, tests: false, libs: &[FluxLib::FluxRs] },
         ["-Zunpretty=expanded".to_string()],
         false,
+        false,
     )?;
     Ok(())
 }
@@ -309,10 +321,12 @@ fn run_inner(
     build_libs: BuildLibs,
     flags: impl IntoIterator<Item = String>,
     rust_fixpoint: bool,
+    vec_seq: bool,
 ) -> Result<(), anyhow::Error> {
     let config = SysrootConfig {
         profile: Profile::Dev,
         rust_fixpoint,
+        vec_seq,
         dst: local_sysroot_dir()?,
         build_libs,
     };
@@ -330,11 +344,17 @@ fn run_inner(
         .run()
 }
 
-fn install(args: &Install, extra: &[&str], rust_fixpoint: bool) -> anyhow::Result<()> {
+fn install(
+    args: &Install,
+    extra: &[&str],
+    rust_fixpoint: bool,
+    vec_seq: bool,
+) -> anyhow::Result<()> {
     let libs = if args.no_extern_specs { &[FluxLib::FluxRs] } else { FluxLib::ALL };
     let config = SysrootConfig {
         profile: args.profile(),
         rust_fixpoint,
+        vec_seq,
         dst: default_sysroot_dir(),
         build_libs: BuildLibs { force: false, tests: false, libs },
     };
@@ -381,6 +401,8 @@ struct SysrootConfig {
     profile: Profile,
     /// Whether rust-fixpoint should be enabled to build `flux-driver`
     rust_fixpoint: bool,
+    /// Whether flux-alloc should be built with the `seq` feature
+    vec_seq: bool,
     /// Destination path for sysroot artifacts
     dst: PathBuf,
     build_libs: BuildLibs,
@@ -458,6 +480,13 @@ fn install_sysroot(config: &SysrootConfig) -> anyhow::Result<()> {
                 .iter()
                 .flat_map(|lib| ["-p", lib.package_name()]),
         )
+        .args(
+            config
+                .vec_seq
+                .then_some(["--features", "flux-alloc/seq"])
+                .into_iter()
+                .flatten(),
+        )
         .env(FLUX_SYSROOT, &config.dst)
         .env_if(config.build_libs.tests, FLUX_SYSROOT_TEST, "1")
         .run_with_cargo_metadata()?;